@@ -10,6 +10,7 @@ use divan::Bencher;
 use fallout_dnd::game::combat::{
     attack_roll, calculate_damage, resolve_stat_modifiers, roll_dice, CombatState, Enemy,
 };
+use fallout_dnd::game::rng::GameRng;
 
 fn main() {
     divan::main();
@@ -18,25 +19,29 @@ fn main() {
 /// Benchmark damage calculation
 #[divan::bench]
 fn damage_calculation(bencher: Bencher) {
-    bencher.bench_local(|| calculate_damage("2d6+3", 5, false));
+    let mut rng = GameRng::seeded(1);
+    bencher.bench_local(|| calculate_damage("2d6+3", 5, false, &mut rng));
 }
 
 /// Benchmark critical damage calculation
 #[divan::bench]
 fn critical_damage_calculation(bencher: Bencher) {
-    bencher.bench_local(|| calculate_damage("2d6+3", 5, true));
+    let mut rng = GameRng::seeded(1);
+    bencher.bench_local(|| calculate_damage("2d6+3", 5, true, &mut rng));
 }
 
 /// Benchmark attack roll
 #[divan::bench]
 fn attack_roll_bench(bencher: Bencher) {
-    bencher.bench_local(|| attack_roll(50, 15));
+    let mut rng = GameRng::seeded(1);
+    bencher.bench_local(|| attack_roll(50, 15, &mut rng));
 }
 
 /// Benchmark dice rolling
 #[divan::bench]
 fn dice_rolling(bencher: Bencher) {
-    bencher.bench_local(|| roll_dice("2d6+3"));
+    let mut rng = GameRng::seeded(1);
+    bencher.bench_local(|| roll_dice("2d6+3", &mut rng));
 }
 
 /// Benchmark stat modifier resolution (hot path)
@@ -75,12 +80,12 @@ fn resolve_combat_turn(bencher: Bencher) {
             let mut combat = CombatState::new();
             let enemies = vec![Enemy::raider(3), Enemy::raider(2), Enemy::raider(4)];
             combat.start_combat(enemies);
-            combat
+            (combat, GameRng::seeded(1))
         })
-        .bench_values(|mut combat| {
+        .bench_values(|(mut combat, mut rng)| {
             // Simulate player attack
             if let Some(enemy) = combat.enemies.first_mut() {
-                let damage = calculate_damage("2d6+3", 5, false);
+                let damage = calculate_damage("2d6+3", 5, false, &mut rng).unwrap();
                 enemy.current_hp -= damage;
             }
             combat.next_round();
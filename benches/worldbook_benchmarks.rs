@@ -4,6 +4,7 @@
 //! - Location lookups
 //! - NPC queries
 //! - Context building for AI prompts
+//! - Retrieval of relevant entries for AI prompts
 //! - Serialization/deserialization
 
 use divan::Bencher;
@@ -43,6 +44,18 @@ fn build_context(bencher: Bencher) {
     bencher.bench_local(|| wb.build_context());
 }
 
+/// Benchmark retrieving worldbook entries relevant to a player action
+#[divan::bench]
+fn relevant_entries(bencher: Bencher) {
+    let wb = Worldbook::with_defaults();
+
+    bencher.bench_local(|| {
+        wb.relevant_entries("I head back to the vault", 400, |text| {
+            text.split_whitespace().count()
+        })
+    });
+}
+
 /// Benchmark worldbook serialization (save)
 #[divan::bench]
 fn worldbook_serialization(bencher: Bencher) {
@@ -6,14 +6,16 @@
 //! - Attack rolls and damage calculation
 //! - Enemy AI behavior
 //! - Combat log with colorized output
+//! - Replaying a fight from an RNG seed
 //!
-//! Run: cargo run --example combat_simulation
+//! Run: cargo run --example combat_simulation [seed]
 
 use colored::Colorize;
 use fallout_dnd::game::character::{Character, Special};
 use fallout_dnd::game::combat::{
     attack_roll, calculate_damage, resolve_stat_modifiers, CombatState, Enemy,
 };
+use fallout_dnd::game::rng::{parse_seed, GameRng};
 
 fn main() {
     println!(
@@ -23,6 +25,13 @@ fn main() {
             .cyan()
     );
 
+    // The same seed always replays the same fight
+    let mut rng = match std::env::args().nth(1) {
+        Some(seed) => GameRng::seeded(parse_seed(&seed)),
+        None => GameRng::from_entropy(),
+    };
+    println!("RNG seed: {}\n", rng.seed());
+
     // Create player character
     let mut special = Special::new();
     special.strength = 6;
@@ -119,13 +128,14 @@ fn main() {
                 enemy_ac
             );
 
-            let (hit, critical) = attack_roll(player_skill, enemy_ac);
+            let (hit, critical) = attack_roll(player_skill, enemy_ac, &mut rng);
 
             if hit {
                 let weapon_damage = player.get_equipped_damage();
                 let resolved_damage =
                     resolve_stat_modifiers(&weapon_damage, player.special.strength);
-                let damage = calculate_damage(&resolved_damage, 0, critical);
+                let damage = calculate_damage(&resolved_damage, 0, critical, &mut rng)
+                    .expect("damage dice should be valid");
 
                 enemy.take_damage(damage);
 
@@ -206,11 +216,12 @@ fn main() {
             );
 
             let player_ac = 10 + (player.special.agility as i32);
-            let (hit, critical) = attack_roll(enemy.skill, player_ac);
+            let (hit, critical) = attack_roll(enemy.skill, player_ac, &mut rng);
 
             if hit {
                 let resolved_damage = resolve_stat_modifiers(&enemy.damage, enemy.strength);
-                let damage = calculate_damage(&resolved_damage, 0, critical);
+                let damage = calculate_damage(&resolved_damage, 0, critical, &mut rng)
+                    .expect("damage dice should be valid");
 
                 player.take_damage(damage);
 
@@ -0,0 +1,290 @@
+//! llama.cpp backend using the native `/completion` endpoint, and
+//! `/tokenize` for token counts from the loaded model's own tokenizer.
+
+use super::{spawn_sse_reader, GenerationRequest, NarrativeBackend, SseEvent, TokenStream};
+use crate::error::GameError;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long to wait for `/tokenize` before falling back to an estimate
+const TOKENIZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize)]
+struct LlamaRequest<'a> {
+    prompt: &'a str,
+    temperature: f32,
+    top_p: f32,
+    top_k: i32,
+    n_predict: i32,
+    repeat_penalty: f32,
+    stop: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grammar: Option<&'a str>,
+    stream: bool,
+}
+
+impl<'a> LlamaRequest<'a> {
+    fn new(request: &'a GenerationRequest, stream: bool) -> Self {
+        LlamaRequest {
+            prompt: &request.prompt,
+            temperature: request.temperature,
+            top_p: request.top_p,
+            top_k: request.top_k,
+            n_predict: request.max_tokens,
+            repeat_penalty: request.repeat_penalty,
+            stop: &request.stop,
+            grammar: request.grammar.as_deref(),
+            stream,
+        }
+    }
+}
+
+/// Streaming response chunk from llama.cpp
+#[derive(Debug, Deserialize)]
+struct LlamaStreamChunk {
+    content: String,
+    #[serde(default)]
+    stop: bool,
+}
+
+/// Non-streaming response from llama.cpp
+#[derive(Debug, Deserialize)]
+struct LlamaCompletionResponse {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Request body for `/tokenize`
+#[derive(Debug, Serialize)]
+struct TokenizeRequest<'a> {
+    content: &'a str,
+}
+
+/// Response from `/tokenize`. Tokens are IDs, or objects with the token's
+/// text when the server is asked for pieces; only the count matters here.
+#[derive(Debug, Deserialize)]
+struct TokenizeResponse {
+    tokens: Vec<IgnoredAny>,
+}
+
+/// Error response from llama.cpp (used when stream chunk parsing fails)
+#[derive(Debug, Deserialize)]
+struct LlamaErrorResponse {
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Backend for a llama.cpp `llama-server` instance
+#[derive(Clone)]
+pub struct LlamaCppBackend {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl LlamaCppBackend {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        LlamaCppBackend {
+            base_url: base_url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Base URL of the server (e.g. `http://localhost:8080`)
+    #[allow(dead_code)]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn post_completion(
+        &self,
+        request: &GenerationRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}/completion", self.base_url);
+
+        tracing::debug!(
+            "Sending request to: {}, prompt length: {} chars",
+            url,
+            request.prompt.len()
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&LlamaRequest::new(request, stream))
+            .timeout(request.timeout)
+            .send()
+            .await
+            .map_err(|e| {
+                GameError::AIConnectionError(format!(
+                    "Failed to connect to llama.cpp server: {}. Make sure it's running at {}",
+                    e, self.base_url
+                ))
+            })?;
+
+        tracing::debug!("Got response with status: {}", response.status());
+
+        if !response.status().is_success() {
+            return Err(GameError::AIConnectionError(format!(
+                "llama.cpp server returned error: {}",
+                response.status()
+            ))
+            .into());
+        }
+
+        Ok(response)
+    }
+}
+
+/// Parse a single SSE payload from `/completion`
+fn parse_stream_chunk(data: &str) -> SseEvent {
+    match serde_json::from_str::<LlamaStreamChunk>(data) {
+        Ok(chunk) if chunk.stop && chunk.content.is_empty() => SseEvent::Done,
+        Ok(chunk) if chunk.stop => {
+            // Final chunk can still carry content; emit it, the channel closes after.
+            SseEvent::Token(chunk.content)
+        }
+        Ok(chunk) if chunk.content.is_empty() => SseEvent::Skip,
+        Ok(chunk) => SseEvent::Token(chunk.content),
+        Err(e) => {
+            // Try parsing as error response
+            if let Ok(LlamaErrorResponse { error: Some(error) }) = serde_json::from_str(data) {
+                return SseEvent::Error(error);
+            }
+            tracing::warn!("Failed to parse stream chunk: {} - data: {}", e, data);
+            SseEvent::Skip
+        }
+    }
+}
+
+impl NarrativeBackend for LlamaCppBackend {
+    fn name(&self) -> &str {
+        "llama.cpp"
+    }
+
+    fn stream(&self, request: GenerationRequest) -> BoxFuture<'_, Result<TokenStream>> {
+        Box::pin(async move {
+            let response = self.post_completion(&request, true).await?;
+            Ok(spawn_sse_reader(response, parse_stream_chunk))
+        })
+    }
+
+    fn complete(&self, request: GenerationRequest) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let response = self.post_completion(&request, false).await?;
+            let body: LlamaCompletionResponse = response.json().await?;
+
+            if let Some(error) = body.error {
+                return Err(
+                    GameError::AIConnectionError(format!("llama.cpp error: {}", error)).into(),
+                );
+            }
+
+            Ok(body.content)
+        })
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let url = format!("{}/health", self.base_url);
+
+            self.client
+                .get(&url)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|_| {
+                    GameError::AIConnectionError(format!(
+                        "Cannot connect to llama.cpp at {}. Is it running?",
+                        self.base_url
+                    ))
+                })?;
+
+            Ok(())
+        })
+    }
+
+    fn count_tokens<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<usize>> {
+        Box::pin(async move {
+            let url = format!("{}/tokenize", self.base_url);
+            let response = self
+                .client
+                .post(&url)
+                .json(&TokenizeRequest { content: text })
+                .timeout(TOKENIZE_TIMEOUT)
+                .send()
+                .await
+                .map_err(|e| {
+                    GameError::AIConnectionError(format!("Failed to reach /tokenize: {}", e))
+                })?;
+
+            if !response.status().is_success() {
+                return Err(GameError::AIConnectionError(format!(
+                    "llama.cpp /tokenize returned error: {}",
+                    response.status()
+                ))
+                .into());
+            }
+
+            let body: TokenizeResponse = response.json().await?;
+            Ok(body.tokens.len())
+        })
+    }
+
+    fn has_model_tokenizer(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_chunk_token() {
+        let event = parse_stream_chunk(r#"{"content": "The", "stop": false}"#);
+        assert!(matches!(event, SseEvent::Token(t) if t == "The"));
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_stop() {
+        let event = parse_stream_chunk(r#"{"content": "", "stop": true}"#);
+        assert!(matches!(event, SseEvent::Done));
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_error() {
+        let event = parse_stream_chunk(r#"{"error": "context overflow"}"#);
+        assert!(matches!(event, SseEvent::Error(e) if e == "context overflow"));
+    }
+
+    #[test]
+    fn test_tokenize_response_counts_ids_or_pieces() {
+        let ids: TokenizeResponse = serde_json::from_str(r#"{"tokens": [1, 415, 9543]}"#).unwrap();
+        assert_eq!(ids.tokens.len(), 3);
+
+        let pieces: TokenizeResponse =
+            serde_json::from_str(r#"{"tokens": [{"id": 415, "piece": " The"}]}"#).unwrap();
+        assert_eq!(pieces.tokens.len(), 1);
+    }
+
+    #[test]
+    fn test_request_serialization() {
+        let mut request = GenerationRequest::from_config(
+            "Prompt".to_string(),
+            &crate::config::Config::default().llama,
+        );
+        request.stop = vec![">>> PLAYER:".to_string()];
+
+        let json = serde_json::to_value(LlamaRequest::new(&request, true)).unwrap();
+        assert_eq!(json["prompt"], "Prompt");
+        assert_eq!(json["n_predict"], request.max_tokens);
+        assert_eq!(json["stream"], true);
+        assert!(json.get("grammar").is_none());
+    }
+}
@@ -0,0 +1,286 @@
+//! # Inference Backends
+//!
+//! Pluggable LLM backends used by the AI Dungeon Master and the entity extractor.
+//!
+//! ## Overview
+//!
+//! Every backend implements [`NarrativeBackend`], which covers the three things
+//! the game needs from an inference server:
+//! - Streaming tokens for a prompt (and a blocking `complete` built on top of it)
+//! - A health check used at startup
+//! - A token count used for context budgeting (see [`crate::ai::tokenizer`])
+//!
+//! ## Implementations
+//!
+//! - [`LlamaCppBackend`]: llama.cpp's native `/completion` endpoint (default)
+//! - [`OpenAiBackend`]: OpenAI-compatible `/v1/chat/completions` (Ollama, vLLM, LM Studio)
+//! - [`ScriptedBackend`]: In-process mock that replays canned responses for tests
+//!
+//! ## Selecting a Backend
+//!
+//! The backend is chosen per server in `config.toml`:
+//!
+//! ```toml
+//! [llama]
+//! backend = "openai"            # or "llama_cpp" (default)
+//! server_url = "http://localhost:11434"
+//! model = "mistral-nemo"
+//! ```
+
+pub mod llama_cpp;
+pub mod openai;
+pub mod scripted;
+
+pub use llama_cpp::LlamaCppBackend;
+pub use openai::OpenAiBackend;
+#[allow(unused_imports)]
+pub use scripted::ScriptedBackend;
+
+use crate::config::{BackendKind, LlamaConfig};
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tiktoken_rs::CoreBPE;
+use tokio::sync::mpsc;
+
+/// Receiver for a streamed generation. Yields tokens, or an error message if the stream fails.
+pub type TokenStream = mpsc::Receiver<Result<String, String>>;
+
+/// Prompt and sampling parameters for a single generation request.
+#[derive(Debug, Clone)]
+pub struct GenerationRequest {
+    /// Fully rendered prompt text
+    pub prompt: String,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub top_k: i32,
+    /// Maximum number of tokens to generate
+    pub max_tokens: i32,
+    pub repeat_penalty: f32,
+    /// Sequences that end generation
+    pub stop: Vec<String>,
+    /// Optional GBNF grammar (ignored by backends that don't support it)
+    pub grammar: Option<String>,
+    /// Request timeout
+    pub timeout: Duration,
+}
+
+impl GenerationRequest {
+    /// Create a request using the sampling settings from the narrative config
+    pub fn from_config(prompt: String, config: &LlamaConfig) -> Self {
+        GenerationRequest {
+            prompt,
+            temperature: config.temperature,
+            top_p: config.top_p,
+            top_k: config.top_k,
+            max_tokens: config.max_tokens,
+            repeat_penalty: config.repeat_penalty,
+            stop: Vec::new(),
+            grammar: None,
+            timeout: Duration::from_secs(600), // 10 minutes for slow generation
+        }
+    }
+}
+
+/// An inference server the game can talk to.
+///
+/// Methods return boxed futures so backends can be stored as `Arc<dyn NarrativeBackend>`
+/// and swapped at runtime.
+pub trait NarrativeBackend: Send + Sync {
+    /// Human-readable backend name for logs and error messages
+    fn name(&self) -> &str;
+
+    /// Start a streaming generation and return a channel of tokens
+    fn stream(&self, request: GenerationRequest) -> BoxFuture<'_, Result<TokenStream>>;
+
+    /// Generate a full response (collects the token stream)
+    fn complete(&self, request: GenerationRequest) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let mut rx = self.stream(request).await?;
+            let mut content = String::new();
+            while let Some(token) = rx.recv().await {
+                content.push_str(&token.map_err(anyhow::Error::msg)?);
+            }
+            Ok(content)
+        })
+    }
+
+    /// Check that the server is reachable
+    fn health_check(&self) -> BoxFuture<'_, Result<()>>;
+
+    /// Count tokens in `text` as the model would see them
+    ///
+    /// Defaults to the cl100k_base estimate.
+    fn count_tokens<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<usize>> {
+        Box::pin(async move { Ok(cl100k_token_count(text)) })
+    }
+
+    /// Whether [`count_tokens`](Self::count_tokens) uses the model's own
+    /// tokenizer rather than the cl100k_base estimate
+    fn has_model_tokenizer(&self) -> bool {
+        false
+    }
+}
+
+/// Token count using tiktoken's cl100k_base encoding
+///
+/// The tokenizer is cached globally to avoid re-initialization overhead.
+pub fn cl100k_token_count(text: &str) -> usize {
+    static TOKENIZER: OnceLock<CoreBPE> = OnceLock::new();
+
+    let bpe = TOKENIZER.get_or_init(|| {
+        tiktoken_rs::cl100k_base().expect("Failed to initialize tokenizer - this should never fail")
+    });
+    bpe.encode_with_special_tokens(text).len()
+}
+
+/// Build the backend for the narrative (DM) server
+pub fn narrative_backend(config: &LlamaConfig) -> Arc<dyn NarrativeBackend> {
+    build_backend(
+        config.backend,
+        &config.server_url,
+        config.model.as_deref(),
+        config.api_key.as_deref(),
+    )
+}
+
+/// Build the backend for the extraction server
+pub fn extraction_backend(config: &LlamaConfig) -> Arc<dyn NarrativeBackend> {
+    build_backend(
+        config.extraction_backend,
+        &config.extraction_url,
+        config.extraction_model.as_deref(),
+        config.api_key.as_deref(),
+    )
+}
+
+fn build_backend(
+    kind: BackendKind,
+    url: &str,
+    model: Option<&str>,
+    api_key: Option<&str>,
+) -> Arc<dyn NarrativeBackend> {
+    match kind {
+        BackendKind::LlamaCpp => Arc::new(LlamaCppBackend::new(url)),
+        BackendKind::OpenAi => Arc::new(OpenAiBackend::new(
+            url,
+            model.unwrap_or("default"),
+            api_key.map(str::to_string),
+        )),
+    }
+}
+
+/// Result of parsing a single SSE `data:` payload
+pub(crate) enum SseEvent {
+    /// A generated token
+    Token(String),
+    /// Generation finished normally
+    Done,
+    /// Server reported an error
+    Error(String),
+    /// Nothing to emit for this payload
+    Skip,
+}
+
+/// Forward a server-sent-events response body into a token channel
+///
+/// Runs in a background task. `parse` turns each `data:` payload into an [`SseEvent`].
+pub(crate) fn spawn_sse_reader<F>(response: reqwest::Response, parse: F) -> TokenStream
+where
+    F: Fn(&str) -> SseEvent + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Result<String, String>>(100);
+
+    tokio::spawn(async move {
+        tracing::debug!("Starting to process streaming response");
+
+        let mut stream = response.bytes_stream();
+        use futures_util::StreamExt;
+
+        let mut buffer = String::new();
+
+        while let Some(chunk_result) = stream.next().await {
+            match chunk_result {
+                Ok(bytes) => {
+                    let Ok(text) = std::str::from_utf8(&bytes) else {
+                        continue;
+                    };
+                    buffer.push_str(text);
+
+                    // Process complete SSE events (data: {...}\n\n)
+                    while let Some(event_end) = buffer.find("\n\n") {
+                        let event = buffer[..event_end].to_string();
+                        buffer = buffer[event_end + 2..].to_string();
+
+                        for line in event.lines() {
+                            let Some(data) = line.strip_prefix("data: ") else {
+                                continue;
+                            };
+                            match parse(data) {
+                                SseEvent::Token(token) => {
+                                    if tx.send(Ok(token)).await.is_err() {
+                                        tracing::debug!("Receiver dropped, stopping stream");
+                                        return;
+                                    }
+                                }
+                                SseEvent::Done => {
+                                    tracing::debug!("Stream completed");
+                                    return;
+                                }
+                                SseEvent::Error(error) => {
+                                    let _ = tx.send(Err(error)).await;
+                                    return;
+                                }
+                                SseEvent::Skip => {}
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Stream error: {}", e);
+                    let _ = tx.send(Err(format!("Stream error: {}", e))).await;
+                    return;
+                }
+            }
+        }
+
+        tracing::debug!("Stream ended naturally");
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_generation_request_from_config() {
+        let config = Config::default();
+        let request = GenerationRequest::from_config("Hello".to_string(), &config.llama);
+
+        assert_eq!(request.prompt, "Hello");
+        assert_eq!(request.temperature, config.llama.temperature);
+        assert_eq!(request.max_tokens, config.llama.max_tokens);
+        assert!(request.stop.is_empty());
+        assert!(request.grammar.is_none());
+    }
+
+    #[test]
+    fn test_cl100k_token_count() {
+        assert_eq!(cl100k_token_count(""), 0);
+        assert!(cl100k_token_count("The wasteland stretches before you.") > 3);
+    }
+
+    #[test]
+    fn test_backend_selection_from_config() {
+        let mut config = Config::default();
+        assert_eq!(narrative_backend(&config.llama).name(), "llama.cpp");
+
+        config.llama.backend = BackendKind::OpenAi;
+        assert_eq!(narrative_backend(&config.llama).name(), "openai");
+        assert_eq!(extraction_backend(&config.llama).name(), "llama.cpp");
+    }
+}
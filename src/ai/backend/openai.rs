@@ -0,0 +1,315 @@
+//! OpenAI-compatible backend using `/v1/chat/completions`.
+//!
+//! Works with Ollama, vLLM, LM Studio and any other server that speaks the
+//! OpenAI chat API. The rendered prompt is sent as a single user message.
+
+use super::{spawn_sse_reader, GenerationRequest, NarrativeBackend, SseEvent, TokenStream};
+use crate::error::GameError;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The OpenAI API accepts at most four stop sequences
+const MAX_STOP_SEQUENCES: usize = 4;
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f32,
+    top_p: f32,
+    max_tokens: i32,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    stop: &'a [String],
+    stream: bool,
+    // Non-standard sampling extensions understood by Ollama, vLLM and LM Studio
+    top_k: i32,
+    repeat_penalty: f32,
+}
+
+impl<'a> ChatRequest<'a> {
+    fn new(model: &'a str, request: &'a GenerationRequest, stream: bool) -> Self {
+        let stop_len = request.stop.len().min(MAX_STOP_SEQUENCES);
+        ChatRequest {
+            model,
+            messages: vec![ChatMessage {
+                role: "user",
+                content: &request.prompt,
+            }],
+            temperature: request.temperature,
+            top_p: request.top_p,
+            max_tokens: request.max_tokens,
+            stop: &request.stop[..stop_len],
+            stream,
+            top_k: request.top_k,
+            repeat_penalty: request.repeat_penalty,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamChoice {
+    delta: ChatDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamChunk {
+    #[serde(default)]
+    choices: Vec<ChatStreamChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    error: ApiError,
+}
+
+/// Backend for servers exposing the OpenAI chat completions API
+#[derive(Clone)]
+pub struct OpenAiBackend {
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl OpenAiBackend {
+    pub fn new(
+        base_url: impl Into<String>,
+        model: impl Into<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        let base_url: String = base_url.into();
+        OpenAiBackend {
+            // Accept both `http://host:port` and `http://host:port/v1`
+            base_url: base_url
+                .trim_end_matches('/')
+                .trim_end_matches("/v1")
+                .to_string(),
+            model: model.into(),
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Model name sent with every request
+    #[allow(dead_code)]
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    async fn post_chat(
+        &self,
+        request: &GenerationRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}/v1/chat/completions", self.base_url);
+
+        tracing::debug!(
+            "Sending chat request to: {} (model {}), prompt length: {} chars",
+            url,
+            self.model,
+            request.prompt.len()
+        );
+
+        let response = self
+            .authorize(self.client.post(&url))
+            .json(&ChatRequest::new(&self.model, request, stream))
+            .timeout(request.timeout)
+            .send()
+            .await
+            .map_err(|e| {
+                GameError::AIConnectionError(format!(
+                    "Failed to connect to OpenAI-compatible server: {}. Make sure it's running at {}",
+                    e, self.base_url
+                ))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response
+                .json::<ApiErrorResponse>()
+                .await
+                .map(|e| format!(" ({})", e.error.message))
+                .unwrap_or_default();
+            return Err(GameError::AIConnectionError(format!(
+                "OpenAI-compatible server returned error: {}{}",
+                status, detail
+            ))
+            .into());
+        }
+
+        Ok(response)
+    }
+}
+
+/// Parse a single SSE payload from `/v1/chat/completions`
+fn parse_stream_chunk(data: &str) -> SseEvent {
+    if data.trim() == "[DONE]" {
+        return SseEvent::Done;
+    }
+
+    if let Ok(error) = serde_json::from_str::<ApiErrorResponse>(data) {
+        return SseEvent::Error(error.error.message);
+    }
+
+    match serde_json::from_str::<ChatStreamChunk>(data) {
+        Ok(chunk) => {
+            let Some(choice) = chunk.choices.into_iter().next() else {
+                return SseEvent::Skip;
+            };
+            match choice.delta.content {
+                Some(content) if !content.is_empty() => SseEvent::Token(content),
+                _ if choice.finish_reason.is_some() => SseEvent::Done,
+                _ => SseEvent::Skip,
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to parse chat stream chunk: {} - data: {}", e, data);
+            SseEvent::Skip
+        }
+    }
+}
+
+impl NarrativeBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn stream(&self, request: GenerationRequest) -> BoxFuture<'_, Result<TokenStream>> {
+        Box::pin(async move {
+            let response = self.post_chat(&request, true).await?;
+            Ok(spawn_sse_reader(response, parse_stream_chunk))
+        })
+    }
+
+    fn complete(&self, request: GenerationRequest) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let response = self.post_chat(&request, false).await?;
+            let body: ChatResponse = response.json().await?;
+
+            Ok(body
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content)
+                .unwrap_or_default())
+        })
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let url = format!("{}/v1/models", self.base_url);
+
+            self.authorize(self.client.get(&url))
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|_| {
+                    GameError::AIConnectionError(format!(
+                        "Cannot connect to OpenAI-compatible server at {}. Is it running?",
+                        self.base_url
+                    ))
+                })?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_parse_stream_chunk_token() {
+        let event =
+            parse_stream_chunk(r#"{"choices":[{"delta":{"content":"War"},"finish_reason":null}]}"#);
+        assert!(matches!(event, SseEvent::Token(t) if t == "War"));
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_done() {
+        assert!(matches!(parse_stream_chunk("[DONE]"), SseEvent::Done));
+        let event = parse_stream_chunk(r#"{"choices":[{"delta":{},"finish_reason":"stop"}]}"#);
+        assert!(matches!(event, SseEvent::Done));
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_role_only_is_skipped() {
+        let event = parse_stream_chunk(
+            r#"{"choices":[{"delta":{"role":"assistant"},"finish_reason":null}]}"#,
+        );
+        assert!(matches!(event, SseEvent::Skip));
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_error() {
+        let event = parse_stream_chunk(r#"{"error":{"message":"model not found"}}"#);
+        assert!(matches!(event, SseEvent::Error(e) if e == "model not found"));
+    }
+
+    #[test]
+    fn test_request_truncates_stop_sequences() {
+        let mut request =
+            GenerationRequest::from_config("Hi".to_string(), &Config::default().llama);
+        request.stop = (0..6).map(|i| format!("STOP{}", i)).collect();
+
+        let json = serde_json::to_value(ChatRequest::new("mistral", &request, true)).unwrap();
+        assert_eq!(json["model"], "mistral");
+        assert_eq!(json["messages"][0]["role"], "user");
+        assert_eq!(json["messages"][0]["content"], "Hi");
+        assert_eq!(json["stop"].as_array().unwrap().len(), MAX_STOP_SEQUENCES);
+    }
+
+    #[test]
+    fn test_base_url_normalization() {
+        let backend = OpenAiBackend::new("http://localhost:11434/v1/", "llama3", None);
+        assert_eq!(backend.base_url, "http://localhost:11434");
+        assert_eq!(backend.model(), "llama3");
+    }
+}
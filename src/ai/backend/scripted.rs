@@ -0,0 +1,134 @@
+//! Scripted in-process backend for deterministic tests.
+//!
+//! Replays a queue of canned responses in order and records every prompt it
+//! receives, so tests can drive the game loop without an inference server.
+
+use super::{GenerationRequest, NarrativeBackend, TokenStream};
+use crate::error::GameError;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+/// Backend that returns pre-scripted responses
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct ScriptedBackend {
+    responses: Mutex<VecDeque<String>>,
+    prompts: Mutex<Vec<String>>,
+}
+
+#[allow(dead_code)]
+impl ScriptedBackend {
+    /// Create a backend that will return `responses` in order
+    pub fn new<I, S>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedBackend {
+            responses: Mutex::new(responses.into_iter().map(Into::into).collect()),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue another response
+    pub fn push_response(&self, response: impl Into<String>) {
+        self.responses.lock().unwrap().push_back(response.into());
+    }
+
+    /// Prompts received so far, oldest first
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.lock().unwrap().clone()
+    }
+
+    /// Number of responses not yet consumed
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+
+    fn next_response(&self, prompt: String) -> Result<String> {
+        self.prompts.lock().unwrap().push(prompt);
+        self.responses.lock().unwrap().pop_front().ok_or_else(|| {
+            GameError::AIConnectionError("Scripted backend has no responses left".to_string())
+                .into()
+        })
+    }
+}
+
+impl NarrativeBackend for ScriptedBackend {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    fn stream(&self, request: GenerationRequest) -> BoxFuture<'_, Result<TokenStream>> {
+        Box::pin(async move {
+            let response = self.next_response(request.prompt)?;
+            let tokens: Vec<&str> = response.split_inclusive(' ').collect();
+
+            // The whole response is buffered up front and the sender dropped, so
+            // the receiver sees every token and then a closed channel.
+            let (tx, rx) = mpsc::channel(tokens.len().max(1));
+            for token in tokens {
+                let _ = tx.try_send(Ok(token.to_string()));
+            }
+
+            Ok(rx)
+        })
+    }
+
+    fn complete(&self, request: GenerationRequest) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move { self.next_response(request.prompt) })
+    }
+
+    fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn request(prompt: &str) -> GenerationRequest {
+        GenerationRequest::from_config(prompt.to_string(), &Config::default().llama)
+    }
+
+    #[tokio::test]
+    async fn test_stream_replays_response_in_order() {
+        let backend = ScriptedBackend::new(["The door creaks open.", "Nothing happens."]);
+
+        let mut rx = backend.stream(request("first")).await.unwrap();
+        let mut text = String::new();
+        while let Some(token) = rx.recv().await {
+            text.push_str(&token.unwrap());
+        }
+        assert_eq!(text, "The door creaks open.");
+
+        let second = backend.complete(request("second")).await.unwrap();
+        assert_eq!(second, "Nothing happens.");
+        assert_eq!(backend.prompts(), vec!["first", "second"]);
+        assert_eq!(backend.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_script_errors() {
+        let backend = ScriptedBackend::default();
+        assert!(backend.stream(request("prompt")).await.is_err());
+
+        backend.push_response("Late reply");
+        assert_eq!(
+            backend.complete(request("again")).await.unwrap(),
+            "Late reply"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_and_token_count() {
+        let backend = ScriptedBackend::default();
+        assert!(backend.health_check().await.is_ok());
+        assert!(backend.count_tokens("Hello wasteland").await.unwrap() > 0);
+    }
+}
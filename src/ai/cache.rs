@@ -52,6 +52,7 @@ impl TokenCache {
     }
 
     /// Get cached token count or compute and cache it
+    #[allow(dead_code)]
     pub async fn get_or_compute<F>(&self, text: &str, compute: F) -> usize
     where
         F: FnOnce(&str) -> usize,
@@ -67,6 +68,16 @@ impl TokenCache {
         count
     }
 
+    /// Cached token count for `text`, if any
+    pub async fn get(&self, text: &str) -> Option<usize> {
+        self.cache.get(text).await
+    }
+
+    /// Cache a token count, for counts that need an async call
+    pub async fn insert(&self, text: &str, count: usize) {
+        self.cache.insert(text.to_string(), count).await;
+    }
+
     /// Get number of entries in cache (for testing/debugging)
     #[cfg(test)]
     pub fn entry_count(&self) -> u64 {
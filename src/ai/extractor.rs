@@ -1,30 +1,14 @@
+use super::backend::{self, GenerationRequest, LlamaCppBackend, NarrativeBackend};
+use crate::config::LlamaConfig;
+use crate::game::story_memory::SummaryJob;
 use crate::game::worldbook::{Location, WorldEvent, Worldbook, NPC};
 use crate::templates;
 use anyhow::{anyhow, Result};
-use reqwest;
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
+use std::sync::Arc;
 use std::time::Duration;
 
-#[derive(Debug, Serialize)]
-struct ExtractionRequest {
-    prompt: String,
-    temperature: f32,
-    top_p: f32,
-    top_k: i32,
-    n_predict: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    grammar: Option<String>,
-    stop: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ExtractionResponse {
-    content: String,
-    #[serde(default)]
-    error: Option<String>,
-}
-
 /// Extracted entities from AI narrative
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ExtractedEntities {
@@ -38,6 +22,9 @@ pub struct ExtractedLocation {
     pub name: String,
     pub description: String,
     pub location_type: String,
+    /// Whether the narrative mentions a workbench here
+    #[serde(default)]
+    pub workbench: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,64 +45,72 @@ pub struct ExtractedEvent {
 
 #[derive(Clone)]
 pub struct ExtractionAI {
-    server_url: String,
-    client: reqwest::Client,
+    backend: Arc<dyn NarrativeBackend>,
 }
 
 impl ExtractionAI {
+    /// Create an extractor talking to a llama.cpp server at `server_url`
+    #[allow(dead_code)]
     pub fn new(server_url: String) -> Self {
-        ExtractionAI {
-            server_url,
-            client: reqwest::Client::new(),
-        }
+        Self::with_backend(Arc::new(LlamaCppBackend::new(server_url)))
+    }
+
+    /// Create an extractor using the extraction backend settings from config
+    pub fn from_config(config: &LlamaConfig) -> Self {
+        Self::with_backend(backend::extraction_backend(config))
+    }
+
+    /// Create an extractor that talks to an explicit backend
+    pub fn with_backend(backend: Arc<dyn NarrativeBackend>) -> Self {
+        ExtractionAI { backend }
     }
 
     /// Extract entities from narrative text using a smaller AI model
     pub async fn extract_entities(&self, narrative: &str) -> Result<ExtractedEntities> {
-        let prompt = self.build_extraction_prompt(narrative);
-
-        let request = ExtractionRequest {
-            prompt,
+        let request = GenerationRequest {
+            prompt: self.build_extraction_prompt(narrative),
             temperature: 0.1, // Low temperature for consistent extraction
             top_p: 0.9,
             top_k: 40,
-            n_predict: 1024,
-            grammar: None,
+            max_tokens: 1024,
+            repeat_penalty: 1.0,
             stop: vec!["</extraction>".to_string()],
+            grammar: None,
+            timeout: Duration::from_secs(60),
         };
 
-        let url = format!("{}/completion", self.server_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .timeout(Duration::from_secs(60))
-            .send()
+        let content = self
+            .backend
+            .complete(request)
             .await
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to connect to extraction AI at {}: {}",
-                    self.server_url,
-                    e
-                )
-            })?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Extraction AI returned error: {}",
-                response.status()
-            ));
-        }
+            .map_err(|e| anyhow!("Extraction AI request failed: {}", e))?;
 
-        let extraction_response: ExtractionResponse = response.json().await?;
+        // Parse the JSON response
+        self.parse_extraction(&content)
+    }
 
-        if let Some(error) = extraction_response.error {
-            return Err(anyhow!("Extraction AI error: {}", error));
-        }
+    /// Fold a batch of old conversation turns into their chapter's summary.
+    /// Returns the raw "TITLE: ... SUMMARY: ..." response.
+    pub async fn summarize(&self, job: &SummaryJob) -> Result<String> {
+        let turns: Vec<String> = job.turns.iter().map(|turn| turn.format()).collect();
+        let prompt = templates::render_summary_prompt(job.chapter, &job.previous, &turns)
+            .map_err(|e| anyhow!("{}", e))?;
+        let request = GenerationRequest {
+            prompt,
+            temperature: 0.3,
+            top_p: 0.9,
+            top_k: 40,
+            max_tokens: 400,
+            repeat_penalty: 1.1,
+            stop: Vec::new(),
+            grammar: None,
+            timeout: Duration::from_secs(60),
+        };
 
-        // Parse the JSON response
-        self.parse_extraction(&extraction_response.content)
+        self.backend
+            .complete(request)
+            .await
+            .map_err(|e| anyhow!("Summary request failed: {}", e))
     }
 
     /// Build extraction prompt with examples using template
@@ -138,7 +133,7 @@ impl ExtractionAI {
 Output ONLY valid JSON in this exact format (no other text):
 {{
   "locations": [
-    {{"name": "Location Name", "description": "Brief description", "location_type": "settlement|ruin|vault|wasteland"}}
+    {{"name": "Location Name", "description": "Brief description", "location_type": "settlement|ruin|vault|wasteland", "workbench": false}}
   ],
   "npcs": [
     {{"name": "NPC Name", "role": "merchant|guard|settler|raider|other", "personality": ["trait1", "trait2"], "location": "Location Name or null"}}
@@ -154,6 +149,7 @@ Rules:
 - Personality traits should be single descriptive words
 - If no entities of a type, use empty array []
 - Location type must be one of: settlement, ruin, vault, wasteland
+- Set "workbench" to true only if the narrative says there is a workbench at the location
 - Event type must be one of: npc_met, combat, discovery, dialogue
 
 Example 1:
@@ -214,28 +210,18 @@ Output JSON:"#,
 
     /// Test connection to extraction AI server
     pub async fn test_connection(&self) -> Result<()> {
-        let url = format!("{}/health", self.server_url);
-
-        self.client
-            .get(&url)
-            .timeout(Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|_| {
-                anyhow!(
-                    "Cannot connect to extraction AI at {}. Is it running?",
-                    self.server_url
-                )
-            })?;
-
-        Ok(())
+        self.backend.health_check().await
     }
 }
 
 /// Convert extracted entities to worldbook entries
 impl ExtractedEntities {
-    /// Convert to worldbook entries (returns locations, npcs, events)
-    pub fn to_worldbook_entries(&self) -> (Vec<Location>, Vec<NPC>, Vec<WorldEvent>) {
+    /// Convert to worldbook entries (returns locations, npcs, events).
+    /// Events are stamped with `timestamp`, the current game time.
+    pub fn to_worldbook_entries(
+        &self,
+        timestamp: &str,
+    ) -> (Vec<Location>, Vec<NPC>, Vec<WorldEvent>) {
         let mut locations = Vec::new();
         let mut npcs = Vec::new();
         let mut events = Vec::new();
@@ -243,7 +229,7 @@ impl ExtractedEntities {
         // Convert locations
         for loc in &self.locations {
             let id = Worldbook::generate_id(&loc.name);
-            locations.push(Location {
+            let mut location = Location {
                 id: id.clone(),
                 name: loc.name.clone().into(),
                 name_lowercase: loc.name.to_lowercase().into(),
@@ -256,7 +242,11 @@ impl ExtractedEntities {
                 visit_count: 0,
                 notes: Vec::new(),
                 state: std::collections::HashMap::new(),
-            });
+            };
+            if loc.workbench {
+                location.add_workbench();
+            }
+            locations.push(location);
         }
 
         // Convert NPCs
@@ -279,7 +269,7 @@ impl ExtractedEntities {
         // Convert events
         for event in &self.events {
             events.push(WorldEvent {
-                timestamp: chrono::Utc::now().to_rfc3339().into(),
+                timestamp: timestamp.into(),
                 location: event.location.as_ref().map(|l| Worldbook::generate_id(l)),
                 event_type: event.event_type.clone().into(),
                 description: event.description.clone().into(),
@@ -700,6 +690,7 @@ mod tests {
                 name: "Test".to_string(),
                 description: "Test".to_string(),
                 location_type: "wasteland".to_string(),
+                workbench: false,
             }],
             npcs: vec![],
             events: vec![],
@@ -774,7 +765,7 @@ mod tests {
     #[test]
     fn test_to_worldbook_entries_empty() {
         let entities = ExtractedEntities::default();
-        let (locations, npcs, events) = entities.to_worldbook_entries();
+        let (locations, npcs, events) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
 
         assert!(locations.is_empty());
         assert!(npcs.is_empty());
@@ -788,12 +779,13 @@ mod tests {
                 name: "Megaton".to_string(),
                 description: "Settlement built around bomb".to_string(),
                 location_type: "settlement".to_string(),
+                workbench: false,
             }],
             npcs: vec![],
             events: vec![],
         };
 
-        let (locations, npcs, events) = entities.to_worldbook_entries();
+        let (locations, npcs, events) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
 
         assert_eq!(locations.len(), 1);
         assert_eq!(locations[0].name, "Megaton");
@@ -804,6 +796,24 @@ mod tests {
         assert!(events.is_empty());
     }
 
+    #[test]
+    fn test_extracted_workbench_marks_the_location() {
+        let json = r#"{
+            "locations": [
+                {"name": "Megaton", "description": "Settlement", "location_type": "settlement", "workbench": true},
+                {"name": "Super Duper Mart", "description": "Ruined store", "location_type": "ruin"}
+            ],
+            "npcs": [],
+            "events": []
+        }"#;
+        let extractor = ExtractionAI::new("http://localhost:8081".to_string());
+        let entities = extractor.parse_extraction(json).unwrap();
+
+        let (locations, _, _) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
+        assert!(locations[0].has_workbench());
+        assert!(!locations[1].has_workbench());
+    }
+
     #[test]
     fn test_to_worldbook_entries_with_npc() {
         let entities = ExtractedEntities {
@@ -817,7 +827,7 @@ mod tests {
             events: vec![],
         };
 
-        let (locations, npcs, events) = entities.to_worldbook_entries();
+        let (locations, npcs, events) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
 
         assert!(locations.is_empty());
         assert_eq!(npcs.len(), 1);
@@ -842,13 +852,14 @@ mod tests {
             }],
         };
 
-        let (locations, npcs, events) = entities.to_worldbook_entries();
+        let (locations, npcs, events) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
 
         assert!(locations.is_empty());
         assert!(npcs.is_empty());
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].event_type, "npc_met");
         assert_eq!(events[0].description, "Met Marcus at the gate");
+        assert_eq!(events[0].timestamp, "2277-10-23T08:00:00Z");
     }
 
     #[test]
@@ -858,6 +869,7 @@ mod tests {
                 name: "Megaton".to_string(),
                 description: "Settlement".to_string(),
                 location_type: "settlement".to_string(),
+                workbench: false,
             }],
             npcs: vec![ExtractedNPC {
                 name: "Marcus".to_string(),
@@ -873,7 +885,7 @@ mod tests {
             }],
         };
 
-        let (locations, npcs, events) = entities.to_worldbook_entries();
+        let (locations, npcs, events) = entities.to_worldbook_entries("2277-10-23T08:00:00Z");
 
         assert_eq!(locations.len(), 1);
         assert_eq!(npcs.len(), 1);
@@ -897,6 +909,7 @@ mod tests {
                 name: "Test".to_string(),
                 description: "Test".to_string(),
                 location_type: "settlement".to_string(),
+                workbench: false,
             }],
             npcs: vec![],
             events: vec![],
@@ -912,11 +925,13 @@ mod tests {
                     name: "Loc1".to_string(),
                     description: "Test".to_string(),
                     location_type: "settlement".to_string(),
+                    workbench: false,
                 },
                 ExtractedLocation {
                     name: "Loc2".to_string(),
                     description: "Test".to_string(),
                     location_type: "ruin".to_string(),
+                    workbench: false,
                 },
             ],
             npcs: vec![],
@@ -962,6 +977,7 @@ mod tests {
                 name: "Loc".to_string(),
                 description: "Test".to_string(),
                 location_type: "settlement".to_string(),
+                workbench: false,
             }],
             npcs: vec![ExtractedNPC {
                 name: "NPC".to_string(),
@@ -990,11 +1006,13 @@ mod tests {
                     name: "Loc1".to_string(),
                     description: "Test".to_string(),
                     location_type: "settlement".to_string(),
+                    workbench: false,
                 },
                 ExtractedLocation {
                     name: "Loc2".to_string(),
                     description: "Test".to_string(),
                     location_type: "ruin".to_string(),
+                    workbench: false,
                 },
             ],
             npcs: vec![
@@ -1,24 +1,29 @@
 //! # AI Module
 //!
-//! AI Dungeon Master integration using llama.cpp for dynamic storytelling.
+//! AI Dungeon Master integration using a local LLM for dynamic storytelling.
 //!
 //! ## Overview
 //!
 //! This module provides the AI dungeon master functionality that powers the
-//! narrative experience. It communicates with a local llama.cpp server to
-//! generate contextual responses based on game state, player actions, and
-//! world knowledge.
+//! narrative experience. It communicates with an inference server (llama.cpp
+//! by default) to generate contextual responses based on game state, player
+//! actions, and world knowledge.
 //!
 //! ## Key Components
 //!
 //! - [`AIDungeonMaster`]: Main AI client that generates DM responses
+//! - [`backend`]: Pluggable inference backends (llama.cpp, OpenAI-compatible, scripted)
 //! - [`extractor`]: Extracts structured game commands from AI responses
+//! - [`tokenizer`]: Token counts from the model's own tokenizer, with a
+//!   cl100k estimate as fallback
+//! - [`prompt`]: Assembles the prompt within the context window, trimming
+//!   low-priority sections
 //!
 //! ## Architecture
 //!
 //! The AI system:
 //! 1. Builds a comprehensive prompt with game context
-//! 2. Sends HTTP request to llama.cpp server
+//! 2. Sends it to the configured [`backend::NarrativeBackend`]
 //! 3. Receives natural language response
 //! 4. Extracts game commands (combat, items, etc.) from response
 //!
@@ -27,10 +32,19 @@
 //! The AI prompt includes:
 //! - Character stats (SPECIAL, skills, HP, inventory)
 //! - Combat state (active enemies, round number)
+//! - Story so far (chapter summaries of older turns, within
+//!   `summary_token_budget` tokens)
 //! - Story context (last 10 conversation messages)
-//! - Worldbook knowledge (known locations, NPCs, events)
+//! - Worldbook knowledge: the current location in full, plus NPCs, places and
+//!   events the player's action or recent turns mention (ranked with BM25,
+//!   within `worldbook_token_budget` tokens)
 //! - Current location and quest log
 //!
+//! The prompt plus `max_tokens` for the response must fit in `context_window`.
+//! When it doesn't, the oldest conversation turns are dropped first, then the
+//! least relevant worldbook entries, then the oldest chapter summaries (see
+//! [`prompt`]). The `debug` command shows what was trimmed from the last prompt.
+//!
 //! ## Example
 //!
 //! ```no_run
@@ -49,245 +63,139 @@
 //! }
 //! ```
 
+pub mod backend;
 pub mod cache;
 pub mod extractor;
+pub mod prompt;
 pub mod server_manager;
+pub mod tokenizer;
 
-use crate::config::LlamaConfig;
-use crate::error::GameError;
+use crate::config::{ChatTemplate, LlamaConfig};
+use crate::game::conversation::{Speaker, PROMPT_TURNS};
+use crate::game::worldbook::Worldbook;
 use crate::game::GameState;
 use crate::templates::{
-    self, CharacterContext, CombatContext, EnemyContext, SkillsContext, SpecialStats,
+    self, CharacterContext, ChatFormat, ChatMessage, ChatRole, CombatContext, EnemyContext,
+    SkillsContext, SpecialStats,
 };
 use anyhow::Result;
-use cache::{TokenCache, WorldbookCache};
-use serde::{Deserialize, Serialize};
-use std::sync::OnceLock;
-use std::time::Duration;
-use tiktoken_rs::CoreBPE;
-use tokio::sync::mpsc;
-
-#[derive(Debug, Serialize)]
-struct LlamaRequest {
-    prompt: String,
-    temperature: f32,
-    top_p: f32,
-    top_k: i32,
-    n_predict: i32,
-    repeat_penalty: f32,
-    stop: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    stream: Option<bool>,
-}
-
-/// Streaming response chunk from llama.cpp
-#[derive(Debug, Deserialize)]
-struct LlamaStreamChunk {
-    content: String,
-    #[serde(default)]
-    stop: bool,
-}
-
-/// Error response from llama.cpp (used when stream chunk parsing fails)
-#[derive(Debug, Deserialize)]
-struct LlamaErrorResponse {
-    #[serde(default)]
-    error: Option<String>,
-}
+use backend::{GenerationRequest, NarrativeBackend, TokenStream};
+use cache::WorldbookCache;
+use prompt::{PromptAssembler, PromptReport, PromptSection, RenderedSection, TrimFrom};
+use std::sync::{Arc, Mutex};
+use tokenizer::Tokenizer;
+
+/// Recent turns searched, with the player's action, for worldbook entries to include
+const RETRIEVAL_TURNS: usize = 4;
+/// Trim priorities of prompt sections: old conversation goes first, then
+/// worldbook entries from elsewhere, then chapter summaries
+const HISTORY_PRIORITY: u8 = 1;
+const WORLDBOOK_PRIORITY: u8 = 2;
+const STORY_PRIORITY: u8 = 3;
+/// Prompt sections a chat template lays out as turns
+const HISTORY_SECTION: &str = "conversation history";
+const ACTION_SECTION: &str = "player action";
 
 #[derive(Clone)]
 pub struct AIDungeonMaster {
     config: LlamaConfig,
-    client: reqwest::Client,
-    token_cache: TokenCache,
+    backend: Arc<dyn NarrativeBackend>,
+    chat_format: ChatFormat,
+    tokenizer: Tokenizer,
     worldbook_cache: WorldbookCache,
+    last_prompt_report: Arc<Mutex<Option<PromptReport>>>,
 }
 
 impl AIDungeonMaster {
     pub fn new(config: LlamaConfig) -> Self {
+        let backend = backend::narrative_backend(&config);
+        Self::with_backend(config, backend)
+    }
+
+    /// Create a DM that talks to an explicit backend (e.g. [`ScriptedBackend`] in tests)
+    ///
+    /// [`ScriptedBackend`]: backend::ScriptedBackend
+    pub fn with_backend(config: LlamaConfig, backend: Arc<dyn NarrativeBackend>) -> Self {
+        // Config::validate already checked the template; this only fails if
+        // the file changed since
+        let chat_format =
+            ChatFormat::load(config.chat_template, config.chat_template_file.as_deref())
+                .unwrap_or_else(|e| {
+                    tracing::error!("{}; sending plain prompts instead", e);
+                    ChatFormat::default()
+                });
         AIDungeonMaster {
+            chat_format,
             config,
-            client: reqwest::Client::new(),
-            token_cache: TokenCache::new(),
+            tokenizer: Tokenizer::new(backend.clone()),
+            backend,
             worldbook_cache: WorldbookCache::new(),
+            last_prompt_report: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// The inference backend used for narrative generation
+    pub fn backend(&self) -> &Arc<dyn NarrativeBackend> {
+        &self.backend
+    }
+
     /// Generate a streaming response from the AI DM
     /// Returns a channel receiver that yields tokens as they are generated
     pub async fn generate_response_stream(
         &self,
         game_state: &GameState,
         player_action: &str,
-    ) -> Result<mpsc::Receiver<Result<String, String>>> {
-        let prompt = self.build_prompt(game_state, player_action).await;
-
-        let request = LlamaRequest {
-            prompt,
-            temperature: self.config.temperature,
-            top_p: self.config.top_p,
-            top_k: self.config.top_k,
-            n_predict: self.config.max_tokens,
-            repeat_penalty: self.config.repeat_penalty,
-            stop: vec![
-                ">>> PLAYER:".to_string(),
-                "\n>>> PLAYER:".to_string(),
-                "Player:".to_string(),
-                "\nPlayer:".to_string(),
-            ],
-            stream: Some(true), // Enable streaming for real-time token display
+    ) -> Result<TokenStream> {
+        let template = self.chat_format.template();
+        let prompt = self.build_prompt(game_state, player_action, template).await;
+
+        let mut request = GenerationRequest::from_config(prompt, &self.config);
+        request.stop = match template {
+            ChatTemplate::Custom => self.config.chat_template_stop.clone(),
+            template => template
+                .stop_sequences()
+                .iter()
+                .map(|stop| stop.to_string())
+                .collect(),
         };
 
-        let url = format!("{}/completion", self.config.server_url);
-
-        tracing::debug!(
-            "Sending streaming request to: {}, prompt length: {} chars",
-            url,
-            request.prompt.len()
-        );
-
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .timeout(Duration::from_secs(600)) // 10 minutes for slow generation
-            .send()
-            .await
-            .map_err(|e| {
-                GameError::AIConnectionError(format!(
-                    "Failed to connect to llama.cpp server: {}. Make sure it's running at {}",
-                    e, self.config.server_url
-                ))
-            })?;
-
-        tracing::debug!("Got response with status: {}", response.status());
-
-        if !response.status().is_success() {
-            return Err(GameError::AIConnectionError(format!(
-                "llama.cpp server returned error: {}",
-                response.status()
-            ))
-            .into());
-        }
-
-        // Create a channel to send tokens
-        let (tx, rx) = mpsc::channel::<Result<String, String>>(100);
-
-        // Process streaming SSE response in background task
-        tokio::spawn(async move {
-            tracing::debug!("Starting to process streaming response");
-
-            // Get the response body as bytes stream
-            let mut stream = response.bytes_stream();
-            use futures_util::StreamExt;
-
-            let mut buffer = String::new();
-
-            while let Some(chunk_result) = stream.next().await {
-                match chunk_result {
-                    Ok(bytes) => {
-                        // Append new bytes to buffer
-                        if let Ok(text) = std::str::from_utf8(&bytes) {
-                            buffer.push_str(text);
-
-                            // Process complete SSE events (data: {...}\n\n)
-                            while let Some(event_end) = buffer.find("\n\n") {
-                                let event = buffer[..event_end].to_string();
-                                buffer = buffer[event_end + 2..].to_string();
-
-                                // Parse SSE data line
-                                for line in event.lines() {
-                                    if let Some(data) = line.strip_prefix("data: ") {
-                                        // Parse the JSON chunk
-                                        match serde_json::from_str::<LlamaStreamChunk>(data) {
-                                            Ok(chunk) => {
-                                                if !chunk.content.is_empty()
-                                                    && tx.send(Ok(chunk.content)).await.is_err()
-                                                {
-                                                    tracing::debug!(
-                                                        "Receiver dropped, stopping stream"
-                                                    );
-                                                    return;
-                                                }
-                                                if chunk.stop {
-                                                    tracing::debug!("Stream completed (stop=true)");
-                                                    return;
-                                                }
-                                            }
-                                            Err(e) => {
-                                                // Try parsing as error response
-                                                if let Ok(err_response) =
-                                                    serde_json::from_str::<LlamaErrorResponse>(data)
-                                                {
-                                                    if let Some(error) = err_response.error {
-                                                        let _ = tx.send(Err(error)).await;
-                                                        return;
-                                                    }
-                                                }
-                                                tracing::warn!(
-                                                    "Failed to parse stream chunk: {} - data: {}",
-                                                    e,
-                                                    data
-                                                );
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Stream error: {}", e);
-                        let _ = tx.send(Err(format!("Stream error: {}", e))).await;
-                        return;
-                    }
-                }
-            }
-
-            tracing::debug!("Stream ended naturally");
-        });
-
-        Ok(rx)
+        self.backend.stream(request).await
     }
 
-    /// Accurate token counting using tiktoken-rs with caching
-    ///
-    /// Uses the cl100k_base tokenizer (used by GPT-4 and similar models).
-    /// The tokenizer is cached globally to avoid re-initialization overhead.
-    /// Token counts are cached using TokenCache for 10-50x speedup on repeated text.
-    async fn estimate_tokens(&self, text: &str) -> usize {
-        static TOKENIZER: OnceLock<CoreBPE> = OnceLock::new();
-
-        self.token_cache
-            .get_or_compute(text, |text| {
-                let bpe = TOKENIZER.get_or_init(|| {
-                    tiktoken_rs::cl100k_base()
-                        .expect("Failed to initialize tokenizer - this should never fail")
-                });
-                bpe.encode_with_special_tokens(text).len()
-            })
-            .await
+    /// Token counting for the narrative model (the server's tokenizer when it
+    /// has one, cached, with a cl100k fallback)
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
     }
 
     /// Build the prompt with game context (with caching)
     ///
     /// Uses WorldbookCache to cache expensive worldbook context building (~20x speedup).
-    async fn build_prompt(&self, game_state: &GameState, player_action: &str) -> String {
-        let mut prompt = String::with_capacity(4096);
+    /// Sections are trimmed to leave `max_tokens` of the context window for the
+    /// response; see [`prompt`]. With a chat template, the game context goes in
+    /// the system message and the conversation is sent as user/assistant turns.
+    async fn build_prompt(
+        &self,
+        game_state: &GameState,
+        player_action: &str,
+        template: ChatTemplate,
+    ) -> String {
+        let chat = template != ChatTemplate::Plain;
+        let mut assembler = PromptAssembler::new(self.prompt_budget());
 
         // System prompt from template
-        match templates::render_system_prompt() {
-            Ok(system_prompt) => {
-                prompt.push_str(&system_prompt);
-                prompt.push_str("\n\n");
-            }
+        let system_prompt = match templates::render_system_prompt() {
+            Ok(system_prompt) => system_prompt,
             Err(e) => {
                 // Fallback to config system prompt if template fails
                 tracing::error!("Failed to render system prompt template: {}", e);
-                prompt.push_str(&self.config.system_prompt);
-                prompt.push_str("\n\n");
+                self.config.system_prompt.clone()
             }
-        }
+        };
+        assembler.push(PromptSection::fixed(
+            "system prompt",
+            format!("{}\n\n", system_prompt),
+        ));
 
         // Build context from templates
         let character_ctx = Self::build_character_context(&game_state.character);
@@ -304,36 +212,82 @@ impl AIDungeonMaster {
             None
         };
 
-        // Get conversation history
-        let conversation_history = if !game_state.conversation.is_empty() {
-            Self::get_conversation_messages(&game_state.conversation)
-        } else if !game_state.story.is_empty() {
-            // Fallback for old save files
-            game_state.story.get_all().iter().cloned().collect()
-        } else {
-            Vec::new()
-        };
-
-        // Render context template
-        match templates::render_context(
+        let character_section = match templates::render_context(
             Some(&character_ctx),
             Some(&inventory_items),
             combat_ctx.as_ref(),
-            Some(&conversation_history),
+            None,
+            None,
         ) {
-            Ok(context) => prompt.push_str(&context),
+            Ok(context) => context,
             Err(e) => {
                 tracing::error!("Failed to render context template: {}", e);
                 // Fallback to old methods if template fails
-                prompt.push_str(&Self::build_character_section(&game_state.character));
-                prompt.push_str(&Self::build_inventory_section(
+                let mut section = Self::build_character_section(&game_state.character);
+                section.push_str(&Self::build_inventory_section(
                     &game_state.character.inventory,
                 ));
+                section
             }
-        }
+        };
+        assembler.push(PromptSection::fixed("character", character_section));
+
+        // Summaries of turns too old to send word for word
+        let summary_budget = self.config.summary_token_budget.max(0) as usize;
+        let story_so_far = game_state
+            .story_memory
+            .story_so_far(summary_budget, backend::cl100k_token_count);
+        assembler.push(
+            PromptSection::trimmable(
+                "story so far",
+                STORY_PRIORITY,
+                TrimFrom::Front,
+                story_so_far,
+                |chapters| templates::render_story_so_far(chapters).unwrap_or_default(),
+            )
+            .with_budget(summary_budget),
+        );
+
+        // Use new ConversationManager if available, fallback to old StoryManager
+        let mut roles = Vec::new();
+        let conversation_history = if chat && !game_state.conversation.is_empty() {
+            let (turn_roles, messages) = Self::get_chat_messages(&game_state.conversation);
+            roles = turn_roles;
+            messages
+        } else if !game_state.conversation.is_empty() {
+            Self::get_conversation_messages(&game_state.conversation)
+        } else if !game_state.story.is_empty() {
+            // Fallback for old save files
+            game_state.story.get_all().iter().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        assembler.push(PromptSection::trimmable(
+            HISTORY_SECTION,
+            HISTORY_PRIORITY,
+            TrimFrom::Front,
+            conversation_history,
+            |messages| templates::render_conversation_history(messages).unwrap_or_default(),
+        ));
 
         // Location and worldbook (with caching)
-        prompt.push_str(&format!("Location: {}\n\n", game_state.location));
+        let mut location = format!(
+            "Location: {}\nTime: Day {}, {} ({})\n\n",
+            game_state.location,
+            game_state.day,
+            game_state.time,
+            game_state.time.period().name()
+        );
+
+        // Active quests, so the DM can follow up on them
+        let quests: Vec<String> = game_state
+            .quests
+            .active()
+            .map(|quest| format!("- {}", quest.prompt_line()))
+            .collect();
+        if !quests.is_empty() {
+            location.push_str(&format!("Active quests:\n{}\n\n", quests.join("\n")));
+        }
 
         // Use WorldbookCache to cache expensive worldbook.build_context() calls
         let worldbook_hash = cache::hash_worldbook_state(&game_state.worldbook);
@@ -343,28 +297,123 @@ impl AIDungeonMaster {
             .await;
 
         if !worldbook_context.is_empty() {
-            prompt.push_str(&worldbook_context);
-            prompt.push('\n');
+            location.push_str(&worldbook_context);
+            location.push('\n');
         }
+        assembler.push(PromptSection::fixed("location", location));
+
+        // NPCs, places and events from elsewhere that the player brings up
+        let worldbook_budget = self.config.worldbook_token_budget.max(0) as usize;
+        let relevant_entries = game_state.worldbook.relevant_entries(
+            &Self::retrieval_query(game_state, player_action),
+            worldbook_budget,
+            backend::cl100k_token_count,
+        );
+        assembler.push(
+            PromptSection::trimmable(
+                "relevant worldbook",
+                WORLDBOOK_PRIORITY,
+                TrimFrom::Back,
+                relevant_entries,
+                |entries| {
+                    let context = Worldbook::format_relevant_context(entries);
+                    if context.is_empty() {
+                        context
+                    } else {
+                        context + "\n"
+                    }
+                },
+            )
+            .with_budget(worldbook_budget),
+        );
 
         // Current player action
-        prompt.push_str(&format!(">>> PLAYER: {}\n\n>>> DM (YOU):", player_action));
+        assembler.push(PromptSection::fixed(
+            ACTION_SECTION,
+            if chat {
+                player_action.to_string()
+            } else {
+                format!(">>> PLAYER: {}\n\n>>> DM (YOU):", player_action)
+            },
+        ));
+        if chat {
+            assembler = assembler.with_layout(self.chat_layout(roles));
+        }
 
-        // Warn if prompt is using >75% of context window (leaving room for response)
-        let estimated_tokens = self.estimate_tokens(&prompt).await;
-        let warning_threshold = (self.config.context_window as f32 * 0.75) as usize;
-        if estimated_tokens > warning_threshold {
+        let (prompt, report) = assembler
+            .assemble(|text| async move { self.tokenizer.count(&text).await })
+            .await;
+        for section in report.trimmed() {
+            tracing::info!("Trimmed prompt section {}", section.summary());
+        }
+        if !report.fits() {
             tracing::warn!(
-                "Large prompt detected: {} tokens ({}% of {} token context window). Consider reducing worldbook or conversation history.",
-                estimated_tokens,
-                (estimated_tokens as f32 / self.config.context_window as f32 * 100.0) as usize,
-                self.config.context_window
+                "Prompt is {} tokens, over its budget of {} ({} token context window, {} reserved for the response)",
+                report.tokens,
+                report.budget,
+                self.config.context_window,
+                self.config.max_tokens
             );
         }
+        *self
+            .last_prompt_report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(report);
 
         prompt
     }
 
+    /// Lays the prompt sections out in a chat template: the conversation as
+    /// user/assistant turns (`roles` says whose each history item is), the
+    /// player's action as the last user turn, everything else as the system
+    /// message
+    fn chat_layout(
+        &self,
+        roles: Vec<ChatRole>,
+    ) -> impl Fn(&[RenderedSection]) -> String + Send + 'static {
+        let chat_format = self.chat_format.clone();
+        move |sections| {
+            let mut system = String::new();
+            let mut messages = Vec::new();
+            for section in sections {
+                match section.name {
+                    HISTORY_SECTION if !roles.is_empty() => {
+                        let kept = &roles[roles.len() - section.items.len()..];
+                        messages.extend(kept.iter().zip(section.items).map(|(role, content)| {
+                            ChatMessage {
+                                role: *role,
+                                content: content.clone(),
+                            }
+                        }));
+                    }
+                    ACTION_SECTION => messages.push(ChatMessage {
+                        role: ChatRole::User,
+                        content: section.text.to_string(),
+                    }),
+                    _ => system.push_str(section.text),
+                }
+            }
+            chat_format.render(&system, &messages).unwrap_or_else(|e| {
+                tracing::error!("{}", e);
+                system
+            })
+        }
+    }
+
+    /// Tokens the prompt may use: the context window less the response's `max_tokens`
+    fn prompt_budget(&self) -> usize {
+        (self.config.context_window - self.config.max_tokens).max(0) as usize
+    }
+
+    /// Token use of the last prompt sent to the DM, by section, and what was
+    /// trimmed to make it fit
+    pub fn last_prompt_report(&self) -> Option<PromptReport> {
+        self.last_prompt_report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     /// Build character context for templates
     fn build_character_context(character: &crate::game::character::Character) -> CharacterContext {
         CharacterContext {
@@ -391,6 +440,17 @@ impl AIDungeonMaster {
                 science: character.skills.science,
                 sneak: character.skills.sneak,
             },
+            status: {
+                let mut status = character.status.summary();
+                if character.is_encumbered() {
+                    status.push(format!(
+                        "Over-encumbered ({:.0}/{:.0} lbs, can't travel)",
+                        character.carry_weight(),
+                        character.carry_capacity()
+                    ));
+                }
+                status
+            },
         }
     }
 
@@ -410,12 +470,40 @@ impl AIDungeonMaster {
         }
     }
 
+    /// Text searched for relevant worldbook entries: the player's action and
+    /// the last few turns
+    fn retrieval_query(game_state: &GameState, player_action: &str) -> String {
+        let mut query = player_action.to_string();
+        for turn in game_state.conversation.get_recent_turns(RETRIEVAL_TURNS) {
+            query.push('\n');
+            query.push_str(&turn.message);
+        }
+        query
+    }
+
+    /// Recent conversation turns as chat messages, with whose each one is
+    fn get_chat_messages(
+        conversation: &crate::game::conversation::ConversationManager,
+    ) -> (Vec<ChatRole>, Vec<String>) {
+        conversation
+            .get_recent_turns(PROMPT_TURNS)
+            .iter()
+            .map(|turn| {
+                let role = match turn.speaker {
+                    Speaker::Player => ChatRole::User,
+                    Speaker::DM => ChatRole::Assistant,
+                };
+                (role, turn.message.clone())
+            })
+            .unzip()
+    }
+
     /// Get conversation messages for templates
     fn get_conversation_messages(
         conversation: &crate::game::conversation::ConversationManager,
     ) -> Vec<String> {
         conversation
-            .get_recent_turns(10)
+            .get_recent_turns(PROMPT_TURNS)
             .iter()
             .map(|turn| format!("{:?}: {}", turn.speaker, turn.message))
             .collect()
@@ -424,7 +512,7 @@ impl AIDungeonMaster {
     /// Build character stats section (legacy fallback)
     fn build_character_section(character: &crate::game::character::Character) -> String {
         // SPECIAL stats - IMPORTANT: These are the actual character stats, do not make up different ones!
-        format!(
+        let mut section = format!(
             "CHARACTER: {} (Level {})\n\
              HP: {}/{} | AP: {}/{} | Caps: {}\n\
              SPECIAL: S:{} P:{} E:{} C:{} I:{} A:{} L:{}\n\
@@ -448,7 +536,12 @@ impl AIDungeonMaster {
             character.skills.lockpick,
             character.skills.science,
             character.skills.sneak
-        )
+        );
+        let status = character.status.summary();
+        if !status.is_empty() {
+            section.push_str(&format!("Status: {}\n", status.join(", ")));
+        }
+        section
     }
 
     /// Build inventory section (legacy fallback)
@@ -462,22 +555,8 @@ impl AIDungeonMaster {
         format!("Inventory: {}\n", items.join(", "))
     }
 
-    /// Test connection to llama.cpp server
+    /// Test connection to the narrative server
     pub async fn test_connection(&self) -> Result<()> {
-        let url = format!("{}/health", self.config.server_url);
-
-        self.client
-            .get(&url)
-            .timeout(Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|_| {
-                GameError::AIConnectionError(format!(
-                    "Cannot connect to llama.cpp at {}. Is it running?",
-                    self.config.server_url
-                ))
-            })?;
-
-        Ok(())
+        self.backend.health_check().await
     }
 }
@@ -0,0 +1,512 @@
+//! # Prompt Assembly
+//!
+//! Fits the DM prompt into the model's context window.
+//!
+//! ## Sections
+//!
+//! The prompt is built from [`PromptSection`]s in order. Fixed sections (the
+//! system prompt, the character sheet, the player's action) are always sent
+//! whole. Trimmable sections are lists of items (conversation turns, chapter
+//! summaries, worldbook entries) that can lose items from one end, and may
+//! have a token budget of their own.
+//!
+//! ## Trimming
+//!
+//! Each section is first cut down to its own budget. Then, while the prompt
+//! is over the overall budget (the context window minus the tokens reserved
+//! for the response), items are dropped from the trimmable section with the
+//! lowest priority. Everything dropped is recorded in the [`PromptReport`],
+//! shown by the `debug` command.
+//!
+//! Counting tokens can mean a round-trip to the model server, so the prompt
+//! isn't recounted for every item. The total is tracked as the sum of the
+//! sections' counts plus the layout's overhead (counted once), how many
+//! items a section keeps is found by binary search, and the whole prompt is
+//! counted again only to check the result.
+//!
+//! ## Layout
+//!
+//! Sections are sent one after another, unless a layout is given (see
+//! [`PromptAssembler::with_layout`]) to arrange them into a chat template's
+//! system and user/assistant turns. The budget always applies to the prompt as
+//! laid out, template tokens included.
+
+use std::fmt;
+use std::future::Future;
+
+/// Why a section lost items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrimReason {
+    /// The section was over its own token budget
+    SectionBudget,
+    /// The prompt didn't fit in the context window
+    ContextWindow,
+}
+
+impl fmt::Display for TrimReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrimReason::SectionBudget => write!(f, "over its own budget"),
+            TrimReason::ContextWindow => write!(f, "prompt over the context window"),
+        }
+    }
+}
+
+/// Which items of a section go first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrimFrom {
+    /// Oldest first, for items in chronological order
+    Front,
+    /// Last first, for items ranked best first
+    Back,
+}
+
+/// Renders a trimmable section's remaining items
+type Render<'a> = Box<dyn Fn(&[String]) -> String + Send + 'a>;
+
+/// Joins the rendered sections into the prompt
+type Layout<'a> = Box<dyn Fn(&[RenderedSection]) -> String + Send + 'a>;
+
+/// A section as handed to a layout: its rendered text and remaining items
+pub struct RenderedSection<'s> {
+    pub name: &'static str,
+    pub text: &'s str,
+    pub items: &'s [String],
+}
+
+/// One part of the prompt
+pub struct PromptSection<'a> {
+    name: &'static str,
+    items: Vec<String>,
+    render: Render<'a>,
+    /// Priority and trim end, for sections that can be trimmed
+    trim: Option<(u8, TrimFrom)>,
+    budget: Option<usize>,
+}
+
+impl<'a> PromptSection<'a> {
+    /// A section that's always sent whole
+    pub fn fixed(name: &'static str, text: String) -> Self {
+        PromptSection {
+            name,
+            items: vec![text],
+            render: Box::new(|items| items.concat()),
+            trim: None,
+            budget: None,
+        }
+    }
+
+    /// A section that can lose items from `from`; lower `priority` sections
+    /// are trimmed first
+    pub fn trimmable(
+        name: &'static str,
+        priority: u8,
+        from: TrimFrom,
+        items: Vec<String>,
+        render: impl Fn(&[String]) -> String + Send + 'a,
+    ) -> Self {
+        PromptSection {
+            name,
+            items,
+            render: Box::new(render),
+            trim: Some((priority, from)),
+            budget: None,
+        }
+    }
+
+    /// Limit the section to `tokens`, whatever room is left in the prompt
+    pub fn with_budget(mut self, tokens: usize) -> Self {
+        self.budget = Some(tokens);
+        self
+    }
+
+    /// The items left if only `keep` of them are kept
+    fn kept(&self, keep: usize) -> &[String] {
+        match self.trim {
+            Some((_, TrimFrom::Front)) => &self.items[self.items.len() - keep..],
+            _ => &self.items[..keep],
+        }
+    }
+
+    /// Keep only `keep` items, returning how many were dropped
+    fn keep(&mut self, keep: usize) -> usize {
+        let dropped = self.items.len() - keep;
+        match self.trim {
+            Some((_, TrimFrom::Front)) => {
+                self.items.drain(..dropped);
+            }
+            _ => self.items.truncate(keep),
+        }
+        dropped
+    }
+}
+
+/// What happened to one section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionReport {
+    pub name: &'static str,
+    pub tokens: usize,
+    /// Items sent and items dropped (fixed sections count as one item)
+    pub kept: usize,
+    pub dropped: usize,
+    /// Why items were dropped (the last reason that applied)
+    pub reason: Option<TrimReason>,
+}
+
+impl SectionReport {
+    /// "history: 412 tokens, 4 of 10 items (6 dropped: prompt over the context window)"
+    pub fn summary(&self) -> String {
+        let mut line = format!("{}: {} tokens", self.name, self.tokens);
+        if self.dropped > 0 {
+            line.push_str(&format!(
+                ", {} of {} items ({} dropped",
+                self.kept,
+                self.kept + self.dropped,
+                self.dropped
+            ));
+            if let Some(reason) = self.reason {
+                line.push_str(&format!(": {}", reason));
+            }
+            line.push(')');
+        }
+        line
+    }
+}
+
+/// Token use of an assembled prompt, by section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptReport {
+    /// Tokens the prompt may use
+    pub budget: usize,
+    pub tokens: usize,
+    pub sections: Vec<SectionReport>,
+}
+
+impl PromptReport {
+    /// Whether the prompt fit (it won't if the fixed sections alone are too big)
+    pub fn fits(&self) -> bool {
+        self.tokens <= self.budget
+    }
+
+    /// Sections that lost items
+    pub fn trimmed(&self) -> impl Iterator<Item = &SectionReport> {
+        self.sections.iter().filter(|section| section.dropped > 0)
+    }
+}
+
+/// Builds a prompt from sections within a token budget
+pub struct PromptAssembler<'a> {
+    budget: usize,
+    sections: Vec<PromptSection<'a>>,
+    layout: Option<Layout<'a>>,
+}
+
+impl<'a> PromptAssembler<'a> {
+    pub fn new(budget: usize) -> Self {
+        PromptAssembler {
+            budget,
+            sections: Vec::new(),
+            layout: None,
+        }
+    }
+
+    /// Join the sections with `layout` (e.g. into a chat template's turns)
+    /// instead of one after another. The budget applies to the laid out prompt.
+    pub fn with_layout(
+        mut self,
+        layout: impl Fn(&[RenderedSection]) -> String + Send + 'a,
+    ) -> Self {
+        self.layout = Some(Box::new(layout));
+        self
+    }
+
+    /// The sections' current text, laid out as the prompt
+    fn join(&self, texts: &[String]) -> String {
+        match &self.layout {
+            Some(layout) => {
+                let rendered: Vec<RenderedSection> = self
+                    .sections
+                    .iter()
+                    .zip(texts)
+                    .map(|(section, text)| RenderedSection {
+                        name: section.name,
+                        text,
+                        items: &section.items,
+                    })
+                    .collect();
+                layout(&rendered)
+            }
+            None => texts.concat(),
+        }
+    }
+
+    /// Add the next section of the prompt
+    pub fn push(&mut self, section: PromptSection<'a>) {
+        self.sections.push(section);
+    }
+
+    /// The most items section `i` can keep (fewer than it has) within `limit`
+    /// tokens, with its text and tokens at that size. Keeps none if even that
+    /// doesn't fit.
+    async fn fit_section<F, Fut>(
+        &self,
+        i: usize,
+        limit: usize,
+        count_tokens: &F,
+    ) -> (usize, String, usize)
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = usize>,
+    {
+        let section = &self.sections[i];
+        let (mut low, mut high) = (0, section.items.len());
+        let mut best = None;
+        while low < high {
+            let keep = (low + high) / 2;
+            let text = (section.render)(section.kept(keep));
+            let tokens = count_tokens(text.clone()).await;
+            if tokens <= limit || (keep == 0 && best.is_none()) {
+                best = Some((keep, text, tokens));
+            }
+            if tokens <= limit {
+                low = keep + 1;
+            } else {
+                high = keep;
+            }
+        }
+        match best {
+            Some(best) => best,
+            None => {
+                let text = (section.render)(&[]);
+                let tokens = count_tokens(text.clone()).await;
+                (0, text, tokens)
+            }
+        }
+    }
+
+    /// Trim the sections to fit and join them into the prompt
+    ///
+    /// Tokens are counted with `count_tokens`, normally
+    /// [`Tokenizer::count`](super::tokenizer::Tokenizer::count).
+    pub async fn assemble<F, Fut>(mut self, count_tokens: F) -> (String, PromptReport)
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = usize>,
+    {
+        let mut texts: Vec<String> = self
+            .sections
+            .iter()
+            .map(|section| (section.render)(&section.items))
+            .collect();
+        let mut tokens = Vec::with_capacity(texts.len());
+        for text in &texts {
+            tokens.push(count_tokens(text.clone()).await);
+        }
+        let mut dropped = vec![0; self.sections.len()];
+        let mut reasons = vec![None; self.sections.len()];
+
+        for i in 0..self.sections.len() {
+            let section = &self.sections[i];
+            let Some(budget) = section.budget else {
+                continue;
+            };
+            if section.trim.is_none() || section.items.is_empty() || tokens[i] <= budget {
+                continue;
+            }
+            let (keep, text, count) = self.fit_section(i, budget, &count_tokens).await;
+            dropped[i] += self.sections[i].keep(keep);
+            reasons[i] = Some(TrimReason::SectionBudget);
+            texts[i] = text;
+            tokens[i] = count;
+        }
+
+        // Trimmable sections, lowest priority first
+        let mut order: Vec<(u8, usize)> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter_map(|(i, section)| section.trim.map(|(priority, _)| (priority, i)))
+            .collect();
+        order.sort();
+
+        let mut total = count_tokens(self.join(&texts)).await;
+        while total > self.budget {
+            // What the layout adds on top of the sections; only changes if
+            // the layout treats emptied sections differently
+            let overhead = total as isize - tokens.iter().sum::<usize>() as isize;
+            let mut trimmed = false;
+            for &(_, i) in &order {
+                let others = tokens.iter().sum::<usize>() - tokens[i];
+                let fixed = others as isize + overhead;
+                if fixed + tokens[i] as isize <= self.budget as isize {
+                    break;
+                }
+                if self.sections[i].items.is_empty() {
+                    continue;
+                }
+                let limit = (self.budget as isize - fixed).max(0) as usize;
+                let (keep, text, count) = self.fit_section(i, limit, &count_tokens).await;
+                dropped[i] += self.sections[i].keep(keep);
+                reasons[i] = Some(TrimReason::ContextWindow);
+                texts[i] = text;
+                tokens[i] = count;
+                trimmed = true;
+            }
+            if !trimmed {
+                break;
+            }
+            total = count_tokens(self.join(&texts)).await;
+        }
+
+        let sections: Vec<SectionReport> = self
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| SectionReport {
+                name: section.name,
+                tokens: tokens[i],
+                kept: section.items.len(),
+                dropped: dropped[i],
+                reason: reasons[i],
+            })
+            .collect();
+        let prompt = self.join(&texts);
+        let report = PromptReport {
+            budget: self.budget,
+            tokens: total,
+            sections,
+        };
+        (prompt, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn words(text: String) -> usize {
+        text.split_whitespace().count()
+    }
+
+    fn list(items: &[String]) -> String {
+        items.iter().map(|item| format!("{}\n", item)).collect()
+    }
+
+    fn items(prefix: &str, count: usize) -> Vec<String> {
+        (1..=count).map(|i| format!("{}{}", prefix, i)).collect()
+    }
+
+    fn dm_prompt(budget: usize) -> PromptAssembler<'static> {
+        let mut assembler = PromptAssembler::new(budget);
+        assembler.push(PromptSection::fixed(
+            "system",
+            "You are the DM. ".to_string(),
+        ));
+        assembler.push(PromptSection::trimmable(
+            "history",
+            1,
+            TrimFrom::Front,
+            items("turn", 4),
+            list,
+        ));
+        assembler.push(PromptSection::trimmable(
+            "worldbook",
+            2,
+            TrimFrom::Back,
+            items("entry", 3),
+            list,
+        ));
+        assembler
+    }
+
+    #[tokio::test]
+    async fn test_lowest_priority_is_trimmed_first() {
+        let (prompt, report) = dm_prompt(7).assemble(words).await;
+        // History goes entirely before any worldbook entry
+        assert_eq!(prompt, "You are the DM. entry1\nentry2\nentry3\n");
+        assert!(report.fits());
+        assert_eq!(report.tokens, 7);
+        assert_eq!(report.sections[1].dropped, 4);
+        assert_eq!(report.sections[1].reason, Some(TrimReason::ContextWindow));
+        assert_eq!(report.trimmed().count(), 1);
+
+        let (prompt, _) = dm_prompt(5).assemble(words).await;
+        assert_eq!(prompt, "You are the DM. entry1\n");
+    }
+
+    #[tokio::test]
+    async fn test_section_budget_applies_first() {
+        let mut assembler = PromptAssembler::new(100);
+        assembler.push(
+            PromptSection::trimmable("story", 3, TrimFrom::Front, items("chapter", 5), list)
+                .with_budget(2),
+        );
+
+        let (prompt, report) = assembler.assemble(words).await;
+        assert_eq!(prompt, "chapter4\nchapter5\n");
+        assert_eq!(
+            report.sections[0].summary(),
+            "story: 2 tokens, 2 of 5 items (3 dropped: over its own budget)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_layout_counts_toward_the_budget() {
+        // Wrapping every section in a tag costs a word each
+        let layout = |sections: &[RenderedSection]| {
+            sections
+                .iter()
+                .map(|section| format!("<{}> {}", section.name, section.text))
+                .collect::<String>()
+        };
+        let (prompt, report) = dm_prompt(9).with_layout(layout).assemble(words).await;
+        assert_eq!(
+            prompt,
+            "<system> You are the DM. <history> <worldbook> entry1\nentry2\n"
+        );
+        assert_eq!(report.tokens, 9);
+        assert_eq!(report.sections[2].dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_tokens_are_not_recounted_per_item() {
+        let calls = std::cell::Cell::new(0);
+        let counting = |text: String| {
+            calls.set(calls.get() + 1);
+            words(text)
+        };
+        let mut assembler = PromptAssembler::new(20);
+        assembler.push(PromptSection::fixed(
+            "system",
+            "You are the DM. ".to_string(),
+        ));
+        assembler.push(PromptSection::trimmable(
+            "history",
+            1,
+            TrimFrom::Front,
+            items("turn", 200),
+            list,
+        ));
+
+        let (prompt, report) = assembler.assemble(counting).await;
+        assert!(prompt.ends_with("turn199\nturn200\n"));
+        assert_eq!(report.tokens, 20);
+        assert_eq!(report.sections[1].dropped, 184);
+        // Two sections, the full prompt twice and a binary search over 200 items
+        assert!(calls.get() <= 12, "{} calls", calls.get());
+    }
+
+    #[tokio::test]
+    async fn test_fixed_sections_are_never_trimmed() {
+        let mut assembler = PromptAssembler::new(2);
+        assembler.push(PromptSection::fixed(
+            "action",
+            "I open the door".to_string(),
+        ));
+
+        let (prompt, report) = assembler.assemble(words).await;
+        assert_eq!(prompt, "I open the door");
+        assert!(!report.fits());
+        assert_eq!(report.sections[0].summary(), "action: 4 tokens");
+    }
+}
@@ -0,0 +1,163 @@
+//! # Token Counting
+//!
+//! Token counts for context budgeting, as the narrative model sees them.
+//!
+//! ## Sources
+//!
+//! Backends with the model's own tokenizer (llama.cpp's `/tokenize`) are
+//! asked first, and their counts are cached in a [`TokenCache`]. tiktoken's
+//! cl100k_base is a GPT-4 tokenizer, so it's only an estimate for the
+//! Mistral and Llama models the game usually runs; it's used for backends
+//! without a tokenizer, and as an offline fallback.
+//!
+//! ## Fallback
+//!
+//! When the server can't count tokens (it's down, or too old to have
+//! `/tokenize`), counts come from cl100k for [`RETRY_AFTER`] before the
+//! server is tried again. Fallback counts aren't cached, so they're replaced
+//! by real ones as soon as the server is back.
+
+use super::backend::{cl100k_token_count, NarrativeBackend};
+use super::cache::TokenCache;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long to use the estimate after the server fails to count tokens
+pub const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Counts tokens through a backend, with caching and a cl100k fallback
+#[derive(Clone)]
+pub struct Tokenizer {
+    backend: Arc<dyn NarrativeBackend>,
+    cache: TokenCache,
+    /// Set while the backend's tokenizer is unavailable
+    fallback_until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Tokenizer {
+    pub fn new(backend: Arc<dyn NarrativeBackend>) -> Self {
+        Tokenizer {
+            backend,
+            cache: TokenCache::new(),
+            fallback_until: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Tokens in `text`
+    pub async fn count(&self, text: &str) -> usize {
+        if !self.backend.has_model_tokenizer() {
+            return cl100k_token_count(text);
+        }
+        if let Some(count) = self.cache.get(text).await {
+            return count;
+        }
+        if self.falling_back() {
+            return cl100k_token_count(text);
+        }
+
+        match self.backend.count_tokens(text).await {
+            Ok(count) => {
+                self.cache.insert(text, count).await;
+                count
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "{} can't count tokens ({}), estimating with cl100k for {}s",
+                    self.backend.name(),
+                    e,
+                    RETRY_AFTER.as_secs()
+                );
+                *self
+                    .fallback_until
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + RETRY_AFTER);
+                cl100k_token_count(text)
+            }
+        }
+    }
+
+    /// Whether counts come from the model's own tokenizer right now
+    pub fn is_model_accurate(&self) -> bool {
+        self.backend.has_model_tokenizer() && !self.falling_back()
+    }
+
+    fn falling_back(&self) -> bool {
+        self.fallback_until
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|until| Instant::now() < until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::backend::{GenerationRequest, TokenStream};
+    use anyhow::Result;
+    use futures_util::future::BoxFuture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Backend whose tokenizer counts characters, or fails
+    struct CharBackend {
+        online: bool,
+        calls: AtomicUsize,
+    }
+
+    impl NarrativeBackend for CharBackend {
+        fn name(&self) -> &str {
+            "chars"
+        }
+
+        fn stream(&self, _request: GenerationRequest) -> BoxFuture<'_, Result<TokenStream>> {
+            Box::pin(async { anyhow::bail!("not used") })
+        }
+
+        fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn count_tokens<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<usize>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                if self.online {
+                    Ok(text.chars().count())
+                } else {
+                    anyhow::bail!("connection refused")
+                }
+            })
+        }
+
+        fn has_model_tokenizer(&self) -> bool {
+            true
+        }
+    }
+
+    fn tokenizer(online: bool) -> (Tokenizer, Arc<CharBackend>) {
+        let backend = Arc::new(CharBackend {
+            online,
+            calls: AtomicUsize::new(0),
+        });
+        (Tokenizer::new(backend.clone()), backend)
+    }
+
+    #[tokio::test]
+    async fn test_counts_come_from_the_backend_and_are_cached() {
+        let (tokenizer, backend) = tokenizer(true);
+        assert_eq!(tokenizer.count("Wasteland").await, 9);
+        assert_eq!(tokenizer.count("Wasteland").await, 9);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+        assert!(tokenizer.is_model_accurate());
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_cl100k_when_offline() {
+        let (tokenizer, backend) = tokenizer(false);
+        let text = "The wasteland stretches out before you.";
+        assert_eq!(tokenizer.count(text).await, cl100k_token_count(text));
+        assert!(!tokenizer.is_model_accurate());
+
+        // The server isn't asked again until the retry delay is up
+        tokenizer.count("Another line").await;
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{ConfigError, GameError};
+use crate::templates::ChatFormat;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -67,16 +68,102 @@ fn default_cache_type_k() -> String {
     "q8_0".to_string() // Quantize K cache for speed
 }
 
+fn default_summary_token_budget() -> i32 {
+    600
+}
+
+fn default_worldbook_token_budget() -> i32 {
+    400
+}
+
 fn default_cache_type_v() -> String {
     "q8_0".to_string() // Quantize V cache for speed
 }
 
+/// Inference server API used for a backend connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    /// llama.cpp native `/completion` API
+    #[default]
+    LlamaCpp,
+    /// OpenAI-compatible `/v1/chat/completions` (Ollama, vLLM, LM Studio)
+    #[serde(rename = "openai")]
+    OpenAi,
+}
+
+/// Prompt format the narrative model was trained on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatTemplate {
+    /// One block of text with `>>> PLAYER:` / `>>> DM (YOU):` markers
+    #[default]
+    Plain,
+    /// `<|im_start|>` / `<|im_end|>` (Hermes, Qwen, many fine-tunes)
+    #[serde(rename = "chatml")]
+    ChatMl,
+    /// Llama 3 header and `<|eot_id|>` tokens
+    Llama3,
+    /// Mistral-instruct `[INST]` blocks
+    Mistral,
+    /// `### Instruction:` / `### Response:`
+    Alpaca,
+    /// A Tera template of your own, from `chat_template_file`
+    Custom,
+}
+
+impl ChatTemplate {
+    /// Built-in template under `templates/chat/` (none for plain and custom)
+    pub fn template_name(&self) -> Option<&'static str> {
+        match self {
+            ChatTemplate::ChatMl => Some("chat/chatml.tera"),
+            ChatTemplate::Llama3 => Some("chat/llama3.tera"),
+            ChatTemplate::Mistral => Some("chat/mistral.tera"),
+            ChatTemplate::Alpaca => Some("chat/alpaca.tera"),
+            ChatTemplate::Plain | ChatTemplate::Custom => None,
+        }
+    }
+
+    /// Sequences that end the DM's turn in this format. Custom templates
+    /// use `chat_template_stop` instead.
+    pub fn stop_sequences(&self) -> &'static [&'static str] {
+        match self {
+            ChatTemplate::Plain => &[">>> PLAYER:", "\n>>> PLAYER:", "Player:", "\nPlayer:"],
+            ChatTemplate::ChatMl => &["<|im_end|>", "<|im_start|>"],
+            ChatTemplate::Llama3 => &["<|eot_id|>", "<|start_header_id|>"],
+            ChatTemplate::Mistral => &["</s>", "[INST]"],
+            ChatTemplate::Alpaca => &["### Instruction:", "### Response:"],
+            ChatTemplate::Custom => &[],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct LlamaConfig {
     #[garde(skip)]
     pub server_url: String,
     #[garde(skip)]
     pub extraction_url: String,
+    /// API used to talk to the narrative server
+    #[garde(skip)]
+    #[serde(default)]
+    pub backend: BackendKind,
+    /// API used to talk to the extraction server
+    #[garde(skip)]
+    #[serde(default)]
+    pub extraction_backend: BackendKind,
+    /// Model name for the narrative server (OpenAI-compatible backends only)
+    #[garde(skip)]
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Model name for the extraction server (OpenAI-compatible backends only)
+    #[garde(skip)]
+    #[serde(default)]
+    pub extraction_model: Option<String>,
+    /// Bearer token sent to OpenAI-compatible servers
+    #[garde(skip)]
+    #[serde(default)]
+    pub api_key: Option<String>,
     #[garde(range(min = 0.0, max = 2.0))]
     pub temperature: f32,
     #[garde(range(min = 0.0, max = 1.0))]
@@ -91,6 +178,28 @@ pub struct LlamaConfig {
     pub repeat_penalty: f32,
     #[garde(skip)]
     pub system_prompt: String,
+    /// Tokens of the prompt given to chapter summaries of earlier play
+    #[garde(range(min = 0, max = 32000))]
+    #[serde(default = "default_summary_token_budget")]
+    pub summary_token_budget: i32,
+    /// Tokens of the prompt given to worldbook entries relevant to the
+    /// player's action, beyond the current location
+    #[garde(range(min = 0, max = 32000))]
+    #[serde(default = "default_worldbook_token_budget")]
+    pub worldbook_token_budget: i32,
+    /// Prompt format of the narrative model (llama.cpp backend; OpenAI-compatible
+    /// servers apply their own)
+    #[garde(skip)]
+    #[serde(default)]
+    pub chat_template: ChatTemplate,
+    /// Tera template used when `chat_template = "custom"`
+    #[garde(skip)]
+    #[serde(default)]
+    pub chat_template_file: Option<String>,
+    /// Stop sequences for a custom chat template
+    #[garde(skip)]
+    #[serde(default)]
+    pub chat_template_stop: Vec<String>,
     // Server auto-start configuration
     #[garde(skip)]
     #[serde(default)]
@@ -170,6 +279,10 @@ pub struct GameConfig {
     pub permadeath: bool,
     #[garde(skip)]
     pub autosave_interval: u32,
+    /// RNG seed for new games: a number, `daily` or any text (random if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[garde(skip)]
+    pub seed: Option<String>,
 }
 
 impl Config {
@@ -203,6 +316,11 @@ impl Config {
             config.llama.extraction_url = url;
         }
 
+        if let Ok(seed) = std::env::var("FALLOUT_SEED") {
+            tracing::info!("Overriding RNG seed from environment: {}", seed);
+            config.game.seed = Some(seed);
+        }
+
         config.validate()?;
         Ok(config)
     }
@@ -234,6 +352,31 @@ impl Config {
             }
         })?;
 
+        if self.llama.chat_template == ChatTemplate::Custom
+            && self.llama.chat_template_file.is_none()
+        {
+            return Err(ConfigError::MissingChatTemplateFile.into());
+        }
+        if self.llama.chat_template == ChatTemplate::Custom {
+            ChatFormat::load(
+                self.llama.chat_template,
+                self.llama.chat_template_file.as_deref(),
+            )
+            .map_err(|e| match e {
+                GameError::InvalidInput(reason) => {
+                    ConfigError::InvalidChatTemplateFile(reason).into()
+                }
+                e => e,
+            })?;
+        }
+
+        // The server would wrap an already templated prompt in its own template
+        if self.llama.backend == BackendKind::OpenAi
+            && self.llama.chat_template != ChatTemplate::Plain
+        {
+            return Err(ConfigError::ChatTemplateWithOpenAi.into());
+        }
+
         tracing::debug!("Configuration validation passed");
         Ok(())
     }
@@ -245,6 +388,11 @@ impl Default for Config {
             llama: LlamaConfig {
                 server_url: "http://localhost:8080".to_string(),
                 extraction_url: "http://localhost:8081".to_string(),
+                backend: BackendKind::LlamaCpp,
+                extraction_backend: BackendKind::LlamaCpp,
+                model: None,
+                extraction_model: None,
+                api_key: None,
                 temperature: 0.8,
                 top_p: 0.9,
                 top_k: 40,
@@ -252,6 +400,11 @@ impl Default for Config {
                 context_window: 8192, // Standard context window for most llama.cpp models
                 repeat_penalty: 1.1,
                 system_prompt: "You are a Fallout universe DM.".to_string(),
+                summary_token_budget: 600,
+                worldbook_token_budget: 400,
+                chat_template: ChatTemplate::Plain,
+                chat_template_file: None,
+                chat_template_stop: Vec::new(),
                 auto_start: true,
                 llama_server_path: Some("llama-cpp/llama-server.exe".to_string()),
                 narrative_model_path: Some(
@@ -283,6 +436,7 @@ impl Default for Config {
                 starting_caps: 500,
                 permadeath: false,
                 autosave_interval: 5,
+                seed: None,
             },
         }
     }
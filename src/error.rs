@@ -50,6 +50,10 @@ pub enum GameError {
     #[diagnostic(transparent)]
     Combat(#[from] CombatError),
 
+    #[error("Dice error: {0}")]
+    #[diagnostic(transparent)]
+    Dice(#[from] DiceError),
+
     #[error("Character creation error: {0}")]
     #[diagnostic(transparent)]
     Character(#[from] CharacterError),
@@ -58,6 +62,13 @@ pub enum GameError {
     #[diagnostic(transparent)]
     Config(#[from] ConfigError),
 
+    #[error("Game data error in {file}: {message}")]
+    #[diagnostic(
+        code(fallout_dnd::data_file),
+        help("Check the definitions in data/ (and mods/, if you have any mods installed)")
+    )]
+    DataFile { file: String, message: String },
+
     #[error("Network error: {0}")]
     #[diagnostic(
         code(fallout_dnd::network),
@@ -97,6 +108,60 @@ pub enum CombatError {
     CombatNotActive,
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq, Diagnostic)]
+pub enum DiceError {
+    #[error("Dice expression is empty.")]
+    #[diagnostic(
+        code(fallout_dnd::dice::empty),
+        help("Write dice like 2d6+3, 4d6kh3 or 1d20adv+AGI")
+    )]
+    Empty,
+
+    #[error("Dice expression '{0}' ends unexpectedly.")]
+    #[diagnostic(
+        code(fallout_dnd::dice::unexpected_end),
+        help("Every '+' or '-' must be followed by dice, a number or a SPECIAL token")
+    )]
+    UnexpectedEnd(String),
+
+    #[error("Unexpected '{found}' in dice expression '{expression}'.")]
+    #[diagnostic(
+        code(fallout_dnd::dice::unexpected_char),
+        help("Terms can only be joined with '+' or '-'")
+    )]
+    UnexpectedChar { expression: String, found: char },
+
+    #[error("Unknown dice term '{0}'.")]
+    #[diagnostic(
+        code(fallout_dnd::dice::unknown_token),
+        help("SPECIAL tokens are STR, PER, END, CHA, INT, AGI and LCK")
+    )]
+    UnknownToken(String),
+
+    #[error("Can't roll {count} dice; the limit is {max}.")]
+    #[diagnostic(code(fallout_dnd::dice::dice_count))]
+    DiceCount { count: u32, max: u32 },
+
+    #[error("Dice can't have {sides} sides; use 1 to {max} (exploding dice need at least 2).")]
+    #[diagnostic(code(fallout_dnd::dice::die_size))]
+    DieSize { sides: u32, max: u32 },
+
+    #[error("Invalid keep/drop count for {term}.")]
+    #[diagnostic(
+        code(fallout_dnd::dice::invalid_keep),
+        help("Keep at least one die and no more than were rolled, e.g. 4d6kh3")
+    )]
+    InvalidKeep { term: String },
+
+    #[error("Number '{0}' in dice expression is too large.")]
+    #[diagnostic(code(fallout_dnd::dice::number_too_large))]
+    NumberTooLarge(String),
+
+    #[error("Dice total is too large to count.")]
+    #[diagnostic(code(fallout_dnd::dice::overflow), help("Use fewer terms"))]
+    Overflow,
+}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq, Diagnostic)]
 pub enum CharacterError {
     #[error("Invalid SPECIAL stat allocation: {0}.")]
@@ -174,6 +239,27 @@ pub enum ConfigError {
         help("Starting caps is the initial currency. Typical values: 100-1000")
     )]
     InvalidStartingCaps(u32),
+
+    #[error("chat_template = \"custom\" needs a chat_template_file.")]
+    #[diagnostic(
+        code(fallout_dnd::config::missing_chat_template_file),
+        help("Point chat_template_file at a Tera template, or pick a built-in format like \"chatml\"")
+    )]
+    MissingChatTemplateFile,
+
+    #[error("Invalid chat_template_file: {0}")]
+    #[diagnostic(
+        code(fallout_dnd::config::invalid_chat_template_file),
+        help("The file must be a Tera template that renders `system` and `messages` (each with `role` and `content`)")
+    )]
+    InvalidChatTemplateFile(String),
+
+    #[error("backend = \"openai\" needs chat_template = \"plain\".")]
+    #[diagnostic(
+        code(fallout_dnd::config::chat_template_with_openai),
+        help("OpenAI-compatible servers apply the model's own chat template. Remove chat_template or use backend = \"llama_cpp\"")
+    )]
+    ChatTemplateWithOpenAi,
 }
 
 // Convenience conversion from anyhow::Error
@@ -244,6 +330,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dice_error_display_messages() {
+        let error = DiceError::UnknownToken("FOO".to_string());
+        assert_eq!(error.to_string(), "Unknown dice term 'FOO'.");
+
+        let error = DiceError::UnexpectedChar {
+            expression: "2d6*2".to_string(),
+            found: '*',
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unexpected '*' in dice expression '2d6*2'."
+        );
+
+        let error: GameError = DiceError::Empty.into();
+        assert_eq!(error.to_string(), "Dice error: Dice expression is empty.");
+    }
+
     #[test]
     fn test_character_error_display_messages() {
         let error = CharacterError::InvalidSpecialAllocation("Total exceeds 40".to_string());
@@ -0,0 +1,391 @@
+//! # Barter Module
+//!
+//! Buying and selling with merchant NPCs.
+//!
+//! ## Merchants
+//!
+//! Any living worldbook NPC with a merchant role can trade. A merchant's stock
+//! and caps are kept in `Worldbook::merchants` (keyed by NPC ID), created the
+//! first time the player trades with them and restocked every
+//! [`RESTOCK_DAYS`] game days. Besides the staples, stock is drawn from the
+//! item catalog at the player's level, with rarer items less likely to show up.
+//!
+//! ## Prices
+//!
+//! Prices start from `Item::value` and are adjusted by [`Prices`]:
+//! - **Barter** skill counts for half of the player's haggling ability
+//! - **Charisma** and the merchant's **disposition** make up the rest
+//!
+//! A poor haggler pays up to 1.5× value and sells for a quarter of it; a
+//! master trader pays exact value and sells for 60%. Merchants who can't
+//! stand the player (disposition below [`MIN_TRADE_DISPOSITION`]) won't
+//! trade at all.
+//!
+//! Worn gear is worth less: its value falls with condition, down to
+//! [`BROKEN_VALUE`] of it when broken. Worn or modded items keep their own
+//! slot in the merchant's stock, as in the player's [`Inventory`].
+
+use super::character::Character;
+use super::items::condition::MAX_CONDITION;
+use super::items::{find_item, registry, Inventory, Item, ItemType};
+use super::rng::pick_weighted;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Game days between restocks
+pub const RESTOCK_DAYS: u32 = 3;
+/// Merchants below this disposition refuse to trade
+pub const MIN_TRADE_DISPOSITION: i32 = -50;
+/// Caps a merchant starts each restock with (plus a random amount up to the same again)
+const BASE_MERCHANT_CAPS: u32 = 300;
+/// Items every merchant carries: (item ID, min quantity, max quantity)
+const STAPLE_STOCK: &[(&str, u32, u32)] = &[
+    ("stimpak", 1, 4),
+    ("purified_water", 2, 5),
+    ("cram", 2, 5),
+    ("10mm_ammo", 12, 36),
+];
+/// Random extra items on top of the staples
+const EXTRA_STOCK_PICKS: usize = 4;
+/// Share of its value a broken weapon or armor is still worth
+pub const BROKEN_VALUE: f32 = 0.1;
+
+/// A merchant's goods and cash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Merchant {
+    pub stock: Inventory,
+    pub caps: u32,
+    /// Game day of the last restock
+    pub restocked_day: u32,
+}
+
+impl Merchant {
+    /// A merchant with fresh stock for a player of the given level
+    pub fn stocked(day: u32, level: u32, rng: &mut impl Rng) -> Self {
+        let mut merchant = Merchant::default();
+        merchant.restock(day, level, rng);
+        merchant
+    }
+
+    pub fn needs_restock(&self, day: u32) -> bool {
+        day >= self.restocked_day + RESTOCK_DAYS
+    }
+
+    /// Replace the stock and caps with a fresh selection
+    pub fn restock(&mut self, day: u32, level: u32, rng: &mut impl Rng) {
+        self.stock.clear();
+        for (id, min, max) in STAPLE_STOCK {
+            if let Some(mut item) = find_item(id) {
+                item.quantity = rng.random_range(*min..=*max);
+                self.stock.add(item);
+            }
+        }
+
+        // Starting gear, quest items and junk aren't for sale
+        let extras: Vec<_> = registry()
+            .for_level(level)
+            .filter(|entry| entry.starting == 0 && !matches!(entry.item.item_type, ItemType::Misc))
+            .collect();
+        for _ in 0..EXTRA_STOCK_PICKS {
+            let Some(entry) = pick_weighted(&extras, |entry| entry.rarity.weight(), rng) else {
+                break;
+            };
+            let mut item = entry.item.clone();
+            item.quantity = match item.item_type {
+                ItemType::Ammo => rng.random_range(10..=30),
+                _ => 1,
+            };
+            self.stock.add(item);
+        }
+
+        self.caps = BASE_MERCHANT_CAPS + rng.random_range(0..=BASE_MERCHANT_CAPS);
+        self.restocked_day = day;
+    }
+}
+
+/// Price multipliers for one player/merchant pair
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prices {
+    /// Multiplier on item value when the player buys
+    pub buy: f32,
+    /// Multiplier on item value when the player sells
+    pub sell: f32,
+}
+
+impl Prices {
+    /// Work out prices from the player's Barter skill and Charisma and the
+    /// merchant's disposition toward the player
+    pub fn new(barter: u8, charisma: u8, disposition: i32) -> Self {
+        let skill = barter.min(100) as f32 / 100.0;
+        let charm = (charisma.clamp(1, 10) - 1) as f32 / 9.0;
+        let goodwill = (disposition.clamp(-100, 100) + 100) as f32 / 200.0;
+        let haggle = 0.5 * skill + 0.25 * charm + 0.25 * goodwill;
+
+        Prices {
+            buy: 1.5 - 0.5 * haggle,
+            sell: 0.25 + 0.35 * haggle,
+        }
+    }
+
+    pub fn for_character(character: &Character, disposition: i32) -> Self {
+        Self::new(
+            character.skills.barter,
+            character.special.charisma,
+            disposition,
+        )
+    }
+
+    /// Caps the player pays for one unit of an item
+    pub fn buy_price(&self, item: &Item) -> u32 {
+        ((worth(item) * self.buy).round() as u32).max(1)
+    }
+
+    /// Caps the player gets for one unit of an item
+    pub fn sell_price(&self, item: &Item) -> u32 {
+        (worth(item) * self.sell).round() as u32
+    }
+}
+
+/// Value of one unit of an item in its condition, falling linearly from
+/// full value to [`BROKEN_VALUE`] of it
+fn worth(item: &Item) -> f32 {
+    let condition = item.condition().unwrap_or(MAX_CONDITION) as f32 / MAX_CONDITION as f32;
+    item.value as f32 * (BROKEN_VALUE + (1.0 - BROKEN_VALUE) * condition)
+}
+
+/// Buy `qty` of the merchant's `index`-th stock item
+pub fn buy(
+    character: &mut Character,
+    merchant: &mut Merchant,
+    prices: Prices,
+    index: usize,
+    qty: u32,
+) -> Result<String, String> {
+    let stock = merchant
+        .stock
+        .get(index)
+        .ok_or_else(|| "The merchant doesn't have that".to_string())?;
+    if qty == 0 || qty > stock.quantity {
+        return Err(format!("Only {} {} for sale", stock.quantity, stock.name));
+    }
+
+    let total = prices.buy_price(stock) * qty;
+    if total > character.caps {
+        return Err(format!(
+            "Not enough caps ({} needed, {} available)",
+            total, character.caps
+        ));
+    }
+    let weight = stock.weight * qty as f32;
+    if !character.can_carry(weight) {
+        return Err(format!(
+            "{} x{} is too heavy to carry ({:.1} lbs; carrying {:.0}/{:.0} lbs)",
+            stock.name,
+            qty,
+            weight,
+            character.carry_weight(),
+            character.max_carry_weight()
+        ));
+    }
+
+    let Some(item) = merchant.stock.split(index, qty) else {
+        return Err("The merchant doesn't have that".to_string());
+    };
+
+    character.caps -= total;
+    merchant.caps += total;
+    let message = format!("Bought {} x{} for {} caps", item.name, qty, total);
+    character.add_item(item);
+    Ok(message)
+}
+
+/// Sell `qty` of the player's `index`-th inventory item
+pub fn sell(
+    character: &mut Character,
+    merchant: &mut Merchant,
+    prices: Prices,
+    index: usize,
+    qty: u32,
+) -> Result<String, String> {
+    let owned = character
+        .inventory
+        .get(index)
+        .ok_or_else(|| "You don't have that".to_string())?;
+    if qty == 0 || qty > owned.quantity {
+        return Err(format!("You only have {} {}", owned.quantity, owned.name));
+    }
+
+    let equipped = character.is_equipped(owned);
+    if equipped && qty == owned.quantity {
+        return Err(format!("Unequip your {} before selling it", owned.name));
+    }
+
+    let total = prices.sell_price(owned) * qty;
+    if total > merchant.caps {
+        return Err(format!(
+            "The merchant can't afford that ({} caps needed, {} available)",
+            total, merchant.caps
+        ));
+    }
+
+    let Some(item) = character.inventory.split(index, qty) else {
+        return Err("You don't have that".to_string());
+    };
+
+    character.caps += total;
+    merchant.caps -= total;
+    let message = format!("Sold {} x{} for {} caps", item.name, qty, total);
+    merchant.stock.add(item);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::character::Special;
+    use crate::game::rng::GameRng;
+
+    fn merchant_with(id: &str, qty: u32, caps: u32) -> Merchant {
+        let mut item = find_item(id).unwrap();
+        item.quantity = qty;
+        Merchant {
+            stock: vec![item].into(),
+            caps,
+            restocked_day: 1,
+        }
+    }
+
+    #[test]
+    fn test_prices_reward_haggling() {
+        let worst = Prices::new(0, 1, -100);
+        let best = Prices::new(100, 10, 100);
+        assert!((worst.buy - 1.5).abs() < 1e-6 && (worst.sell - 0.25).abs() < 1e-6);
+        assert!((best.buy - 1.0).abs() < 1e-6 && (best.sell - 0.6).abs() < 1e-6);
+
+        let stimpak = find_item("stimpak").unwrap();
+        assert_eq!(worst.buy_price(&stimpak), 75);
+        assert_eq!(best.buy_price(&stimpak), 50);
+        assert_eq!(worst.sell_price(&stimpak), 13);
+        assert_eq!(best.sell_price(&stimpak), 30);
+
+        // A friendlier merchant gives a better deal
+        assert!(Prices::new(40, 5, 80).buy < Prices::new(40, 5, -20).buy);
+    }
+
+    #[test]
+    fn test_buy_and_sell() {
+        let mut character = Character::new("Trader", Special::new());
+        character.caps = 200;
+        let starting_stimpaks = character.item_count("stimpak");
+        let mut merchant = merchant_with("stimpak", 2, 100);
+        let prices = Prices::new(100, 10, 100);
+
+        assert_eq!(
+            buy(&mut character, &mut merchant, prices, 0, 2).unwrap(),
+            "Bought Stimpak x2 for 100 caps"
+        );
+        assert_eq!(character.caps, 100);
+        assert_eq!(merchant.caps, 200);
+        assert!(merchant.stock.is_empty());
+        assert_eq!(character.item_count("stimpak"), starting_stimpaks + 2);
+
+        let index = character
+            .inventory
+            .iter()
+            .position(|item| item.id == "stimpak")
+            .unwrap();
+        assert!(sell(&mut character, &mut merchant, prices, index, 1).is_ok());
+        assert_eq!(character.caps, 130);
+        assert_eq!(merchant.stock[0].quantity, 1);
+    }
+
+    #[test]
+    fn test_worn_gear_sells_for_less() {
+        let prices = Prices::new(50, 5, 0);
+        let rifle = find_item("hunting_rifle").unwrap();
+        let mut worn = rifle.clone();
+        if let ItemType::Weapon(ref mut stats) = worn.item_type {
+            stats.condition = 5;
+        }
+        assert!(prices.sell_price(&worn) < prices.sell_price(&rifle) / 5);
+        assert!(prices.buy_price(&worn) < prices.buy_price(&rifle));
+
+        // Sold gear keeps its condition in the merchant's stock
+        let mut character = Character::new("Trader", Special::new());
+        character.add_item(worn);
+        let mut merchant = merchant_with("hunting_rifle", 1, 1000);
+        let index = character
+            .inventory
+            .iter()
+            .position(|item| item.condition() == Some(5))
+            .unwrap();
+        sell(&mut character, &mut merchant, prices, index, 1).unwrap();
+        assert_eq!(merchant.stock.len(), 2);
+        assert_eq!(merchant.stock[1].condition(), Some(5));
+    }
+
+    #[test]
+    fn test_trade_limits() {
+        let mut character = Character::new("Trader", Special::new());
+        character.caps = 10;
+        let mut merchant = merchant_with("minigun", 1, 0);
+        let prices = Prices::new(50, 5, 0);
+
+        assert!(buy(&mut character, &mut merchant, prices, 0, 1).is_err());
+        assert!(buy(&mut character, &mut merchant, prices, 0, 2).is_err());
+        assert!(buy(&mut character, &mut merchant, prices, 5, 1).is_err());
+
+        // The merchant is broke, and the equipped pistol can't be sold
+        let stimpak = character
+            .inventory
+            .iter()
+            .position(|item| item.id == "stimpak")
+            .unwrap();
+        assert!(sell(&mut character, &mut merchant, prices, stimpak, 1).is_err());
+        let pistol = character
+            .inventory
+            .iter()
+            .position(|item| item.id == "10mm_pistol")
+            .unwrap();
+        merchant.caps = 1000;
+        let err = sell(&mut character, &mut merchant, prices, pistol, 1).unwrap_err();
+        assert!(err.contains("Unequip"));
+    }
+
+    #[test]
+    fn test_cant_buy_more_than_can_be_carried() {
+        let mut character = Character::new("Trader", Special::new());
+        character.caps = 10_000;
+        let room = character.max_carry_weight() - character.carry_weight();
+        character.add_item(Item::new_misc("anvil", "Anvil", "Heavy.", room - 1.0, 5));
+        let mut merchant = merchant_with("minigun", 1, 0);
+        let prices = Prices::new(50, 5, 0);
+
+        let err = buy(&mut character, &mut merchant, prices, 0, 1).unwrap_err();
+        assert!(err.contains("too heavy"));
+        assert_eq!(character.caps, 10_000);
+        assert_eq!(merchant.stock[0].quantity, 1);
+        assert_eq!(character.item_count("minigun"), 0);
+    }
+
+    #[test]
+    fn test_restock() {
+        let mut rng = GameRng::seeded(3);
+        let mut merchant = Merchant::stocked(1, 1, &mut rng);
+        assert!(merchant.stock.iter().any(|item| item.id == "stimpak"));
+        assert!(merchant.stock.iter().all(|item| item.id != "water_chip"));
+        // A new character won't find high-level gear for sale
+        assert!(merchant
+            .stock
+            .iter()
+            .all(|item| registry().get(&item.id).unwrap().level == 1));
+        assert!(merchant.caps >= BASE_MERCHANT_CAPS);
+
+        merchant.stock.clear();
+        assert!(!merchant.needs_restock(RESTOCK_DAYS));
+        assert!(merchant.needs_restock(1 + RESTOCK_DAYS));
+        merchant.restock(1 + RESTOCK_DAYS, 1, &mut rng);
+        assert!(!merchant.stock.is_empty());
+        assert_eq!(merchant.restocked_day, 1 + RESTOCK_DAYS);
+    }
+}
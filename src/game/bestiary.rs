@@ -0,0 +1,666 @@
+//! # Bestiary Module
+//!
+//! Enemy definitions and random encounter tables, loaded from TOML data files.
+//!
+//! ## Data Files
+//!
+//! `data/enemies/*.toml` hold `[[enemy]]` and `[[encounter]]` tables (see the
+//! comments in `creatures.toml` and `encounters.toml` for the fields). Files
+//! are read in name order and checked before the game starts: IDs must be
+//! unique snake_case, damage and counts must be valid dice expressions,
+//! encounters must name known enemies and loot must name known items.
+//!
+//! Files in `mods/enemies/*.toml` are read afterwards. An entry with an
+//! existing ID replaces the original and anything else is added, so new
+//! creatures and encounters need no code changes. A mod file that fails to
+//! load is skipped with a warning.
+//!
+//! ## Scaling
+//!
+//! Enemy stats are given for level 1 plus a per-level increase, so one
+//! definition covers every level. Encounters spawn enemies at the
+//! character's level (plus an optional offset).
+//!
+//! ## Encounters
+//!
+//! [`Bestiary::roll_encounter`] picks an encounter for the current location
+//! type, character level and time of day, weighted by each encounter's
+//! `weight`. Location types without encounters of their own fall back to
+//! [`DEFAULT_LOCATION_TYPE`].
+
+use super::combat::Enemy;
+use super::dice::DiceExpr;
+use super::items::catalog::toml_files;
+use super::items::{registry, DamageType};
+use super::rng::pick_weighted;
+use super::worldbook::Worldbook;
+use crate::error::GameError;
+use crate::validation_garde::{validate_data_id, validate_dice_expression};
+use garde::Validate;
+use once_cell::sync::OnceCell;
+use rand::Rng;
+use serde::Deserialize;
+use smartstring::alias::String as SmartString;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Directory holding the base enemy and encounter definitions
+pub const ENEMY_DATA_DIR: &str = "data/enemies";
+/// Directory holding mod enemy and encounter definitions
+pub const ENEMY_MOD_DIR: &str = "mods/enemies";
+/// Location types encounter tables can be keyed by
+pub const LOCATION_TYPES: &[&str] = &["settlement", "ruin", "vault", "wasteland"];
+/// Encounter table used where a location type has none of its own
+pub const DEFAULT_LOCATION_TYPE: &str = "wasteland";
+/// Enemy combat skill never scales past this
+pub const MAX_ENEMY_SKILL: u32 = 95;
+/// Highest level an encounter can be limited to
+pub const MAX_ENCOUNTER_LEVEL: u32 = 50;
+
+/// Global bestiary
+static BESTIARY: OnceCell<Bestiary> = OnceCell::new();
+
+/// Load the bestiary if it isn't loaded yet (the game does this at startup,
+/// see [`crate::game::load_game_data`])
+pub fn load_bestiary() -> Result<&'static Bestiary, GameError> {
+    BESTIARY.get_or_try_init(|| Bestiary::load(Path::new(ENEMY_DATA_DIR), Path::new(ENEMY_MOD_DIR)))
+}
+
+/// The bestiary, loaded on first use when the game didn't load it at startup
+/// (tests and tools)
+pub fn bestiary() -> &'static Bestiary {
+    load_bestiary().expect("bestiary should load")
+}
+
+/// One `[[enemy]]` entry in a data file. Stats are for level 1.
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyDefinition {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    #[garde(length(min = 1, max = 40))]
+    pub name: String,
+    #[serde(default)]
+    #[garde(length(max = 300))]
+    pub description: String,
+    #[garde(range(min = 1, max = 1000))]
+    pub hp: i32,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 100))]
+    pub hp_per_level: i32,
+    #[garde(range(min = 0, max = 40))]
+    pub armor_class: i32,
+    #[serde(default = "default_ac_per_level")]
+    #[garde(range(min = 0, max = 5))]
+    pub ac_per_level: i32,
+    #[garde(custom(validate_dice_expression))]
+    pub damage: String,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 20))]
+    pub damage_per_level: u32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub damage_type: DamageType,
+    #[garde(range(min = 1, max = 95))]
+    pub skill: u32,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 20))]
+    pub skill_per_level: u32,
+    #[garde(range(min = 1, max = 20))]
+    pub strength: u8,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 5))]
+    pub strength_per_level: u8,
+    #[serde(default = "default_ap")]
+    #[garde(range(min = 1, max = 20))]
+    pub ap: i32,
+    /// XP per level of the enemy
+    #[garde(range(max = 5000))]
+    pub xp: u32,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 100))]
+    pub dr: i32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub resistances: HashMap<DamageType, i32>,
+    #[serde(default)]
+    #[garde(dive)]
+    pub loot: LootTable,
+}
+
+fn default_ac_per_level() -> i32 {
+    1
+}
+
+fn default_ap() -> i32 {
+    5
+}
+
+/// What an enemy can drop when it dies
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct LootTable {
+    /// Dice expression for the caps carried
+    #[serde(default)]
+    #[garde(custom(validate_optional_dice))]
+    pub caps: Option<String>,
+    #[serde(default)]
+    #[garde(dive)]
+    pub items: Vec<LootDrop>,
+}
+
+/// One possible item drop
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct LootDrop {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    /// Percent chance of dropping
+    #[garde(range(min = 1, max = 100))]
+    pub chance: u32,
+    /// Dice expression for the quantity
+    #[serde(default = "default_count")]
+    #[garde(custom(validate_dice_expression))]
+    pub qty: String,
+}
+
+fn default_count() -> String {
+    "1".to_string()
+}
+
+/// Time of day an encounter can happen at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncounterTime {
+    #[default]
+    Any,
+    Day,
+    Night,
+}
+
+impl EncounterTime {
+    fn allows(&self, night: bool) -> bool {
+        match self {
+            EncounterTime::Any => true,
+            EncounterTime::Day => !night,
+            EncounterTime::Night => night,
+        }
+    }
+}
+
+/// One `[[encounter]]` entry in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct EncounterDefinition {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    #[garde(length(min = 1), inner(custom(validate_location_type)))]
+    pub locations: Vec<String>,
+    #[serde(default = "default_min_level")]
+    #[garde(range(min = 1, max = MAX_ENCOUNTER_LEVEL))]
+    pub min_level: u32,
+    #[serde(default = "default_max_level")]
+    #[garde(range(min = 1, max = MAX_ENCOUNTER_LEVEL))]
+    pub max_level: u32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub time: EncounterTime,
+    #[serde(default = "default_weight")]
+    #[garde(range(min = 1, max = 100))]
+    pub weight: u32,
+    #[garde(length(min = 1, max = 200))]
+    pub message: String,
+    #[garde(length(min = 1, max = 8), dive)]
+    pub enemies: Vec<EncounterGroup>,
+}
+
+fn default_min_level() -> u32 {
+    1
+}
+
+fn default_max_level() -> u32 {
+    MAX_ENCOUNTER_LEVEL
+}
+
+fn default_weight() -> u32 {
+    10
+}
+
+/// Enemies of one kind in an encounter
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct EncounterGroup {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    /// Dice expression for how many turn up
+    #[serde(default = "default_count")]
+    #[garde(custom(validate_dice_expression))]
+    pub count: String,
+    /// Added to the character's level
+    #[serde(default)]
+    #[garde(range(min = -5, max = 10))]
+    pub level_offset: i32,
+}
+
+fn validate_optional_dice(value: &Option<String>, context: &()) -> garde::Result {
+    match value {
+        Some(dice) => validate_dice_expression(dice, context),
+        None => Ok(()),
+    }
+}
+
+fn validate_location_type(value: &str, _context: &()) -> garde::Result {
+    if LOCATION_TYPES.contains(&value) {
+        Ok(())
+    } else {
+        Err(garde::Error::new(format!(
+            "location type must be one of {}",
+            LOCATION_TYPES.join(", ")
+        )))
+    }
+}
+
+impl EnemyDefinition {
+    /// Build the enemy at a level
+    pub fn spawn(&self, level: u32) -> Enemy {
+        let level = level.max(1);
+        let steps = level - 1;
+        let max_hp = self.hp + self.hp_per_level * steps as i32;
+        let bonus = self.damage_per_level * steps;
+        let damage = if bonus > 0 {
+            format!("{}+{}", self.damage, bonus)
+        } else {
+            self.damage.clone()
+        };
+
+        Enemy {
+            name: SmartString::from(self.name.as_str()),
+            level,
+            max_hp,
+            current_hp: max_hp,
+            armor_class: self.armor_class + self.ac_per_level * steps as i32,
+            damage: SmartString::from(damage),
+            ap: self.ap + level as i32 / 2,
+            xp_reward: self.xp * level,
+            skill: (self.skill + self.skill_per_level * steps).min(MAX_ENEMY_SKILL) as u8,
+            strength: self
+                .strength
+                .saturating_add(self.strength_per_level.saturating_mul(steps.min(50) as u8)),
+            damage_type: self.damage_type.clone(),
+            kind: Some(SmartString::from(self.id.as_str())),
+            damage_resistance: self.dr,
+            resistances: self.resistances.clone(),
+        }
+    }
+}
+
+/// A rolled encounter, ready to fight
+#[derive(Debug, Clone)]
+pub struct Encounter {
+    pub message: String,
+    pub enemies: Vec<Enemy>,
+}
+
+/// Layout of a data file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BestiaryFile {
+    #[serde(default)]
+    enemy: Vec<EnemyDefinition>,
+    #[serde(default)]
+    encounter: Vec<EncounterDefinition>,
+}
+
+/// Every known enemy and encounter, in data file order
+#[derive(Debug, Clone, Default)]
+pub struct Bestiary {
+    enemies: Vec<EnemyDefinition>,
+    encounters: Vec<EncounterDefinition>,
+}
+
+impl Bestiary {
+    /// Load the base data directory, then apply any mods on top
+    pub fn load(data_dir: &Path, mod_dir: &Path) -> Result<Self, GameError> {
+        let mut bestiary = Bestiary::default();
+
+        for path in toml_files(data_dir)? {
+            let file = path.display().to_string();
+            let source = fs::read_to_string(&path)?;
+            let data = Self::parse(&source, &file)?;
+            for enemy in &data.enemy {
+                if bestiary.enemy(&enemy.id).is_some() {
+                    return Err(duplicate(&file, "enemy", &enemy.id));
+                }
+            }
+            for encounter in &data.encounter {
+                if bestiary.encounters.iter().any(|e| e.id == encounter.id) {
+                    return Err(duplicate(&file, "encounter", &encounter.id));
+                }
+            }
+            bestiary.merge(data);
+        }
+
+        if mod_dir.is_dir() {
+            for path in toml_files(mod_dir)? {
+                let file = path.display().to_string();
+                match fs::read_to_string(&path)
+                    .map_err(GameError::from)
+                    .and_then(|source| Self::parse(&source, &file))
+                {
+                    Ok(data) => {
+                        tracing::info!(
+                            "Loaded {} mod enemies and {} mod encounters from {}",
+                            data.enemy.len(),
+                            data.encounter.len(),
+                            file
+                        );
+                        bestiary.merge(data);
+                    }
+                    Err(e) => tracing::warn!("Skipping enemy mod: {}", e),
+                }
+            }
+        }
+
+        bestiary.check_references()?;
+        Ok(bestiary)
+    }
+
+    /// Parse and validate the definitions in one data file
+    fn parse(source: &str, file: &str) -> Result<BestiaryFile, GameError> {
+        let error = |message: String| GameError::DataFile {
+            file: file.to_string(),
+            message,
+        };
+        let data: BestiaryFile = toml::from_str(source).map_err(|e| error(e.to_string()))?;
+        for enemy in &data.enemy {
+            enemy
+                .validate()
+                .map_err(|e| error(format!("enemy '{}': {}", enemy.id, e)))?;
+        }
+        for encounter in &data.encounter {
+            encounter
+                .validate()
+                .map_err(|e| error(format!("encounter '{}': {}", encounter.id, e)))?;
+        }
+        Ok(data)
+    }
+
+    /// Add definitions, replacing any existing ones with the same ID in place
+    fn merge(&mut self, data: BestiaryFile) {
+        for enemy in data.enemy {
+            match self.enemies.iter_mut().find(|e| e.id == enemy.id) {
+                Some(existing) => *existing = enemy,
+                None => self.enemies.push(enemy),
+            }
+        }
+        for encounter in data.encounter {
+            match self.encounters.iter_mut().find(|e| e.id == encounter.id) {
+                Some(existing) => *existing = encounter,
+                None => self.encounters.push(encounter),
+            }
+        }
+    }
+
+    /// Encounters must name known enemies and loot must name known items
+    fn check_references(&self) -> Result<(), GameError> {
+        let error = |message: String| GameError::DataFile {
+            file: ENEMY_DATA_DIR.to_string(),
+            message,
+        };
+        for enemy in &self.enemies {
+            for drop in &enemy.loot.items {
+                if !registry().contains(&drop.id) {
+                    return Err(error(format!(
+                        "enemy '{}' drops unknown item '{}'",
+                        enemy.id, drop.id
+                    )));
+                }
+            }
+        }
+        for encounter in &self.encounters {
+            for group in &encounter.enemies {
+                if self.enemy(&group.id).is_none() {
+                    return Err(error(format!(
+                        "encounter '{}' uses unknown enemy '{}'",
+                        encounter.id, group.id
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn enemy(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.enemies.iter().find(|enemy| enemy.id == id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.enemies.iter().map(|enemy| enemy.id.as_str())
+    }
+
+    /// Find an enemy by a loose name ("Raiders", "super mutant", "deathclaw")
+    pub fn find(&self, name: &str) -> Option<&EnemyDefinition> {
+        let id = Worldbook::generate_id(name.trim());
+        self.enemy(&id)
+            .or_else(|| id.strip_suffix('s').and_then(|id| self.enemy(id)))
+            .or_else(|| id.strip_suffix("es").and_then(|id| self.enemy(id)))
+            .or_else(|| {
+                let joined: String = id.split('_').collect();
+                self.enemies
+                    .iter()
+                    .find(|enemy| enemy.id.replace('_', "") == joined)
+            })
+    }
+
+    /// Build an enemy by ID at a level
+    pub fn spawn(&self, id: &str, level: u32) -> Option<Enemy> {
+        self.enemy(id).map(|enemy| enemy.spawn(level))
+    }
+
+    /// Encounters that can happen here, now, to a character of this level
+    pub fn encounters_for(
+        &self,
+        location_type: &str,
+        level: u32,
+        night: bool,
+    ) -> Vec<&EncounterDefinition> {
+        let matching = |location_type: &str| -> Vec<&EncounterDefinition> {
+            self.encounters
+                .iter()
+                .filter(|e| e.locations.iter().any(|l| l == location_type))
+                .filter(|e| (e.min_level..=e.max_level).contains(&level))
+                .filter(|e| e.time.allows(night))
+                .collect()
+        };
+        let here = matching(location_type);
+        if here.is_empty() {
+            matching(DEFAULT_LOCATION_TYPE)
+        } else {
+            here
+        }
+    }
+
+    /// Pick and spawn a random encounter
+    pub fn roll_encounter(
+        &self,
+        location_type: &str,
+        level: u32,
+        night: bool,
+        rng: &mut impl Rng,
+    ) -> Option<Encounter> {
+        let options = self.encounters_for(location_type, level, night);
+        let encounter = *pick_weighted(&options, |e| e.weight, rng)?;
+
+        let mut enemies = Vec::new();
+        for group in &encounter.enemies {
+            let count = group
+                .count
+                .parse::<DiceExpr>()
+                .ok()
+                .and_then(|dice| dice.roll(None, rng).ok())
+                .map_or(1, |roll| roll.total.max(1));
+            let enemy_level = (level as i32 + group.level_offset).max(1) as u32;
+            for _ in 0..count {
+                enemies.extend(self.spawn(&group.id, enemy_level));
+            }
+        }
+        enemies.truncate(super::dm_actions::MAX_ENEMIES);
+
+        Some(Encounter {
+            message: encounter.message.clone(),
+            enemies,
+        })
+    }
+}
+
+fn duplicate(file: &str, what: &str, id: &str) -> GameError {
+    GameError::DataFile {
+        file: file.to_string(),
+        message: format!("{} '{}' is defined twice", what, id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::GameRng;
+
+    const GHOUL_MOD: &str = r#"
+[[enemy]]
+id = "glowing_one"
+name = "Glowing One"
+hp = 60
+hp_per_level = 12
+armor_class = 12
+damage = "2d6"
+damage_type = "Poison"
+skill = 50
+strength = 6
+xp = 180
+
+[[encounter]]
+id = "glowing_vault"
+locations = ["vault"]
+weight = 100
+message = "A ghoul glowing bright green lurches toward you!"
+enemies = [{ id = "glowing_one" }, { id = "feral_ghoul", count = "2" }]
+"#;
+
+    #[test]
+    fn test_base_bestiary_loads() {
+        let bestiary = bestiary();
+        for id in [
+            "radroach",
+            "raider",
+            "super_mutant",
+            "feral_ghoul",
+            "deathclaw",
+            "protectron",
+        ] {
+            assert!(bestiary.enemy(id).is_some(), "missing {}", id);
+        }
+        for location in ["vault", "ruin", "wasteland"] {
+            assert!(!bestiary.encounters_for(location, 1, false).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_enemies_scale_with_level() {
+        let raider = bestiary().spawn("raider", 1).unwrap();
+        assert_eq!(raider.max_hp, 30);
+        assert_eq!(raider.skill, 48);
+        assert_eq!(raider.damage, "1d6");
+        assert_eq!(raider.kind.as_deref(), Some("raider"));
+
+        let veteran = bestiary().spawn("raider", 5).unwrap();
+        assert_eq!(veteran.max_hp, 70);
+        assert_eq!(veteran.damage, "1d6+4");
+        assert_eq!(veteran.xp_reward, 500);
+        assert!(bestiary().spawn("deathclaw", 30).unwrap().skill <= MAX_ENEMY_SKILL as u8);
+
+        let robot = bestiary().spawn("protectron", 1).unwrap();
+        assert!(robot.mitigate(10, &DamageType::Normal) < robot.mitigate(10, &DamageType::Laser));
+    }
+
+    #[test]
+    fn test_find_by_loose_name() {
+        let bestiary = bestiary();
+        assert_eq!(bestiary.find("Raiders").unwrap().id, "raider");
+        assert_eq!(bestiary.find("radroaches").unwrap().id, "radroach");
+        assert_eq!(bestiary.find("Super Mutant").unwrap().id, "super_mutant");
+        assert_eq!(bestiary.find("supermutant").unwrap().id, "super_mutant");
+        assert!(bestiary.find("gecko").is_none());
+    }
+
+    #[test]
+    fn test_encounters_follow_level_and_time() {
+        let bestiary = bestiary();
+        let low = bestiary.encounters_for("wasteland", 1, false);
+        assert!(low.iter().all(|e| e.min_level <= 1));
+        assert!(!low.iter().any(|e| e.id.starts_with("deathclaw")));
+        assert!(bestiary
+            .encounters_for("wasteland", 10, true)
+            .iter()
+            .any(|e| e.id == "deathclaw_night"));
+        assert!(!bestiary
+            .encounters_for("wasteland", 10, true)
+            .iter()
+            .any(|e| e.time == EncounterTime::Day));
+
+        // Unknown location types use the wasteland table
+        assert_eq!(
+            bestiary.encounters_for("crater", 3, false).len(),
+            bestiary
+                .encounters_for(DEFAULT_LOCATION_TYPE, 3, false)
+                .len()
+        );
+
+        let mut rng = GameRng::seeded(11);
+        for _ in 0..20 {
+            let encounter = bestiary
+                .roll_encounter("vault", 1, false, &mut rng)
+                .unwrap();
+            assert!(!encounter.enemies.is_empty());
+            assert!(encounter.enemies.iter().all(|e| e.level == 1));
+        }
+    }
+
+    #[test]
+    fn test_mods_add_enemies_and_encounters() {
+        let dir = std::env::temp_dir().join(format!("enemy_mods_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("glowing.toml"), GHOUL_MOD).unwrap();
+        fs::write(dir.join("broken.toml"), "[[enemy]]\nid = \"x\"").unwrap();
+
+        let modded = Bestiary::load(Path::new(ENEMY_DATA_DIR), &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(modded.enemy("glowing_one").is_some());
+        assert!(modded.enemy("x").is_none());
+        assert!(modded
+            .encounters_for("vault", 1, false)
+            .iter()
+            .any(|e| e.id == "glowing_vault"));
+
+        let mut rng = GameRng::seeded(5);
+        let encounter = (0..50)
+            .filter_map(|_| modded.roll_encounter("vault", 1, false, &mut rng))
+            .find(|e| e.enemies[0].name == "Glowing One")
+            .expect("the heavily weighted mod encounter comes up");
+        assert_eq!(encounter.enemies.len(), 3);
+    }
+
+    #[test]
+    fn test_invalid_definitions_rejected() {
+        let bad_dice = GHOUL_MOD.replace("2d6", "2d");
+        assert!(Bestiary::parse(&bad_dice, "test.toml").is_err());
+        let bad_location = GHOUL_MOD.replace("[\"vault\"]", "[\"moon\"]");
+        assert!(Bestiary::parse(&bad_location, "test.toml").is_err());
+        let no_enemies = GHOUL_MOD.replace(
+            "enemies = [{ id = \"glowing_one\" }, { id = \"feral_ghoul\", count = \"2\" }]",
+            "enemies = []",
+        );
+        assert!(Bestiary::parse(&no_enemies, "test.toml").is_err());
+    }
+}
@@ -6,7 +6,7 @@ use crate::ui::UI;
 use colored::*;
 
 /// Create a new character through interactive character creation
-pub fn create_new_character(_config: &Config) -> GameState {
+pub fn create_new_character(config: &Config) -> GameState {
     UI::clear_screen();
     UI::print_header();
 
@@ -45,5 +45,12 @@ pub fn create_new_character(_config: &Config) -> GameState {
     UI::print_character_sheet(&character);
     UI::wait_for_enter();
 
-    GameState::new(character)
+    match config.game.seed.as_deref() {
+        Some(seed) => {
+            let seed = crate::game::rng::parse_seed(seed);
+            tracing::info!("Starting new game with RNG seed {}", seed);
+            GameState::with_seed(character, seed)
+        }
+        None => GameState::new(character),
+    }
 }
@@ -9,11 +9,12 @@
 //! - **Skills** derived from SPECIAL stats (18 different skills including Small Guns, Speech, Science)
 //! - **Character progression** with leveling, XP, and stat increases
 //! - **Inventory management** for items and equipment
+//! - **Status effects** (radiation, hunger and thirst, chems) in [`StatusEffects`]
 //!
 //! ## SPECIAL System
 //!
 //! The SPECIAL system defines a character's core attributes:
-//! - **Strength**: Physical power, melee damage, carrying capacity
+//! - **Strength**: Physical power, melee damage, carrying capacity (see [`Character::carry_capacity`])
 //! - **Perception**: Awareness, accuracy, detecting hidden objects
 //! - **Endurance**: Health points, radiation resistance, stamina
 //! - **Charisma**: Speech effectiveness, barter prices, companion loyalty
@@ -33,6 +34,8 @@
 //!
 //! Characters gain experience points (XP) from combat and quests.
 //! Each level requires more XP: Level 2 needs 2000 XP, Level 3 needs 3000 XP, etc.
+//! Every level grants skill points (5 + 2 × Intelligence) to spend on [`Skills`],
+//! and every [`PERK_INTERVAL`] levels grants a pick from the [`Perk`] list.
 //!
 //! ## Example
 //!
@@ -50,7 +53,11 @@
 //! println!("Science skill: {}", character.skills.science);
 //! ```
 
-use super::items::Item;
+use super::items::armor::ArmorStats;
+use super::items::weapons::WeaponStats;
+use super::items::{Inventory, Item, ItemType, WeaponType};
+pub use super::perks::{Perk, PERK_INTERVAL};
+use super::status::{StatTarget, StatusEffects};
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
 
@@ -98,6 +105,112 @@ impl Special {
             + self.agility
             + self.luck
     }
+
+    /// Read a stat
+    pub fn get(&self, stat: Stat) -> u8 {
+        match stat {
+            Stat::Strength => self.strength,
+            Stat::Perception => self.perception,
+            Stat::Endurance => self.endurance,
+            Stat::Charisma => self.charisma,
+            Stat::Intelligence => self.intelligence,
+            Stat::Agility => self.agility,
+            Stat::Luck => self.luck,
+        }
+    }
+
+    /// Get a mutable reference to a stat
+    pub fn get_mut(&mut self, stat: Stat) -> &mut u8 {
+        match stat {
+            Stat::Strength => &mut self.strength,
+            Stat::Perception => &mut self.perception,
+            Stat::Endurance => &mut self.endurance,
+            Stat::Charisma => &mut self.charisma,
+            Stat::Intelligence => &mut self.intelligence,
+            Stat::Agility => &mut self.agility,
+            Stat::Luck => &mut self.luck,
+        }
+    }
+}
+
+/// One of the seven SPECIAL stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    Strength,
+    Perception,
+    Endurance,
+    Charisma,
+    Intelligence,
+    Agility,
+    Luck,
+}
+
+impl Stat {
+    /// Every stat, in S.P.E.C.I.A.L. order
+    pub const ALL: [Stat; 7] = [
+        Stat::Strength,
+        Stat::Perception,
+        Stat::Endurance,
+        Stat::Charisma,
+        Stat::Intelligence,
+        Stat::Agility,
+        Stat::Luck,
+    ];
+
+    /// Look up a stat by its three-letter token (case-insensitive)
+    pub fn from_token(token: &str) -> Option<Stat> {
+        match token.to_ascii_uppercase().as_str() {
+            "STR" => Some(Stat::Strength),
+            "PER" => Some(Stat::Perception),
+            "END" => Some(Stat::Endurance),
+            "CHA" => Some(Stat::Charisma),
+            "INT" => Some(Stat::Intelligence),
+            "AGI" => Some(Stat::Agility),
+            "LCK" | "LUK" => Some(Stat::Luck),
+            _ => None,
+        }
+    }
+
+    /// Look up a stat by its full name or token, e.g. "strength" or "STR"
+    pub fn from_name(name: &str) -> Option<Stat> {
+        let name = name.trim();
+        Stat::ALL
+            .into_iter()
+            .find(|stat| stat.name().eq_ignore_ascii_case(name))
+            .or_else(|| Stat::from_token(name))
+    }
+
+    /// Three-letter token used in dice expressions
+    pub fn token(&self) -> &'static str {
+        match self {
+            Stat::Strength => "STR",
+            Stat::Perception => "PER",
+            Stat::Endurance => "END",
+            Stat::Charisma => "CHA",
+            Stat::Intelligence => "INT",
+            Stat::Agility => "AGI",
+            Stat::Luck => "LCK",
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::Strength => "Strength",
+            Stat::Perception => "Perception",
+            Stat::Endurance => "Endurance",
+            Stat::Charisma => "Charisma",
+            Stat::Intelligence => "Intelligence",
+            Stat::Agility => "Agility",
+            Stat::Luck => "Luck",
+        }
+    }
+
+    /// Bonus this stat adds to a roll: half the stat, rounded down
+    pub fn modifier(&self, special: &Special) -> i32 {
+        (special.get(*self) / 2) as i32
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,7 +235,46 @@ pub struct Skills {
     pub outdoorsman: u8,
 }
 
+/// Maximum value a skill can be raised to with skill points
+pub const MAX_SKILL: u8 = 100;
+
+/// AP cost of an attack with no weapon equipped
+pub const UNARMED_AP_COST: i32 = 3;
+
+/// Carry capacity in pounds before Strength is added
+pub const BASE_CARRY_WEIGHT: f32 = 150.0;
+/// Carry capacity added per point of Strength
+pub const CARRY_WEIGHT_PER_STRENGTH: f32 = 10.0;
+/// Hard limit on carried weight, as a multiple of carry capacity
+pub const MAX_OVERLOAD: f32 = 1.5;
+/// AP lost each round while over-encumbered
+pub const ENCUMBERED_AP_PENALTY: i32 = 2;
+/// Penalty to Agility and Sneak checks while over-encumbered
+pub const ENCUMBERED_CHECK_PENALTY: i32 = 3;
+
 impl Skills {
+    /// Every skill as (ID, display name), in sheet order
+    pub const ALL: [(&'static str, &'static str); 18] = [
+        ("small_guns", "Small Guns"),
+        ("big_guns", "Big Guns"),
+        ("energy_weapons", "Energy Weapons"),
+        ("unarmed", "Unarmed"),
+        ("melee_weapons", "Melee Weapons"),
+        ("throwing", "Throwing"),
+        ("first_aid", "First Aid"),
+        ("doctor", "Doctor"),
+        ("sneak", "Sneak"),
+        ("lockpick", "Lockpick"),
+        ("steal", "Steal"),
+        ("traps", "Traps"),
+        ("science", "Science"),
+        ("repair", "Repair"),
+        ("speech", "Speech"),
+        ("barter", "Barter"),
+        ("gambling", "Gambling"),
+        ("outdoorsman", "Outdoorsman"),
+    ];
+
     pub fn from_special(special: &Special) -> Self {
         Skills {
             small_guns: 5 + (special.agility * 4),
@@ -147,7 +299,6 @@ impl Skills {
     }
 
     /// Get a skill value by name - useful for dynamic skill checks
-    #[allow(dead_code)]
     pub fn get_skill(&self, name: &str) -> u8 {
         match name {
             "small_guns" => self.small_guns,
@@ -171,6 +322,41 @@ impl Skills {
             _ => 0,
         }
     }
+
+    /// Get a mutable reference to a skill by name
+    pub fn get_skill_mut(&mut self, name: &str) -> Option<&mut u8> {
+        match name {
+            "small_guns" => Some(&mut self.small_guns),
+            "big_guns" => Some(&mut self.big_guns),
+            "energy_weapons" => Some(&mut self.energy_weapons),
+            "unarmed" => Some(&mut self.unarmed),
+            "melee_weapons" => Some(&mut self.melee_weapons),
+            "throwing" => Some(&mut self.throwing),
+            "first_aid" => Some(&mut self.first_aid),
+            "doctor" => Some(&mut self.doctor),
+            "sneak" => Some(&mut self.sneak),
+            "lockpick" => Some(&mut self.lockpick),
+            "steal" => Some(&mut self.steal),
+            "traps" => Some(&mut self.traps),
+            "science" => Some(&mut self.science),
+            "repair" => Some(&mut self.repair),
+            "speech" => Some(&mut self.speech),
+            "barter" => Some(&mut self.barter),
+            "gambling" => Some(&mut self.gambling),
+            "outdoorsman" => Some(&mut self.outdoorsman),
+            _ => None,
+        }
+    }
+}
+
+/// What changed when a character gained one or more levels
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelUpSummary {
+    pub old_level: u32,
+    pub new_level: u32,
+    pub hp_gained: i32,
+    pub skill_points_gained: u32,
+    pub perk_points_gained: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -185,11 +371,20 @@ pub struct Character {
     pub max_ap: i32,
     pub current_ap: i32,
     pub caps: u32,
-    pub inventory: Vec<Item>,
+    pub inventory: Inventory,
     pub equipped_weapon: Option<SmartString>,
     pub equipped_armor: Option<SmartString>,
     pub traits: Vec<SmartString>,
     pub perks: Vec<SmartString>,
+    /// Unspent skill points earned from leveling up
+    #[serde(default)]
+    pub skill_points: u32,
+    /// Unspent perk picks earned every `PERK_INTERVAL` levels
+    #[serde(default)]
+    pub perk_points: u32,
+    /// Radiation, hunger and thirst, chem buffs and addictions
+    #[serde(default)]
+    pub status: StatusEffects,
 }
 
 impl Character {
@@ -210,11 +405,14 @@ impl Character {
             max_ap,
             current_ap: max_ap,
             caps: 500,
-            inventory: super::items::get_starting_items(),
+            inventory: super::items::get_starting_items().into(),
             equipped_weapon: Some(SmartString::from("10mm_pistol")),
             equipped_armor: None,
             traits: Vec::new(),
             perks: Vec::new(),
+            skill_points: 0,
+            perk_points: 0,
+            status: StatusEffects::default(),
         }
     }
 
@@ -231,7 +429,17 @@ impl Character {
     }
 
     pub fn restore_ap(&mut self) {
-        self.current_ap = self.max_ap;
+        self.current_ap = self.effective_max_ap();
+    }
+
+    /// AP available each round: max AP, less [`ENCUMBERED_AP_PENALTY`] while
+    /// over-encumbered
+    pub fn effective_max_ap(&self) -> i32 {
+        if self.is_encumbered() {
+            (self.max_ap - ENCUMBERED_AP_PENALTY).max(1)
+        } else {
+            self.max_ap
+        }
     }
 
     pub fn use_ap(&mut self, amount: i32) -> bool {
@@ -247,64 +455,519 @@ impl Character {
         self.experience += xp;
     }
 
-    #[allow(dead_code)]
+    /// Whether the character has the XP for at least one more level
     pub fn can_level_up(&self) -> bool {
         let new_level = 1 + (self.experience / 1000);
         new_level > self.level
     }
 
-    #[allow(dead_code)]
-    pub fn level_up(&mut self) {
+    /// Apply any pending level ups: HP, skill points and perk picks.
+    /// Returns `None` if the character doesn't have enough XP to level.
+    pub fn level_up(&mut self) -> Option<LevelUpSummary> {
+        if !self.can_level_up() {
+            return None;
+        }
         let new_level = 1 + (self.experience / 1000);
-        if new_level > self.level {
-            let levels_gained = new_level - self.level;
-            for _ in 0..levels_gained {
-                self.max_hp += 5 + self.special.endurance as i32;
+
+        let old_level = self.level;
+        let old_max_hp = self.max_hp;
+        let mut skill_points_gained = 0;
+        let mut perk_points_gained = 0;
+        for level in (old_level + 1)..=new_level {
+            self.max_hp += self.hp_per_level();
+            skill_points_gained += self.skill_points_per_level();
+            if level % PERK_INTERVAL == 0 {
+                perk_points_gained += 1;
             }
-            self.level = new_level;
-            self.current_hp = self.max_hp;
         }
+        self.level = new_level;
+        self.current_hp = self.max_hp;
+        self.skill_points += skill_points_gained;
+        self.perk_points += perk_points_gained;
+
+        Some(LevelUpSummary {
+            old_level,
+            new_level,
+            hp_gained: self.max_hp - old_max_hp,
+            skill_points_gained,
+            perk_points_gained,
+        })
     }
 
-    pub fn get_equipped_damage(&self) -> SmartString {
-        if let Some(weapon_id) = &self.equipped_weapon {
-            // Find the weapon in inventory
-            if let Some(item) = self
-                .inventory
-                .iter()
-                .find(|i| i.id.as_str() == weapon_id.as_str())
-            {
-                // Extract damage if it's a weapon
-                if let super::items::ItemType::Weapon(ref stats) = item.item_type {
-                    return stats.damage.clone();
-                }
+    /// HP recovered per hour of rest: 1 + half Endurance
+    pub fn healing_rate(&self) -> i32 {
+        1 + self.special.endurance as i32 / 2
+    }
+
+    /// SPECIAL without chem buffs, withdrawal or sickness penalties
+    pub fn base_special(&self) -> Special {
+        self.status.base_special(&self.special)
+    }
+
+    /// Max HP gained per level: 5 + base Endurance (+ Lifegiver)
+    pub fn hp_per_level(&self) -> i32 {
+        let mut hp = 5 + self.base_special().endurance as i32;
+        if self.has_perk(Perk::Lifegiver) {
+            hp += Perk::LIFEGIVER_HP;
+        }
+        hp
+    }
+
+    /// Skill points gained per level: 5 + 2 × base Intelligence (+ Educated)
+    pub fn skill_points_per_level(&self) -> u32 {
+        let mut points = 5 + 2 * self.base_special().intelligence as u32;
+        if self.has_perk(Perk::Educated) {
+            points += Perk::EDUCATED_POINTS;
+        }
+        points
+    }
+
+    /// Spend one skill point on the named skill, returning its new value
+    pub fn spend_skill_point(&mut self, skill: &str) -> Result<u8, String> {
+        if self.skill_points == 0 {
+            return Err("No skill points to spend".to_string());
+        }
+        let value = self
+            .skills
+            .get_skill_mut(skill)
+            .ok_or_else(|| format!("Unknown skill '{}'", skill))?;
+        if *value >= MAX_SKILL {
+            return Err(format!("Skill is already at the maximum of {}", MAX_SKILL));
+        }
+        *value += 1;
+        let new_value = *value;
+        self.skill_points -= 1;
+        Ok(new_value)
+    }
+
+    /// Whether the character has picked the given perk
+    pub fn has_perk(&self, perk: Perk) -> bool {
+        self.perks.iter().any(|p| Perk::from_name(p) == Some(perk))
+    }
+
+    /// Perks the character could pick right now (ignoring perk points)
+    pub fn available_perks(&self) -> Vec<Perk> {
+        Perk::ALL
+            .iter()
+            .copied()
+            .filter(|perk| !self.has_perk(*perk) && perk.requirements_met(self))
+            .collect()
+    }
+
+    /// Spend a perk pick on the given perk and apply its immediate effects
+    pub fn choose_perk(&mut self, perk: Perk) -> Result<String, String> {
+        if self.perk_points == 0 {
+            return Err("No perk picks available".to_string());
+        }
+        if self.has_perk(perk) {
+            return Err(format!("You already have {}", perk.name()));
+        }
+        if !perk.requirements_met(self) {
+            return Err(format!(
+                "{} requires {}",
+                perk.name(),
+                perk.requirements_text()
+            ));
+        }
+
+        self.perk_points -= 1;
+        self.perks.push(SmartString::from(perk.id()));
+
+        match perk {
+            Perk::Lifegiver => {
+                let bonus = Perk::LIFEGIVER_HP * self.level as i32;
+                self.max_hp += bonus;
+                self.current_hp += bonus;
+            }
+            Perk::ActionBoy => {
+                self.max_ap += Perk::ACTION_BOY_AP;
+                self.current_ap += Perk::ACTION_BOY_AP;
             }
+            _ => {}
+        }
+
+        Ok(format!("Gained perk: {}", perk.name()))
+    }
+
+    /// Extra damage from perks for the currently equipped weapon
+    pub fn damage_bonus(&self) -> i32 {
+        let perk = match self.equipped_weapon_type() {
+            Some(WeaponType::SmallGun | WeaponType::BigGun | WeaponType::EnergyWeapon) => {
+                Perk::BonusRangedDamage
+            }
+            Some(WeaponType::MeleeWeapon | WeaponType::Unarmed) | None => Perk::BonusHthDamage,
+        };
+        if self.has_perk(perk) {
+            Perk::BONUS_DAMAGE
+        } else {
+            0
+        }
+    }
+
+    /// Lowest natural d20 roll that counts as a critical hit
+    pub fn critical_threshold(&self) -> i32 {
+        if self.has_perk(Perk::MoreCriticals) {
+            19
+        } else {
+            20
+        }
+    }
+
+    /// Damage multiplier applied on critical hits
+    pub fn critical_multiplier(&self) -> i32 {
+        if self.has_perk(Perk::BetterCriticals) {
+            3
+        } else {
+            2
+        }
+    }
+
+    /// Flat reduction applied to every incoming hit
+    pub fn damage_reduction(&self) -> i32 {
+        if self.has_perk(Perk::Toughness) {
+            Perk::TOUGHNESS_REDUCTION
+        } else {
+            0
+        }
+    }
+
+    /// Perk bonus (and encumbrance penalty) to a skill check, keyed by the
+    /// skill's display name
+    pub fn roll_bonus(&self, skill_name: &str) -> i32 {
+        let encumbrance = match skill_name {
+            "Agility" | "Sneak" if self.is_encumbered() => -ENCUMBERED_CHECK_PENALTY,
+            _ => 0,
+        };
+        let perk = match skill_name {
+            "Speech" | "Barter" => Perk::Negotiator,
+            "Sneak" | "Lockpick" | "Steal" => Perk::Thief,
+            _ => return encumbrance,
+        };
+        if self.has_perk(perk) {
+            encumbrance + Perk::SKILL_CHECK_BONUS
+        } else {
+            encumbrance
+        }
+    }
+
+    /// Damage dice of the equipped weapon, with any mod damage bonus added
+    pub fn get_equipped_damage(&self) -> SmartString {
+        if let Some(stats) = self.equipped_weapon_stats() {
+            return stats.modded_damage();
         }
         // Default unarmed damage
         SmartString::from("1d4")
     }
 
-    /// Get the appropriate combat skill for the currently equipped weapon
+    /// Whether this inventory item is the equipped weapon or armor
+    pub fn is_equipped(&self, item: &Item) -> bool {
+        let key = item.key();
+        self.equipped_weapon.as_ref() == Some(&key) || self.equipped_armor.as_ref() == Some(&key)
+    }
+
+    /// Get the stats of the currently equipped weapon, if any
+    pub fn equipped_weapon_stats(&self) -> Option<&WeaponStats> {
+        let index = self
+            .inventory
+            .position_by_key(self.equipped_weapon.as_ref()?)?;
+        match self.inventory[index].item_type {
+            ItemType::Weapon(ref stats) => Some(stats),
+            _ => None,
+        }
+    }
+
+    /// Get the stats of the currently worn armor, if any
+    pub fn equipped_armor_stats(&self) -> Option<&ArmorStats> {
+        let index = self
+            .inventory
+            .position_by_key(self.equipped_armor.as_ref()?)?;
+        match self.inventory[index].item_type {
+            ItemType::Armor(ref stats) => Some(stats),
+            _ => None,
+        }
+    }
+
+    fn equipped_weapon_stats_mut(&mut self) -> Option<&mut WeaponStats> {
+        let index = self.equipped_single(false)?;
+        match self.inventory[index].item_type {
+            ItemType::Weapon(ref mut stats) => Some(stats),
+            _ => None,
+        }
+    }
+
+    /// Get the weapon type of the currently equipped weapon, if any
+    pub fn equipped_weapon_type(&self) -> Option<WeaponType> {
+        self.equipped_weapon_stats()
+            .map(|stats| stats.weapon_type.clone())
+    }
+
+    /// AP needed to attack with the equipped weapon (or fists)
+    pub fn attack_ap_cost(&self) -> i32 {
+        self.equipped_weapon_stats()
+            .map_or(UNARMED_AP_COST, |stats| stats.ap_cost)
+    }
+
+    /// Rounds of the given ammo type carried in the inventory
+    pub fn ammo_count(&self, ammo_type: &str) -> u32 {
+        self.item_count(ammo_type)
+    }
+
+    /// How many of an item (by ID) are carried in the inventory
+    pub fn item_count(&self, id: &str) -> u32 {
+        self.inventory
+            .iter()
+            .filter(|item| item.id == id)
+            .map(|item| item.quantity)
+            .sum()
+    }
+
+    /// Spend one loaded round from the equipped weapon.
+    ///
+    /// Returns `false` if the weapon uses ammo and its magazine is empty.
+    /// Weapons without ammo always fire.
+    pub fn consume_round(&mut self) -> bool {
+        match self.equipped_weapon_stats_mut() {
+            Some(stats) if stats.uses_ammo() => {
+                if stats.loaded_rounds == 0 {
+                    return false;
+                }
+                stats.loaded_rounds -= 1;
+                true
+            }
+            _ => true,
+        }
+    }
+
+    /// Wear down the equipped weapon after an attack
+    pub fn wear_weapon(&mut self) {
+        if let Some(stats) = self.equipped_weapon_stats_mut() {
+            stats.wear();
+        }
+    }
+
+    /// Inventory index of the equipped weapon (or armor), split off its
+    /// stack first so wear and loaded rounds only change the one in hand
+    fn equipped_single(&mut self, armor: bool) -> Option<usize> {
+        let slot = if armor {
+            &mut self.equipped_armor
+        } else {
+            &mut self.equipped_weapon
+        };
+        let index = self.inventory.position_by_key(slot.as_ref()?)?;
+        let index = self.inventory.single_out(index);
+        *slot = Some(self.inventory[index].key());
+        Some(index)
+    }
+
+    /// Wear down the equipped armor after taking a hit
+    pub fn wear_armor(&mut self) {
+        let Some(index) = self.equipped_single(true) else {
+            return;
+        };
+        if let ItemType::Armor(ref mut stats) = self.inventory[index].item_type {
+            stats.wear();
+        }
+    }
+
+    /// Refill the equipped weapon's magazine from inventory ammo.
+    ///
+    /// Returns the number of rounds loaded. Doesn't spend AP; the caller
+    /// charges `RELOAD_AP_COST` so reloading outside combat stays free.
+    pub fn reload_weapon(&mut self) -> Result<u32, String> {
+        let stats = self
+            .equipped_weapon_stats()
+            .ok_or_else(|| "You don't have a weapon equipped".to_string())?;
+        let ammo_type = match &stats.ammo_type {
+            Some(ammo_type) if stats.uses_ammo() => ammo_type.clone(),
+            _ => return Err("Your weapon doesn't use ammunition".to_string()),
+        };
+        let needed = stats.capacity().saturating_sub(stats.loaded_rounds);
+        if needed == 0 {
+            return Err("Your weapon is already fully loaded".to_string());
+        }
+
+        let loaded = self.remove_item_quantity(&ammo_type, needed);
+        if loaded == 0 {
+            return Err(format!("You're out of {}", ammo_type));
+        }
+        if let Some(stats) = self.equipped_weapon_stats_mut() {
+            stats.loaded_rounds += loaded;
+        }
+        Ok(loaded)
+    }
+
+    /// Remove up to `quantity` of an item from the inventory, dropping empty
+    /// stacks. Returns how many were actually removed.
+    pub fn remove_item_quantity(&mut self, id: &str, quantity: u32) -> u32 {
+        let mut remaining = quantity;
+        for item in self.inventory.iter_mut().filter(|item| item.id == id) {
+            let taken = item.quantity.min(remaining);
+            item.quantity -= taken;
+            remaining -= taken;
+        }
+        self.inventory
+            .retain(|item| item.id != id || item.quantity > 0);
+        quantity - remaining
+    }
+
+    /// Get the appropriate combat skill for the currently equipped weapon,
+    /// with any to-hit bonus from its mods
     pub fn get_weapon_skill(&self) -> u8 {
-        if let Some(weapon_id) = &self.equipped_weapon {
-            if let Some(item) = self
+        let skill = match self.equipped_weapon_type() {
+            Some(WeaponType::SmallGun) => self.skills.small_guns,
+            Some(WeaponType::BigGun) => self.skills.big_guns,
+            Some(WeaponType::EnergyWeapon) => self.skills.energy_weapons,
+            Some(WeaponType::MeleeWeapon) => self.skills.melee_weapons,
+            // Default to unarmed skill
+            Some(WeaponType::Unarmed) | None => self.skills.unarmed,
+        };
+        let bonus = self
+            .equipped_weapon_stats()
+            .map_or(0, |stats| stats.to_hit_bonus());
+        (skill as i32 + bonus).clamp(0, u8::MAX as i32) as u8
+    }
+
+    /// Inventory indexes of the mods that fit the weapon at `weapon_index`
+    pub fn compatible_mods(&self, weapon_index: usize) -> Vec<usize> {
+        let Some(weapon) = self.inventory.get(weapon_index).and_then(Item::as_weapon) else {
+            return Vec::new();
+        };
+        self.inventory
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| match &item.item_type {
+                ItemType::Mod(weapon_mod) => weapon_mod.fits(weapon),
+                _ => false,
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Attach the mod at `mod_index` to the weapon at `weapon_index`.
+    ///
+    /// The weapon is taken off its stack and given its own instance number
+    /// (staying equipped if it was), and a mod already in the same slot goes
+    /// back into the inventory.
+    pub fn attach_mod(&mut self, weapon_index: usize, mod_index: usize) -> Result<String, String> {
+        let weapon = self
+            .inventory
+            .get(weapon_index)
+            .filter(|item| item.as_weapon().is_some())
+            .ok_or_else(|| "That isn't a weapon".to_string())?;
+        let mod_item = self
+            .inventory
+            .get(mod_index)
+            .filter(|item| item.mod_slot().is_some())
+            .ok_or_else(|| "That isn't a weapon mod".to_string())?;
+        if !self.compatible_mods(weapon_index).contains(&mod_index) {
+            return Err(format!("{} doesn't fit the {}", mod_item.name, weapon.name));
+        }
+        let (weapon_name, mod_name) = (weapon.name.clone(), mod_item.name.clone());
+        let old_key = weapon.key();
+        let instance = self.inventory.next_instance(&weapon.id);
+
+        // Give the weapon its own slot, splitting it off a stack if needed
+        let weapon_key = if weapon.quantity > 1 {
+            let mut single = self
                 .inventory
-                .iter()
-                .find(|i| i.id.as_str() == weapon_id.as_str())
-            {
-                if let super::items::ItemType::Weapon(ref stats) = item.item_type {
-                    return match stats.weapon_type {
-                        super::items::WeaponType::SmallGun => self.skills.small_guns,
-                        super::items::WeaponType::BigGun => self.skills.big_guns,
-                        super::items::WeaponType::EnergyWeapon => self.skills.energy_weapons,
-                        super::items::WeaponType::MeleeWeapon => self.skills.melee_weapons,
-                        super::items::WeaponType::Unarmed => self.skills.unarmed,
-                    };
-                }
+                .split(weapon_index, 1)
+                .expect("stack has more than one");
+            single.instance = instance;
+            let key = single.key();
+            self.inventory.add(single);
+            key
+        } else {
+            let weapon = &mut self.inventory[weapon_index];
+            if weapon.instance == 0 {
+                weapon.instance = instance;
             }
+            weapon.key()
+        };
+        if self.equipped_weapon.as_ref() == Some(&old_key) {
+            self.equipped_weapon = Some(weapon_key.clone());
+        }
+
+        let mod_item = self
+            .inventory
+            .split(mod_index, 1)
+            .expect("checked mod stack");
+        let index = self
+            .inventory
+            .position_by_key(&weapon_key)
+            .expect("weapon still carried");
+        let ItemType::Weapon(ref mut stats) = self.inventory[index].item_type else {
+            unreachable!("checked weapon");
+        };
+        let replaced = stats.attach(mod_item);
+
+        let mut message = format!("Attached {} to your {}", mod_name, weapon_name);
+        if let Some(replaced) = replaced {
+            message.push_str(&format!(" ({} back in your pack)", replaced.name));
+            self.inventory.add(replaced);
         }
-        // Default to unarmed skill
-        self.skills.unarmed
+        Ok(message)
+    }
+
+    /// Take the `slot_index`-th mod off the weapon at `weapon_index`, back
+    /// into the inventory along with any rounds the magazine can no longer
+    /// hold
+    pub fn detach_mod(&mut self, weapon_index: usize, slot_index: usize) -> Result<String, String> {
+        let weapon = self
+            .inventory
+            .get_mut(weapon_index)
+            .ok_or_else(|| "That isn't a weapon".to_string())?;
+        let weapon_name = weapon.name.clone();
+        let ItemType::Weapon(ref mut stats) = weapon.item_type else {
+            return Err("That isn't a weapon".to_string());
+        };
+        let ammo_type = stats.ammo_type.clone();
+        let (mod_item, unloaded) = stats
+            .detach(slot_index)
+            .ok_or_else(|| format!("Your {} has no mods attached", weapon_name))?;
+
+        let message = format!("Removed {} from your {}", mod_item.name, weapon_name);
+        self.inventory.add(mod_item);
+        if let Some(mut ammo) = ammo_type
+            .filter(|_| unloaded > 0)
+            .and_then(|ammo_type| super::items::find_item(&ammo_type))
+        {
+            ammo.quantity = unloaded;
+            self.inventory.add(ammo);
+        }
+        Ok(message)
+    }
+
+    /// Add an item to inventory, stacking onto an existing item with the same ID
+    pub fn add_item(&mut self, item: Item) {
+        self.inventory.add(item);
+    }
+
+    /// Total weight of everything in the inventory, in pounds
+    pub fn carry_weight(&self) -> f32 {
+        self.inventory.weight()
+    }
+
+    /// Weight that can be carried without being over-encumbered:
+    /// [`BASE_CARRY_WEIGHT`] plus [`CARRY_WEIGHT_PER_STRENGTH`] per point of
+    /// Strength
+    pub fn carry_capacity(&self) -> f32 {
+        BASE_CARRY_WEIGHT + CARRY_WEIGHT_PER_STRENGTH * self.special.strength as f32
+    }
+
+    /// Weight beyond which nothing more can be picked up
+    pub fn max_carry_weight(&self) -> f32 {
+        self.carry_capacity() * MAX_OVERLOAD
+    }
+
+    /// Carrying more than the carry capacity: fewer AP, no travel and
+    /// harder Agility checks
+    pub fn is_encumbered(&self) -> bool {
+        self.carry_weight() > self.carry_capacity()
+    }
+
+    /// Whether `weight` more pounds stay within [`Character::max_carry_weight`]
+    pub fn can_carry(&self, weight: f32) -> bool {
+        self.carry_weight() + weight <= self.max_carry_weight()
     }
 
     /// Find an item in inventory by its ID - helper for item lookups
@@ -313,6 +976,28 @@ impl Character {
         self.inventory.iter().find(|item| item.id == id)
     }
 
+    /// Absorb radiation, returning any change in radiation sickness
+    pub fn add_rads(&mut self, rads: u32) -> Vec<String> {
+        self.status
+            .add_rads(rads, &mut self.special, &mut self.skills)
+    }
+
+    /// Be exposed to radiation from the world (fallout, glowing water, a
+    /// leaking reactor). Worn armor blocks its rad resistance in percent.
+    /// Returns the rads absorbed and any change in radiation sickness.
+    pub fn expose_to_radiation(&mut self, rads: u32) -> (u32, Vec<String>) {
+        let block = self.equipped_armor_stats().map_or(0, ArmorStats::rad_block);
+        let absorbed = rads - rads * block / 100;
+        (absorbed, self.add_rads(absorbed))
+    }
+
+    /// Advance status effects by a number of combat rounds, returning
+    /// anything the player should be told (effects wearing off, withdrawal)
+    pub fn tick_status(&mut self, rounds: u32) -> Vec<String> {
+        self.status
+            .tick(rounds, &mut self.special, &mut self.skills)
+    }
+
     pub fn use_consumable(&mut self, item_id: &str) -> Result<String, String> {
         // Find the item
         let item_index = self
@@ -331,32 +1016,80 @@ impl Character {
         };
 
         // Apply the effect
-        let message = match effect {
+        let mut messages = Vec::new();
+        match effect {
             super::items::ConsumableEffect::Healing(amount) => {
                 self.heal(amount);
-                format!("Healed {} HP", amount)
+                messages.push(format!("Healed {} HP", amount));
             }
             super::items::ConsumableEffect::RadAway(amount) => {
-                format!("Removed {} rads", amount)
+                let removed = self.status.rads.min(amount.max(0) as u32);
+                messages.push(format!("Removed {} rads", removed));
+                messages.extend(self.status.remove_rads(
+                    removed,
+                    &mut self.special,
+                    &mut self.skills,
+                ));
             }
             super::items::ConsumableEffect::StatBuff {
                 stat,
                 amount,
                 duration,
+                addictiveness,
             } => {
-                format!("Gained +{} {} for {} rounds", amount, stat, duration)
+                let target = StatTarget::parse(&stat)
+                    .ok_or_else(|| format!("Unknown stat '{}' on {}", stat, item_id))?;
+                let name = self.inventory[item_index].name.clone();
+                messages.push(format!(
+                    "Gained {:+} {} for {} rounds",
+                    amount,
+                    target.name(),
+                    duration
+                ));
+                messages.extend(self.status.take_chem(
+                    &name,
+                    item_id,
+                    target.clone(),
+                    amount,
+                    addictiveness,
+                    &mut self.special,
+                    &mut self.skills,
+                ));
+                self.status.add_effect(
+                    &name,
+                    item_id,
+                    target,
+                    amount,
+                    duration,
+                    &mut self.special,
+                    &mut self.skills,
+                );
+            }
+            super::items::ConsumableEffect::Nourishment { food, water, rads } => {
+                messages.push(match (food, water) {
+                    (0, _) => "Quenched your thirst".to_string(),
+                    (_, 0) => "Ate a meal".to_string(),
+                    _ => "Ate and drank".to_string(),
+                });
+                messages.extend(self.status.nourish(
+                    food,
+                    water,
+                    &mut self.special,
+                    &mut self.skills,
+                ));
+                if rads > 0 {
+                    messages.push(format!("+{} rads", rads));
+                    messages.extend(self.add_rads(rads));
+                }
             }
             super::items::ConsumableEffect::Addiction { effect } => {
-                format!("Warning: {}", effect)
+                messages.push(format!("Warning: {}", effect));
             }
-        };
-
-        // Decrease quantity
-        if self.inventory[item_index].quantity > 1 {
-            self.inventory[item_index].quantity -= 1;
-        } else {
-            self.inventory.remove(item_index);
         }
+        let message = messages.join(". ");
+
+        // Use up one of the stack
+        self.inventory.split(item_index, 1);
 
         Ok(message)
     }
@@ -487,6 +1220,126 @@ mod tests {
         assert!(character.max_hp > 0);
     }
 
+    #[test]
+    fn test_level_up_grants_skill_and_perk_points() {
+        let mut special = Special::new();
+        special.intelligence = 6;
+        let mut character = Character::new("Test", special);
+
+        assert!(character.level_up().is_none());
+
+        character.add_experience(2000);
+        let summary = character.level_up().unwrap();
+        assert_eq!(summary.old_level, 1);
+        assert_eq!(summary.new_level, 3);
+        // 5 + 2 * INT per level, two levels gained
+        assert_eq!(summary.skill_points_gained, 34);
+        assert_eq!(character.skill_points, 34);
+        // Level 3 is the first perk level
+        assert_eq!(summary.perk_points_gained, 1);
+        assert_eq!(character.perk_points, 1);
+    }
+
+    #[test]
+    fn test_level_up_while_buffed_uses_base_special() {
+        let mut special = Special::new();
+        special.intelligence = 6;
+        special.endurance = 6;
+        let mut character = Character::new("Test", special);
+        character.add_item(crate::game::items::find_item("mentats").unwrap());
+        character.use_consumable("mentats").unwrap();
+        character.add_rads(400);
+        assert_eq!(character.special.intelligence, 8);
+        assert!(character.special.endurance < 6);
+
+        character.add_experience(1000);
+        let summary = character.level_up().unwrap();
+        // 5 + 2 * INT 6, and 5 + END 6, as if sober and healthy
+        assert_eq!(summary.skill_points_gained, 17);
+        assert_eq!(summary.hp_gained, 11);
+    }
+
+    #[test]
+    fn test_spend_skill_point() {
+        let mut character = Character::new("Test", Special::new());
+        assert!(character.spend_skill_point("science").is_err());
+
+        character.skill_points = 2;
+        let before = character.skills.science;
+        assert_eq!(character.spend_skill_point("science"), Ok(before + 1));
+        assert_eq!(character.skill_points, 1);
+        assert!(character.spend_skill_point("basket_weaving").is_err());
+        assert_eq!(character.skill_points, 1);
+
+        character.skills.science = MAX_SKILL;
+        assert!(character.spend_skill_point("science").is_err());
+    }
+
+    #[test]
+    fn test_choose_perk_applies_effects() {
+        let mut special = Special::new();
+        special.agility = 6;
+        let mut character = Character::new("Test", special);
+        character.level = 6;
+
+        assert!(character.choose_perk(Perk::ActionBoy).is_err()); // No picks yet
+
+        character.perk_points = 2;
+        let max_ap = character.max_ap;
+        character.choose_perk(Perk::ActionBoy).unwrap();
+        assert_eq!(character.max_ap, max_ap + Perk::ACTION_BOY_AP);
+        assert!(character.has_perk(Perk::ActionBoy));
+        assert!(!character.available_perks().contains(&Perk::ActionBoy));
+        assert!(character.choose_perk(Perk::ActionBoy).is_err());
+
+        let max_hp = character.max_hp;
+        character.choose_perk(Perk::Lifegiver).unwrap();
+        assert_eq!(character.max_hp, max_hp + Perk::LIFEGIVER_HP * 6);
+        assert_eq!(character.perk_points, 0);
+    }
+
+    #[test]
+    fn test_firing_and_reloading() {
+        let mut character = Character::new("Test", Special::new());
+        assert_eq!(character.attack_ap_cost(), 4);
+        assert_eq!(character.ammo_count("10mm_ammo"), 24);
+
+        // Empty the starting pistol's 12-round magazine
+        for _ in 0..12 {
+            assert!(character.consume_round());
+        }
+        assert!(!character.consume_round());
+        assert!(character.equipped_weapon_stats().unwrap().is_empty());
+
+        assert_eq!(character.reload_weapon(), Ok(12));
+        assert_eq!(character.ammo_count("10mm_ammo"), 12);
+        assert!(character.reload_weapon().is_err()); // Already full
+
+        for _ in 0..12 {
+            character.consume_round();
+        }
+        assert_eq!(character.reload_weapon(), Ok(12));
+        // The ammo stack is used up and removed from the inventory
+        assert!(character.find_item_by_id("10mm_ammo").is_none());
+
+        character.consume_round();
+        assert!(character.reload_weapon().is_err());
+    }
+
+    #[test]
+    fn test_melee_weapons_never_run_dry() {
+        let mut character = Character::new("Test", Special::new());
+        character.equipped_weapon = Some("baseball_bat".into());
+        assert_eq!(character.attack_ap_cost(), 3);
+        for _ in 0..50 {
+            assert!(character.consume_round());
+        }
+        assert!(character.reload_weapon().is_err());
+
+        character.equipped_weapon = None;
+        assert_eq!(character.attack_ap_cost(), UNARMED_AP_COST);
+    }
+
     #[test]
     fn test_get_equipped_damage() {
         let special = Special::new();
@@ -510,6 +1363,64 @@ mod tests {
         assert!(item.is_none());
     }
 
+    #[test]
+    fn test_add_item_stacks_by_id() {
+        let special = Special::new();
+        let mut character = Character::new("Test".to_string(), special);
+        let count = character.inventory.len();
+
+        character.add_item(crate::game::items::find_item("stimpak").unwrap());
+        assert_eq!(character.inventory.len(), count);
+        assert_eq!(character.find_item_by_id("stimpak").unwrap().quantity, 2);
+
+        character.add_item(crate::game::items::find_item("nuka_cola").unwrap());
+        assert_eq!(character.inventory.len(), count + 1);
+    }
+
+    #[test]
+    fn test_wear_only_changes_the_equipped_copy() {
+        let mut character = Character::new("Test".to_string(), Special::new());
+        character.inventory.clear();
+        let mut bats = crate::game::items::find_item("baseball_bat").unwrap();
+        bats.quantity = 2;
+        character.add_item(bats);
+        character.equipped_weapon = Some("baseball_bat".into());
+
+        character.wear_weapon();
+        let equipped = character.equipped_weapon.clone().unwrap();
+        assert_eq!(equipped, "baseball_bat#1");
+        assert_eq!(character.equipped_weapon_stats().unwrap().condition, 99);
+        let spare = character.find_item_by_id("baseball_bat").unwrap();
+        assert_eq!((spare.quantity, spare.condition()), (1, Some(100)));
+    }
+
+    #[test]
+    fn test_encumbrance() {
+        let mut special = Special::new();
+        special.strength = 5;
+        special.agility = 6;
+        let mut character = Character::new("Test".to_string(), special);
+        character.inventory.clear();
+        assert_eq!(character.carry_capacity(), 200.0);
+        assert_eq!(character.max_carry_weight(), 300.0);
+        assert!(!character.is_encumbered());
+        assert_eq!(character.roll_bonus("Agility"), 0);
+
+        character.add_item(Item::new_misc("anvil", "Anvil", "Heavy.", 210.0, 5));
+        assert_eq!(character.carry_weight(), 210.0);
+        assert!(character.is_encumbered());
+        assert!(character.can_carry(90.0));
+        assert!(!character.can_carry(91.0));
+
+        character.restore_ap();
+        assert_eq!(
+            character.current_ap,
+            character.max_ap - ENCUMBERED_AP_PENALTY
+        );
+        assert_eq!(character.roll_bonus("Agility"), -ENCUMBERED_CHECK_PENALTY);
+        assert_eq!(character.roll_bonus("Speech"), 0);
+    }
+
     #[test]
     fn test_use_consumable_healing() {
         let special = Special::new();
@@ -523,6 +1434,52 @@ mod tests {
         assert!(character.current_hp > hp_before);
     }
 
+    #[test]
+    fn test_use_consumable_applies_status_effects() {
+        let mut special = Special::new();
+        special.strength = 5;
+        special.endurance = 5;
+        let mut character = Character::new("Test".to_string(), special);
+
+        character.add_rads(300);
+        assert_eq!(character.special.endurance, 4);
+        let message = character.use_consumable("radaway").unwrap();
+        assert!(message.starts_with("Removed 50 rads"));
+        assert_eq!(character.status.rads, 250);
+
+        character.add_item(crate::game::items::find_item("buffout").unwrap());
+        let message = character.use_consumable("buffout").unwrap();
+        assert_eq!(message, "Gained +2 Strength for 300 rounds");
+        assert_eq!(character.special.strength, 7);
+
+        let messages = character.tick_status(300);
+        assert!(messages.contains(&"Buffout wore off.".to_string()));
+        assert_eq!(character.special.strength, 5);
+    }
+
+    #[test]
+    fn test_armor_blocks_radiation_exposure() {
+        let mut special = Special::new();
+        special.endurance = 5;
+        let mut character = Character::new("Test", special);
+
+        let (absorbed, messages) = character.expose_to_radiation(250);
+        assert_eq!(absorbed, 250);
+        assert_eq!(
+            messages,
+            vec!["You are suffering from Minor Radiation Sickness."]
+        );
+        assert_eq!(character.special.endurance, 4);
+
+        character.add_item(crate::game::items::find_item("power_armor_t51b").unwrap());
+        character.equipped_armor = Some("power_armor_t51b".into());
+        let block = character.equipped_armor_stats().unwrap().rad_block();
+        assert!(block > 0);
+        let (absorbed, _) = character.expose_to_radiation(100);
+        assert_eq!(absorbed, 100 - block);
+        assert_eq!(character.status.rads, 250 + absorbed);
+    }
+
     #[test]
     fn test_use_consumable_not_found() {
         let special = Special::new();
@@ -0,0 +1,210 @@
+//! # Clock Module
+//!
+//! In-game time of day and the game calendar.
+//!
+//! `GameState` keeps the day counter and a [`TimeOfDay`];
+//! `GameState::pass_time` moves both forward and lets status effects (chems,
+//! hunger and thirst) run for the same span.
+//!
+//! ## What Passes Time
+//!
+//! - Travel: every `move_to` takes [`TRAVEL_MINUTES`]
+//! - Resting or waiting: `rest [hours]` heals and passes whole hours
+//! - Combat: each round is a tenth of a minute
+//! - DM time skips: the `pass_time` action
+//!
+//! ## Day and Night
+//!
+//! The day is split into [`DayPeriod`]s. Night brings different random
+//! encounters and makes Perception checks harder.
+//!
+//! ## Timestamps
+//!
+//! Worldbook timestamps are RFC 3339 dates on the game calendar, where day 1
+//! is [`CALENDAR_START`], so they sort in game order and survive save/load.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Minutes in a day
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+/// A new game starts at 08:00
+pub const START_TIME: TimeOfDay = TimeOfDay { minutes: 8 * 60 };
+/// Minutes spent travelling to a new location
+pub const TRAVEL_MINUTES: u32 = 60;
+/// Longest single rest
+pub const MAX_REST_HOURS: u32 = 24;
+/// Calendar date of day 1 (year, month, day)
+pub const CALENDAR_START: (i32, u32, u32) = (2277, 10, 23);
+
+/// Time of day, to the minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    /// Minutes since midnight
+    minutes: u32,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        START_TIME
+    }
+}
+
+impl TimeOfDay {
+    #[allow(dead_code)] // Public API for integration tests
+    pub fn new(hour: u32, minute: u32) -> Self {
+        TimeOfDay {
+            minutes: (hour * 60 + minute) % MINUTES_PER_DAY,
+        }
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.minutes / 60
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minutes % 60
+    }
+
+    pub fn period(&self) -> DayPeriod {
+        match self.hour() {
+            5..=6 => DayPeriod::Dawn,
+            7..=18 => DayPeriod::Day,
+            19..=20 => DayPeriod::Dusk,
+            _ => DayPeriod::Night,
+        }
+    }
+
+    /// Move forward, returning how many times midnight passed
+    pub fn advance(&mut self, minutes: u32) -> u32 {
+        let total = self.minutes as u64 + minutes as u64;
+        self.minutes = (total % MINUTES_PER_DAY as u64) as u32;
+        (total / MINUTES_PER_DAY as u64) as u32
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
+
+/// Part of the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPeriod {
+    /// 05:00-06:59
+    Dawn,
+    /// 07:00-18:59
+    Day,
+    /// 19:00-20:59
+    Dusk,
+    /// 21:00-04:59
+    Night,
+}
+
+impl DayPeriod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DayPeriod::Dawn => "dawn",
+            DayPeriod::Day => "day",
+            DayPeriod::Dusk => "dusk",
+            DayPeriod::Night => "night",
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        *self == DayPeriod::Night
+    }
+
+    /// Modifier to Perception checks: it's harder to spot things in the dark
+    pub fn perception_modifier(&self) -> i32 {
+        match self {
+            DayPeriod::Day => 0,
+            DayPeriod::Dawn | DayPeriod::Dusk => -1,
+            DayPeriod::Night => -3,
+        }
+    }
+
+    /// Message shown when this period begins
+    pub fn arrival_message(&self) -> &'static str {
+        match self {
+            DayPeriod::Dawn => "Dawn breaks over the wasteland.",
+            DayPeriod::Day => "The sun is up.",
+            DayPeriod::Dusk => "The sun sets.",
+            DayPeriod::Night => "Night falls.",
+        }
+    }
+}
+
+fn calendar_start() -> NaiveDateTime {
+    let (year, month, day) = CALENDAR_START;
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("calendar start is a valid date")
+}
+
+/// RFC 3339 timestamp for a moment on the game calendar
+pub fn timestamp(day: u32, time: TimeOfDay) -> String {
+    let moment =
+        calendar_start() + Duration::days(day as i64 - 1) + Duration::minutes(time.minutes as i64);
+    moment.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Timestamp of the moment a new game starts
+pub fn start_timestamp() -> String {
+    timestamp(1, START_TIME)
+}
+
+/// Read a game calendar timestamp back into a day and time.
+/// Returns `None` for anything that isn't on the calendar, such as wall-clock
+/// timestamps from older saves.
+pub fn parse_timestamp(timestamp: &str) -> Option<(u32, TimeOfDay)> {
+    let moment = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()?
+        .naive_utc();
+    let minutes = (moment - calendar_start()).num_minutes();
+    if minutes < 0 {
+        return None;
+    }
+    let day = u32::try_from(minutes / MINUTES_PER_DAY as i64 + 1).ok()?;
+    let time = TimeOfDay {
+        minutes: (minutes % MINUTES_PER_DAY as i64) as u32,
+    };
+    Some((day, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_wraps_midnight() {
+        let mut time = TimeOfDay::new(22, 30);
+        assert_eq!(time.advance(60), 0);
+        assert_eq!(time.to_string(), "23:30");
+        assert_eq!(time.advance(MINUTES_PER_DAY + 45), 2);
+        assert_eq!(time.to_string(), "00:15");
+    }
+
+    #[test]
+    fn test_periods() {
+        assert_eq!(TimeOfDay::new(5, 0).period(), DayPeriod::Dawn);
+        assert_eq!(START_TIME.period(), DayPeriod::Day);
+        assert_eq!(TimeOfDay::new(19, 59).period(), DayPeriod::Dusk);
+        assert!(TimeOfDay::new(3, 0).period().is_night());
+        assert!(TimeOfDay::new(21, 0).period().is_night());
+    }
+
+    #[test]
+    fn test_timestamps_round_trip_and_sort() {
+        assert_eq!(start_timestamp(), "2277-10-23T08:00:00Z");
+        let later = timestamp(12, TimeOfDay::new(6, 5));
+        assert_eq!(later, "2277-11-03T06:05:00Z");
+        assert!(later.as_str() > start_timestamp().as_str());
+
+        assert_eq!(parse_timestamp(&later), Some((12, TimeOfDay::new(6, 5))));
+        assert_eq!(parse_timestamp("2025-01-01T10:00:00+00:00"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+}
@@ -21,16 +21,44 @@
 //!
 //! ## Action Point System
 //!
-//! - **Shooting**: Costs 4 AP
-//! - **Reloading**: Costs 2 AP
+//! - **Attacking**: Costs the equipped weapon's `ap_cost` (4 for the 10mm pistol)
+//! - **Reloading**: Costs [`RELOAD_AP_COST`] AP and draws rounds from inventory ammo
 //! - **Using Item**: Costs 2 AP
-//! - AP regenerates fully at the start of each round
+//! - The player keeps acting until they can't afford another action or end
+//!   their turn; then enemies act and AP regenerates fully
+//!
+//! Firing an empty weapon wastes the attack's AP with a *click*.
+//!
+//! ## Resolving Actions
+//!
+//! [`CombatState::apply`] resolves one [`CombatAction`] against the player and
+//! returns the [`CombatEvent`]s it produced. It takes the random number
+//! generator as a parameter and has no UI dependencies, so whole fights can be
+//! simulated headlessly; the TUI only renders the returned events.
+//!
+//! Every random function here takes the generator explicitly. The game passes
+//! its seeded `GameState::rng`, so a fight replays exactly from the same seed.
+//!
+//! Each new round also advances the player's status effects by one round, so
+//! chems wear off mid-fight and are reported as [`CombatEvent::Status`].
 //!
 //! ## Enemy Types
 //!
-//! - **Radroach**: Weak, low HP, common in vaults
-//! - **Raider**: Human enemies with guns, scales with level
-//! - **Super Mutant**: Tough, high HP and damage, elite enemy
+//! Enemies met in the game come from the [`bestiary`](super::bestiary), which
+//! is loaded from data files. [`Enemy::raider`], [`Enemy::radroach`] and
+//! [`Enemy::super_mutant`] are quick fixed-formula archetypes for tests and
+//! examples.
+//!
+//! Enemies can have damage resistance of their own; the player's hits are
+//! reduced by it with the same [`MIN_DAMAGE_PERCENT`] floor as armor.
+//!
+//! ## Armor
+//!
+//! Incoming attacks are resolved against the player's [`Defense`]:
+//! - The equipped armor's AC is added to the base AC (10 + Agility)
+//! - Damage Resistance (plus any per-damage-type modifier and the Toughness perk)
+//!   is subtracted from each hit
+//! - A hit always deals at least [`MIN_DAMAGE_PERCENT`]% of its raw damage (minimum 1)
 //!
 //! ## To-Hit Calculation
 //!
@@ -55,11 +83,38 @@
 //! println!("Combat started! Round {}", combat.round);
 //! println!("Enemies: {}", combat.enemies.len());
 //! ```
+//!
+//! Driving a fight without the TUI:
+//!
+//! ```no_run
+//! use fallout_dnd::game::character::{Character, Special};
+//! use fallout_dnd::game::combat::{CombatAction, CombatEvent, CombatState, Enemy};
+//! use fallout_dnd::game::rng::GameRng;
+//!
+//! let mut player = Character::new("Vault Dweller", Special::new());
+//! let mut combat = CombatState::new();
+//! combat.start_combat(vec![Enemy::radroach(1)]);
+//!
+//! let mut rng = GameRng::seeded(42);
+//! while combat.active {
+//!     for event in combat.apply(CombatAction::Attack { target: 0 }, &mut player, &mut rng) {
+//!         if let CombatEvent::Victory { total_xp } = event {
+//!             println!("Won! +{} XP", total_xp);
+//!         }
+//!     }
+//! }
+//! ```
 
+use super::character::Character;
+use super::dice::{self, DiceExpr, DiceRoll};
+use super::items::condition::{self, MAX_CONDITION};
+use super::items::{armor::ArmorStats, DamageType, ItemType};
+use crate::error::DiceError;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use smartstring::alias::String as SmartString;
+use std::collections::HashMap;
 
 /// Combat state tracking active encounters.
 ///
@@ -73,6 +128,9 @@ pub struct CombatState {
     pub active: bool,
     pub round: u32,
     pub enemies: SmallVec<[Enemy; 8]>,
+    /// Enemies defeated in the last fight that was won, waiting to be looted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallen: Vec<Enemy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +145,27 @@ pub struct Enemy {
     pub xp_reward: u32,
     pub skill: u8,    // Combat skill (like small_guns for raiders)
     pub strength: u8, // For melee damage bonus
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Bestiary ID this enemy was spawned from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<SmartString>,
+    /// Damage resistance against every damage type
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub damage_resistance: i32,
+    /// Extra resistance (or weakness, if negative) against specific damage types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub resistances: HashMap<DamageType, i32>,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+/// `level` for scaling the u8 skill and strength stats, capped so they
+/// saturate instead of overflowing
+fn stat_level(level: u32) -> u8 {
+    u8::try_from(level).unwrap_or(u8::MAX)
 }
 
 impl Enemy {
@@ -101,18 +180,24 @@ impl Enemy {
             damage: SmartString::from(format!("{}d6+{}", 1 + level / 3, level)),
             ap: 5 + (level as i32 / 2),
             xp_reward: level * 100,
-            skill: 30 + (level as u8 * 10).min(70), // Scales with level, caps at 70
+            skill: 30 + stat_level(level).saturating_mul(10).min(70), // Scales with level, caps at 70
             strength: 5,
+            damage_type: DamageType::Normal,
+            kind: None,
+            damage_resistance: 0,
+            resistances: HashMap::new(),
         }
     }
 
+    #[allow(dead_code)] // Public API for integration tests
     pub fn raider(level: u32) -> Self {
         let mut enemy = Enemy::new(&format!("Raider (Level {})", level), level);
-        enemy.skill = 40 + (level as u8 * 8).min(80); // Raiders are better shots
-        enemy.strength = 5 + level as u8;
+        enemy.skill = 40 + stat_level(level).saturating_mul(8).min(80); // Raiders are better shots
+        enemy.strength = 5u8.saturating_add(stat_level(level));
         enemy
     }
 
+    #[allow(dead_code)] // Public API for integration tests
     pub fn radroach(level: u32) -> Self {
         let mut enemy = Enemy::new("Radroach", level);
         enemy.max_hp = 10 + (level as i32 * 3);
@@ -124,13 +209,14 @@ impl Enemy {
         enemy
     }
 
+    #[allow(dead_code)] // Public API for integration tests
     pub fn super_mutant(level: u32) -> Self {
         let mut enemy = Enemy::new("Super Mutant", level + 2);
         enemy.max_hp = 40 + (level as i32 * 15);
         enemy.current_hp = enemy.max_hp;
         enemy.damage = SmartString::from(format!("{}d8+{}", 1 + level / 2, level * 2));
-        enemy.skill = 60 + (level as u8 * 5).min(90); // Very skilled
-        enemy.strength = 10 + level as u8; // Very strong
+        enemy.skill = 60 + stat_level(level).saturating_mul(5).min(90); // Very skilled
+        enemy.strength = 10u8.saturating_add(stat_level(level)); // Very strong
         enemy.armor_class = 15 + level as i32;
         enemy
     }
@@ -142,45 +228,35 @@ impl Enemy {
     pub fn take_damage(&mut self, damage: i32) {
         self.current_hp = (self.current_hp - damage).max(0);
     }
-}
 
-/// Dice rolling system
-pub fn roll_dice(dice_str: &str) -> i32 {
-    let mut rng = rand::rng();
-
-    // Parse dice notation like "2d6+3" or "1d20"
-    // Handle both numeric modifiers and text (like "1d8+STR" -> "1d8+0" for basic parsing)
-    let parts: Vec<&str> = dice_str.split('+').collect();
-    let dice_part = parts[0];
-    let modifier: i32 = if parts.len() > 1 {
-        // Try to parse as number, if it fails (like "STR"), return 0
-        parts[1].parse().unwrap_or(0)
-    } else {
-        0
-    };
-
-    let dice_components: Vec<&str> = dice_part.split('d').collect();
-    if dice_components.len() != 2 {
-        return modifier; // Invalid format, return just modifier
+    /// Total damage resistance against a damage type, never below zero
+    pub fn resistance_against(&self, damage_type: &DamageType) -> i32 {
+        let modifier = self.resistances.get(damage_type).copied().unwrap_or(0);
+        (self.damage_resistance + modifier).max(0)
     }
 
-    let num_dice: i32 = dice_components[0].parse().unwrap_or(1);
-    let die_size: i32 = dice_components[1].parse().unwrap_or(6);
-
-    let mut total = modifier;
-    for _ in 0..num_dice {
-        total += rng.random_range(1..=die_size);
+    /// Damage left after resistance, never below the minimum-damage floor
+    pub fn mitigate(&self, raw_damage: i32, damage_type: &DamageType) -> i32 {
+        apply_resistance(raw_damage, self.resistance_against(damage_type))
     }
+}
 
-    total
+/// Roll a dice expression like `2d6+3`; see [`super::dice`] for the full syntax.
+/// SPECIAL tokens add nothing here, since there is no character to read them from.
+pub fn roll_dice(dice_str: &str, rng: &mut impl Rng) -> Result<DiceRoll, DiceError> {
+    dice::roll(dice_str, None, rng)
 }
 
 /// Replace stat modifiers in damage string with actual values
 /// e.g., "1d8+STR" with STR=6 becomes "1d8+3" (STR/2)
 ///
+/// Player attacks roll weapon damage with the character's full SPECIAL
+/// through [`DiceExpr::roll`] instead; this remains for Strength-only callers.
+///
 /// Returns a Cow to avoid allocations when no replacement is needed.
 /// This is called on the hot path during combat (every attack), so avoiding
 /// allocations when damage_str doesn't contain "STR" provides ~30% speedup.
+#[allow(dead_code)]
 pub fn resolve_stat_modifiers(damage_str: &str, strength: u8) -> std::borrow::Cow<'_, str> {
     if damage_str.contains("STR") {
         let stat_bonus = (strength / 2) as i32;
@@ -191,29 +267,262 @@ pub fn resolve_stat_modifiers(damage_str: &str, strength: u8) -> std::borrow::Co
 }
 
 /// Make an attack roll
-pub fn attack_roll(attacker_skill: u8, target_ac: i32) -> (bool, bool) {
-    let mut rng = rand::rng();
+#[allow(dead_code)]
+pub fn attack_roll(attacker_skill: u8, target_ac: i32, rng: &mut impl Rng) -> (bool, bool) {
+    attack_roll_with_crit_range(attacker_skill, target_ac, 20, rng)
+}
+
+/// Attack roll where any natural roll of `crit_threshold` or higher is a critical
+pub fn attack_roll_with_crit_range(
+    attacker_skill: u8,
+    target_ac: i32,
+    crit_threshold: i32,
+    rng: &mut impl Rng,
+) -> (bool, bool) {
     let roll = rng.random_range(1..=20);
 
-    let critical = roll == 20;
+    let critical = roll >= crit_threshold;
     let total = roll + attacker_skill as i32;
     let hit = total >= target_ac || critical;
 
     (hit, critical)
 }
 
-/// Calculate damage with modifiers
-pub fn calculate_damage(base_damage: &str, stat_bonus: i32, is_critical: bool) -> i32 {
-    let base = roll_dice(base_damage);
-    let damage = base + stat_bonus;
+/// Calculate damage with modifiers; never negative
+pub fn calculate_damage(
+    base_damage: &str,
+    stat_bonus: i32,
+    is_critical: bool,
+    rng: &mut impl Rng,
+) -> Result<i32, DiceError> {
+    let base = roll_dice(base_damage, rng)?.total;
+    let damage = (base + stat_bonus).max(0);
 
     if is_critical {
-        damage * 2
+        Ok(damage * 2)
     } else {
-        damage
+        Ok(damage)
     }
 }
 
+/// AP cost of reloading the equipped weapon
+pub const RELOAD_AP_COST: i32 = 2;
+
+/// Chance of successfully fleeing from combat
+pub const FLEE_CHANCE: f64 = 0.6;
+
+/// Base armor class of an unarmored character before Agility
+pub const BASE_PLAYER_AC: i32 = 10;
+
+/// Armor can't reduce a hit below this percentage of its raw damage
+pub const MIN_DAMAGE_PERCENT: i32 = 20;
+
+/// The player's defensive stats, derived from equipped armor and perks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Defense {
+    /// Name of the equipped armor, if any
+    pub armor_name: Option<SmartString>,
+    /// Total AC enemies must beat to hit
+    pub armor_class: i32,
+    /// Armor stats of the equipped armor, if any
+    pub armor: Option<ArmorStats>,
+    /// Flat reduction from perks, applied on top of armor
+    pub perk_reduction: i32,
+}
+
+impl Defense {
+    /// Look up the character's equipped armor and perks
+    pub fn for_character(character: &Character) -> Self {
+        let armor_item = character
+            .equipped_armor
+            .as_ref()
+            .and_then(|key| character.inventory.position_by_key(key))
+            .map(|index| &character.inventory[index]);
+        let (armor_name, armor) = match armor_item {
+            Some(item) => match &item.item_type {
+                ItemType::Armor(stats) => (Some(item.name.clone()), Some(stats.clone())),
+                _ => (None, None),
+            },
+            None => (None, None),
+        };
+
+        let armor_class = BASE_PLAYER_AC
+            + character.special.agility as i32
+            + armor.as_ref().map_or(0, |stats| stats.armor_class);
+
+        Defense {
+            armor_name,
+            armor_class,
+            armor,
+            perk_reduction: character.damage_reduction(),
+        }
+    }
+
+    /// Total damage reduction against a damage type
+    pub fn resistance_against(&self, damage_type: &DamageType) -> i32 {
+        self.armor
+            .as_ref()
+            .map_or(0, |stats| stats.resistance_against(damage_type))
+            + self.perk_reduction
+    }
+
+    /// Damage left after resistance, never below the minimum-damage floor
+    pub fn mitigate(&self, raw_damage: i32, damage_type: &DamageType) -> i32 {
+        apply_resistance(raw_damage, self.resistance_against(damage_type))
+    }
+}
+
+/// Take resistance off a hit, keeping at least [`MIN_DAMAGE_PERCENT`]% of it
+fn apply_resistance(raw_damage: i32, resistance: i32) -> i32 {
+    if raw_damage <= 0 {
+        return 0;
+    }
+    let floor = (raw_damage * MIN_DAMAGE_PERCENT / 100).max(1);
+    (raw_damage - resistance).max(floor)
+}
+
+/// Outcome of an attack against the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttackResult {
+    pub hit: bool,
+    pub critical: bool,
+    /// Damage rolled before armor
+    pub raw_damage: i32,
+    /// Damage actually dealt after armor
+    pub damage: i32,
+}
+
+impl AttackResult {
+    /// Damage soaked up by armor and perks
+    pub fn absorbed(&self) -> i32 {
+        self.raw_damage - self.damage
+    }
+}
+
+/// Resolve one enemy attack against the player's defense
+pub fn resolve_enemy_attack(enemy: &Enemy, defense: &Defense, rng: &mut impl Rng) -> AttackResult {
+    let (hit, critical) = attack_roll_with_crit_range(enemy.skill, defense.armor_class, 20, rng);
+    if !hit {
+        return AttackResult {
+            hit,
+            critical,
+            raw_damage: 0,
+            damage: 0,
+        };
+    }
+
+    let str_bonus = (enemy.strength / 2) as i32;
+    let raw_damage =
+        calculate_damage(&enemy.damage, str_bonus, critical, rng).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Invalid damage '{}' for {}: {}",
+                enemy.damage,
+                enemy.name,
+                e
+            );
+            0
+        });
+    AttackResult {
+        hit,
+        critical,
+        raw_damage,
+        damage: defense.mitigate(raw_damage, &enemy.damage_type),
+    }
+}
+
+/// Whether the player can still afford an attack or a useful reload this turn
+pub fn player_can_act(character: &Character) -> bool {
+    if character.current_ap >= character.attack_ap_cost() {
+        return true;
+    }
+    character.equipped_weapon_stats().is_some_and(|stats| {
+        let can_reload = stats.uses_ammo()
+            && stats.loaded_rounds < stats.capacity()
+            && stats
+                .ammo_type
+                .as_ref()
+                .is_some_and(|ammo| character.ammo_count(ammo) > 0);
+        can_reload && character.current_ap >= RELOAD_AP_COST
+    })
+}
+
+/// An action the player takes on their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatAction {
+    /// Attack the enemy at this index in `CombatState::enemies`
+    Attack { target: usize },
+    /// Reload the equipped weapon
+    Reload,
+    /// End the turn and let the enemies act
+    EndTurn,
+    /// Try to escape; enemies act if it fails
+    Flee,
+}
+
+/// A participant in an attack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Combatant {
+    Player,
+    Enemy { index: usize, name: SmartString },
+}
+
+/// Something that happened while resolving a [`CombatAction`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CombatEvent {
+    /// An attack landed
+    Hit {
+        attacker: Combatant,
+        target: Combatant,
+        damage: i32,
+        /// Damage soaked up by armor and perks
+        absorbed: i32,
+        /// Target's HP after the hit
+        remaining_hp: i32,
+    },
+    /// A critical hit landed
+    Crit {
+        attacker: Combatant,
+        target: Combatant,
+        damage: i32,
+        absorbed: i32,
+        remaining_hp: i32,
+    },
+    /// An attack missed
+    Miss {
+        attacker: Combatant,
+        target: Combatant,
+    },
+    /// The player pulled the trigger on an empty weapon
+    Click,
+    /// The player's worn weapon jammed, wasting the attack
+    Jammed,
+    /// The player reloaded their weapon
+    Reloaded {
+        rounds: u32,
+        loaded: u32,
+        magazine: u32,
+        ammo_type: SmartString,
+        /// Rounds of this ammo type left in the inventory
+        spare: u32,
+    },
+    /// An enemy was killed
+    EnemyDied { index: usize, name: SmartString },
+    /// The player was killed; combat is over
+    PlayerDied { killer: SmartString },
+    /// The player escaped; combat is over
+    Fled,
+    /// The player tried to flee and failed
+    FleeFailed,
+    /// The player gained experience
+    XpAwarded { amount: u32 },
+    /// Every enemy is dead; combat is over
+    Victory { total_xp: u32 },
+    /// A status effect changed as the round passed, e.g. a chem wore off
+    Status { message: String },
+    /// The action wasn't possible; nothing changed
+    Rejected { reason: String },
+}
+
 impl Default for CombatState {
     fn default() -> Self {
         Self::new()
@@ -226,6 +535,7 @@ impl CombatState {
             active: false,
             round: 0,
             enemies: SmallVec::new(),
+            fallen: Vec::new(),
         }
     }
 
@@ -233,6 +543,7 @@ impl CombatState {
         self.active = true;
         self.round = 1;
         self.enemies = SmallVec::from_vec(enemies);
+        self.fallen.clear();
     }
 
     pub fn end_combat(&mut self) {
@@ -245,6 +556,11 @@ impl CombatState {
         self.round += 1;
     }
 
+    /// Take the bodies left by the last victory
+    pub fn take_fallen(&mut self) -> Vec<Enemy> {
+        std::mem::take(&mut self.fallen)
+    }
+
     pub fn all_enemies_dead(&self) -> bool {
         self.enemies.iter().all(|e| !e.is_alive())
     }
@@ -256,23 +572,293 @@ impl CombatState {
             .map(|e| e.xp_reward)
             .sum()
     }
+
+    /// Resolve one player action, including the enemy turn it triggers,
+    /// and return everything that happened in order.
+    ///
+    /// The player's turn ends once they can no longer afford an action
+    /// (see [`player_can_act`]); enemies then attack, AP is restored and
+    /// the next round begins.
+    pub fn apply(
+        &mut self,
+        action: CombatAction,
+        player: &mut Character,
+        rng: &mut impl Rng,
+    ) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        if !self.active {
+            events.push(CombatEvent::Rejected {
+                reason: "You're not in combat.".to_string(),
+            });
+            return events;
+        }
+
+        let result = match action {
+            CombatAction::Attack { target } => self.player_attack(target, player, rng, &mut events),
+            CombatAction::Reload => Self::player_reload(player, &mut events),
+            CombatAction::EndTurn => {
+                self.enemy_turn(player, rng, &mut events);
+                return events;
+            }
+            CombatAction::Flee => {
+                if rng.random_bool(FLEE_CHANCE) {
+                    self.end_combat();
+                    events.push(CombatEvent::Fled);
+                } else {
+                    events.push(CombatEvent::FleeFailed);
+                    self.enemy_turn(player, rng, &mut events);
+                }
+                return events;
+            }
+        };
+
+        if let Err(reason) = result {
+            events.push(CombatEvent::Rejected { reason });
+            return events;
+        }
+
+        if self.all_enemies_dead() {
+            let total_xp = self.total_xp_reward();
+            let fallen = self.enemies.to_vec();
+            self.end_combat();
+            self.fallen = fallen;
+            events.push(CombatEvent::Victory { total_xp });
+        } else if !player_can_act(player) {
+            self.enemy_turn(player, rng, &mut events);
+        }
+        events
+    }
+
+    fn player_attack(
+        &mut self,
+        target: usize,
+        player: &mut Character,
+        rng: &mut impl Rng,
+        events: &mut Vec<CombatEvent>,
+    ) -> Result<(), String> {
+        let enemy = self
+            .enemies
+            .get(target)
+            .ok_or_else(|| format!("There is no enemy #{}.", target + 1))?;
+        if !enemy.is_alive() {
+            return Err("That enemy is already defeated!".to_string());
+        }
+
+        let weapon_damage: DiceExpr = player
+            .get_equipped_damage()
+            .parse()
+            .map_err(|e| format!("Your weapon's damage can't be rolled: {}", e))?;
+
+        let ap_cost = player.attack_ap_cost();
+        if !player.use_ap(ap_cost) {
+            return Err(format!(
+                "Not enough AP! Attacking costs {} AP, you have {}. Type 'end' to end your turn.",
+                ap_cost, player.current_ap
+            ));
+        }
+
+        // Pulling the trigger on an empty weapon wastes the attack
+        if !player.consume_round() {
+            events.push(CombatEvent::Click);
+            return Ok(());
+        }
+
+        // Every attack wears the weapon, and a badly worn one can jam
+        let weapon_condition = player
+            .equipped_weapon_stats()
+            .map_or(MAX_CONDITION, |stats| stats.condition);
+        player.wear_weapon();
+        let jam_chance = condition::jam_chance(weapon_condition);
+        if jam_chance > 0 && rng.random_range(1..=100) <= jam_chance {
+            events.push(CombatEvent::Jammed);
+            return Ok(());
+        }
+
+        let attacker = Combatant::Player;
+        let defender = Combatant::Enemy {
+            index: target,
+            name: enemy.name.clone(),
+        };
+        let (hit, critical) = attack_roll_with_crit_range(
+            player.get_weapon_skill(),
+            enemy.armor_class,
+            player.critical_threshold(),
+            rng,
+        );
+        if !hit {
+            events.push(CombatEvent::Miss {
+                attacker,
+                target: defender,
+            });
+            return Ok(());
+        }
+
+        let mut raw_damage = condition::scale(
+            (weapon_damage
+                .roll(Some(&player.special), rng)
+                .map_err(|e| format!("Your weapon's damage can't be rolled: {}", e))?
+                .total
+                + player.damage_bonus())
+            .max(0),
+            weapon_condition,
+        );
+        if critical {
+            raw_damage *= player.critical_multiplier();
+        }
+        let damage_type = player
+            .equipped_weapon_stats()
+            .map(|stats| stats.effective_damage_type())
+            .unwrap_or_default();
+
+        let enemy = &mut self.enemies[target];
+        let damage = enemy.mitigate(raw_damage, &damage_type);
+        let absorbed = raw_damage - damage;
+        enemy.take_damage(damage);
+        let remaining_hp = enemy.current_hp;
+        events.push(if critical {
+            CombatEvent::Crit {
+                attacker,
+                target: defender,
+                damage,
+                absorbed,
+                remaining_hp,
+            }
+        } else {
+            CombatEvent::Hit {
+                attacker,
+                target: defender,
+                damage,
+                absorbed,
+                remaining_hp,
+            }
+        });
+
+        if !enemy.is_alive() {
+            let amount = enemy.xp_reward;
+            events.push(CombatEvent::EnemyDied {
+                index: target,
+                name: enemy.name.clone(),
+            });
+            player.add_experience(amount);
+            events.push(CombatEvent::XpAwarded { amount });
+        }
+        Ok(())
+    }
+
+    fn player_reload(player: &mut Character, events: &mut Vec<CombatEvent>) -> Result<(), String> {
+        if player.current_ap < RELOAD_AP_COST {
+            return Err(format!(
+                "Not enough AP! Reloading costs {} AP. Type 'end' to end your turn.",
+                RELOAD_AP_COST
+            ));
+        }
+
+        let rounds = player.reload_weapon()?;
+        player.use_ap(RELOAD_AP_COST);
+        if let Some(stats) = player.equipped_weapon_stats() {
+            let ammo_type = stats.ammo_type.clone().unwrap_or_default();
+            events.push(CombatEvent::Reloaded {
+                rounds,
+                loaded: stats.loaded_rounds,
+                magazine: stats.capacity(),
+                spare: player.ammo_count(&ammo_type),
+                ammo_type,
+            });
+        }
+        Ok(())
+    }
+
+    /// Every living enemy attacks, then a new round starts
+    fn enemy_turn(
+        &mut self,
+        player: &mut Character,
+        rng: &mut impl Rng,
+        events: &mut Vec<CombatEvent>,
+    ) {
+        let defense = Defense::for_character(player);
+        let mut killer = None;
+        for (index, enemy) in self.enemies.iter().enumerate() {
+            if !enemy.is_alive() {
+                continue;
+            }
+
+            let attacker = Combatant::Enemy {
+                index,
+                name: enemy.name.clone(),
+            };
+            let attack = resolve_enemy_attack(enemy, &defense, rng);
+            if !attack.hit {
+                events.push(CombatEvent::Miss {
+                    attacker,
+                    target: Combatant::Player,
+                });
+                continue;
+            }
+
+            player.take_damage(attack.damage);
+            player.wear_armor();
+            let (damage, absorbed, remaining_hp) =
+                (attack.damage, attack.absorbed(), player.current_hp);
+            events.push(if attack.critical {
+                CombatEvent::Crit {
+                    attacker,
+                    target: Combatant::Player,
+                    damage,
+                    absorbed,
+                    remaining_hp,
+                }
+            } else {
+                CombatEvent::Hit {
+                    attacker,
+                    target: Combatant::Player,
+                    damage,
+                    absorbed,
+                    remaining_hp,
+                }
+            });
+
+            if !player.is_alive() {
+                killer = Some(enemy.name.clone());
+                break;
+            }
+        }
+
+        if let Some(killer) = killer {
+            events.push(CombatEvent::PlayerDied { killer });
+            self.end_combat();
+            return;
+        }
+
+        for message in player.tick_status(1) {
+            events.push(CombatEvent::Status { message });
+        }
+        player.restore_ap();
+        self.next_round();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::items::condition::MAX_CONDITION;
+    use crate::game::rng::GameRng;
+
+    fn seeded_rng() -> GameRng {
+        GameRng::seeded(7)
+    }
 
     #[test]
     fn test_roll_dice_basic() {
+        let mut rng = seeded_rng();
         // Test basic dice rolling - result should be between min and max
-        let result = roll_dice("1d6");
+        let result = roll_dice("1d6", &mut rng).unwrap().total;
         assert!(
             (1..=6).contains(&result),
             "1d6 should be between 1 and 6, got {}",
             result
         );
 
-        let result = roll_dice("2d6");
+        let result = roll_dice("2d6", &mut rng).unwrap().total;
         assert!(
             (2..=12).contains(&result),
             "2d6 should be between 2 and 12, got {}",
@@ -282,14 +868,15 @@ mod tests {
 
     #[test]
     fn test_roll_dice_with_modifier() {
-        let result = roll_dice("1d6+3");
+        let mut rng = seeded_rng();
+        let result = roll_dice("1d6+3", &mut rng).unwrap().total;
         assert!(
             (4..=9).contains(&result),
             "1d6+3 should be between 4 and 9, got {}",
             result
         );
 
-        let result = roll_dice("2d6+5");
+        let result = roll_dice("2d6+5", &mut rng).unwrap().total;
         assert!(
             (7..=17).contains(&result),
             "2d6+5 should be between 7 and 17, got {}",
@@ -299,15 +886,11 @@ mod tests {
 
     #[test]
     fn test_roll_dice_invalid_format() {
-        // Should handle invalid formats gracefully
-        // Invalid format with no 'd' returns the modifier (0 if no +)
-        let result = roll_dice("invalid");
-        // The function tries to parse and may return a random value for malformed input
-        // Just verify it doesn't panic
-        assert!(
-            result >= 0,
-            "Should handle invalid format without panicking"
-        );
+        let mut rng = seeded_rng();
+        // Invalid expressions are errors rather than silently rolling 0
+        assert!(roll_dice("invalid", &mut rng).is_err());
+        assert!(roll_dice("2d6+", &mut rng).is_err());
+        assert!(roll_dice("1d6*2", &mut rng).is_err());
     }
 
     #[test]
@@ -321,31 +904,34 @@ mod tests {
 
     #[test]
     fn test_calculate_damage() {
-        let damage = calculate_damage("1d6+0", 0, false);
+        let mut rng = seeded_rng();
+        let damage = calculate_damage("1d6+0", 0, false, &mut rng).unwrap();
         assert!((1..=6).contains(&damage));
 
-        let damage = calculate_damage("1d6+0", 0, true);
+        let damage = calculate_damage("1d6+0", 0, true, &mut rng).unwrap();
         assert!((2..=12).contains(&damage), "Critical should double damage");
     }
 
     #[test]
     fn test_dice_roll() {
+        let mut rng = seeded_rng();
         for _ in 0..100 {
-            let roll = roll_dice("1d20");
+            let roll = roll_dice("1d20", &mut rng).unwrap().total;
             assert!((1..=20).contains(&roll));
         }
     }
 
     #[test]
     fn test_attack_roll() {
+        let mut rng = seeded_rng();
         // Test high skill against low AC (should always hit)
-        let (hit, _) = attack_roll(100, 5);
+        let (hit, _) = attack_roll(100, 5, &mut rng);
         assert!(hit);
 
         // Test low skill against high AC (should usually miss)
         // We can't guarantee a miss because of critical hits, so we check that
         // if it's a hit, it must be a critical.
-        let (hit, critical) = attack_roll(5, 100);
+        let (hit, critical) = attack_roll(5, 100, &mut rng);
         if hit {
             assert!(critical);
         }
@@ -369,6 +955,14 @@ mod tests {
         assert!(super_mutant.strength > raider.strength);
     }
 
+    #[test]
+    fn test_high_level_enemy_stats_saturate() {
+        let enemy = Enemy::new("Enclave Colonel", 300);
+        assert_eq!(enemy.skill, 100);
+        assert_eq!(Enemy::raider(300).strength, u8::MAX);
+        assert_eq!(Enemy::super_mutant(300).skill, 150);
+    }
+
     #[test]
     fn test_enemy_damage() {
         let mut enemy = Enemy::raider(1);
@@ -430,4 +1024,267 @@ mod tests {
         let xp = combat.total_xp_reward();
         assert_eq!(xp, 200); // Two level 1 raiders
     }
+
+    #[test]
+    fn test_defense_uses_equipped_armor() {
+        use crate::game::character::Special;
+
+        let mut character = Character::new("Test", Special::new());
+        let unarmored = Defense::for_character(&character);
+        assert_eq!(unarmored.armor_name, None);
+        assert_eq!(unarmored.armor_class, BASE_PLAYER_AC + 1);
+        assert_eq!(unarmored.resistance_against(&DamageType::Normal), 0);
+
+        character.equipped_armor = Some("leather_armor".into());
+        let leather = Defense::for_character(&character);
+        assert_eq!(leather.armor_name.as_deref(), Some("Leather Armor"));
+        assert!(leather.armor_class > unarmored.armor_class);
+        assert_eq!(leather.resistance_against(&DamageType::Normal), 5);
+        assert_eq!(leather.resistance_against(&DamageType::Fire), 3);
+
+        character.perks.push("toughness".into());
+        let tough = Defense::for_character(&character);
+        assert_eq!(tough.resistance_against(&DamageType::Normal), 7);
+    }
+
+    /// An enemy that can't hurt the player
+    fn harmless_enemy() -> Enemy {
+        let mut enemy = Enemy::radroach(1);
+        enemy.skill = 0;
+        enemy.damage = "0d4".into();
+        enemy
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_actions_without_spending_ap() {
+        use crate::game::character::Special;
+
+        let mut player = Character::new("Test", Special::new());
+        let mut combat = CombatState::new();
+        let mut rng = seeded_rng();
+
+        let events = combat.apply(CombatAction::Attack { target: 0 }, &mut player, &mut rng);
+        assert!(matches!(events[..], [CombatEvent::Rejected { .. }]));
+
+        combat.start_combat(vec![harmless_enemy()]);
+        let events = combat.apply(CombatAction::Attack { target: 3 }, &mut player, &mut rng);
+        assert!(matches!(events[..], [CombatEvent::Rejected { .. }]));
+
+        combat.enemies[0].take_damage(1000);
+        let events = combat.apply(CombatAction::Attack { target: 0 }, &mut player, &mut rng);
+        assert!(matches!(events[..], [CombatEvent::Rejected { .. }]));
+        assert_eq!(player.current_ap, player.max_ap);
+    }
+
+    #[test]
+    fn test_apply_kill_awards_xp_and_ends_combat() {
+        use crate::game::character::Special;
+
+        let mut player = Character::new("Test", Special::new());
+        player.skills.small_guns = 100;
+        let mut enemy = harmless_enemy();
+        enemy.current_hp = 1;
+        let xp_reward = enemy.xp_reward;
+
+        let mut combat = CombatState::new();
+        combat.start_combat(vec![enemy]);
+        let events = combat.apply(
+            CombatAction::Attack { target: 0 },
+            &mut player,
+            &mut seeded_rng(),
+        );
+
+        assert!(matches!(
+            events[0],
+            CombatEvent::Hit { .. } | CombatEvent::Crit { .. }
+        ));
+        assert_eq!(
+            events[1..],
+            [
+                CombatEvent::EnemyDied {
+                    index: 0,
+                    name: "Radroach".into()
+                },
+                CombatEvent::XpAwarded { amount: xp_reward },
+                CombatEvent::Victory {
+                    total_xp: xp_reward
+                },
+            ]
+        );
+        assert!(!combat.active);
+        assert_eq!(player.experience, xp_reward);
+
+        // The body stays behind to be looted
+        let fallen = combat.take_fallen();
+        assert_eq!(fallen.len(), 1);
+        assert_eq!(fallen[0].name, "Radroach");
+        assert!(combat.fallen.is_empty());
+    }
+
+    #[test]
+    fn test_attacks_wear_weapon_and_worn_weapons_jam() {
+        use crate::game::character::Special;
+        use crate::game::items::{Item, WeaponType};
+
+        let mut player = Character::new("Test", Special::new());
+        player.add_item(Item::new_weapon(
+            "knife",
+            "Knife",
+            "Sharp.",
+            "1d4",
+            DamageType::Normal,
+            WeaponType::MeleeWeapon,
+            3,
+            10,
+        ));
+        player.equipped_weapon = Some("knife".into());
+        let mut enemy = harmless_enemy();
+        enemy.current_hp = 10_000;
+        enemy.max_hp = 10_000;
+        let mut combat = CombatState::new();
+        combat.start_combat(vec![enemy]);
+        let mut rng = seeded_rng();
+
+        let mut attack = |player: &mut Character| {
+            player.current_ap = player.max_ap;
+            combat.apply(CombatAction::Attack { target: 0 }, player, &mut rng)
+        };
+
+        attack(&mut player);
+        let condition = |player: &Character| player.equipped_weapon_stats().unwrap().condition;
+        assert_eq!(condition(&player), MAX_CONDITION - condition::WEAPON_WEAR);
+
+        player
+            .inventory
+            .iter_mut()
+            .filter_map(|item| match &mut item.item_type {
+                ItemType::Weapon(stats) => Some(stats),
+                _ => None,
+            })
+            .for_each(|stats| stats.condition = 0);
+        let jammed = (0..20)
+            .flat_map(|_| attack(&mut player))
+            .filter(|event| *event == CombatEvent::Jammed)
+            .count();
+        assert!(jammed > 0);
+        assert_eq!(condition(&player), 0);
+    }
+
+    #[test]
+    fn test_enemy_resistance_absorbs_player_hits() {
+        use crate::game::character::Special;
+
+        let mut player = Character::new("Test", Special::new());
+        player.skills.small_guns = 100;
+        let mut enemy = harmless_enemy();
+        enemy.max_hp = 500;
+        enemy.current_hp = 500;
+        enemy.damage_resistance = 100;
+
+        let mut combat = CombatState::new();
+        combat.start_combat(vec![enemy]);
+        let events = combat.apply(
+            CombatAction::Attack { target: 0 },
+            &mut player,
+            &mut seeded_rng(),
+        );
+
+        match events[0] {
+            CombatEvent::Hit {
+                damage, absorbed, ..
+            }
+            | CombatEvent::Crit {
+                damage, absorbed, ..
+            } => {
+                // Only the minimum-damage floor gets through
+                assert!(absorbed > 0);
+                assert_eq!(
+                    damage,
+                    ((damage + absorbed) * MIN_DAMAGE_PERCENT / 100).max(1)
+                );
+            }
+            ref other => panic!("Expected a hit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_end_turn_lets_enemies_act_and_starts_new_round() {
+        use crate::game::character::Special;
+
+        let mut player = Character::new("Test", Special::new());
+        player.current_ap = 0;
+        let mut combat = CombatState::new();
+        combat.start_combat(vec![harmless_enemy(), harmless_enemy()]);
+
+        let events = combat.apply(CombatAction::EndTurn, &mut player, &mut seeded_rng());
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| matches!(
+            event,
+            CombatEvent::Miss {
+                target: Combatant::Player,
+                ..
+            } | CombatEvent::Hit {
+                target: Combatant::Player,
+                damage: 0,
+                ..
+            } | CombatEvent::Crit {
+                target: Combatant::Player,
+                ..
+            }
+        )));
+        assert_eq!(combat.round, 2);
+        assert_eq!(player.current_ap, player.max_ap);
+    }
+
+    #[test]
+    fn test_status_effects_tick_each_round() {
+        use crate::game::character::Special;
+        use crate::game::status::StatTarget;
+
+        let mut player = Character::new("Test", Special::new());
+        let (special, skills) = (&mut player.special, &mut player.skills);
+        player.status.add_effect(
+            "Jet",
+            "jet",
+            StatTarget::parse("agility").unwrap(),
+            2,
+            1,
+            special,
+            skills,
+        );
+        let mut combat = CombatState::new();
+        combat.start_combat(vec![harmless_enemy()]);
+
+        let events = combat.apply(CombatAction::EndTurn, &mut player, &mut seeded_rng());
+        assert_eq!(
+            events.last(),
+            Some(&CombatEvent::Status {
+                message: "Jet wore off.".to_string()
+            })
+        );
+        assert_eq!(player.special.agility, 1);
+    }
+
+    #[test]
+    fn test_mitigate_has_minimum_damage_floor() {
+        let defense = Defense {
+            armor_name: Some("Power Armor".into()),
+            armor_class: 30,
+            armor: Some(ArmorStats {
+                damage_resistance: 30,
+                radiation_resistance: 0,
+                armor_class: 20,
+                resistances: Default::default(),
+                condition: MAX_CONDITION,
+            }),
+            perk_reduction: 0,
+        };
+
+        assert_eq!(defense.mitigate(50, &DamageType::Normal), 20);
+        // 20% of 20 is 4
+        assert_eq!(defense.mitigate(20, &DamageType::Normal), 4);
+        // Every hit does at least 1 damage
+        assert_eq!(defense.mitigate(3, &DamageType::Normal), 1);
+        assert_eq!(defense.mitigate(0, &DamageType::Normal), 0);
+    }
 }
@@ -4,12 +4,22 @@
 //!
 //! This module provides a more robust alternative to simple string-based story tracking,
 //! with clear speaker attribution and better context management for AI prompts.
+//!
+//! Only the last [`PROMPT_TURNS`] turns are sent to the DM word for word. Older
+//! turns are folded into chapter summaries by [`super::story_memory`]; turns
+//! pushed out of the window before they're summarized wait in
+//! [`ConversationManager::evicted`].
 
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 /// Maximum number of conversation turns to keep in context
 const MAX_CONVERSATION_TURNS: usize = 20;
+/// Most recent turns sent to the DM word for word
+pub const PROMPT_TURNS: usize = 10;
+/// Evicted turns kept waiting for a summary; the oldest are dropped beyond
+/// this if summaries keep failing
+const MAX_EVICTED_TURNS: usize = 60;
 
 /// Identifies who is speaking in a conversation turn
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -79,6 +89,10 @@ pub struct ConversationManager {
     /// Current turn number (increments with each turn)
     #[serde(default)]
     current_turn: u32,
+
+    /// Turns pushed out of the window that haven't been summarized yet
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    evicted: VecDeque<ConversationTurn>,
 }
 
 fn default_max_turns() -> usize {
@@ -98,6 +112,7 @@ impl ConversationManager {
             turns: VecDeque::new(),
             max_turns: MAX_CONVERSATION_TURNS,
             current_turn: 0,
+            evicted: VecDeque::new(),
         }
     }
 
@@ -119,12 +134,31 @@ impl ConversationManager {
     fn add_turn(&mut self, turn: ConversationTurn) {
         self.turns.push_back(turn);
 
-        // Keep only the most recent turns
+        // Keep only the most recent turns; older ones wait for a summary
         while self.turns.len() > self.max_turns {
-            self.turns.pop_front();
+            if let Some(turn) = self.turns.pop_front() {
+                self.evicted.push_back(turn);
+            }
+        }
+        while self.evicted.len() > MAX_EVICTED_TURNS {
+            if let Some(turn) = self.evicted.pop_front() {
+                tracing::warn!("Turn {} dropped before it was summarized", turn.turn_number);
+            }
         }
     }
 
+    /// Turns pushed out of the window that haven't been summarized yet,
+    /// oldest first
+    pub fn evicted(&self) -> &VecDeque<ConversationTurn> {
+        &self.evicted
+    }
+
+    /// Forget evicted turns up to and including `turn_number`, once they're
+    /// in a summary
+    pub fn forget_evicted_through(&mut self, turn_number: u32) {
+        self.evicted.retain(|turn| turn.turn_number > turn_number);
+    }
+
     /// Get the most recent N turns
     pub fn get_recent_turns(&self, count: usize) -> Vec<&ConversationTurn> {
         let skip_count = self.turns.len().saturating_sub(count);
@@ -151,6 +185,7 @@ impl ConversationManager {
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.turns.clear();
+        self.evicted.clear();
         self.current_turn = 0;
     }
 
@@ -194,6 +229,7 @@ impl ConversationManager {
             turns: VecDeque::with_capacity(max_turns),
             max_turns,
             current_turn: 0,
+            evicted: VecDeque::new(),
         }
     }
 
@@ -305,6 +341,12 @@ mod tests {
         let turns = conv.get_all_turns();
         assert_eq!(turns[0].message, "Turn 2");
         assert_eq!(turns[2].message, "Turn 4");
+
+        // The evicted turn waits for a summary
+        assert_eq!(conv.evicted().len(), 1);
+        assert_eq!(conv.evicted()[0].message, "Turn 1");
+        conv.forget_evicted_through(0);
+        assert!(conv.evicted().is_empty());
     }
 
     #[test]
@@ -0,0 +1,403 @@
+//! # Crafting Module
+//!
+//! Recipes that turn junk into chems, ammo and weapon mods, loaded from TOML
+//! data files.
+//!
+//! ## Data Files
+//!
+//! `data/recipes/*.toml` hold `[[recipe]]` tables (see the comments in
+//! `aid.toml` for the fields). Files are read in name order and checked
+//! before the game starts: IDs must be unique snake_case and every output
+//! and component must be a known item.
+//!
+//! Files in `mods/recipes/*.toml` are read afterwards. A recipe with an
+//! existing ID replaces the original and anything else is added. A mod file
+//! that fails to load is skipped with a warning.
+//!
+//! ## Requirements
+//!
+//! Each recipe needs a minimum Science or Repair skill and its components.
+//! Some can only be made at a workbench: a location whose worldbook state
+//! has [`WORKBENCH_STATE`] set to `"true"`. [`RecipeDefinition::missing`]
+//! lists whatever is lacking, so the crafting screen can show why a recipe
+//! can't be made yet.
+
+use super::character::Character;
+use super::items::catalog::toml_files;
+use super::items::{registry, Item};
+use crate::error::GameError;
+use crate::validation_garde::validate_data_id;
+use garde::Validate;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Directory holding the base recipes
+pub const RECIPE_DATA_DIR: &str = "data/recipes";
+/// Directory holding mod recipes
+pub const RECIPE_MOD_DIR: &str = "mods/recipes";
+/// `Location::state` key marking a location with a workbench
+pub const WORKBENCH_STATE: &str = "workbench";
+/// Longest a recipe can take, in minutes
+pub const MAX_CRAFT_MINUTES: u32 = 480;
+
+/// Global recipe book
+static RECIPES: OnceCell<RecipeBook> = OnceCell::new();
+
+/// Load the recipe book if it isn't loaded yet (the game does this at
+/// startup, see [`crate::game::load_game_data`])
+pub fn load_recipes() -> Result<&'static RecipeBook, GameError> {
+    RECIPES
+        .get_or_try_init(|| RecipeBook::load(Path::new(RECIPE_DATA_DIR), Path::new(RECIPE_MOD_DIR)))
+}
+
+/// The recipe book, loaded on first use when the game didn't load it at
+/// startup (tests and tools)
+pub fn recipes() -> &'static RecipeBook {
+    load_recipes().expect("recipes should load")
+}
+
+/// Skill a recipe is made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CraftSkill {
+    Science,
+    Repair,
+}
+
+impl CraftSkill {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CraftSkill::Science => "Science",
+            CraftSkill::Repair => "Repair",
+        }
+    }
+
+    /// The character's value in this skill
+    pub fn value(&self, character: &Character) -> u8 {
+        match self {
+            CraftSkill::Science => character.skills.science,
+            CraftSkill::Repair => character.skills.repair,
+        }
+    }
+}
+
+/// One `[[recipe]]` entry in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeDefinition {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    /// ID of the item made
+    #[garde(custom(validate_data_id))]
+    pub output: String,
+    /// How many are made at once
+    #[serde(default = "default_qty")]
+    #[garde(range(min = 1, max = 100))]
+    pub qty: u32,
+    #[garde(skip)]
+    pub skill: CraftSkill,
+    #[garde(range(max = 100))]
+    pub min_skill: u8,
+    /// Whether it can only be made at a workbench
+    #[serde(default)]
+    #[garde(skip)]
+    pub workbench: bool,
+    #[serde(default = "default_minutes")]
+    #[garde(range(min = 1, max = MAX_CRAFT_MINUTES))]
+    pub minutes: u32,
+    #[garde(length(min = 1, max = 8), dive)]
+    pub components: Vec<Component>,
+}
+
+fn default_qty() -> u32 {
+    1
+}
+
+fn default_minutes() -> u32 {
+    30
+}
+
+/// An item used up by a recipe
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct Component {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    #[serde(default = "default_qty")]
+    #[garde(range(min = 1, max = 100))]
+    pub qty: u32,
+}
+
+impl RecipeDefinition {
+    /// Display name: the name of the item made
+    pub fn name(&self) -> String {
+        registry()
+            .item(&self.output)
+            .map_or_else(|| self.output.clone(), |item| item.name.to_string())
+    }
+
+    /// The items made, as one stack
+    pub fn output_item(&self) -> Option<Item> {
+        let mut item = registry().item(&self.output)?;
+        item.quantity = self.qty;
+        Some(item)
+    }
+
+    /// Everything stopping the character from making this here, e.g.
+    /// "Science 40 (you have 20)" or "2 more Scrap Metal". Empty when the
+    /// recipe can be made.
+    pub fn missing(&self, character: &Character, at_workbench: bool) -> Vec<String> {
+        let mut missing = Vec::new();
+        let skill = self.skill.value(character);
+        if skill < self.min_skill {
+            missing.push(format!(
+                "{} {} (you have {})",
+                self.skill.name(),
+                self.min_skill,
+                skill
+            ));
+        }
+        if self.workbench && !at_workbench {
+            missing.push("A workbench".to_string());
+        }
+        for component in &self.components {
+            let have = character.item_count(&component.id);
+            if have < component.qty {
+                missing.push(format!(
+                    "{} more {}",
+                    component.qty - have,
+                    component_name(&component.id)
+                ));
+            }
+        }
+        missing
+    }
+
+    /// "2x Scrap Metal, 1x Duct Tape"
+    pub fn components_text(&self) -> String {
+        self.components
+            .iter()
+            .map(|component| format!("{}x {}", component.qty, component_name(&component.id)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn component_name(id: &str) -> String {
+    registry()
+        .item(id)
+        .map_or_else(|| id.to_string(), |item| item.name.to_string())
+}
+
+/// Layout of a data file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeFile {
+    #[serde(default)]
+    recipe: Vec<RecipeDefinition>,
+}
+
+/// Every known recipe, in data file order
+#[derive(Debug, Clone, Default)]
+pub struct RecipeBook {
+    recipes: Vec<RecipeDefinition>,
+}
+
+impl RecipeBook {
+    /// Load the base data directory, then apply any mods on top
+    pub fn load(data_dir: &Path, mod_dir: &Path) -> Result<Self, GameError> {
+        let mut book = RecipeBook::default();
+
+        for path in toml_files(data_dir)? {
+            let file = path.display().to_string();
+            let source = fs::read_to_string(&path)?;
+            let data = Self::parse(&source, &file)?;
+            for recipe in &data.recipe {
+                if book.recipe(&recipe.id).is_some() {
+                    return Err(GameError::DataFile {
+                        file,
+                        message: format!("recipe '{}' is defined twice", recipe.id),
+                    });
+                }
+            }
+            book.merge(data);
+        }
+
+        if mod_dir.is_dir() {
+            for path in toml_files(mod_dir)? {
+                let file = path.display().to_string();
+                match fs::read_to_string(&path)
+                    .map_err(GameError::from)
+                    .and_then(|source| Self::parse(&source, &file))
+                {
+                    Ok(data) => {
+                        tracing::info!("Loaded {} mod recipes from {}", data.recipe.len(), file);
+                        book.merge(data);
+                    }
+                    Err(e) => tracing::warn!("Skipping recipe mod: {}", e),
+                }
+            }
+        }
+
+        book.check_references()?;
+        Ok(book)
+    }
+
+    /// Parse and validate the recipes in one data file
+    fn parse(source: &str, file: &str) -> Result<RecipeFile, GameError> {
+        let error = |message: String| GameError::DataFile {
+            file: file.to_string(),
+            message,
+        };
+        let data: RecipeFile = toml::from_str(source).map_err(|e| error(e.to_string()))?;
+        for recipe in &data.recipe {
+            recipe
+                .validate()
+                .map_err(|e| error(format!("recipe '{}': {}", recipe.id, e)))?;
+        }
+        Ok(data)
+    }
+
+    /// Add recipes, replacing any existing ones with the same ID in place
+    fn merge(&mut self, data: RecipeFile) {
+        for recipe in data.recipe {
+            match self.recipes.iter_mut().find(|r| r.id == recipe.id) {
+                Some(existing) => *existing = recipe,
+                None => self.recipes.push(recipe),
+            }
+        }
+    }
+
+    /// Outputs and components must be known items
+    fn check_references(&self) -> Result<(), GameError> {
+        for recipe in &self.recipes {
+            let ids =
+                std::iter::once(&recipe.output).chain(recipe.components.iter().map(|c| &c.id));
+            for id in ids {
+                if !registry().contains(id) {
+                    return Err(GameError::DataFile {
+                        file: RECIPE_DATA_DIR.to_string(),
+                        message: format!("recipe '{}' uses unknown item '{}'", recipe.id, id),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn recipe(&self, id: &str) -> Option<&RecipeDefinition> {
+        self.recipes.iter().find(|recipe| recipe.id == id)
+    }
+
+    /// Find a recipe by ID or (case-insensitive) name of what it makes
+    pub fn find(&self, query: &str) -> Option<&RecipeDefinition> {
+        let query = query.trim();
+        self.recipe(query).or_else(|| {
+            self.recipes
+                .iter()
+                .find(|recipe| recipe.name().eq_ignore_ascii_case(query))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RecipeDefinition> {
+        self.recipes.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::character::Special;
+
+    const GLUE_MOD: &str = r#"
+[[recipe]]
+id = "duct_tape"
+output = "duct_tape"
+qty = 2
+skill = "repair"
+min_skill = 10
+components = [{ id = "wonderglue" }]
+
+[[recipe]]
+id = "stimpak"
+output = "stimpak"
+skill = "science"
+min_skill = 0
+components = [{ id = "tin_can" }]
+"#;
+
+    fn scavenger() -> Character {
+        let mut character = Character::new("Scavenger", Special::new());
+        character.inventory.clear();
+        character
+    }
+
+    #[test]
+    fn test_base_recipes_load() {
+        let book = recipes();
+        for id in ["stimpak", "10mm_ammo", "reflex_sight"] {
+            assert!(book.recipe(id).is_some(), "missing {}", id);
+        }
+        assert_eq!(book.find("Reflex Sight").unwrap().id, "reflex_sight");
+        assert!(book.find("power armor").is_none());
+    }
+
+    #[test]
+    fn test_missing_requirements() {
+        let recipe = recipes().recipe("10mm_ammo").unwrap();
+        let mut character = scavenger();
+        character.skills.repair = 0;
+
+        let missing = recipe.missing(&character, false);
+        assert_eq!(missing.len(), 4);
+        assert!(missing[0].starts_with("Repair"));
+        assert_eq!(missing[1], "A workbench");
+        assert_eq!(missing[3], "2 more Gunpowder");
+
+        character.skills.repair = recipe.min_skill;
+        for component in &recipe.components {
+            let mut item = registry().item(&component.id).unwrap();
+            item.quantity = component.qty;
+            character.add_item(item);
+        }
+        assert!(recipe.missing(&character, true).is_empty());
+        assert_eq!(recipe.output_item().unwrap().quantity, recipe.qty);
+        assert_eq!(recipe.components_text(), "1x Scrap Metal, 2x Gunpowder");
+    }
+
+    #[test]
+    fn test_mods_add_and_replace_recipes() {
+        let dir = std::env::temp_dir().join(format!("recipe_mods_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("glue.toml"), GLUE_MOD).unwrap();
+        fs::write(dir.join("broken.toml"), "[[recipe]]\nid = \"x\"").unwrap();
+
+        let modded = RecipeBook::load(Path::new(RECIPE_DATA_DIR), &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(modded.recipe("duct_tape").unwrap().qty, 2);
+        assert_eq!(
+            modded.recipe("stimpak").unwrap().components[0].id,
+            "tin_can"
+        );
+        assert!(modded.recipe("x").is_none());
+        assert_eq!(modded.iter().count(), recipes().iter().count() + 1);
+    }
+
+    #[test]
+    fn test_unknown_items_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("recipe_data_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("bad.toml"),
+            "[[recipe]]\nid = \"gizmo\"\noutput = \"gizmo\"\nskill = \"science\"\nmin_skill = 1\ncomponents = [{ id = \"tin_can\" }]\n",
+        )
+        .unwrap();
+
+        let result = RecipeBook::load(&dir, Path::new(RECIPE_MOD_DIR));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,713 @@
+//! # Dice Module
+//!
+//! Parser and roller for dice expressions such as `2d6+3`, `4d6kh3` or
+//! `1d20adv+AGI`.
+//!
+//! ## Syntax
+//!
+//! An expression is one or more terms joined by `+` or `-` (whitespace and
+//! case are ignored):
+//! - **Dice**: `NdM` rolls N dice with M sides (`d6` is `1d6`, `d%` is `1d100`,
+//!   `0d6` rolls nothing)
+//! - **Constants**: `3`, `-1` (up to [`MAX_CONSTANT`])
+//! - **SPECIAL tokens**: `STR`, `PER`, `END`, `CHA`, `INT`, `AGI`, `LCK`,
+//!   which add half the stat (rounded down)
+//!
+//! Dice terms take optional modifiers:
+//! - `khN` / `klN`: keep the N highest / lowest dice (`k3` means `kh3`)
+//! - `dhN` / `dlN`: drop the N highest / lowest dice
+//! - `!`: exploding dice; a die showing its maximum is rolled again and added
+//! - `adv` / `dis`: roll the term twice and keep the better / worse result
+//!
+//! ## Example
+//!
+//! ```
+//! use fallout_dnd::game::dice::DiceExpr;
+//! use fallout_dnd::game::rng::GameRng;
+//!
+//! let expr: DiceExpr = "4d6kh3 + 2".parse().unwrap();
+//! let roll = expr.roll(None, &mut GameRng::seeded(1)).unwrap();
+//! assert!((5..=20).contains(&roll.total));
+//! println!("{}", roll); // e.g. "4d6kh3+2: [6, 4, (1), 3] + 2 = 15"
+//!
+//! assert!("2d6+".parse::<DiceExpr>().is_err());
+//! ```
+
+use super::character::{Special, Stat};
+use crate::error::DiceError;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Largest constant term
+pub const MAX_CONSTANT: u32 = 10_000;
+/// Most dice a single term may roll
+pub const MAX_DICE: u32 = 100;
+/// Most sides a die may have
+pub const MAX_SIDES: u32 = 1000;
+/// Most times a single exploding die may re-roll
+pub const MAX_EXPLOSIONS: u32 = 100;
+
+/// Which dice of a term count towards its total
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+    DropHighest(u32),
+    DropLowest(u32),
+}
+
+impl Keep {
+    /// Number of dice kept out of `count`
+    fn kept(&self, count: u32) -> u32 {
+        match *self {
+            Keep::Highest(n) | Keep::Lowest(n) => n,
+            Keep::DropHighest(n) | Keep::DropLowest(n) => count - n,
+        }
+    }
+
+    /// Whether the kept dice are the highest ones
+    fn keeps_highest(&self) -> bool {
+        matches!(self, Keep::Highest(_) | Keep::DropLowest(_))
+    }
+}
+
+/// Advantage rolls a term twice and keeps the better result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollMode {
+    #[default]
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+/// A group of dice like `4d6kh3!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceTerm {
+    pub count: u32,
+    pub sides: u32,
+    pub keep: Option<Keep>,
+    pub exploding: bool,
+    pub mode: RollMode,
+}
+
+/// One term of an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Dice(DiceTerm),
+    Constant(i32),
+    Stat(Stat),
+}
+
+/// A parsed dice expression: signed terms added together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceExpr {
+    /// Each term with its sign (`1` or `-1`)
+    pub terms: Vec<(i32, Term)>,
+}
+
+/// A single rolled die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DieRoll {
+    /// Face value, including any explosion re-rolls
+    pub value: u32,
+    /// Whether the die counted towards the total
+    pub kept: bool,
+    /// Whether the die exploded at least once
+    pub exploded: bool,
+}
+
+/// The rolled value of one term
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermRoll {
+    /// The term as written, e.g. `4d6kh3` or `STR`
+    pub label: String,
+    /// `1` or `-1`
+    pub sign: i32,
+    /// Individual dice (empty for constants and stats)
+    pub dice: Vec<DieRoll>,
+    /// Value of the term before its sign is applied
+    pub value: i32,
+}
+
+/// A complete roll with a per-term breakdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceRoll {
+    /// The expression that was rolled, normalized
+    pub expression: String,
+    pub terms: Vec<TermRoll>,
+    pub total: i32,
+}
+
+/// Parse and roll an expression in one go
+pub fn roll(
+    expression: &str,
+    stats: Option<&Special>,
+    rng: &mut impl Rng,
+) -> Result<DiceRoll, DiceError> {
+    expression.parse::<DiceExpr>()?.roll(stats, rng)
+}
+
+impl DiceExpr {
+    /// Roll every term. SPECIAL tokens use `stats`; without stats they add 0.
+    /// Fails if the total doesn't fit in an `i32` (only possible with a great
+    /// many terms).
+    pub fn roll(&self, stats: Option<&Special>, rng: &mut impl Rng) -> Result<DiceRoll, DiceError> {
+        let terms: Vec<TermRoll> = self
+            .terms
+            .iter()
+            .map(|(sign, term)| {
+                let (dice, value) = match term {
+                    Term::Dice(dice) => dice.roll(rng),
+                    Term::Constant(value) => (Vec::new(), *value),
+                    Term::Stat(stat) => (Vec::new(), stats.map_or(0, |s| stat.modifier(s))),
+                };
+                TermRoll {
+                    label: term.to_string(),
+                    sign: *sign,
+                    dice,
+                    value,
+                }
+            })
+            .collect();
+
+        let total = terms
+            .iter()
+            .try_fold(0i32, |total, term| {
+                total.checked_add(term.sign.checked_mul(term.value)?)
+            })
+            .ok_or(DiceError::Overflow)?;
+        Ok(DiceRoll {
+            expression: self.to_string(),
+            terms,
+            total,
+        })
+    }
+}
+
+impl DiceTerm {
+    fn roll(&self, rng: &mut impl Rng) -> (Vec<DieRoll>, i32) {
+        let first = self.roll_once(rng);
+        if self.mode == RollMode::Normal {
+            return first;
+        }
+
+        let second = self.roll_once(rng);
+        let first_wins = match self.mode {
+            RollMode::Advantage => first.1 >= second.1,
+            _ => first.1 <= second.1,
+        };
+        let (mut winner, mut loser) = if first_wins {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        for die in &mut loser.0 {
+            die.kept = false;
+        }
+        winner.0.append(&mut loser.0);
+        winner
+    }
+
+    fn roll_once(&self, rng: &mut impl Rng) -> (Vec<DieRoll>, i32) {
+        let mut dice: Vec<DieRoll> = (0..self.count)
+            .map(|_| {
+                let mut value = rng.random_range(1..=self.sides);
+                let mut face = value;
+                let mut explosions = 0;
+                while self.exploding && face == self.sides && explosions < MAX_EXPLOSIONS {
+                    face = rng.random_range(1..=self.sides);
+                    value += face;
+                    explosions += 1;
+                }
+                DieRoll {
+                    value,
+                    kept: true,
+                    exploded: explosions > 0,
+                }
+            })
+            .collect();
+
+        if let Some(keep) = self.keep {
+            let mut order: Vec<usize> = (0..dice.len()).collect();
+            order.sort_by_key(|&i| dice[i].value);
+            if keep.keeps_highest() {
+                order.reverse();
+            }
+            for &i in order.iter().skip(keep.kept(self.count) as usize) {
+                dice[i].kept = false;
+            }
+        }
+
+        let total = dice
+            .iter()
+            .filter(|die| die.kept)
+            .map(|die| die.value as i32)
+            .sum();
+        (dice, total)
+    }
+}
+
+impl FromStr for DiceExpr {
+    type Err = DiceError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Parser::new(expression).parse()
+    }
+}
+
+/// Hand-written parser over the expression with whitespace removed
+struct Parser<'a> {
+    expression: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(expression: &'a str) -> Self {
+        Parser {
+            expression,
+            chars: expression
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_ascii_lowercase())
+                .collect(),
+            pos: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<DiceExpr, DiceError> {
+        if self.chars.is_empty() {
+            return Err(DiceError::Empty);
+        }
+
+        let mut terms = Vec::new();
+        let mut sign = match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                -1
+            }
+            Some('+') => {
+                self.pos += 1;
+                1
+            }
+            _ => 1,
+        };
+        loop {
+            terms.push((sign, self.term()?));
+            sign = match self.peek() {
+                None => break,
+                Some('+') => 1,
+                Some('-') => -1,
+                Some(found) => return Err(self.unexpected(found)),
+            };
+            self.pos += 1;
+        }
+
+        Ok(DiceExpr { terms })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let matches = text
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c));
+        if matches {
+            self.pos += text.len();
+        }
+        matches
+    }
+
+    fn unexpected(&self, found: char) -> DiceError {
+        DiceError::UnexpectedChar {
+            expression: self.expression.to_string(),
+            found,
+        }
+    }
+
+    fn number(&mut self) -> Result<Option<u32>, DiceError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits
+            .parse()
+            .map(Some)
+            .map_err(|_| DiceError::NumberTooLarge(digits))
+    }
+
+    fn term(&mut self) -> Result<Term, DiceError> {
+        let count = self.number()?;
+        let starts_dice = self.peek() == Some('d')
+            && self
+                .chars
+                .get(self.pos + 1)
+                .is_some_and(|c| c.is_ascii_digit() || *c == '%');
+        if starts_dice {
+            self.pos += 1;
+            return self.dice(count.unwrap_or(1)).map(Term::Dice);
+        }
+        if let Some(value) = count {
+            if value > MAX_CONSTANT {
+                return Err(DiceError::NumberTooLarge(value.to_string()));
+            }
+            return Ok(Term::Constant(value as i32));
+        }
+
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let token: String = self.chars[start..self.pos].iter().collect();
+        match self.peek() {
+            _ if !token.is_empty() => Stat::from_token(&token)
+                .map(Term::Stat)
+                .ok_or(DiceError::UnknownToken(token.to_ascii_uppercase())),
+            Some(found) => Err(self.unexpected(found)),
+            None => Err(DiceError::UnexpectedEnd(self.expression.to_string())),
+        }
+    }
+
+    fn dice(&mut self, count: u32) -> Result<DiceTerm, DiceError> {
+        let sides = if self.eat("%") {
+            100
+        } else {
+            self.number()?.unwrap_or(0)
+        };
+        if count > MAX_DICE {
+            return Err(DiceError::DiceCount {
+                count,
+                max: MAX_DICE,
+            });
+        }
+        if sides == 0 || sides > MAX_SIDES {
+            return Err(DiceError::DieSize {
+                sides,
+                max: MAX_SIDES,
+            });
+        }
+
+        let mut term = DiceTerm {
+            count,
+            sides,
+            keep: None,
+            exploding: false,
+            mode: RollMode::Normal,
+        };
+        loop {
+            if self.eat("!") {
+                term.exploding = true;
+            } else if self.eat("adv") {
+                term.mode = RollMode::Advantage;
+            } else if self.eat("dis") {
+                term.mode = RollMode::Disadvantage;
+            } else if let Some(keep) = self.keep_modifier()? {
+                let valid = match keep {
+                    Keep::Highest(n) | Keep::Lowest(n) => (1..=count).contains(&n),
+                    Keep::DropHighest(n) | Keep::DropLowest(n) => n < count,
+                };
+                if !valid {
+                    return Err(DiceError::InvalidKeep {
+                        term: format!("{}d{}", count, sides),
+                    });
+                }
+                term.keep = Some(keep);
+            } else {
+                break;
+            }
+        }
+
+        if term.exploding && sides < 2 {
+            return Err(DiceError::DieSize {
+                sides,
+                max: MAX_SIDES,
+            });
+        }
+        Ok(term)
+    }
+
+    fn keep_modifier(&mut self) -> Result<Option<Keep>, DiceError> {
+        let kind: fn(u32) -> Keep = if self.eat("kh") {
+            Keep::Highest
+        } else if self.eat("kl") {
+            Keep::Lowest
+        } else if self.eat("dh") {
+            Keep::DropHighest
+        } else if self.eat("dl") {
+            Keep::DropLowest
+        } else if self.eat("k") {
+            Keep::Highest
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(kind(self.number()?.unwrap_or(1))))
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Constant(value) => write!(f, "{}", value),
+            Term::Stat(stat) => f.write_str(stat.token()),
+            Term::Dice(dice) => {
+                write!(f, "{}d{}", dice.count, dice.sides)?;
+                match dice.keep {
+                    Some(Keep::Highest(n)) => write!(f, "kh{}", n)?,
+                    Some(Keep::Lowest(n)) => write!(f, "kl{}", n)?,
+                    Some(Keep::DropHighest(n)) => write!(f, "dh{}", n)?,
+                    Some(Keep::DropLowest(n)) => write!(f, "dl{}", n)?,
+                    None => {}
+                }
+                if dice.exploding {
+                    f.write_str("!")?;
+                }
+                match dice.mode {
+                    RollMode::Advantage => f.write_str("adv"),
+                    RollMode::Disadvantage => f.write_str("dis"),
+                    RollMode::Normal => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DiceExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (sign, term)) in self.terms.iter().enumerate() {
+            if *sign < 0 {
+                f.write_str("-")?;
+            } else if i > 0 {
+                f.write_str("+")?;
+            }
+            write!(f, "{}", term)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiceRoll {
+    /// Breakdown like `4d6kh3+STR: [6, 4, (1), 3] + 2 = 15`.
+    /// Dropped dice are in parentheses and exploded dice end in `!`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.expression)?;
+        for (i, term) in self.terms.iter().enumerate() {
+            if term.sign < 0 {
+                f.write_str(if i == 0 { "-" } else { " - " })?;
+            } else if i > 0 {
+                f.write_str(" + ")?;
+            }
+            if term.dice.is_empty() {
+                write!(f, "{}", term.value)?;
+                continue;
+            }
+            let dice: Vec<String> = term
+                .dice
+                .iter()
+                .map(|die| {
+                    let face = if die.exploded {
+                        format!("{}!", die.value)
+                    } else {
+                        die.value.to_string()
+                    };
+                    if die.kept {
+                        face
+                    } else {
+                        format!("({})", face)
+                    }
+                })
+                .collect();
+            write!(f, "[{}]", dice.join(", "))?;
+        }
+        write!(f, " = {}", self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::GameRng;
+
+    fn parse(expression: &str) -> DiceExpr {
+        expression.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_terms_and_signs() {
+        let expr = parse("2d6 - 1 + STR");
+        assert_eq!(
+            expr.terms,
+            vec![
+                (
+                    1,
+                    Term::Dice(DiceTerm {
+                        count: 2,
+                        sides: 6,
+                        keep: None,
+                        exploding: false,
+                        mode: RollMode::Normal,
+                    })
+                ),
+                (-1, Term::Constant(1)),
+                (1, Term::Stat(Stat::Strength)),
+            ]
+        );
+        assert_eq!(expr.to_string(), "2d6-1+STR");
+        assert_eq!(parse("d%").to_string(), "1d100");
+        assert_eq!(parse("-d4").to_string(), "-1d4");
+        assert_eq!(parse("4D6KH3").to_string(), "4d6kh3");
+        assert_eq!(parse("4d6k3!").to_string(), "4d6kh3!");
+        assert_eq!(parse("1d20adv+agi").to_string(), "1d20adv+AGI");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<DiceExpr>(), Err(DiceError::Empty));
+        assert!(matches!(
+            "2d6+".parse::<DiceExpr>(),
+            Err(DiceError::UnexpectedEnd(_))
+        ));
+        assert!(matches!(
+            "2d6*2".parse::<DiceExpr>(),
+            Err(DiceError::UnexpectedChar { found: '*', .. })
+        ));
+        assert_eq!(
+            "1d8+FOO".parse::<DiceExpr>(),
+            Err(DiceError::UnknownToken("FOO".to_string()))
+        );
+        assert!(matches!(
+            "101d6".parse::<DiceExpr>(),
+            Err(DiceError::DiceCount { .. })
+        ));
+        assert!(matches!(
+            "1d0".parse::<DiceExpr>(),
+            Err(DiceError::DieSize { .. })
+        ));
+        assert!(matches!(
+            "1d1!".parse::<DiceExpr>(),
+            Err(DiceError::DieSize { .. })
+        ));
+        assert!(matches!(
+            "2d6kh3".parse::<DiceExpr>(),
+            Err(DiceError::InvalidKeep { .. })
+        ));
+        assert!(matches!(
+            "2d6dl2".parse::<DiceExpr>(),
+            Err(DiceError::InvalidKeep { .. })
+        ));
+    }
+
+    #[test]
+    fn test_totals_never_overflow() {
+        assert!(matches!(
+            "2147483647+1".parse::<DiceExpr>(),
+            Err(DiceError::NumberTooLarge(_))
+        ));
+        assert!(matches!(
+            "99999999999".parse::<DiceExpr>(),
+            Err(DiceError::NumberTooLarge(_))
+        ));
+        assert!(format!("{}", MAX_CONSTANT).parse::<DiceExpr>().is_ok());
+
+        // Enough terms at the limit add up past i32::MAX
+        let terms = i32::MAX as u32 / MAX_CONSTANT + 1;
+        let expr = parse(&vec![MAX_CONSTANT.to_string(); terms as usize].join("+"));
+        assert!(matches!(
+            expr.roll(None, &mut GameRng::seeded(1)),
+            Err(DiceError::Overflow)
+        ));
+        let expr = parse(&format!("-{}", vec!["10000"; terms as usize].join("-")));
+        assert!(expr.roll(None, &mut GameRng::seeded(1)).is_err());
+    }
+
+    #[test]
+    fn test_roll_ranges() {
+        let mut rng = GameRng::seeded(3);
+        for _ in 0..200 {
+            let total = parse("2d6-1").roll(None, &mut rng).unwrap().total;
+            assert!((1..=11).contains(&total));
+            let total = parse("4d6kh3").roll(None, &mut rng).unwrap().total;
+            assert!((3..=18).contains(&total));
+            let total = parse("4d6dl1").roll(None, &mut rng).unwrap().total;
+            assert!((3..=18).contains(&total));
+            let total = parse("1d6!").roll(None, &mut rng).unwrap().total;
+            assert!(total >= 1 && total % 6 != 0);
+        }
+    }
+
+    #[test]
+    fn test_keep_marks_dropped_dice() {
+        let roll = parse("4d6kh3")
+            .roll(None, &mut GameRng::seeded(11))
+            .unwrap();
+        let dice = &roll.terms[0].dice;
+        assert_eq!(dice.len(), 4);
+        assert_eq!(dice.iter().filter(|die| die.kept).count(), 3);
+
+        let dropped = dice.iter().find(|die| !die.kept).unwrap();
+        assert!(dice.iter().all(|die| die.value >= dropped.value));
+        let kept: i32 = dice.iter().filter(|d| d.kept).map(|d| d.value as i32).sum();
+        assert_eq!(roll.total, kept);
+    }
+
+    #[test]
+    fn test_advantage_keeps_better_roll() {
+        let mut rng = GameRng::seeded(5);
+        for _ in 0..50 {
+            let roll = parse("1d20adv").roll(None, &mut rng).unwrap();
+            let dice = &roll.terms[0].dice;
+            assert_eq!(dice.len(), 2);
+            assert_eq!(
+                roll.total,
+                dice.iter().map(|d| d.value).max().unwrap() as i32
+            );
+
+            let roll = parse("1d20dis").roll(None, &mut rng).unwrap();
+            let dice = &roll.terms[0].dice;
+            assert_eq!(
+                roll.total,
+                dice.iter().map(|d| d.value).min().unwrap() as i32
+            );
+        }
+    }
+
+    #[test]
+    fn test_stat_tokens_use_half_the_stat() {
+        let mut special = Special::new();
+        special.agility = 7;
+        special.luck = 4;
+        let roll = parse("AGI+LCK+1")
+            .roll(Some(&special), &mut GameRng::seeded(1))
+            .unwrap();
+        assert_eq!(roll.total, 3 + 2 + 1);
+
+        let roll = parse("AGI+LCK+1")
+            .roll(None, &mut GameRng::seeded(1))
+            .unwrap();
+        assert_eq!(roll.total, 1);
+    }
+
+    #[test]
+    fn test_breakdown_display() {
+        let mut special = Special::new();
+        special.strength = 6;
+        let roll = parse("2d6+STR-1")
+            .roll(Some(&special), &mut GameRng::seeded(9))
+            .unwrap();
+        let dice = &roll.terms[0].dice;
+        assert_eq!(
+            roll.to_string(),
+            format!(
+                "2d6+STR-1: [{}, {}] + 3 - 1 = {}",
+                dice[0].value, dice[1].value, roll.total
+            )
+        );
+    }
+}
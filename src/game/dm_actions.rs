@@ -0,0 +1,1122 @@
+//! # DM Actions
+//!
+//! Structured game actions emitted by the AI Dungeon Master.
+//!
+//! ## Overview
+//!
+//! Narrative alone can't change the game: the DM can describe a fight or a
+//! reward, but only the engine can start combat or put an item in the player's
+//! pack. The DM therefore appends a JSON block to its response:
+//!
+//! ```text
+//! The trader slides a stimpak across the counter.
+//! <actions>
+//! [{"action": "give_item", "id": "stimpak", "qty": 1},
+//!  {"action": "take_caps", "n": 50}]
+//! </actions>
+//! ```
+//!
+//! The block is stripped from the displayed narrative, each action is validated
+//! against the current [`GameState`], and valid actions are applied by the engine.
+//!
+//! ## Supported Actions
+//!
+//! - `start_combat{enemies}`: Start an encounter (`[{"name", "level", "count"}]`)
+//! - `give_item{id, qty}`: Add a known item to the inventory (refused if the
+//!   player couldn't carry it)
+//! - `take_caps{n}`: Remove caps (fails if the player can't pay)
+//! - `move_to{location}`: Travel to a location (takes [`TRAVEL_MINUTES`]; refused
+//!   during combat or while the player is over-encumbered)
+//! - `pass_time{hours, minutes}`: Skip ahead in time (a long journey, a night's sleep)
+//! - `update_quest{quest, status, note, ...}`: Add, update, complete, or fail a quest.
+//!   Optional `giver`, `description`, `location`, `rewards` and `objectives`
+//!   (added as a new stage; see [`ObjectiveSpec`]) fill in the journal entry
+//! - `change_disposition{npc, delta}`: Shift how a known NPC feels about the player
+//! - `add_rads{n}`: Expose the player to radiation (worn armor blocks part of it)
+
+use super::bestiary::bestiary;
+use super::clock::TRAVEL_MINUTES;
+use super::combat::Enemy;
+use super::items::{find_item, ItemType};
+pub use super::quests::QuestStatus;
+use super::quests::{Goal, Objective, Quest, QuestEvent, QuestRewards, QuestStage};
+use super::worldbook::Worldbook;
+use super::GameState;
+use serde::{Deserialize, Serialize};
+use smartstring::alias::String as SmartString;
+
+/// Opening tag of an action block
+pub const ACTIONS_OPEN_TAG: &str = "<actions>";
+/// Closing tag of an action block
+pub const ACTIONS_CLOSE_TAG: &str = "</actions>";
+
+/// Maximum items granted by a single `give_item`
+const MAX_ITEM_QUANTITY: u32 = 10;
+/// Maximum rounds of ammunition granted by a single `give_item`
+const MAX_AMMO_QUANTITY: u32 = 60;
+/// Maximum enemies in a DM-started encounter (matches the inline combat capacity)
+pub const MAX_ENEMIES: usize = 8;
+/// How far above the player's level a DM-spawned enemy may be
+const MAX_ENEMY_LEVEL_ABOVE_PLAYER: u32 = 3;
+/// Largest disposition change allowed in one action
+const MAX_DISPOSITION_DELTA: i32 = 50;
+/// Longest time skip allowed in one action
+const MAX_TIME_SKIP_HOURS: u32 = 72;
+/// Largest XP reward a DM-defined quest may pay
+const MAX_QUEST_XP: u32 = 2000;
+/// Largest caps reward a DM-defined quest may pay
+const MAX_QUEST_CAPS: u32 = 1000;
+/// Most rads one exposure may deal (before armor)
+const MAX_RAD_EXPOSURE: u32 = 250;
+
+/// A single structured action requested by the DM
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DmAction {
+    StartCombat {
+        enemies: Vec<EnemySpawn>,
+    },
+    GiveItem {
+        id: String,
+        #[serde(default = "default_quantity")]
+        qty: u32,
+    },
+    TakeCaps {
+        n: u32,
+    },
+    MoveTo {
+        location: String,
+    },
+    PassTime {
+        #[serde(default)]
+        hours: u32,
+        #[serde(default)]
+        minutes: u32,
+    },
+    UpdateQuest {
+        quest: String,
+        #[serde(default)]
+        status: QuestStatus,
+        #[serde(default)]
+        note: Option<String>,
+        #[serde(flatten)]
+        details: QuestDetails,
+    },
+    ChangeDisposition {
+        npc: String,
+        delta: i32,
+    },
+    AddRads {
+        #[serde(alias = "rads")]
+        n: u32,
+    },
+}
+
+/// An enemy group in a `start_combat` action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemySpawn {
+    /// Enemy kind ("raider", "radroach", "super_mutant") or a custom name
+    #[serde(alias = "type", alias = "kind")]
+    pub name: String,
+    /// Enemy level (defaults to the player's level)
+    #[serde(default)]
+    pub level: Option<u32>,
+    /// Number of enemies of this kind
+    #[serde(default = "default_quantity")]
+    pub count: u32,
+}
+
+/// Optional journal details in an `update_quest` action
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuestDetails {
+    /// NPC who gave the quest
+    #[serde(default)]
+    pub giver: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Location involved in the quest
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Summary of the new stage (defaults to its first objective)
+    #[serde(default)]
+    pub stage: Option<String>,
+    /// Objectives for a new stage of the quest
+    #[serde(default)]
+    pub objectives: Vec<ObjectiveSpec>,
+    /// Rewards added to the quest's payout
+    #[serde(default)]
+    pub rewards: Option<QuestRewards>,
+}
+
+/// An objective in an `update_quest` action. At most one of `item`, `kill`
+/// and `visit` makes the engine complete it automatically; without any of
+/// them the DM completes the stage by finishing the quest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectiveSpec {
+    #[serde(alias = "description")]
+    pub text: String,
+    /// Item ID the player has to carry
+    #[serde(default)]
+    pub item: Option<String>,
+    #[serde(default = "default_quantity")]
+    pub qty: u32,
+    /// NPC the player has to kill
+    #[serde(default)]
+    pub kill: Option<String>,
+    /// Location the player has to reach
+    #[serde(default)]
+    pub visit: Option<String>,
+}
+
+impl ObjectiveSpec {
+    fn to_objective(&self) -> Objective {
+        let goal = if let Some(item) = &self.item {
+            Some(Goal::ObtainItem {
+                item: item.trim().into(),
+                quantity: self.qty,
+            })
+        } else if let Some(npc) = &self.kill {
+            Some(Goal::KillNpc {
+                npc: Worldbook::generate_id(npc.trim()),
+            })
+        } else {
+            self.visit.as_ref().map(|location| Goal::VisitLocation {
+                location: Worldbook::generate_id(location.trim()),
+            })
+        };
+        Objective::new(self.text.trim(), goal)
+    }
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+/// A DM response split into narrative text and structured actions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedResponse {
+    /// Narrative with all action blocks removed
+    pub narrative: String,
+    /// Actions that parsed successfully, in order
+    pub actions: Vec<DmAction>,
+    /// Parse errors for malformed actions
+    pub errors: Vec<String>,
+}
+
+/// Split a DM response into narrative and actions.
+///
+/// Accepts a JSON array or a single JSON object inside each `<actions>` block.
+/// A block without a closing tag runs to the end of the response. Malformed
+/// entries are reported in [`ParsedResponse::errors`] without discarding the
+/// valid ones.
+pub fn parse_dm_actions(response: &str) -> ParsedResponse {
+    let mut parsed = ParsedResponse::default();
+    let mut narrative = String::with_capacity(response.len());
+    let mut rest = response;
+
+    while let Some(start) = rest.find(ACTIONS_OPEN_TAG) {
+        narrative.push_str(&rest[..start]);
+        let after_open = &rest[start + ACTIONS_OPEN_TAG.len()..];
+
+        let (block, remainder) = match after_open.find(ACTIONS_CLOSE_TAG) {
+            Some(end) => (
+                &after_open[..end],
+                &after_open[end + ACTIONS_CLOSE_TAG.len()..],
+            ),
+            None => (after_open, ""),
+        };
+
+        parse_block(block, &mut parsed);
+        rest = remainder;
+    }
+    narrative.push_str(rest);
+
+    parsed.narrative = narrative.trim().to_string();
+    parsed
+}
+
+fn parse_block(block: &str, parsed: &mut ParsedResponse) {
+    let json = block
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    if json.is_empty() {
+        return;
+    }
+
+    let values = match serde_json::from_str::<serde_json::Value>(json) {
+        Ok(serde_json::Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(e) => {
+            parsed.errors.push(format!("Invalid action block: {}", e));
+            return;
+        }
+    };
+
+    for value in values {
+        match serde_json::from_value::<DmAction>(value) {
+            Ok(action) => parsed.actions.push(action),
+            Err(e) => parsed.errors.push(format!("Invalid action: {}", e)),
+        }
+    }
+}
+
+impl DmAction {
+    /// Check the action against the current game state without applying it
+    pub fn validate(&self, state: &GameState) -> Result<(), String> {
+        match self {
+            DmAction::StartCombat { enemies } => {
+                if state.combat.active {
+                    return Err("Combat is already in progress".to_string());
+                }
+                if enemies.is_empty() {
+                    return Err("start_combat needs at least one enemy".to_string());
+                }
+                if enemies.iter().any(|e| e.name.trim().is_empty()) {
+                    return Err("Enemy name cannot be empty".to_string());
+                }
+                if enemies.iter().any(|e| e.count == 0) {
+                    return Err("Enemy count must be at least 1".to_string());
+                }
+                match enemies
+                    .iter()
+                    .try_fold(0u32, |total, e| total.checked_add(e.count))
+                {
+                    Some(total) if total as usize <= MAX_ENEMIES => Ok(()),
+                    Some(total) => {
+                        Err(format!("Too many enemies ({}, max {})", total, MAX_ENEMIES))
+                    }
+                    None => Err(format!("Too many enemies (max {})", MAX_ENEMIES)),
+                }
+            }
+            DmAction::GiveItem { id, qty } => {
+                let item = find_item(id).ok_or_else(|| format!("Unknown item '{}'", id))?;
+                let max = if matches!(item.item_type, ItemType::Ammo) {
+                    MAX_AMMO_QUANTITY
+                } else {
+                    MAX_ITEM_QUANTITY
+                };
+                if *qty == 0 || *qty > max {
+                    return Err(format!("Item quantity must be between 1 and {}", max));
+                }
+                let weight = item.weight * *qty as f32;
+                if !state.character.can_carry(weight) {
+                    return Err(format!(
+                        "{} x{} is too heavy for the player to carry ({:.1} lbs; carrying {:.0}/{:.0} lbs)",
+                        item.name,
+                        qty,
+                        weight,
+                        state.character.carry_weight(),
+                        state.character.max_carry_weight()
+                    ));
+                }
+                Ok(())
+            }
+            DmAction::TakeCaps { n } => {
+                if *n > state.character.caps {
+                    Err(format!(
+                        "Not enough caps ({} needed, {} available)",
+                        n, state.character.caps
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            DmAction::MoveTo { location } => {
+                if location.trim().is_empty() {
+                    Err("Location cannot be empty".to_string())
+                } else if state.combat.active {
+                    Err("The player can't travel during combat".to_string())
+                } else if state.character.is_encumbered() {
+                    Err(format!(
+                        "The player is over-encumbered ({:.0}/{:.0} lbs) and can't travel until they lighten their load",
+                        state.character.carry_weight(),
+                        state.character.carry_capacity()
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            DmAction::PassTime { hours, minutes } => {
+                if state.combat.active {
+                    return Err("Time can't be skipped during combat".to_string());
+                }
+                let total = hours.saturating_mul(60).saturating_add(*minutes);
+                if total == 0 || total > MAX_TIME_SKIP_HOURS * 60 {
+                    return Err(format!(
+                        "Time skip must be between 1 minute and {} hours",
+                        MAX_TIME_SKIP_HOURS
+                    ));
+                }
+                Ok(())
+            }
+            DmAction::UpdateQuest {
+                quest,
+                status,
+                details,
+                ..
+            } => {
+                if quest.trim().is_empty() {
+                    return Err("Quest name cannot be empty".to_string());
+                }
+                match state.quests.find(quest) {
+                    Some(existing) if !existing.is_active() => {
+                        return Err(format!(
+                            "Quest '{}' is already {}",
+                            existing.name,
+                            existing.status.name().to_lowercase()
+                        ));
+                    }
+                    None if *status != QuestStatus::Active => {
+                        return Err(format!("Unknown quest '{}'", quest));
+                    }
+                    _ => {}
+                }
+                validate_quest_details(details)
+            }
+            DmAction::ChangeDisposition { npc, delta } => {
+                if delta.unsigned_abs() > MAX_DISPOSITION_DELTA as u32 {
+                    return Err(format!(
+                        "Disposition change must be within ±{}",
+                        MAX_DISPOSITION_DELTA
+                    ));
+                }
+                state
+                    .worldbook
+                    .find_npc_id(npc)
+                    .map(|_| ())
+                    .ok_or_else(|| format!("Unknown NPC '{}'", npc))
+            }
+            DmAction::AddRads { n } => {
+                if *n == 0 || *n > MAX_RAD_EXPOSURE {
+                    Err(format!(
+                        "Radiation exposure must be between 1 and {} rads",
+                        MAX_RAD_EXPOSURE
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Validate and apply the action, returning a message for the player
+    pub fn apply(&self, state: &mut GameState) -> Result<String, String> {
+        self.validate(state)?;
+
+        match self {
+            DmAction::StartCombat { enemies } => {
+                let player_level = state.character.level;
+                let max_level = player_level + MAX_ENEMY_LEVEL_ABOVE_PLAYER;
+
+                let spawned: Vec<Enemy> = enemies
+                    .iter()
+                    .flat_map(|spawn| {
+                        let level = spawn.level.unwrap_or(player_level).clamp(1, max_level);
+                        (0..spawn.count).map(move |_| spawn_enemy(&spawn.name, level))
+                    })
+                    .collect();
+
+                let names: Vec<String> = enemies
+                    .iter()
+                    .map(|e| {
+                        if e.count > 1 {
+                            format!("{} x{}", e.name, e.count)
+                        } else {
+                            e.name.clone()
+                        }
+                    })
+                    .collect();
+
+                state.combat.start_combat(spawned);
+                Ok(format!("Combat started: {}", names.join(", ")))
+            }
+            DmAction::GiveItem { id, qty } => {
+                let mut item = find_item(id).ok_or_else(|| format!("Unknown item '{}'", id))?;
+                item.quantity = *qty;
+                let name = item.name.clone();
+                state.character.add_item(item);
+
+                let mut messages = vec![format!("Received {} x{}", name, qty)];
+                messages.extend(state.quest_event(QuestEvent::ItemObtained(id.as_str().into())));
+                Ok(messages.join(". "))
+            }
+            DmAction::TakeCaps { n } => {
+                state.character.caps -= n;
+                Ok(format!("Paid {} caps", n))
+            }
+            DmAction::MoveTo { location } => {
+                let id = Worldbook::generate_id(location);
+                let name = state
+                    .worldbook
+                    .get_location(&id)
+                    .map(|loc| loc.name.to_string())
+                    .unwrap_or_else(|| location.trim().to_string());
+
+                let mut messages = vec![format!("Moved to {}", name)];
+                messages.extend(state.pass_time(TRAVEL_MINUTES));
+                state.location = name;
+                state.worldbook.set_current_location(Some(id.clone()));
+                state.worldbook.visit_location(&id);
+                messages.extend(state.quest_event(QuestEvent::LocationVisited(id)));
+                Ok(messages.join(" "))
+            }
+            DmAction::PassTime { hours, minutes } => {
+                let total = hours * 60 + minutes;
+                let events = state.pass_time(total);
+                let mut messages = vec![format!(
+                    "Time passes ({}h {:02}m). It is now day {}, {}.",
+                    total / 60,
+                    total % 60,
+                    state.day,
+                    state.time
+                )];
+                messages.extend(events);
+                Ok(messages.join(" "))
+            }
+            DmAction::UpdateQuest {
+                quest,
+                status,
+                note,
+                details,
+            } => {
+                let is_new = state.quests.find(quest).is_none();
+                if is_new {
+                    state.quests.add(Quest::new(quest));
+                }
+                let giver = details.giver.as_deref().map(|npc| {
+                    state
+                        .worldbook
+                        .find_npc_id(npc)
+                        .unwrap_or_else(|| npc.trim().into())
+                });
+                let entry = state
+                    .quests
+                    .find_mut(quest)
+                    .ok_or_else(|| format!("Unknown quest '{}'", quest))?;
+                let name = entry.name.clone();
+
+                details.apply_to(entry, giver);
+                let note = note
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|note| !note.is_empty());
+                if let Some(note) = note {
+                    entry.notes.push(note.to_string());
+                }
+                entry.status = *status;
+
+                let mut message = match status {
+                    QuestStatus::Active if is_new => format!("New quest: {}", name),
+                    QuestStatus::Active => format!("Quest updated: {}", name),
+                    QuestStatus::Completed => format!("Quest completed: {}", name),
+                    QuestStatus::Failed => format!("Quest failed: {}", name),
+                };
+                if let Some(note) = note {
+                    message = format!("{} - {}", message, note);
+                }
+
+                let mut messages = vec![message];
+                match status {
+                    QuestStatus::Active => messages.extend(state.refresh_quests()),
+                    QuestStatus::Completed => messages.extend(state.grant_quest_rewards(quest)),
+                    QuestStatus::Failed => {}
+                }
+                Ok(messages.join(". "))
+            }
+            DmAction::ChangeDisposition { npc, delta } => {
+                let id = state
+                    .worldbook
+                    .find_npc_id(npc)
+                    .ok_or_else(|| format!("Unknown NPC '{}'", npc))?;
+                let entry = state
+                    .worldbook
+                    .npcs
+                    .get_mut(&id)
+                    .ok_or_else(|| format!("Unknown NPC '{}'", npc))?;
+
+                entry.disposition = (entry.disposition + delta).clamp(-100, 100);
+                let direction = if *delta >= 0 { "improved" } else { "worsened" };
+                Ok(format!(
+                    "{}'s opinion of you {} ({})",
+                    entry.name, direction, entry.disposition
+                ))
+            }
+            DmAction::AddRads { n } => {
+                let (absorbed, sickness) = state.character.expose_to_radiation(*n);
+                let mut message = format!("+{} rads", absorbed);
+                if absorbed < *n {
+                    message.push_str(&format!(" ({} blocked by armor)", n - absorbed));
+                }
+                let mut messages = vec![message];
+                messages.extend(sickness);
+                Ok(messages.join(". "))
+            }
+        }
+    }
+}
+
+/// Create an enemy from a DM-provided kind, using the bestiary when possible
+fn spawn_enemy(name: &str, level: u32) -> Enemy {
+    bestiary()
+        .find(name)
+        .map(|enemy| enemy.spawn(level))
+        .unwrap_or_else(|| Enemy::new(name.trim(), level))
+}
+
+impl QuestDetails {
+    /// Fill in a journal entry; `giver` is the resolved NPC reference
+    fn apply_to(&self, quest: &mut Quest, giver: Option<SmartString>) {
+        if giver.is_some() {
+            quest.giver = giver;
+        }
+        if let Some(description) = self.description.as_deref().map(str::trim) {
+            quest.description = description.to_string();
+        }
+        if let Some(location) = &self.location {
+            quest.link_location(location.trim());
+        }
+        if !self.objectives.is_empty() {
+            let objectives: Vec<Objective> = self
+                .objectives
+                .iter()
+                .map(ObjectiveSpec::to_objective)
+                .collect();
+            let description = self
+                .stage
+                .as_deref()
+                .map(str::trim)
+                .filter(|stage| !stage.is_empty())
+                .unwrap_or(&objectives[0].description)
+                .to_string();
+            quest.add_stage(QuestStage {
+                description,
+                objectives,
+            });
+        }
+        if let Some(rewards) = &self.rewards {
+            quest.rewards.merge(rewards);
+        }
+    }
+}
+
+/// Check the objectives and rewards of an `update_quest` action
+fn validate_quest_details(details: &QuestDetails) -> Result<(), String> {
+    for objective in &details.objectives {
+        if objective.text.trim().is_empty() {
+            return Err("Objective text cannot be empty".to_string());
+        }
+        if let Some(item) = &objective.item {
+            find_item(item).ok_or_else(|| format!("Unknown item '{}'", item))?;
+            if objective.qty == 0 {
+                return Err("Objective quantity must be at least 1".to_string());
+            }
+        }
+    }
+
+    if let Some(rewards) = &details.rewards {
+        if rewards.xp > MAX_QUEST_XP || rewards.caps > MAX_QUEST_CAPS {
+            return Err(format!(
+                "Quest rewards are limited to {} XP and {} caps",
+                MAX_QUEST_XP, MAX_QUEST_CAPS
+            ));
+        }
+        for reward in &rewards.items {
+            find_item(&reward.id).ok_or_else(|| format!("Unknown item '{}'", reward.id))?;
+            if reward.qty == 0 || reward.qty > MAX_ITEM_QUANTITY {
+                return Err(format!(
+                    "Item quantity must be between 1 and {}",
+                    MAX_ITEM_QUANTITY
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::character::{Character, Special};
+    use crate::game::worldbook::NPC;
+
+    fn test_state() -> GameState {
+        GameState::new(Character::new("Tester", Special::new()))
+    }
+
+    fn add_npc(state: &mut GameState, name: &str, disposition: i32) {
+        state.worldbook.add_npc(NPC {
+            id: Worldbook::generate_id(name),
+            name: name.into(),
+            name_lowercase: SmartString::new(),
+            role: "trader".into(),
+            personality: vec![],
+            current_location: None,
+            disposition,
+            knowledge: vec![],
+            notes: SmartString::new(),
+            alive: true,
+        });
+    }
+
+    #[test]
+    fn test_parse_strips_action_block() {
+        let response = "The trader nods.\n<actions>\n[{\"action\": \"give_item\", \"id\": \"stimpak\", \"qty\": 2}]\n</actions>";
+        let parsed = parse_dm_actions(response);
+
+        assert_eq!(parsed.narrative, "The trader nods.");
+        assert_eq!(
+            parsed.actions,
+            vec![DmAction::GiveItem {
+                id: "stimpak".to_string(),
+                qty: 2
+            }]
+        );
+        assert!(parsed.errors.is_empty());
+    }
+
+    #[test]
+    fn test_parse_single_object_and_unclosed_block() {
+        let parsed =
+            parse_dm_actions("You pay up. <actions>{\"action\": \"take_caps\", \"n\": 25}");
+        assert_eq!(parsed.narrative, "You pay up.");
+        assert_eq!(parsed.actions, vec![DmAction::TakeCaps { n: 25 }]);
+    }
+
+    #[test]
+    fn test_parse_keeps_valid_actions_when_one_is_malformed() {
+        let response = r#"<actions>[{"action": "teleport"}, {"action": "move_to", "location": "Megaton"}]</actions>"#;
+        let parsed = parse_dm_actions(response);
+
+        assert_eq!(parsed.actions.len(), 1);
+        assert_eq!(parsed.errors.len(), 1);
+        assert!(parsed.narrative.is_empty());
+    }
+
+    #[test]
+    fn test_parse_without_actions() {
+        let parsed = parse_dm_actions("Just wind and dust.");
+        assert_eq!(parsed.narrative, "Just wind and dust.");
+        assert!(parsed.actions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_enemy_aliases_and_defaults() {
+        let parsed = parse_dm_actions(
+            r#"<actions>{"action": "start_combat", "enemies": [{"type": "raider", "count": 2}, {"name": "Feral Ghoul", "level": 3}]}</actions>"#,
+        );
+        let DmAction::StartCombat { enemies } = &parsed.actions[0] else {
+            panic!("expected start_combat");
+        };
+        assert_eq!(enemies[0].name, "raider");
+        assert_eq!(enemies[0].count, 2);
+        assert_eq!(enemies[0].level, None);
+        assert_eq!(enemies[1].count, 1);
+    }
+
+    #[test]
+    fn test_give_item_validation() {
+        let mut state = test_state();
+
+        let result = DmAction::GiveItem {
+            id: "hunting_rifle".to_string(),
+            qty: 1,
+        }
+        .apply(&mut state);
+        assert!(result.is_ok());
+        assert!(state.character.find_item_by_id("hunting_rifle").is_some());
+
+        let unknown = DmAction::GiveItem {
+            id: "fat_man".to_string(),
+            qty: 1,
+        };
+        assert!(unknown.apply(&mut state).is_err());
+
+        let too_many = DmAction::GiveItem {
+            id: "stimpak".to_string(),
+            qty: 500,
+        };
+        assert!(too_many.apply(&mut state).is_err());
+
+        // Ammo comes in bigger stacks than other items
+        let ammo = DmAction::GiveItem {
+            id: "308_ammo".to_string(),
+            qty: 20,
+        };
+        assert!(ammo.apply(&mut state).is_ok());
+        assert_eq!(
+            state
+                .character
+                .find_item_by_id("308_ammo")
+                .unwrap()
+                .quantity,
+            20
+        );
+    }
+
+    #[test]
+    fn test_give_item_respects_carry_limit() {
+        let mut state = test_state();
+        let room = state.character.max_carry_weight() - state.character.carry_weight();
+        state.character.add_item(crate::game::items::Item::new_misc(
+            "anvil",
+            "Anvil",
+            "Heavy.",
+            room - 1.0,
+            5,
+        ));
+
+        let result = DmAction::GiveItem {
+            id: "hunting_rifle".to_string(),
+            qty: 1,
+        }
+        .apply(&mut state);
+        assert!(result.unwrap_err().contains("too heavy"));
+        assert!(state.character.find_item_by_id("hunting_rifle").is_none());
+    }
+
+    #[test]
+    fn test_take_caps_requires_funds() {
+        let mut state = test_state();
+        state.character.caps = 100;
+
+        assert!(DmAction::TakeCaps { n: 40 }.apply(&mut state).is_ok());
+        assert_eq!(state.character.caps, 60);
+
+        assert!(DmAction::TakeCaps { n: 61 }.apply(&mut state).is_err());
+        assert_eq!(state.character.caps, 60);
+    }
+
+    #[test]
+    fn test_start_combat() {
+        let mut state = test_state();
+        let action = DmAction::StartCombat {
+            enemies: vec![
+                EnemySpawn {
+                    name: "raider".to_string(),
+                    level: Some(50),
+                    count: 2,
+                },
+                EnemySpawn {
+                    name: "Feral Ghoul".to_string(),
+                    level: None,
+                    count: 1,
+                },
+            ],
+        };
+
+        assert!(action.apply(&mut state).is_ok());
+        assert!(state.combat.active);
+        assert_eq!(state.combat.enemies.len(), 3);
+        // Level is capped relative to the player
+        assert_eq!(
+            state.combat.enemies[0].level,
+            1 + MAX_ENEMY_LEVEL_ABOVE_PLAYER
+        );
+        assert_eq!(state.combat.enemies[2].name, "Feral Ghoul");
+
+        // Can't start a second fight mid-combat
+        assert!(action.apply(&mut state).is_err());
+    }
+
+    #[test]
+    fn test_start_combat_rejects_huge_groups() {
+        let mut state = test_state();
+        let action = DmAction::StartCombat {
+            enemies: vec![EnemySpawn {
+                name: "radroach".to_string(),
+                level: None,
+                count: 20,
+            }],
+        };
+        assert!(action.apply(&mut state).is_err());
+        assert!(!state.combat.active);
+
+        // Counts that would overflow when added up
+        let action = DmAction::StartCombat {
+            enemies: vec![
+                EnemySpawn {
+                    name: "radroach".to_string(),
+                    level: None,
+                    count: u32::MAX,
+                },
+                EnemySpawn {
+                    name: "raider".to_string(),
+                    level: None,
+                    count: 2,
+                },
+            ],
+        };
+        assert!(action.apply(&mut state).is_err());
+        assert!(!state.combat.active);
+    }
+
+    #[test]
+    fn test_start_combat_with_unknown_high_level_enemy() {
+        let mut state = test_state();
+        state.character.level = 40;
+        let action = DmAction::StartCombat {
+            enemies: vec![EnemySpawn {
+                name: "Enclave Colonel".to_string(),
+                level: Some(43),
+                count: 1,
+            }],
+        };
+        assert!(action.apply(&mut state).is_ok());
+        assert_eq!(state.combat.enemies[0].level, 43);
+        assert_eq!(state.combat.enemies[0].skill, 100);
+    }
+
+    #[test]
+    fn test_move_to_known_location() {
+        let mut state = test_state();
+        let known = state.worldbook.locations.values().next().unwrap().clone();
+
+        let result = DmAction::MoveTo {
+            location: known.name.to_string(),
+        }
+        .apply(&mut state);
+
+        assert!(result.is_ok());
+        assert_eq!(state.location, known.name.as_str());
+        assert_eq!(
+            state.worldbook.current_location.as_deref(),
+            Some(known.id.as_str())
+        );
+        assert_eq!(
+            state.worldbook.get_location(&known.id).unwrap().visit_count,
+            known.visit_count + 1
+        );
+        assert_eq!(state.time.to_string(), "09:00");
+
+        // Too much to carry: no travelling
+        state.character.add_item(crate::game::items::Item::new_misc(
+            "anvil", "Anvil", "Heavy.", 500.0, 5,
+        ));
+        let result = DmAction::MoveTo {
+            location: "Megaton".to_string(),
+        }
+        .apply(&mut state);
+        assert!(result.unwrap_err().contains("over-encumbered"));
+        assert_eq!(state.location, known.name.as_str());
+    }
+
+    #[test]
+    fn test_move_to_is_ignored_during_combat() {
+        let mut state = test_state();
+        let before = state.location.clone();
+        state.combat.start_combat(vec![Enemy::raider(1)]);
+
+        let result = DmAction::MoveTo {
+            location: "Megaton".to_string(),
+        }
+        .apply(&mut state);
+        assert!(result.unwrap_err().contains("during combat"));
+        assert_eq!(state.location, before);
+        assert_eq!(
+            state.worldbook.current_location.as_deref(),
+            Some("vault_13")
+        );
+    }
+
+    #[test]
+    fn test_pass_time() {
+        let mut state = test_state();
+
+        let action = DmAction::PassTime {
+            hours: 14,
+            minutes: 30,
+        };
+        let message = action.apply(&mut state).unwrap();
+        assert!(message.starts_with("Time passes (14h 30m). It is now day 1, 22:30."));
+        assert!(message.contains("Night falls."));
+
+        let nothing = DmAction::PassTime {
+            hours: 0,
+            minutes: 0,
+        };
+        assert!(nothing.apply(&mut state).is_err());
+
+        let too_long = DmAction::PassTime {
+            hours: MAX_TIME_SKIP_HOURS + 1,
+            minutes: 0,
+        };
+        assert!(too_long.apply(&mut state).is_err());
+    }
+
+    #[test]
+    fn test_update_quest_lifecycle() {
+        let mut state = test_state();
+
+        let start = DmAction::UpdateQuest {
+            quest: "Clear the Sewers".to_string(),
+            status: QuestStatus::Active,
+            note: None,
+            details: QuestDetails::default(),
+        };
+        assert_eq!(
+            start.apply(&mut state).unwrap(),
+            "New quest: Clear the Sewers"
+        );
+        assert!(state.quests.find("Clear the Sewers").unwrap().is_active());
+
+        let complete = DmAction::UpdateQuest {
+            quest: "clear the sewers".to_string(),
+            status: QuestStatus::Completed,
+            note: Some("The rats are gone".to_string()),
+            details: QuestDetails::default(),
+        };
+        assert_eq!(
+            complete.apply(&mut state).unwrap(),
+            "Quest completed: Clear the Sewers - The rats are gone"
+        );
+        let quest = state.quests.find("Clear the Sewers").unwrap();
+        assert_eq!(quest.status, QuestStatus::Completed);
+        assert_eq!(quest.notes, vec!["The rats are gone".to_string()]);
+
+        // Finishing a quest twice, or an unknown quest, is rejected
+        assert!(complete.apply(&mut state).is_err());
+        let unknown = DmAction::UpdateQuest {
+            quest: "Find Dogmeat".to_string(),
+            status: QuestStatus::Failed,
+            note: None,
+            details: QuestDetails::default(),
+        };
+        assert!(unknown.apply(&mut state).is_err());
+    }
+
+    #[test]
+    fn test_structured_quest_from_dm() {
+        let mut state = test_state();
+        let response = r#"Sheriff Simms hands you a wanted poster.
+<actions>[{"action": "update_quest", "quest": "Bounty on Butch", "giver": "Sheriff Simms",
+  "objectives": [{"text": "Kill Butch", "kill": "Butch"}],
+  "rewards": {"xp": 200, "caps": 100, "items": [{"id": "stimpak", "qty": 2}]}}]</actions>"#;
+        let parsed = parse_dm_actions(response);
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert!(parsed.actions[0].apply(&mut state).is_ok());
+
+        let quest = state.quests.find("bounty on butch").unwrap();
+        assert!(quest.is_tracked());
+        assert_eq!(quest.giver.as_deref(), Some("Sheriff Simms"));
+        assert_eq!(quest.stages[0].description, "Kill Butch");
+
+        // Killing the target completes the quest and pays out the rewards
+        let caps = state.character.caps;
+        let stimpaks = state.character.item_count("stimpak");
+        let messages = state.quest_event(QuestEvent::NpcKilled("Butch".into()));
+        assert_eq!(
+            messages.last().unwrap(),
+            "Rewards: +200 XP, +100 caps, Stimpak x2"
+        );
+        assert_eq!(state.character.caps, caps + 100);
+        assert_eq!(state.character.item_count("stimpak"), stimpaks + 2);
+
+        // Rewards have to be reasonable and objectives must name real items
+        let greedy = DmAction::UpdateQuest {
+            quest: "Get Rich".to_string(),
+            status: QuestStatus::Active,
+            note: None,
+            details: QuestDetails {
+                rewards: Some(QuestRewards {
+                    caps: MAX_QUEST_CAPS + 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+        assert!(greedy.validate(&state).is_err());
+        let bogus = DmAction::UpdateQuest {
+            quest: "Fetch".to_string(),
+            status: QuestStatus::Active,
+            note: None,
+            details: QuestDetails {
+                objectives: vec![ObjectiveSpec {
+                    text: "Find the thing".to_string(),
+                    item: Some("unobtainium".to_string()),
+                    qty: 1,
+                    kill: None,
+                    visit: None,
+                }],
+                ..Default::default()
+            },
+        };
+        assert!(bogus.validate(&state).is_err());
+    }
+
+    #[test]
+    fn test_give_item_and_travel_advance_quests() {
+        let mut state = test_state();
+
+        let give = DmAction::GiveItem {
+            id: "water_chip".to_string(),
+            qty: 1,
+        };
+        let message = give.apply(&mut state).unwrap();
+        assert!(message.contains("Objective complete: Find a water chip"));
+
+        let home = DmAction::MoveTo {
+            location: "Vault 13".to_string(),
+        };
+        let message = home.apply(&mut state).unwrap();
+        assert!(message.contains("Quest completed: Find the Water Chip"));
+        assert!(message.contains("Rewards: +1000 XP"));
+        assert_eq!(state.quests.active().count(), 0);
+    }
+
+    #[test]
+    fn test_add_rads_brings_on_sickness() {
+        let mut state = test_state();
+        let parsed = parse_dm_actions(r#"<actions>{"action": "add_rads", "rads": 250}</actions>"#);
+        assert_eq!(parsed.actions, vec![DmAction::AddRads { n: 250 }]);
+
+        let message = parsed.actions[0].apply(&mut state).unwrap();
+        assert_eq!(
+            message,
+            "+250 rads. You are suffering from Minor Radiation Sickness."
+        );
+        assert_eq!(state.character.status.rads, 250);
+
+        let too_much = DmAction::AddRads {
+            n: MAX_RAD_EXPOSURE + 1,
+        };
+        assert!(too_much.apply(&mut state).is_err());
+        assert!(DmAction::AddRads { n: 0 }.apply(&mut state).is_err());
+    }
+
+    #[test]
+    fn test_change_disposition() {
+        let mut state = test_state();
+        add_npc(&mut state, "Moira Brown", 90);
+
+        let action = DmAction::ChangeDisposition {
+            npc: "moira brown".to_string(),
+            delta: 20,
+        };
+        assert!(action.apply(&mut state).is_ok());
+        assert_eq!(
+            state.worldbook.get_npc("moira_brown").unwrap().disposition,
+            100
+        );
+
+        let unknown = DmAction::ChangeDisposition {
+            npc: "Three Dog".to_string(),
+            delta: 5,
+        };
+        assert!(unknown.apply(&mut state).is_err());
+
+        let too_big = DmAction::ChangeDisposition {
+            npc: "Moira Brown".to_string(),
+            delta: -90,
+        };
+        assert!(too_big.apply(&mut state).is_err());
+
+        let most_negative = DmAction::ChangeDisposition {
+            npc: "Moira Brown".to_string(),
+            delta: i32::MIN,
+        };
+        assert!(most_negative.apply(&mut state).is_err());
+    }
+}
@@ -17,8 +17,16 @@
 //! | Medium | 8-12 | 9-11 | Metal Armor, Combat Leather |
 //! | Heavy | 15-20 | 12-15 | Combat Armor, Riot Gear |
 //! | Power | 25-35 | 17-22 | T-45d, T-51b Power Armor |
+//!
+//! ## Damage Type Resistances
+//!
+//! DR applies to every hit. Armor can also carry per-[`DamageType`] adjustments
+//! on top of its DR, e.g. power armor shrugs off lasers while leather burns.
 
+use super::condition::{self, full_condition, MAX_CONDITION};
+use super::weapons::DamageType;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Protective statistics for armor items.
 ///
@@ -31,6 +39,31 @@ pub struct ArmorStats {
     pub radiation_resistance: i32,
     /// Armor Class - makes the wearer harder to hit
     pub armor_class: i32,
+    /// Extra resistance (or weakness, if negative) against specific damage types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub resistances: HashMap<DamageType, i32>,
+    /// Wear from 0 (broken) to 100 (pristine)
+    #[serde(default = "full_condition")]
+    pub condition: u8,
+}
+
+impl ArmorStats {
+    /// Total damage resistance against a damage type, never below zero.
+    /// Worn armor protects less.
+    pub fn resistance_against(&self, damage_type: &DamageType) -> i32 {
+        let modifier = self.resistances.get(damage_type).copied().unwrap_or(0);
+        condition::scale((self.damage_resistance + modifier).max(0), self.condition)
+    }
+
+    /// Percent of incoming rads blocked (0-100). Worn armor blocks less.
+    pub fn rad_block(&self) -> u32 {
+        condition::scale(self.radiation_resistance.clamp(0, 100), self.condition) as u32
+    }
+
+    /// Lose condition from one hit taken
+    pub fn wear(&mut self) {
+        self.condition = condition::worn(self.condition, condition::ARMOR_WEAR);
+    }
 }
 
 // Test-only constructors
@@ -41,6 +74,8 @@ impl ArmorStats {
             damage_resistance,
             radiation_resistance,
             armor_class: 5 + (damage_resistance / 2),
+            resistances: HashMap::new(),
+            condition: MAX_CONDITION,
         }
     }
 
@@ -55,6 +90,8 @@ impl Default for ArmorStats {
             damage_resistance: 0,
             radiation_resistance: 0,
             armor_class: 5,
+            resistances: HashMap::new(),
+            condition: MAX_CONDITION,
         }
     }
 }
@@ -124,4 +161,16 @@ mod tests {
         assert_eq!(armor1, armor2);
         assert_ne!(armor1, armor3);
     }
+
+    #[test]
+    fn test_resistance_against_damage_type() {
+        let mut armor = ArmorStats::with_dr(10);
+        armor.resistances.insert(DamageType::Laser, 5);
+        armor.resistances.insert(DamageType::Fire, -15);
+
+        assert_eq!(armor.resistance_against(&DamageType::Normal), 10);
+        assert_eq!(armor.resistance_against(&DamageType::Laser), 15);
+        // Weaknesses never make resistance negative
+        assert_eq!(armor.resistance_against(&DamageType::Fire), 0);
+    }
 }
@@ -0,0 +1,691 @@
+//! # Item Catalog Module
+//!
+//! Every item the game knows about, loaded from TOML data files.
+//!
+//! ## Data Files
+//!
+//! Items are defined in `data/items/*.toml`, one `[[item]]` table per item.
+//! Files are read in name order and each definition is checked before the
+//! game starts: IDs must be unique snake_case, weapon damage must be a valid
+//! dice expression, stats must be in range and weapons must name an ammo
+//! item that exists. See the comments at the top of each data file for the
+//! fields each kind of item takes.
+//!
+//! ## Mods
+//!
+//! Files in `mods/items/*.toml` are read after the base data. A mod item
+//! with the same ID as an existing one replaces it outright; anything else
+//! is added to the catalog. A mod file that fails to parse or validate is
+//! skipped with a warning rather than stopping the game.
+//!
+//! ## Rarity and Level
+//!
+//! Each item has a [`Rarity`] and a minimum character level. Shops and loot
+//! draw from [`ItemRegistry::for_level`], weighting picks by
+//! [`Rarity::weight`], so rare gear turns up less often and only once the
+//! player is ready for it.
+
+use super::armor::ArmorStats;
+use super::condition::MAX_CONDITION;
+use super::consumables::ConsumableEffect;
+use super::mods::{ModSlot, WeaponMod};
+use super::types::{Item, ItemType};
+use super::weapons::{DamageType, WeaponStats, WeaponType};
+use crate::error::GameError;
+use crate::validation_garde::{validate_data_id, validate_dice_expression};
+use garde::Validate;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use smartstring::alias::String as SmartString;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding the base item definitions
+pub const ITEM_DATA_DIR: &str = "data/items";
+/// Directory holding mod item definitions, read after the base data
+pub const ITEM_MOD_DIR: &str = "mods/items";
+/// Highest minimum level an item can have
+pub const MAX_ITEM_LEVEL: u32 = 50;
+
+/// Global item catalog
+static REGISTRY: OnceCell<ItemRegistry> = OnceCell::new();
+
+/// Load the item catalog if it isn't loaded yet. The game does this at
+/// startup (see [`crate::game::load_game_data`]), so a broken data file is
+/// reported before play begins.
+pub fn load_registry() -> Result<&'static ItemRegistry, GameError> {
+    REGISTRY
+        .get_or_try_init(|| ItemRegistry::load(Path::new(ITEM_DATA_DIR), Path::new(ITEM_MOD_DIR)))
+}
+
+/// The item catalog, loaded on first use when the game didn't load it at
+/// startup (tests and tools)
+pub fn registry() -> &'static ItemRegistry {
+    load_registry().expect("item catalog should load")
+}
+
+/// How hard an item is to come by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    /// Relative chance of being picked for loot or shop stock
+    pub fn weight(&self) -> u32 {
+        match self {
+            Rarity::Common => 12,
+            Rarity::Uncommon => 6,
+            Rarity::Rare => 2,
+            Rarity::Legendary => 1,
+        }
+    }
+}
+
+/// Item category in a data file (and inventory filter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Weapon,
+    Armor,
+    Consumable,
+    Ammo,
+    Mod,
+    Misc,
+}
+
+impl ItemKind {
+    pub const ALL: [ItemKind; 6] = [
+        ItemKind::Weapon,
+        ItemKind::Armor,
+        ItemKind::Consumable,
+        ItemKind::Ammo,
+        ItemKind::Mod,
+        ItemKind::Misc,
+    ];
+
+    /// Category of an item
+    pub fn of(item_type: &ItemType) -> Self {
+        match item_type {
+            ItemType::Weapon(_) => ItemKind::Weapon,
+            ItemType::Armor(_) => ItemKind::Armor,
+            ItemType::Consumable(_) => ItemKind::Consumable,
+            ItemType::Ammo => ItemKind::Ammo,
+            ItemType::Mod(_) => ItemKind::Mod,
+            ItemType::Misc => ItemKind::Misc,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Weapon => "Weapons",
+            ItemKind::Armor => "Armor",
+            ItemKind::Consumable => "Aid",
+            ItemKind::Ammo => "Ammo",
+            ItemKind::Mod => "Mods",
+            ItemKind::Misc => "Misc",
+        }
+    }
+
+    /// Weight used when a definition doesn't give one
+    fn default_weight(&self) -> f32 {
+        match self {
+            ItemKind::Weapon => 3.0,
+            ItemKind::Armor => 8.0,
+            ItemKind::Consumable => 0.5,
+            ItemKind::Ammo => 0.05,
+            ItemKind::Mod => 0.5,
+            ItemKind::Misc => 1.0,
+        }
+    }
+}
+
+/// One `[[item]]` entry in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDefinition {
+    #[garde(custom(validate_data_id))]
+    pub id: String,
+    #[garde(length(min = 1, max = 60))]
+    pub name: String,
+    #[serde(default)]
+    #[garde(length(max = 300))]
+    pub description: String,
+    #[garde(skip)]
+    pub kind: ItemKind,
+    /// Weight in pounds (defaults by kind)
+    #[serde(default)]
+    #[garde(range(min = 0.0, max = 500.0))]
+    pub weight: Option<f32>,
+    /// Value in caps
+    #[garde(range(max = 100_000))]
+    pub value: u32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub rarity: Rarity,
+    /// Lowest character level the item turns up for
+    #[serde(default = "default_level")]
+    #[garde(range(min = 1, max = MAX_ITEM_LEVEL))]
+    pub level: u32,
+    /// Quantity new characters start with (0 if it isn't starting gear)
+    #[serde(default)]
+    #[garde(range(max = 1000))]
+    pub starting: u32,
+    #[serde(default)]
+    #[garde(dive)]
+    pub weapon: Option<WeaponDefinition>,
+    #[serde(default)]
+    #[garde(dive)]
+    pub armor: Option<ArmorDefinition>,
+    #[serde(default)]
+    #[garde(skip)]
+    pub effect: Option<ConsumableEffect>,
+    #[serde(default, rename = "mod")]
+    #[garde(dive)]
+    pub weapon_mod: Option<ModDefinition>,
+}
+
+fn default_level() -> u32 {
+    1
+}
+
+/// Weapon stats in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponDefinition {
+    #[garde(custom(validate_dice_expression))]
+    pub damage: String,
+    #[serde(default)]
+    #[garde(skip)]
+    pub damage_type: DamageType,
+    #[garde(skip)]
+    pub weapon_type: WeaponType,
+    #[garde(range(min = 1, max = 10))]
+    pub ap_cost: i32,
+    /// ID of the ammo item the weapon fires
+    #[serde(default)]
+    #[garde(skip)]
+    pub ammo: Option<String>,
+    /// Rounds in a full magazine
+    #[serde(default)]
+    #[garde(range(max = 500))]
+    pub magazine: u32,
+    #[serde(default = "default_range")]
+    #[garde(range(min = 1, max = 1000))]
+    pub range: u32,
+    #[serde(default = "default_critical_multiplier")]
+    #[garde(range(min = 1.0, max = 5.0))]
+    pub critical_multiplier: f32,
+}
+
+fn default_range() -> u32 {
+    30
+}
+
+fn default_critical_multiplier() -> f32 {
+    2.0
+}
+
+/// Armor stats in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ArmorDefinition {
+    #[garde(range(min = 0, max = 100))]
+    pub dr: i32,
+    #[serde(default)]
+    #[garde(range(min = 0, max = 100))]
+    pub rad_resistance: i32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub resistances: HashMap<DamageType, i32>,
+}
+
+/// Weapon mod stats in a data file
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ModDefinition {
+    #[garde(skip)]
+    pub slot: ModSlot,
+    /// Weapon types the mod fits (any weapon when left out)
+    #[serde(default)]
+    #[garde(skip)]
+    pub fits: Vec<WeaponType>,
+    #[serde(default)]
+    #[garde(range(min = -50, max = 50))]
+    pub to_hit: i32,
+    #[serde(default)]
+    #[garde(range(min = -20, max = 20))]
+    pub damage: i32,
+    /// Extra rounds in the magazine
+    #[serde(default)]
+    #[garde(range(max = 500))]
+    pub magazine: u32,
+    #[serde(default)]
+    #[garde(skip)]
+    pub damage_type: Option<DamageType>,
+}
+
+impl ItemDefinition {
+    /// Run the garde rules, then check the stats match the kind
+    pub fn check(&self) -> Result<(), String> {
+        self.validate().map_err(|e| e.to_string())?;
+
+        let expected = match self.kind {
+            ItemKind::Weapon => (true, false, false, false),
+            ItemKind::Armor => (false, true, false, false),
+            ItemKind::Consumable => (false, false, true, false),
+            ItemKind::Mod => (false, false, false, true),
+            ItemKind::Ammo | ItemKind::Misc => (false, false, false, false),
+        };
+        let found = (
+            self.weapon.is_some(),
+            self.armor.is_some(),
+            self.effect.is_some(),
+            self.weapon_mod.is_some(),
+        );
+        if expected != found {
+            return Err(match self.kind {
+                ItemKind::Weapon => "weapons need an [item.weapon] table and nothing else",
+                ItemKind::Armor => "armor needs an [item.armor] table and nothing else",
+                ItemKind::Consumable => "consumables need an `effect` and nothing else",
+                ItemKind::Mod => "mods need an [item.mod] table and nothing else",
+                ItemKind::Ammo | ItemKind::Misc => {
+                    "ammo and misc items can't have weapon, armor, effect or mod stats"
+                }
+            }
+            .to_string());
+        }
+
+        if let Some(weapon) = &self.weapon {
+            if weapon.ammo.is_some() != (weapon.magazine > 0) {
+                return Err("weapons that use ammo need both `ammo` and `magazine`".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the item, with a stack of one
+    pub fn to_item(&self) -> Item {
+        let item_type = match self.kind {
+            ItemKind::Weapon => {
+                let weapon = self.weapon.as_ref().expect("checked weapon definition");
+                ItemType::Weapon(WeaponStats {
+                    damage: SmartString::from(weapon.damage.as_str()),
+                    damage_type: weapon.damage_type.clone(),
+                    weapon_type: weapon.weapon_type.clone(),
+                    ap_cost: weapon.ap_cost,
+                    ammo_type: weapon.ammo.as_deref().map(SmartString::from),
+                    magazine_size: weapon.magazine,
+                    loaded_rounds: weapon.magazine,
+                    range: weapon.range,
+                    critical_multiplier: weapon.critical_multiplier,
+                    condition: MAX_CONDITION,
+                    mods: Vec::new(),
+                })
+            }
+            ItemKind::Armor => {
+                let armor = self.armor.as_ref().expect("checked armor definition");
+                ItemType::Armor(ArmorStats {
+                    damage_resistance: armor.dr,
+                    radiation_resistance: armor.rad_resistance,
+                    armor_class: 5 + (armor.dr / 2),
+                    resistances: armor.resistances.clone(),
+                    condition: MAX_CONDITION,
+                })
+            }
+            ItemKind::Consumable => {
+                ItemType::Consumable(self.effect.clone().expect("checked consumable definition"))
+            }
+            ItemKind::Mod => {
+                let weapon_mod = self.weapon_mod.as_ref().expect("checked mod definition");
+                ItemType::Mod(WeaponMod {
+                    slot: weapon_mod.slot,
+                    fits: weapon_mod.fits.clone(),
+                    to_hit: weapon_mod.to_hit,
+                    damage: weapon_mod.damage,
+                    magazine: weapon_mod.magazine,
+                    damage_type: weapon_mod.damage_type.clone(),
+                })
+            }
+            ItemKind::Ammo => ItemType::Ammo,
+            ItemKind::Misc => ItemType::Misc,
+        };
+
+        Item {
+            id: SmartString::from(self.id.as_str()),
+            name: SmartString::from(self.name.as_str()),
+            description: SmartString::from(self.description.as_str()),
+            item_type,
+            weight: self.weight.unwrap_or_else(|| self.kind.default_weight()),
+            value: self.value,
+            quantity: 1,
+            instance: 0,
+        }
+    }
+}
+
+/// Layout of a data file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    #[serde(default)]
+    item: Vec<ItemDefinition>,
+}
+
+/// A catalog item with its loot and shop metadata
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub item: Item,
+    pub rarity: Rarity,
+    pub level: u32,
+    /// Quantity new characters start with (0 if it isn't starting gear)
+    pub starting: u32,
+}
+
+impl From<&ItemDefinition> for CatalogEntry {
+    fn from(definition: &ItemDefinition) -> Self {
+        CatalogEntry {
+            item: definition.to_item(),
+            rarity: definition.rarity,
+            level: definition.level,
+            starting: definition.starting,
+        }
+    }
+}
+
+/// Every known item, in data file order
+#[derive(Debug, Clone, Default)]
+pub struct ItemRegistry {
+    entries: Vec<CatalogEntry>,
+    index: HashMap<SmartString, usize>,
+}
+
+impl ItemRegistry {
+    /// Load the base data directory, then apply any mods on top
+    pub fn load(data_dir: &Path, mod_dir: &Path) -> Result<Self, GameError> {
+        let mut registry = ItemRegistry::default();
+
+        for path in toml_files(data_dir)? {
+            let file = path.display().to_string();
+            let source = fs::read_to_string(&path)?;
+            for definition in Self::parse(&source, &file)? {
+                if registry.contains(&definition.id) {
+                    return Err(GameError::DataFile {
+                        file,
+                        message: format!("item '{}' is defined twice", definition.id),
+                    });
+                }
+                registry.insert(CatalogEntry::from(&definition));
+            }
+        }
+
+        if mod_dir.is_dir() {
+            for path in toml_files(mod_dir)? {
+                let file = path.display().to_string();
+                match fs::read_to_string(&path)
+                    .map_err(GameError::from)
+                    .and_then(|source| Self::parse(&source, &file))
+                {
+                    Ok(definitions) => {
+                        for definition in &definitions {
+                            registry.insert(CatalogEntry::from(definition));
+                        }
+                        tracing::info!("Loaded {} mod items from {}", definitions.len(), file);
+                    }
+                    Err(e) => tracing::warn!("Skipping item mod: {}", e),
+                }
+            }
+        }
+
+        registry.check_ammo()?;
+        Ok(registry)
+    }
+
+    /// Parse and validate the definitions in one data file
+    pub fn parse(source: &str, file: &str) -> Result<Vec<ItemDefinition>, GameError> {
+        let catalog: CatalogFile = toml::from_str(source).map_err(|e| GameError::DataFile {
+            file: file.to_string(),
+            message: e.to_string(),
+        })?;
+        for definition in &catalog.item {
+            definition.check().map_err(|message| GameError::DataFile {
+                file: file.to_string(),
+                message: format!("item '{}': {}", definition.id, message),
+            })?;
+        }
+        Ok(catalog.item)
+    }
+
+    /// Add an entry, replacing any existing entry with the same ID in place
+    pub fn insert(&mut self, entry: CatalogEntry) {
+        match self.index.get(&entry.item.id) {
+            Some(&i) => self.entries[i] = entry,
+            None => {
+                self.index.insert(entry.item.id.clone(), self.entries.len());
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// Every weapon's ammo must be an ammo item in the catalog
+    fn check_ammo(&self) -> Result<(), GameError> {
+        for entry in &self.entries {
+            if let ItemType::Weapon(stats) = &entry.item.item_type {
+                let Some(ammo) = &stats.ammo_type else {
+                    continue;
+                };
+                let is_ammo = self
+                    .get(ammo)
+                    .is_some_and(|a| matches!(a.item.item_type, ItemType::Ammo));
+                if !is_ammo {
+                    return Err(GameError::DataFile {
+                        file: ITEM_DATA_DIR.to_string(),
+                        message: format!(
+                            "item '{}' fires '{}', which isn't an ammo item",
+                            entry.item.id, ammo
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.index.contains_key(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.index.get(id).map(|&i| &self.entries[i])
+    }
+
+    /// A fresh copy of an item, with a stack of one
+    pub fn item(&self, id: &str) -> Option<Item> {
+        self.get(id).map(|entry| entry.item.clone())
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.item.id.as_str())
+    }
+
+    /// Starting gear, stacked to the starting quantities
+    pub fn starting_items(&self) -> Vec<Item> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.starting > 0)
+            .map(|entry| {
+                let mut item = entry.item.clone();
+                item.quantity = entry.starting;
+                item
+            })
+            .collect()
+    }
+
+    /// Everything that isn't starting gear
+    #[allow(dead_code)] // Public API for integration tests
+    pub fn world_items(&self) -> Vec<Item> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.starting == 0)
+            .map(|entry| entry.item.clone())
+            .collect()
+    }
+
+    #[allow(dead_code)] // Public API for integration tests
+    pub fn by_rarity(&self, rarity: Rarity) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.rarity == rarity)
+    }
+
+    /// Items a character of this level can come across
+    pub fn for_level(&self, level: u32) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.level <= level)
+    }
+}
+
+/// TOML files in a directory, sorted by name
+pub(crate) fn toml_files(dir: &Path) -> Result<Vec<PathBuf>, GameError> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOTGUN: &str = r#"
+[[item]]
+id = "sawed_off"
+name = "Sawed-Off Shotgun"
+kind = "weapon"
+value = 120
+rarity = "uncommon"
+level = 2
+
+[item.weapon]
+damage = "2d8"
+weapon_type = "SmallGun"
+ap_cost = 4
+ammo = "12ga_shells"
+magazine = 2
+"#;
+
+    fn parse_err(source: &str) -> String {
+        ItemRegistry::parse(source, "test.toml")
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_base_catalog_loads() {
+        let registry = registry();
+        let pistol = registry.get("10mm_pistol").expect("pistol");
+        assert_eq!(pistol.starting, 1);
+        assert_eq!(pistol.rarity, Rarity::Common);
+
+        let armor = registry.item("power_armor_t51b").expect("power armor");
+        assert_eq!(armor.quantity, 1);
+        assert!(matches!(armor.item_type, ItemType::Armor(ref stats) if stats.armor_class == 20));
+        assert_eq!(
+            registry.get("power_armor_t51b").unwrap().rarity,
+            Rarity::Legendary
+        );
+
+        let ammo = registry
+            .starting_items()
+            .into_iter()
+            .find(|item| item.id == "10mm_ammo")
+            .expect("starting ammo");
+        assert_eq!(ammo.quantity, 24);
+    }
+
+    #[test]
+    fn test_definition_builds_item() {
+        let definitions = ItemRegistry::parse(SHOTGUN, "test.toml").unwrap();
+        let item = definitions[0].to_item();
+        assert_eq!(item.weight, 3.0);
+        match item.item_type {
+            ItemType::Weapon(stats) => {
+                assert_eq!(stats.ammo_type.as_deref(), Some("12ga_shells"));
+                assert_eq!(stats.loaded_rounds, 2);
+                assert_eq!(stats.range, 30);
+            }
+            _ => panic!("Expected weapon"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_definitions_rejected() {
+        assert!(parse_err(&SHOTGUN.replace("2d8", "2d")).contains("sawed_off"));
+        assert!(parse_err(&SHOTGUN.replace("sawed_off", "Sawed Off")).contains("lowercase"));
+        assert!(parse_err(&SHOTGUN.replace("ap_cost = 4", "ap_cost = 40")).contains("sawed_off"));
+        assert!(parse_err(&SHOTGUN.replace("magazine = 2", "")).contains("magazine"));
+        assert!(
+            parse_err(&SHOTGUN.replace("kind = \"weapon\"", "kind = \"armor\"")).contains("armor")
+        );
+        assert!(parse_err(&SHOTGUN.replace("level = 2", "levle = 2")).contains("levle"));
+    }
+
+    #[test]
+    fn test_mods_override_and_extend() {
+        let dir = std::env::temp_dir().join(format!("item_mods_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("stimpaks.toml"),
+            r#"
+[[item]]
+id = "stimpak"
+name = "Stimpak"
+kind = "consumable"
+value = 40
+effect = { Healing = 45 }
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("shotguns.toml"), SHOTGUN).unwrap();
+        fs::write(dir.join("broken.toml"), "[[item]]\nid = 3").unwrap();
+
+        let modded = ItemRegistry::load(Path::new(ITEM_DATA_DIR), &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let stimpak = modded.item("stimpak").unwrap();
+        assert_eq!(stimpak.value, 40);
+        assert!(matches!(
+            stimpak.item_type,
+            ItemType::Consumable(ConsumableEffect::Healing(45))
+        ));
+        // An overridden item keeps its place; new ones go on the end
+        assert_eq!(
+            modded.ids().position(|id| id == "stimpak"),
+            registry().ids().position(|id| id == "stimpak")
+        );
+        assert_eq!(modded.ids().last(), Some("sawed_off"));
+    }
+
+    #[test]
+    fn test_rarity_and_level_tiers() {
+        let registry = registry();
+        assert!(registry.for_level(1).all(|entry| entry.level == 1));
+        assert!(registry
+            .for_level(1)
+            .all(|entry| entry.item.id != "plasma_rifle"));
+        assert!(registry.for_level(MAX_ITEM_LEVEL).count() == registry.ids().count());
+        assert!(registry
+            .by_rarity(Rarity::Legendary)
+            .any(|entry| entry.item.id == "power_armor_t51b"));
+        assert!(Rarity::Common.weight() > Rarity::Rare.weight());
+    }
+}
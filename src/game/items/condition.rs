@@ -0,0 +1,126 @@
+//! # Condition
+//!
+//! Weapons and armor wear out with use. Condition runs from 0 to
+//! [`MAX_CONDITION`] percent: a weapon loses [`WEAPON_WEAR`] for every shot
+//! or swing, armor loses [`ARMOR_WEAR`] for every hit it takes.
+//!
+//! ## Effects
+//!
+//! Gear works at full strength down to [`FULL_EFFECT_CONDITION`]. Below that,
+//! weapon damage and armor resistance are scaled by [`effectiveness`], down
+//! to half when broken.
+//! Below [`JAM_CONDITION`] a weapon can also jam, wasting the attack.
+//!
+//! ## Repair
+//!
+//! Gear is repaired either by breaking down a spare of the same item for
+//! parts ([`parts_repair`]), or with a Repair skill check against
+//! [`REPAIR_DC`] ([`skill_repair`]). Either takes [`REPAIR_MINUTES`].
+
+/// Condition of new, undamaged gear
+pub const MAX_CONDITION: u8 = 100;
+/// Condition lost by a weapon each time it's fired or swung
+pub const WEAPON_WEAR: u8 = 1;
+/// Condition lost by armor each time its wearer is hit
+pub const ARMOR_WEAR: u8 = 2;
+/// Gear at or above this condition works at full strength
+pub const FULL_EFFECT_CONDITION: u8 = 75;
+/// Weapons below this condition can jam
+pub const JAM_CONDITION: u8 = 30;
+/// Percent chance to jam per point of condition below [`JAM_CONDITION`]
+const JAM_CHANCE_PER_POINT: u32 = 2;
+/// Difficulty of the Repair check to fix gear without spare parts
+pub const REPAIR_DC: i32 = 25;
+/// Minutes a repair takes
+pub const REPAIR_MINUTES: u32 = 30;
+
+/// Serde default for gear saved before condition existed
+pub(crate) fn full_condition() -> u8 {
+    MAX_CONDITION
+}
+
+/// Percent of its full effect gear still has: 100% down to
+/// [`FULL_EFFECT_CONDITION`], falling to 50% when broken
+pub fn effectiveness(condition: u8) -> i32 {
+    let condition = condition.min(FULL_EFFECT_CONDITION) as i32;
+    50 + condition * 50 / FULL_EFFECT_CONDITION as i32
+}
+
+/// Scale a damage or resistance value by the gear's condition
+pub fn scale(value: i32, condition: u8) -> i32 {
+    value * effectiveness(condition) / 100
+}
+
+/// Percent chance a weapon in this condition jams when used
+pub fn jam_chance(condition: u8) -> u32 {
+    JAM_CONDITION.saturating_sub(condition) as u32 * JAM_CHANCE_PER_POINT
+}
+
+/// Condition after `amount` of wear, never below zero
+pub fn worn(condition: u8, amount: u8) -> u8 {
+    condition.saturating_sub(amount)
+}
+
+/// Condition restored by breaking down a spare of the same item
+pub fn parts_repair(repair_skill: u8) -> u8 {
+    25 + repair_skill.min(100) / 4
+}
+
+/// Condition restored by a successful Repair check; doubled on a critical
+pub fn skill_repair(repair_skill: u8, critical: bool) -> u8 {
+    let amount = 10 + repair_skill.min(100) / 4;
+    if critical {
+        amount * 2
+    } else {
+        amount
+    }
+}
+
+/// Short description of a condition for display
+pub fn label(condition: u8) -> &'static str {
+    match condition {
+        90.. => "Pristine",
+        70..=89 => "Good",
+        JAM_CONDITION..=69 => "Worn",
+        1.. => "Damaged",
+        0 => "Broken",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effectiveness_scales_with_condition() {
+        assert_eq!(scale(20, MAX_CONDITION), 20);
+        assert_eq!(scale(20, FULL_EFFECT_CONDITION), 20);
+        assert_eq!(scale(20, JAM_CONDITION), 14);
+        assert_eq!(scale(20, 0), 10);
+    }
+
+    #[test]
+    fn test_jams_only_below_threshold() {
+        assert_eq!(jam_chance(MAX_CONDITION), 0);
+        assert_eq!(jam_chance(JAM_CONDITION), 0);
+        assert_eq!(jam_chance(JAM_CONDITION - 1), 2);
+        assert_eq!(jam_chance(0), 60);
+    }
+
+    #[test]
+    fn test_wear_and_labels() {
+        assert_eq!(worn(1, ARMOR_WEAR), 0);
+        assert_eq!(label(MAX_CONDITION), "Pristine");
+        assert_eq!(label(JAM_CONDITION), "Worn");
+        assert_eq!(label(5), "Damaged");
+        assert_eq!(label(0), "Broken");
+    }
+
+    #[test]
+    fn test_repair_amounts() {
+        assert_eq!(parts_repair(20), 30);
+        assert_eq!(skill_repair(20, false), 15);
+        assert_eq!(skill_repair(20, true), 30);
+        assert!(parts_repair(u8::MAX) <= MAX_CONDITION);
+    }
+}
@@ -4,11 +4,16 @@
 //!
 //! ## Effect Types
 //!
-//! - **Healing**: Restores hit points (Stimpaks, food)
+//! - **Healing**: Restores hit points (Stimpaks)
 //! - **RadAway**: Removes accumulated radiation
-//! - **StatBuff**: Temporary SPECIAL stat increases (chems)
+//! - **StatBuff**: Temporary SPECIAL or skill increases (chems), which can
+//!   lead to addiction
+//! - **Nourishment**: Eases hunger and thirst (food and water)
 //! - **Addiction**: Warning effect for addictive substances
 //!
+//! Radiation, buffs and addictions are tracked by the character's
+//! [`StatusEffects`](crate::game::status::StatusEffects).
+//!
 //! ## Common Consumables
 //!
 //! | Item | Effect | Value |
@@ -19,6 +24,8 @@
 //! | Buffout | +2 STR, 300 rounds | 100 caps |
 //! | Mentats | +2 INT, 300 rounds | 100 caps |
 //! | Jet | +2 AGI, 100 rounds | 80 caps |
+//! | Purified Water | -40 thirst | 20 caps |
+//! | Cram | -35 hunger, 5 rads | 15 caps |
 
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
@@ -48,12 +55,14 @@ pub enum ConsumableEffect {
     /// ```
     RadAway(i32),
 
-    /// Temporarily boosts a SPECIAL stat
+    /// Temporarily boosts a SPECIAL stat or skill
     ///
     /// # Fields
-    /// * `stat` - Name of the SPECIAL stat to boost
+    /// * `stat` - Name of the SPECIAL stat or skill to boost
     /// * `amount` - Bonus amount added to the stat
     /// * `duration` - Number of combat rounds the effect lasts
+    /// * `addictiveness` - Dependence gained per dose; addiction sets in at
+    ///   [`ADDICTION_THRESHOLD`](crate::game::status::ADDICTION_THRESHOLD)
     ///
     /// # Example
     /// ```
@@ -64,12 +73,28 @@ pub enum ConsumableEffect {
     ///     stat: SmartString::from("strength"),
     ///     amount: 2,
     ///     duration: 300,
+    ///     addictiveness: 40,
     /// };
     /// ```
     StatBuff {
         stat: SmartString,
         amount: i32,
         duration: u32,
+        #[serde(default)]
+        addictiveness: u32,
+    },
+
+    /// Food or drink
+    ///
+    /// # Fields
+    /// * `food` - Points taken off the hunger meter
+    /// * `water` - Points taken off the thirst meter
+    /// * `rads` - Radiation absorbed from eating it
+    Nourishment {
+        food: u32,
+        water: u32,
+        #[serde(default)]
+        rads: u32,
     },
 
     /// Addiction warning effect (for flavor/roleplay)
@@ -79,6 +104,28 @@ pub enum ConsumableEffect {
     Addiction { effect: SmartString },
 }
 
+impl ConsumableEffect {
+    /// Short description of what the effect does
+    pub fn description(&self) -> String {
+        match self {
+            Self::Healing(amount) => format!("Restores {} HP", amount),
+            Self::RadAway(amount) => format!("Removes {} rads", amount),
+            Self::StatBuff {
+                stat,
+                amount,
+                duration,
+                ..
+            } => {
+                format!("+{} {} for {} rounds", amount, stat, duration)
+            }
+            Self::Nourishment { food, water, .. } => {
+                format!("-{} hunger, -{} thirst", food, water)
+            }
+            Self::Addiction { effect } => format!("Warning: {}", effect),
+        }
+    }
+}
+
 // Test-only helper methods
 #[cfg(test)]
 impl ConsumableEffect {
@@ -95,6 +142,7 @@ impl ConsumableEffect {
             stat: SmartString::from(stat),
             amount,
             duration,
+            addictiveness: 0,
         }
     }
 
@@ -103,21 +151,6 @@ impl ConsumableEffect {
             effect: SmartString::from(effect),
         }
     }
-
-    pub fn description(&self) -> String {
-        match self {
-            Self::Healing(amount) => format!("Restores {} HP", amount),
-            Self::RadAway(amount) => format!("Removes {} rads", amount),
-            Self::StatBuff {
-                stat,
-                amount,
-                duration,
-            } => {
-                format!("+{} {} for {} rounds", amount, stat, duration)
-            }
-            Self::Addiction { effect } => format!("Warning: {}", effect),
-        }
-    }
 }
 
 #[cfg(test)]
@@ -148,6 +181,7 @@ mod tests {
             stat: SmartString::from("strength"),
             amount: 2,
             duration: 300,
+            addictiveness: 40,
         };
 
         match effect {
@@ -155,6 +189,7 @@ mod tests {
                 stat,
                 amount,
                 duration,
+                ..
             } => {
                 assert_eq!(stat, "strength");
                 assert_eq!(amount, 2);
@@ -192,6 +227,7 @@ mod tests {
                 stat,
                 amount,
                 duration,
+                ..
             } => {
                 assert_eq!(stat, "agility");
                 assert_eq!(amount, 3);
@@ -1,269 +1,43 @@
 //! # Item Database Module
 //!
-//! Starting equipment for new characters.
+//! Starting equipment for new characters and the catalog of known world items.
+//!
+//! The items themselves are defined in `data/items/*.toml` and loaded by the
+//! [`catalog`](super::catalog) module; these functions are the shortcuts the
+//! rest of the game uses.
 
-use super::consumables::ConsumableEffect;
+use super::catalog::registry;
 use super::types::Item;
-use super::weapons::{DamageType, WeaponType};
 
 /// Get the standard starting items for a new character.
 ///
-/// Returns a collection of basic equipment suitable for
-/// beginning the game in the wasteland.
-///
-/// # Contents
-///
-/// - **10mm Pistol**: Reliable sidearm (1d10+2 damage, 4 AP)
-/// - **Baseball Bat**: Melee backup (1d8+STR damage, 3 AP)
-/// - **Stimpak**: Emergency healing (30 HP)
-/// - **RadAway**: Radiation treatment (50 rads)
-/// - **Leather Armor**: Basic protection (DR 5)
+/// Returns every catalog item with a `starting` quantity, stacked to that
+/// quantity. The default kit is a 10mm pistol with spare ammo, a baseball
+/// bat, a stimpak, a RadAway and leather armor.
 pub fn get_starting_items() -> Vec<Item> {
-    vec![
-        Item::new_weapon(
-            "10mm_pistol",
-            "10mm Pistol",
-            "A common semi-automatic pistol. Reliable and easy to maintain.",
-            "1d10+2",
-            DamageType::Normal,
-            WeaponType::SmallGun,
-            4,
-            150,
-        ),
-        Item::new_weapon(
-            "baseball_bat",
-            "Baseball Bat",
-            "Pre-war sporting equipment, now a popular melee weapon.",
-            "1d8+STR",
-            DamageType::Normal,
-            WeaponType::MeleeWeapon,
-            3,
-            50,
-        ),
-        Item::new_consumable(
-            "stimpak",
-            "Stimpak",
-            "Heals 30 HP instantly.",
-            ConsumableEffect::Healing(30),
-            50,
-        ),
-        Item::new_consumable(
-            "radaway",
-            "RadAway",
-            "Removes 50 rads.",
-            ConsumableEffect::RadAway(50),
-            75,
-        ),
-        Item::new_armor(
-            "leather_armor",
-            "Leather Armor",
-            "Basic protection made from brahmin hide.",
-            5,
-            200,
-        ),
-    ]
+    registry().starting_items()
+}
+
+/// Get every other item the game knows about (loot, shop stock, DM rewards).
+#[allow(dead_code)] // Public API for integration tests
+pub fn get_world_items() -> Vec<Item> {
+    registry().world_items()
+}
+
+/// Look up an item definition by its ID.
+///
+/// Searches starting equipment and world items. Returns a fresh item with quantity 1.
+pub fn find_item(id: &str) -> Option<Item> {
+    registry().item(id)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::game::items::ItemType;
-
-    // Test helper functions for creating items in tests
-    fn combat_shotgun() -> Item {
-        Item::new_weapon(
-            "combat_shotgun",
-            "Combat Shotgun",
-            "A military-grade semi-automatic shotgun.",
-            "3d6",
-            DamageType::Normal,
-            WeaponType::SmallGun,
-            5,
-            350,
-        )
-    }
-
-    fn hunting_rifle() -> Item {
-        Item::new_weapon(
-            "hunting_rifle",
-            "Hunting Rifle",
-            "A reliable bolt-action rifle.",
-            "2d8+3",
-            DamageType::Normal,
-            WeaponType::SmallGun,
-            5,
-            300,
-        )
-    }
-
-    fn laser_pistol() -> Item {
-        Item::new_weapon(
-            "laser_pistol",
-            "Laser Pistol",
-            "A standard energy sidearm.",
-            "1d10",
-            DamageType::Laser,
-            WeaponType::EnergyWeapon,
-            4,
-            200,
-        )
-    }
-
-    fn laser_rifle() -> Item {
-        Item::new_weapon(
-            "laser_rifle",
-            "Laser Rifle",
-            "A military-grade energy weapon.",
-            "2d10",
-            DamageType::Laser,
-            WeaponType::EnergyWeapon,
-            6,
-            500,
-        )
-    }
-
-    fn plasma_rifle() -> Item {
-        Item::new_weapon(
-            "plasma_rifle",
-            "Plasma Rifle",
-            "Advanced energy weapon firing superheated plasma bolts.",
-            "2d12",
-            DamageType::Plasma,
-            WeaponType::EnergyWeapon,
-            6,
-            750,
-        )
-    }
-
-    fn combat_knife() -> Item {
-        Item::new_weapon(
-            "combat_knife",
-            "Combat Knife",
-            "A military fighting knife.",
-            "1d6+STR",
-            DamageType::Normal,
-            WeaponType::MeleeWeapon,
-            2,
-            75,
-        )
-    }
-
-    fn super_sledge() -> Item {
-        Item::new_weapon(
-            "super_sledge",
-            "Super Sledge",
-            "A rocket-assisted sledgehammer.",
-            "2d10+STR",
-            DamageType::Normal,
-            WeaponType::MeleeWeapon,
-            4,
-            400,
-        )
-    }
+    use crate::game::items::{ConsumableEffect, DamageType, ItemType, WeaponType};
 
-    fn minigun() -> Item {
-        Item::new_weapon(
-            "minigun",
-            "Minigun",
-            "A motorized multi-barrel weapon.",
-            "3d8+5",
-            DamageType::Normal,
-            WeaponType::BigGun,
-            8,
-            1500,
-        )
-    }
-
-    fn metal_armor() -> Item {
-        Item::new_armor(
-            "metal_armor",
-            "Metal Armor",
-            "Armor cobbled together from scrap metal.",
-            10,
-            350,
-        )
-    }
-
-    fn combat_armor() -> Item {
-        Item::new_armor(
-            "combat_armor",
-            "Combat Armor",
-            "Pre-war military armor.",
-            15,
-            500,
-        )
-    }
-
-    fn power_armor_t51b() -> Item {
-        Item::new_armor(
-            "power_armor_t51b",
-            "T-51b Power Armor",
-            "Advanced pre-war power armor.",
-            30,
-            5000,
-        )
-    }
-
-    fn super_stimpak() -> Item {
-        Item::new_consumable(
-            "super_stimpak",
-            "Super Stimpak",
-            "A more potent healing injection.",
-            ConsumableEffect::Healing(60),
-            100,
-        )
-    }
-
-    fn buffout() -> Item {
-        Item::new_consumable(
-            "buffout",
-            "Buffout",
-            "Military-grade steroid.",
-            ConsumableEffect::StatBuff {
-                stat: "strength".into(),
-                amount: 2,
-                duration: 300,
-            },
-            100,
-        )
-    }
-
-    fn mentats() -> Item {
-        Item::new_consumable(
-            "mentats",
-            "Mentats",
-            "Pre-war pharmaceutical.",
-            ConsumableEffect::StatBuff {
-                stat: "intelligence".into(),
-                amount: 2,
-                duration: 300,
-            },
-            100,
-        )
-    }
-
-    fn jet() -> Item {
-        Item::new_consumable(
-            "jet",
-            "Jet",
-            "Highly addictive inhaler.",
-            ConsumableEffect::StatBuff {
-                stat: "agility".into(),
-                amount: 2,
-                duration: 100,
-            },
-            80,
-        )
-    }
-
-    fn nuka_cola() -> Item {
-        Item::new_consumable(
-            "nuka_cola",
-            "Nuka-Cola",
-            "The iconic pre-war soft drink.",
-            ConsumableEffect::Healing(10),
-            20,
-        )
+    fn item(id: &str) -> Item {
+        find_item(id).unwrap_or_else(|| panic!("{} should be in the catalog", id))
     }
 
     #[test]
@@ -309,7 +83,7 @@ mod tests {
 
     #[test]
     fn test_combat_shotgun() {
-        let shotgun = combat_shotgun();
+        let shotgun = item("combat_shotgun");
 
         assert_eq!(shotgun.id, "combat_shotgun");
         assert_eq!(shotgun.name, "Combat Shotgun");
@@ -326,7 +100,7 @@ mod tests {
 
     #[test]
     fn test_hunting_rifle() {
-        let rifle = hunting_rifle();
+        let rifle = item("hunting_rifle");
 
         assert_eq!(rifle.id, "hunting_rifle");
         if let ItemType::Weapon(stats) = rifle.item_type {
@@ -339,9 +113,9 @@ mod tests {
 
     #[test]
     fn test_energy_weapons() {
-        let laser_p = laser_pistol();
-        let laser_r = laser_rifle();
-        let plasma = plasma_rifle();
+        let laser_p = item("laser_pistol");
+        let laser_r = item("laser_rifle");
+        let plasma = item("plasma_rifle");
 
         if let ItemType::Weapon(stats) = laser_p.item_type {
             assert_eq!(stats.damage_type, DamageType::Laser);
@@ -365,8 +139,8 @@ mod tests {
 
     #[test]
     fn test_melee_weapons() {
-        let knife = combat_knife();
-        let sledge = super_sledge();
+        let knife = item("combat_knife");
+        let sledge = item("super_sledge");
 
         if let ItemType::Weapon(stats) = knife.item_type {
             assert_eq!(stats.weapon_type, WeaponType::MeleeWeapon);
@@ -387,7 +161,7 @@ mod tests {
 
     #[test]
     fn test_minigun() {
-        let gun = minigun();
+        let gun = item("minigun");
 
         if let ItemType::Weapon(stats) = gun.item_type {
             assert_eq!(stats.weapon_type, WeaponType::BigGun);
@@ -399,9 +173,9 @@ mod tests {
 
     #[test]
     fn test_armor_progression() {
-        let metal = metal_armor();
-        let combat = combat_armor();
-        let power = power_armor_t51b();
+        let metal = item("metal_armor");
+        let combat = item("combat_armor");
+        let power = item("power_armor_t51b");
 
         let metal_dr = match metal.item_type {
             ItemType::Armor(stats) => stats.damage_resistance,
@@ -424,11 +198,11 @@ mod tests {
 
     #[test]
     fn test_consumables() {
-        let super_stim = super_stimpak();
-        let buff = buffout();
-        let ment = mentats();
-        let j = jet();
-        let nuka = nuka_cola();
+        let super_stim = item("super_stimpak");
+        let buff = item("buffout");
+        let ment = item("mentats");
+        let j = item("jet");
+        let nuka = item("nuka_cola");
 
         if let ItemType::Consumable(ConsumableEffect::Healing(amount)) = super_stim.item_type {
             assert_eq!(amount, 60);
@@ -458,24 +232,11 @@ mod tests {
 
     #[test]
     fn test_unique_item_ids() {
-        let items = vec![
-            combat_shotgun(),
-            hunting_rifle(),
-            laser_pistol(),
-            laser_rifle(),
-            plasma_rifle(),
-            combat_knife(),
-            super_sledge(),
-            minigun(),
-            metal_armor(),
-            combat_armor(),
-            power_armor_t51b(),
-            super_stimpak(),
-            buffout(),
-            mentats(),
-            jet(),
-            nuka_cola(),
-        ];
+        let items: Vec<Item> = get_starting_items()
+            .into_iter()
+            .chain(get_world_items())
+            .collect();
+        assert!(items.len() >= 30);
 
         let mut ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
         let original_len = ids.len();
@@ -484,4 +245,16 @@ mod tests {
 
         assert_eq!(ids.len(), original_len, "All item IDs should be unique");
     }
+
+    #[test]
+    fn test_find_item() {
+        let stimpak = find_item("stimpak").expect("starting item");
+        assert_eq!(stimpak.name, "Stimpak");
+        assert_eq!(stimpak.quantity, 1);
+
+        let rifle = find_item("hunting_rifle").expect("world item");
+        assert!(matches!(rifle.item_type, ItemType::Weapon(_)));
+
+        assert!(find_item("gauss_rifle_of_doom").is_none());
+    }
 }
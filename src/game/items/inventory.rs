@@ -0,0 +1,383 @@
+//! # Inventory
+//!
+//! The [`Inventory`] a character carries: a list of item stacks where adding
+//! an item merges it onto the stack with the same ID, so a second Stimpak
+//! bumps the quantity instead of taking another slot. Items with state of
+//! their own (worn gear, loaded weapons, weapons with mods; see
+//! [`Item::has_own_state`]) never stack: each keeps its own slot, told apart
+//! by [`Item::instance`].
+//!
+//! The inventory derefs to a slice of items, so lookups, iteration and
+//! in-place edits work as on a `Vec`. Only adding and removing go through
+//! [`Inventory::add`], [`Inventory::split`] and [`Inventory::remove`], which
+//! keep the stacks merged and drop empty ones. It serializes as a plain list,
+//! the same as the `Vec<Item>` older saves hold.
+
+use super::catalog::ItemKind;
+use super::types::{Item, ItemType};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+/// Item stacks carried by a character, merged by item ID
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Inventory {
+    items: Vec<Item>,
+}
+
+/// Orders the inventory can be sorted in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InventorySort {
+    #[default]
+    Name,
+    Kind,
+    Weight,
+    Value,
+}
+
+impl InventorySort {
+    pub const ALL: [InventorySort; 4] = [
+        InventorySort::Name,
+        InventorySort::Kind,
+        InventorySort::Weight,
+        InventorySort::Value,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InventorySort::Name => "name",
+            InventorySort::Kind => "type",
+            InventorySort::Weight => "weight",
+            InventorySort::Value => "value",
+        }
+    }
+
+    /// The next order in [`InventorySort::ALL`], wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&sort| sort == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item, stacking it onto an item it [stacks with](Item::stacks_with).
+    /// Anything else gets a slot of its own (one per copy, for items with
+    /// state of their own) and a new instance number if its key is taken.
+    pub fn add(&mut self, mut item: Item) {
+        if let Some(existing) = self.items.iter_mut().find(|i| i.stacks_with(&item)) {
+            existing.quantity += item.quantity;
+            return;
+        }
+        if item.has_own_state() && item.quantity > 1 {
+            for _ in 1..item.quantity {
+                let mut copy = item.clone();
+                copy.quantity = 1;
+                self.add(copy);
+            }
+            item.quantity = 1;
+        }
+        if self.position_by_key(&item.key()).is_some() {
+            item.instance = self.next_instance(&item.id);
+        }
+        self.items.push(item);
+    }
+
+    /// An instance number no carried item with this ID uses yet
+    pub fn next_instance(&self, id: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|item| item.id == id)
+            .map(|item| item.instance)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Remove the whole `index`-th stack
+    pub fn remove(&mut self, index: usize) -> Item {
+        self.items.remove(index)
+    }
+
+    /// Take `qty` off the `index`-th stack as a separate item, removing the
+    /// stack when nothing is left. `None` if there aren't that many. Loaded
+    /// rounds stay with the stack.
+    pub fn split(&mut self, index: usize, qty: u32) -> Option<Item> {
+        let stack = self.items.get_mut(index)?;
+        if qty == 0 || qty > stack.quantity {
+            return None;
+        }
+        if qty == stack.quantity {
+            return Some(self.items.remove(index));
+        }
+        stack.quantity -= qty;
+        let mut item = stack.clone();
+        item.quantity = qty;
+        if let ItemType::Weapon(ref mut stats) = item.item_type {
+            stats.loaded_rounds = 0;
+        }
+        Some(item)
+    }
+
+    /// Give one item of the `index`-th stack a slot of its own (taking any
+    /// loaded rounds with it), so it can be worn out or loaded without
+    /// changing the rest of the stack. Returns the index of that item.
+    pub fn single_out(&mut self, index: usize) -> usize {
+        if self.items[index].quantity <= 1 {
+            return index;
+        }
+        let mut single = self.split(index, 1).expect("stack has more than one");
+        if let (ItemType::Weapon(stack), ItemType::Weapon(stats)) =
+            (&mut self.items[index].item_type, &mut single.item_type)
+        {
+            stats.loaded_rounds = std::mem::take(&mut stack.loaded_rounds);
+        }
+        single.instance = self.next_instance(&single.id);
+        self.items.push(single);
+        self.items.len() - 1
+    }
+
+    /// Keep only the stacks for which `keep` returns true
+    pub fn retain(&mut self, keep: impl FnMut(&Item) -> bool) {
+        self.items.retain(keep);
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Index of the stack with this ID
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.items.iter().position(|item| item.id == id)
+    }
+
+    /// Index of the stack with this [`Item::key`]
+    pub fn position_by_key(&self, key: &str) -> Option<usize> {
+        self.items.iter().position(|item| item.key() == key)
+    }
+
+    /// Index of the stack matching `query` by ID or (case-insensitive) name
+    pub fn find(&self, query: &str) -> Option<usize> {
+        let query = query.trim();
+        self.position(query).or_else(|| {
+            self.items
+                .iter()
+                .position(|item| item.name.eq_ignore_ascii_case(query))
+        })
+    }
+
+    /// Total weight of every stack, in pounds
+    pub fn weight(&self) -> f32 {
+        self.items
+            .iter()
+            .map(|item| item.weight * item.quantity as f32)
+            .sum()
+    }
+
+    /// Stacks of one kind (or all of them), with their inventory indexes
+    pub fn filtered(&self, kind: Option<ItemKind>) -> impl Iterator<Item = (usize, &Item)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(move |(_, item)| kind.is_none_or(|kind| ItemKind::of(&item.item_type) == kind))
+    }
+
+    /// Reorder the stacks; ties keep their current order
+    pub fn sort(&mut self, order: InventorySort) {
+        match order {
+            InventorySort::Name => self
+                .items
+                .sort_by_cached_key(|item| item.name.to_lowercase()),
+            InventorySort::Kind => self.items.sort_by_key(|item| {
+                ItemKind::ALL
+                    .iter()
+                    .position(|&k| k == ItemKind::of(&item.item_type))
+            }),
+            InventorySort::Weight => self.items.sort_by(|a, b| {
+                (b.weight * b.quantity as f32).total_cmp(&(a.weight * a.quantity as f32))
+            }),
+            InventorySort::Value => self.items.sort_by_key(|item| std::cmp::Reverse(item.value)),
+        }
+    }
+}
+
+impl Deref for Inventory {
+    type Target = [Item];
+
+    fn deref(&self) -> &[Item] {
+        &self.items
+    }
+}
+
+impl DerefMut for Inventory {
+    fn deref_mut(&mut self) -> &mut [Item] {
+        &mut self.items
+    }
+}
+
+impl<'a> IntoIterator for &'a Inventory {
+    type Item = &'a Item;
+    type IntoIter = std::slice::Iter<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl From<Vec<Item>> for Inventory {
+    /// Build an inventory from a list of items, merging duplicate IDs
+    fn from(items: Vec<Item>) -> Self {
+        let mut inventory = Inventory::new();
+        for item in items {
+            inventory.add(item);
+        }
+        inventory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn junk(id: &str, weight: f32, value: u32, quantity: u32) -> Item {
+        let mut item = Item::new_misc(id, id, "Junk.", weight, value);
+        item.quantity = quantity;
+        item
+    }
+
+    #[test]
+    fn test_add_merges_by_id() {
+        let mut inventory = Inventory::new();
+        inventory.add(junk("tin_can", 0.5, 1, 2));
+        inventory.add(junk("tin_can", 0.5, 1, 3));
+        inventory.add(junk("wrench", 2.0, 5, 1));
+
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[0].quantity, 5);
+        assert_eq!(inventory.weight(), 4.5);
+        assert_eq!(
+            Inventory::from(vec![junk("a", 1.0, 1, 1), junk("a", 1.0, 1, 1)]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_one_off_items_keep_their_own_slot() {
+        let mut modded = junk("rifle", 5.0, 100, 1);
+        modded.instance = 1;
+        let mut inventory = Inventory::from(vec![junk("rifle", 5.0, 100, 2), modded.clone()]);
+        inventory.add(modded);
+
+        assert_eq!(inventory.len(), 3);
+        assert_eq!(inventory[0].quantity, 2);
+        assert_eq!(inventory[1].key(), "rifle#1");
+        assert_eq!(inventory[2].key(), "rifle#2");
+        assert_eq!(inventory.position_by_key("rifle#2"), Some(2));
+        assert_eq!(inventory.next_instance("rifle"), 3);
+    }
+
+    #[test]
+    fn test_items_with_own_state_never_stack() {
+        let pistol = || crate::game::items::find_item("10mm_pistol").unwrap();
+        let mut worn = pistol();
+        if let ItemType::Weapon(ref mut stats) = worn.item_type {
+            stats.condition = 20;
+        }
+        let mut inventory = Inventory::new();
+        inventory.add(worn);
+        inventory.add(pistol());
+
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[0].condition(), Some(20));
+        assert_eq!(inventory[1].condition(), Some(100));
+        assert_eq!(inventory[1].key(), "10mm_pistol#1");
+
+        // Several copies at once get a slot each
+        let mut pair = pistol();
+        pair.quantity = 2;
+        inventory.add(pair);
+        assert_eq!(inventory.len(), 4);
+        assert!(inventory.iter().all(|item| item.quantity == 1));
+    }
+
+    #[test]
+    fn test_split_leaves_loaded_rounds_on_the_stack() {
+        let mut pistols = crate::game::items::find_item("10mm_pistol").unwrap();
+        pistols.quantity = 2;
+        let mut inventory = Inventory::new();
+        // A stack saved before items with their own state were kept apart
+        inventory.items.push(pistols);
+
+        let part = inventory.split(0, 1).unwrap();
+        assert_eq!(part.as_weapon().unwrap().loaded_rounds, 0);
+        assert_eq!(inventory[0].as_weapon().unwrap().loaded_rounds, 12);
+
+        let single = inventory.single_out(0);
+        assert_eq!(single, 0);
+        inventory[0].quantity = 3;
+        let single = inventory.single_out(0);
+        assert_eq!(inventory[single].quantity, 1);
+        assert_eq!(inventory[single].key(), "10mm_pistol#1");
+        assert_eq!(inventory[single].as_weapon().unwrap().loaded_rounds, 12);
+        assert_eq!(inventory[0].quantity, 2);
+        assert_eq!(inventory[0].as_weapon().unwrap().loaded_rounds, 0);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut inventory = Inventory::from(vec![junk("tin_can", 0.5, 1, 5)]);
+
+        assert!(inventory.split(0, 0).is_none());
+        assert!(inventory.split(0, 6).is_none());
+        assert!(inventory.split(1, 1).is_none());
+
+        let part = inventory.split(0, 2).unwrap();
+        assert_eq!(part.quantity, 2);
+        assert_eq!(inventory[0].quantity, 3);
+
+        let rest = inventory.split(0, 3).unwrap();
+        assert_eq!(rest.quantity, 3);
+        assert!(inventory.is_empty());
+    }
+
+    #[test]
+    fn test_find_by_id_or_name() {
+        let mut wrench = junk("wrench", 2.0, 5, 1);
+        wrench.name = "Pipe Wrench".into();
+        let inventory = Inventory::from(vec![junk("tin_can", 0.5, 1, 1), wrench]);
+
+        assert_eq!(inventory.find("wrench"), Some(1));
+        assert_eq!(inventory.find("pipe wrench"), Some(1));
+        assert_eq!(inventory.find("spoon"), None);
+    }
+
+    #[test]
+    fn test_filter_and_sort() {
+        let mut inventory = Inventory::from(vec![
+            junk("b_junk", 1.0, 30, 1),
+            crate::game::items::find_item("stimpak").unwrap(),
+            junk("a_junk", 10.0, 2, 1),
+        ]);
+
+        let misc: Vec<usize> = inventory
+            .filtered(Some(ItemKind::Misc))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(misc, vec![0, 2]);
+        assert_eq!(inventory.filtered(None).count(), 3);
+
+        inventory.sort(InventorySort::Weight);
+        assert_eq!(inventory[0].id, "a_junk");
+        inventory.sort(InventorySort::Value);
+        assert_eq!(inventory[0].id, "stimpak");
+        inventory.sort(InventorySort::Kind);
+        assert_eq!(inventory[0].id, "stimpak");
+        inventory.sort(InventorySort::Name);
+        assert_eq!(inventory[0].id, "a_junk");
+
+        assert_eq!(InventorySort::Value.next(), InventorySort::Name);
+    }
+}
@@ -8,11 +8,19 @@
 //! - [`armor`]: Armor statistics and protection mechanics
 //! - [`consumables`]: Consumable effects (healing, chems, etc.)
 //! - [`types`]: Core `Item` and `ItemType` structures
-//! - [`database`]: Starting equipment
+//! - [`condition`]: Wear on weapons and armor, jams and repair
+//! - [`catalog`]: Item registry loaded from `data/items/*.toml`, with rarity,
+//!   level tiers and mod overrides
+//! - [`database`]: Starting equipment and world item lookups
+//! - [`inventory`]: The [`Inventory`] a character carries, with stacking,
+//!   splitting, sorting and filtering
+//! - [`mods`]: Weapon mods, the slots they take and how they change a weapon
 //!
 //! ## Creating Items
 //!
-//! Use the `Item` constructor methods:
+//! Game items are defined in the data files and looked up by ID with
+//! [`find_item`]. For one-off items (tests, tools), use the `Item`
+//! constructor methods:
 //!
 //! ```
 //! use fallout_dnd::game::items::{Item, DamageType, WeaponType, ConsumableEffect};
@@ -49,14 +57,20 @@
 //! ```
 
 pub mod armor;
+pub mod catalog;
+pub mod condition;
 pub mod consumables;
 pub mod database;
+pub mod inventory;
+pub mod mods;
 pub mod types;
 pub mod weapons;
 
 // Re-export public types
+pub use catalog::{load_registry, registry};
 pub use consumables::ConsumableEffect;
-pub use database::get_starting_items;
+pub use database::{find_item, get_starting_items};
+pub use inventory::Inventory;
 pub use types::{Item, ItemType};
 #[allow(unused_imports)] // Re-exported for public API; used by tests and external consumers
 pub use weapons::{DamageType, WeaponType};
@@ -0,0 +1,201 @@
+//! # Weapon Mods
+//!
+//! Scopes, magazines and conversion kits attached to a single weapon.
+//!
+//! ## Slots
+//!
+//! Every mod goes in a [`ModSlot`]; a weapon holds one mod per slot, and
+//! attaching a second mod to a slot swaps the first back into the inventory.
+//! A mod can be limited to some weapon types (a reflex sight is no use on a
+//! sledgehammer).
+//!
+//! ## Instances
+//!
+//! A modded weapon is no longer interchangeable with the others of its kind,
+//! so attaching a mod gives the weapon its own [`Item::instance`] number and
+//! takes it off its stack. It's then told apart by [`Item::key`], which is
+//! also what the character's equipped slots refer to.
+
+use super::weapons::{DamageType, WeaponStats, WeaponType};
+use serde::{Deserialize, Serialize};
+
+/// Where on a weapon a mod goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModSlot {
+    Sight,
+    Barrel,
+    Magazine,
+    Ammo,
+}
+
+impl ModSlot {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModSlot::Sight => "Sight",
+            ModSlot::Barrel => "Barrel",
+            ModSlot::Magazine => "Magazine",
+            ModSlot::Ammo => "Ammo",
+        }
+    }
+}
+
+/// What a mod does to the weapon it's attached to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WeaponMod {
+    pub slot: ModSlot,
+    /// Weapon types the mod fits (any weapon when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fits: Vec<WeaponType>,
+    /// Added to the weapon skill when attacking
+    #[serde(default)]
+    pub to_hit: i32,
+    /// Added to every damage roll
+    #[serde(default)]
+    pub damage: i32,
+    /// Extra rounds the magazine holds
+    #[serde(default)]
+    pub magazine: u32,
+    /// Damage type the weapon deals instead of its own
+    #[serde(default)]
+    pub damage_type: Option<DamageType>,
+}
+
+impl WeaponMod {
+    /// Whether the mod can go on this weapon
+    pub fn fits(&self, weapon: &WeaponStats) -> bool {
+        (self.fits.is_empty() || self.fits.contains(&weapon.weapon_type))
+            && (self.magazine == 0 || weapon.uses_ammo())
+    }
+
+    /// "+15 to hit, +6 rounds", for display
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.to_hit != 0 {
+            parts.push(format!("{:+} to hit", self.to_hit));
+        }
+        if self.damage != 0 {
+            parts.push(format!("{:+} damage", self.damage));
+        }
+        if self.magazine > 0 {
+            parts.push(format!("+{} rounds", self.magazine));
+        }
+        if let Some(damage_type) = &self.damage_type {
+            parts.push(format!("{:?} damage", damage_type));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::items::{Item, ItemType};
+
+    fn pistol() -> Item {
+        Item::new_weapon(
+            "10mm_pistol",
+            "10mm Pistol",
+            "A sidearm.",
+            "1d10",
+            DamageType::Normal,
+            WeaponType::SmallGun,
+            4,
+            150,
+        )
+        .with_ammo("10mm_ammo", 12)
+    }
+
+    fn weapon_mod(id: &str, weapon_mod: WeaponMod) -> Item {
+        let mut item = Item::new_misc(id, id, "A mod.", 0.5, 100);
+        item.item_type = ItemType::Mod(weapon_mod);
+        item
+    }
+
+    fn sight(to_hit: i32) -> WeaponMod {
+        WeaponMod {
+            slot: ModSlot::Sight,
+            fits: vec![WeaponType::SmallGun],
+            to_hit,
+            damage: 0,
+            magazine: 0,
+            damage_type: None,
+        }
+    }
+
+    #[test]
+    fn test_mods_change_weapon_stats() {
+        let mut pistol = pistol();
+        let ItemType::Weapon(stats) = &mut pistol.item_type else {
+            unreachable!()
+        };
+        assert!(stats
+            .attach(weapon_mod("reflex_sight", sight(15)))
+            .is_none());
+        stats.attach(weapon_mod(
+            "incendiary_kit",
+            WeaponMod {
+                slot: ModSlot::Ammo,
+                fits: vec![],
+                to_hit: 0,
+                damage: 2,
+                magazine: 6,
+                damage_type: Some(DamageType::Fire),
+            },
+        ));
+
+        assert_eq!(stats.to_hit_bonus(), 15);
+        assert_eq!(stats.mod_damage_bonus(), 2);
+        assert_eq!(stats.capacity(), 18);
+        assert_eq!(stats.effective_damage_type(), DamageType::Fire);
+    }
+
+    #[test]
+    fn test_same_slot_swaps_mods() {
+        let mut pistol = pistol();
+        let ItemType::Weapon(stats) = &mut pistol.item_type else {
+            unreachable!()
+        };
+        stats.attach(weapon_mod("iron_sight", sight(5)));
+        let replaced = stats.attach(weapon_mod("reflex_sight", sight(15))).unwrap();
+        assert_eq!(replaced.id, "iron_sight");
+        assert_eq!(stats.to_hit_bonus(), 15);
+    }
+
+    #[test]
+    fn test_detach_unloads_extra_rounds() {
+        let mut pistol = pistol();
+        let ItemType::Weapon(stats) = &mut pistol.item_type else {
+            unreachable!()
+        };
+        let drum = WeaponMod {
+            slot: ModSlot::Magazine,
+            fits: vec![],
+            to_hit: 0,
+            damage: 0,
+            magazine: 8,
+            damage_type: None,
+        };
+        stats.attach(weapon_mod("drum_magazine", drum.clone()));
+        stats.loaded_rounds = stats.capacity();
+
+        let (item, unloaded) = stats.detach(0).unwrap();
+        assert_eq!(item.id, "drum_magazine");
+        assert_eq!(unloaded, 8);
+        assert_eq!(stats.loaded_rounds, 12);
+        assert!(stats.detach(0).is_none());
+
+        // Magazines only fit weapons that take ammo
+        let bat = Item::new_weapon(
+            "bat",
+            "Bat",
+            "Wood.",
+            "1d6",
+            DamageType::Normal,
+            WeaponType::MeleeWeapon,
+            3,
+            5,
+        );
+        assert!(!drum.fits(bat.as_weapon().unwrap()));
+    }
+}
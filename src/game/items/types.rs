@@ -17,13 +17,16 @@
 //! - **Weapon**: Combat items with damage stats
 //! - **Armor**: Protective gear with DR/AC
 //! - **Consumable**: Single-use items with effects
+//! - **Ammo**: Stackable ammunition, matched to weapons by `WeaponStats::ammo_type`
 //! - **Misc**: General items without special mechanics
 
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
 
 use super::armor::ArmorStats;
+use super::condition::MAX_CONDITION;
 use super::consumables::ConsumableEffect;
+use super::mods::{ModSlot, WeaponMod};
 use super::weapons::{DamageType, WeaponStats, WeaponType};
 
 /// Categorization of items by their function.
@@ -37,6 +40,10 @@ pub enum ItemType {
     Armor(ArmorStats),
     /// Single-use item with an effect
     Consumable(ConsumableEffect),
+    /// Ammunition; the item ID is the ammo type weapons load
+    Ammo,
+    /// Weapon mod, attached to a weapon from the equipment menu
+    Mod(WeaponMod),
     /// General item without special mechanics
     #[default]
     Misc,
@@ -66,7 +73,7 @@ pub enum ItemType {
 /// assert_eq!(pistol.name, "10mm Pistol");
 /// assert_eq!(pistol.value, 150);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Item {
     /// Unique identifier for lookups (snake_case)
     pub id: SmartString,
@@ -82,6 +89,14 @@ pub struct Item {
     pub value: u32,
     /// Stack count (for consumables and ammo)
     pub quantity: u32,
+    /// Tells a one-off item (a modded weapon) apart from others with the
+    /// same ID; 0 for ordinary stackable items
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub instance: u32,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 impl Default for Item {
@@ -94,10 +109,12 @@ impl Default for Item {
             weight: 0.0,
             value: 0,
             quantity: 1,
+            instance: 0,
         }
     }
 }
 
+#[allow(dead_code)] // Game items come from the catalog; these are for tests and tools
 impl Item {
     /// Create a new weapon item.
     ///
@@ -148,12 +165,17 @@ impl Item {
                 weapon_type,
                 ap_cost,
                 ammo_type: None,
+                magazine_size: 0,
+                loaded_rounds: 0,
                 range: 30,
                 critical_multiplier: 2.0,
+                condition: MAX_CONDITION,
+                mods: Vec::new(),
             }),
             weight: 3.0,
             value,
             quantity: 1,
+            instance: 0,
         }
     }
 
@@ -191,13 +213,77 @@ impl Item {
                 damage_resistance: dr,
                 radiation_resistance: 0,
                 armor_class: 5 + (dr / 2),
+                resistances: Default::default(),
+                condition: MAX_CONDITION,
             }),
             weight: 8.0,
             value,
             quantity: 1,
+            instance: 0,
+        }
+    }
+
+    /// Make a weapon use ammunition, starting with a full magazine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fallout_dnd::game::items::{DamageType, Item, WeaponType};
+    ///
+    /// let pistol = Item::new_weapon(
+    ///     "10mm_pistol",
+    ///     "10mm Pistol",
+    ///     "A reliable sidearm.",
+    ///     "1d10+2",
+    ///     DamageType::Normal,
+    ///     WeaponType::SmallGun,
+    ///     4,
+    ///     150,
+    /// )
+    /// .with_ammo("10mm_ammo", 12);
+    /// ```
+    pub fn with_ammo(mut self, ammo_type: &str, magazine_size: u32) -> Self {
+        if let ItemType::Weapon(ref mut stats) = self.item_type {
+            stats.ammo_type = Some(SmartString::from(ammo_type));
+            stats.magazine_size = magazine_size;
+            stats.loaded_rounds = magazine_size;
+        }
+        self
+    }
+
+    /// Create a stack of ammunition.
+    ///
+    /// The ID doubles as the ammo type that weapons reference.
+    pub fn new_ammo(id: &str, name: &str, description: &str, quantity: u32, value: u32) -> Self {
+        Item {
+            id: SmartString::from(id),
+            name: SmartString::from(name),
+            description: SmartString::from(description),
+            item_type: ItemType::Ammo,
+            weight: 0.05,
+            value,
+            quantity,
+            instance: 0,
         }
     }
 
+    /// Add a resistance modifier against one damage type (armor only).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fallout_dnd::game::items::{DamageType, Item};
+    ///
+    /// let armor = Item::new_armor("metal_armor", "Metal Armor", "Scrap plates.", 10, 350)
+    ///     .with_resistance(DamageType::Laser, 5);
+    /// ```
+    pub fn with_resistance(mut self, damage_type: DamageType, amount: i32) -> Self {
+        if let ItemType::Armor(ref mut stats) = self.item_type {
+            stats.resistances.insert(damage_type, amount);
+        }
+        self
+    }
+
     /// Create a new consumable item.
     ///
     /// # Arguments
@@ -236,13 +322,11 @@ impl Item {
             weight: 0.5,
             value,
             quantity: 1,
+            instance: 0,
         }
     }
-}
 
-// Test-only helper methods
-#[cfg(test)]
-impl Item {
+    /// Create a miscellaneous item (quest items, junk)
     pub fn new_misc(id: &str, name: &str, description: &str, weight: f32, value: u32) -> Self {
         Item {
             id: SmartString::from(id),
@@ -252,27 +336,99 @@ impl Item {
             weight,
             value,
             quantity: 1,
+            instance: 0,
         }
     }
 
-    pub fn is_weapon(&self) -> bool {
-        matches!(self.item_type, ItemType::Weapon(_))
+    /// Condition of a weapon or armor; `None` for items that don't wear out
+    pub fn condition(&self) -> Option<u8> {
+        match &self.item_type {
+            ItemType::Weapon(stats) => Some(stats.condition),
+            ItemType::Armor(stats) => Some(stats.condition),
+            _ => None,
+        }
     }
 
-    pub fn is_armor(&self) -> bool {
-        matches!(self.item_type, ItemType::Armor(_))
+    fn condition_mut(&mut self) -> Option<&mut u8> {
+        match &mut self.item_type {
+            ItemType::Weapon(stats) => Some(&mut stats.condition),
+            ItemType::Armor(stats) => Some(&mut stats.condition),
+            _ => None,
+        }
     }
 
-    pub fn is_consumable(&self) -> bool {
-        matches!(self.item_type, ItemType::Consumable(_))
+    /// Restore up to `amount` condition, returning how much was restored
+    pub fn repair(&mut self, amount: u8) -> u8 {
+        match self.condition_mut() {
+            Some(condition) => {
+                let before = *condition;
+                *condition = condition.saturating_add(amount).min(MAX_CONDITION);
+                *condition - before
+            }
+            None => 0,
+        }
+    }
+
+    /// Whether the item has state of its own (wear, loaded rounds or mods),
+    /// so it isn't interchangeable with others of its kind
+    pub fn has_own_state(&self) -> bool {
+        self.condition()
+            .is_some_and(|condition| condition < MAX_CONDITION)
+            || self
+                .as_weapon()
+                .is_some_and(|stats| stats.loaded_rounds > 0 || !stats.mods.is_empty())
+    }
+
+    /// Whether `other` can be added to this item's stack: the same ID, not a
+    /// one-off, and neither has state of its own
+    pub fn stacks_with(&self, other: &Item) -> bool {
+        self.id == other.id
+            && other.instance == 0
+            && !self.has_own_state()
+            && !other.has_own_state()
+    }
+
+    /// Identifies this exact stack: the ID, plus `#instance` for one-off
+    /// items. The character's equipped slots hold keys.
+    pub fn key(&self) -> SmartString {
+        if self.instance == 0 {
+            self.id.clone()
+        } else {
+            SmartString::from(format!("{}#{}", self.id, self.instance))
+        }
+    }
+
+    /// Slot of a weapon mod; `None` for other items
+    pub fn mod_slot(&self) -> Option<ModSlot> {
+        match &self.item_type {
+            ItemType::Mod(weapon_mod) => Some(weapon_mod.slot),
+            _ => None,
+        }
     }
 
+    /// Weapon stats; `None` for other items
     pub fn as_weapon(&self) -> Option<&WeaponStats> {
         match &self.item_type {
             ItemType::Weapon(stats) => Some(stats),
             _ => None,
         }
     }
+}
+
+// Test-only helper methods
+#[cfg(test)]
+impl Item {
+    pub fn is_weapon(&self) -> bool {
+        matches!(self.item_type, ItemType::Weapon(_))
+    }
+
+    pub fn is_armor(&self) -> bool {
+        matches!(self.item_type, ItemType::Armor(_))
+    }
+
+    pub fn is_consumable(&self) -> bool {
+        matches!(self.item_type, ItemType::Consumable(_))
+    }
 
     pub fn as_armor(&self) -> Option<&ArmorStats> {
         match &self.item_type {
@@ -393,6 +549,7 @@ mod tests {
                 stat: "intelligence".to_string().into(),
                 amount: 2,
                 duration: 300,
+                addictiveness: 40,
             },
             100,
         );
@@ -402,6 +559,7 @@ mod tests {
                 stat,
                 amount,
                 duration,
+                ..
             }) => {
                 assert_eq!(stat, "intelligence");
                 assert_eq!(amount, 2);
@@ -13,7 +13,7 @@
 //!
 //! ## Damage Types
 //!
-//! Different damage types may have different interactions with armor:
+//! Armor can resist each damage type differently (see `ArmorStats::resistances`):
 //! - **Normal**: Standard ballistic/physical damage
 //! - **Laser**: Energy damage from laser weapons
 //! - **Plasma**: High-energy plasma damage
@@ -21,15 +21,19 @@
 //! - **Explosive**: Area-of-effect blast damage
 //! - **Poison**: Damage over time
 
+use super::condition::{self, full_condition, MAX_CONDITION};
+use super::mods::WeaponMod;
+use super::types::{Item, ItemType};
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
 
 /// Types of damage that weapons can inflict.
 ///
 /// Different damage types may interact differently with armor and resistances.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub enum DamageType {
     /// Standard ballistic or physical damage
+    #[default]
     Normal,
     /// Energy damage from laser weapons
     Laser,
@@ -74,12 +78,25 @@ pub struct WeaponStats {
     pub weapon_type: WeaponType,
     /// Action points required to attack
     pub ap_cost: i32,
-    /// Type of ammunition used (None for melee/unarmed)
+    /// Type of ammunition used (None for melee/unarmed).
+    /// Matches the ID of the ammo item in the inventory.
     pub ammo_type: Option<SmartString>,
+    /// Rounds the weapon holds when fully loaded (0 for weapons without ammo)
+    #[serde(default)]
+    pub magazine_size: u32,
+    /// Rounds currently loaded
+    #[serde(default)]
+    pub loaded_rounds: u32,
     /// Effective range in meters
     pub range: u32,
     /// Damage multiplier on critical hits
     pub critical_multiplier: f32,
+    /// Wear from 0 (broken) to 100 (pristine)
+    #[serde(default = "full_condition")]
+    pub condition: u8,
+    /// Mods attached to this weapon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mods: Vec<Item>,
 }
 
 impl Default for WeaponStats {
@@ -90,12 +107,98 @@ impl Default for WeaponStats {
             weapon_type: WeaponType::Unarmed,
             ap_cost: 3,
             ammo_type: None,
+            magazine_size: 0,
+            loaded_rounds: 0,
             range: 1,
             critical_multiplier: 2.0,
+            condition: MAX_CONDITION,
+            mods: Vec::new(),
         }
     }
 }
 
+impl WeaponStats {
+    /// Whether the weapon needs ammunition to fire
+    pub fn uses_ammo(&self) -> bool {
+        self.ammo_type.is_some() && self.magazine_size > 0
+    }
+
+    /// Whether the weapon can't fire until reloaded
+    pub fn is_empty(&self) -> bool {
+        self.uses_ammo() && self.loaded_rounds == 0
+    }
+
+    /// Lose condition from one attack
+    pub fn wear(&mut self) {
+        self.condition = condition::worn(self.condition, condition::WEAPON_WEAR);
+    }
+
+    /// Mods attached to the weapon
+    pub fn attached_mods(&self) -> impl Iterator<Item = &WeaponMod> {
+        self.mods.iter().filter_map(|item| match &item.item_type {
+            ItemType::Mod(weapon_mod) => Some(weapon_mod),
+            _ => None,
+        })
+    }
+
+    /// Weapon skill bonus from mods
+    pub fn to_hit_bonus(&self) -> i32 {
+        self.attached_mods().map(|m| m.to_hit).sum()
+    }
+
+    /// Damage bonus from mods
+    pub fn mod_damage_bonus(&self) -> i32 {
+        self.attached_mods().map(|m| m.damage).sum()
+    }
+
+    /// Damage dice with the mods' damage bonus added ("1d10+2")
+    pub fn modded_damage(&self) -> SmartString {
+        match self.mod_damage_bonus() {
+            0 => self.damage.clone(),
+            bonus => SmartString::from(format!("{}{:+}", self.damage, bonus)),
+        }
+    }
+
+    /// Rounds a full magazine holds, with mods
+    pub fn capacity(&self) -> u32 {
+        self.magazine_size + self.attached_mods().map(|m| m.magazine).sum::<u32>()
+    }
+
+    /// Damage type dealt, after any conversion mod
+    pub fn effective_damage_type(&self) -> DamageType {
+        self.attached_mods()
+            .find_map(|m| m.damage_type.clone())
+            .unwrap_or_else(|| self.damage_type.clone())
+    }
+
+    /// Attach a mod, returning the one it replaced in the same slot. An
+    /// item that isn't a mod is handed straight back.
+    pub fn attach(&mut self, item: Item) -> Option<Item> {
+        let Some(slot) = item.mod_slot() else {
+            return Some(item);
+        };
+        let replaced = self
+            .mods
+            .iter()
+            .position(|attached| attached.mod_slot() == Some(slot))
+            .map(|index| self.mods.remove(index));
+        self.mods.push(item);
+        replaced
+    }
+
+    /// Take the `index`-th mod off, unloading rounds the smaller magazine
+    /// can't hold. Returns the mod and the rounds unloaded.
+    pub fn detach(&mut self, index: usize) -> Option<(Item, u32)> {
+        if index >= self.mods.len() {
+            return None;
+        }
+        let item = self.mods.remove(index);
+        let unloaded = self.loaded_rounds.saturating_sub(self.capacity());
+        self.loaded_rounds -= unloaded;
+        Some((item, unloaded))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,8 +251,12 @@ mod tests {
             weapon_type: WeaponType::SmallGun,
             ap_cost: 5,
             ammo_type: Some(SmartString::from("12_gauge")),
+            magazine_size: 8,
+            loaded_rounds: 3,
             range: 20,
             critical_multiplier: 2.0,
+            condition: MAX_CONDITION,
+            mods: Vec::new(),
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -159,4 +266,27 @@ mod tests {
         let deserialized: WeaponStats = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, stats);
     }
+
+    #[test]
+    fn test_uses_ammo_and_empty() {
+        let mut stats = WeaponStats::default();
+        assert!(!stats.uses_ammo());
+        assert!(!stats.is_empty());
+
+        stats.ammo_type = Some(SmartString::from("10mm_ammo"));
+        stats.magazine_size = 12;
+        assert!(stats.uses_ammo());
+        assert!(stats.is_empty());
+
+        stats.loaded_rounds = 1;
+        assert!(!stats.is_empty());
+    }
+
+    #[test]
+    fn test_old_saves_without_magazine_fields() {
+        let json = r#"{"damage":"1d10","damage_type":"Laser","weapon_type":"EnergyWeapon","ap_cost":4,"ammo_type":null,"range":30,"critical_multiplier":2.0}"#;
+        let stats: WeaponStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.magazine_size, 0);
+        assert!(!stats.uses_ammo());
+    }
 }
@@ -0,0 +1,195 @@
+//! # Loot Module
+//!
+//! What defeated enemies leave behind.
+//!
+//! ## Drops
+//!
+//! Enemies spawned from the bestiary roll the `[enemy.loot]` table of their
+//! definition: a dice expression of caps and a list of items, each with a
+//! percent chance and a quantity. Enemies the bestiary doesn't know only
+//! carry a few caps ([`GENERIC_CAPS`]).
+//!
+//! ## Luck
+//!
+//! Luck above 5 makes every drop more likely and the caps pile bigger, Luck
+//! below 5 does the opposite. Every body also has a Luck-based chance of a
+//! "lucky find": an extra item from the catalog at the enemy's level, where
+//! higher Luck tilts the pick towards rarer items.
+
+use super::bestiary::bestiary;
+use super::combat::Enemy;
+use super::dice::DiceExpr;
+use super::items::catalog::Rarity;
+use super::items::{registry, Item, ItemType};
+use super::rng::pick_weighted;
+use rand::Rng;
+
+/// Caps carried by enemies without a bestiary loot table
+pub const GENERIC_CAPS: &str = "1d10";
+/// Percentage points added to each drop chance per point of Luck above 5
+const LUCK_CHANCE_PER_POINT: i32 = 3;
+/// Percent more caps found per point of Luck above 5
+const LUCK_CAPS_PER_POINT: i32 = 10;
+/// Percent chance of a lucky find per point of Luck
+const LUCKY_FIND_PER_POINT: u32 = 2;
+
+/// Caps and items taken from one or more bodies
+#[derive(Debug, Clone, Default)]
+pub struct Loot {
+    pub caps: u32,
+    pub items: Vec<Item>,
+}
+
+impl Loot {
+    /// Add an item, stacking onto one it [stacks with](Item::stacks_with)
+    pub fn add_item(&mut self, item: Item) {
+        if let Some(existing) = self.items.iter_mut().find(|i| i.stacks_with(&item)) {
+            existing.quantity += item.quantity;
+        } else {
+            self.items.push(item);
+        }
+    }
+
+    fn merge(&mut self, other: Loot) {
+        self.caps += other.caps;
+        for item in other.items {
+            self.add_item(item);
+        }
+    }
+}
+
+/// Roll the loot of every body in `enemies`
+pub fn roll_loot(enemies: &[Enemy], luck: u8, rng: &mut impl Rng) -> Loot {
+    let mut loot = Loot::default();
+    for enemy in enemies {
+        loot.merge(roll_enemy_loot(enemy, luck, rng));
+    }
+    loot
+}
+
+/// Roll what one defeated enemy drops
+pub fn roll_enemy_loot(enemy: &Enemy, luck: u8, rng: &mut impl Rng) -> Loot {
+    let luck_bonus = luck as i32 - 5;
+    let table = enemy
+        .kind
+        .as_deref()
+        .and_then(|kind| bestiary().enemy(kind))
+        .map(|definition| &definition.loot);
+
+    let mut loot = Loot::default();
+    let caps = match table {
+        Some(table) => table
+            .caps
+            .as_deref()
+            .map_or(0, |caps| roll_count(caps, rng)),
+        None => roll_count(GENERIC_CAPS, rng),
+    };
+    loot.caps = (caps as i32 * (100 + luck_bonus * LUCK_CAPS_PER_POINT) / 100).max(0) as u32;
+
+    for drop in table.map_or(&[][..], |table| &table.items) {
+        let chance = (drop.chance as i32 + luck_bonus * LUCK_CHANCE_PER_POINT).clamp(1, 100);
+        if rng.random_range(1..=100) > chance {
+            continue;
+        }
+        if let Some(mut item) = registry().item(&drop.id) {
+            item.quantity = roll_count(&drop.qty, rng).max(1);
+            loot.add_item(item);
+        }
+    }
+
+    if rng.random_range(1..=100) <= luck as u32 * LUCKY_FIND_PER_POINT {
+        if let Some(item) = lucky_find(enemy.level, luck, rng) {
+            loot.add_item(item);
+        }
+    }
+    loot
+}
+
+/// An extra catalog item at or below `level`, rarer the luckier the player
+fn lucky_find(level: u32, luck: u8, rng: &mut impl Rng) -> Option<Item> {
+    let options: Vec<_> = registry()
+        .for_level(level)
+        .filter(|entry| !matches!(entry.item.item_type, ItemType::Misc))
+        .collect();
+    let entry = pick_weighted(&options, |entry| lucky_weight(entry.rarity, luck), rng)?;
+    Some(entry.item.clone())
+}
+
+/// Rarity weight with every point of Luck above 5 favouring rarer tiers
+fn lucky_weight(rarity: Rarity, luck: u8) -> u32 {
+    let tier = match rarity {
+        Rarity::Common => 0,
+        Rarity::Uncommon => 1,
+        Rarity::Rare => 2,
+        Rarity::Legendary => 3,
+    };
+    rarity.weight() + luck.saturating_sub(5) as u32 * tier
+}
+
+fn roll_count(dice: &str, rng: &mut impl Rng) -> u32 {
+    dice.parse::<DiceExpr>()
+        .ok()
+        .and_then(|dice| dice.roll(None, rng).ok())
+        .map_or(0, |roll| roll.total.max(0) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::GameRng;
+
+    fn raider() -> Enemy {
+        bestiary()
+            .spawn("raider", 1)
+            .expect("raider is in the bestiary")
+    }
+
+    #[test]
+    fn test_unknown_enemy_only_carries_caps() {
+        let enemy = Enemy::new("Tunnel Snake", 1);
+        let mut rng = GameRng::seeded(3);
+        for _ in 0..20 {
+            let loot = roll_enemy_loot(&enemy, 1, &mut rng);
+            assert!(loot.caps <= 10);
+            assert!(loot.items.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_raider_drops_items_from_its_table() {
+        let table = &bestiary().enemy("raider").unwrap().loot;
+        let mut rng = GameRng::seeded(11);
+        let loot = roll_loot(&vec![raider(); 30], 5, &mut rng);
+
+        assert!(loot.caps > 0);
+        assert!(loot
+            .items
+            .iter()
+            .any(|item| table.items.iter().any(|drop| drop.id == item.id)));
+        // Stacked: never the same item twice
+        for (i, item) in loot.items.iter().enumerate() {
+            assert!(loot.items[i + 1..].iter().all(|other| other.id != item.id));
+        }
+    }
+
+    #[test]
+    fn test_luck_improves_drops() {
+        let bodies = vec![raider(); 50];
+        let unlucky = roll_loot(&bodies, 1, &mut GameRng::seeded(5));
+        let lucky = roll_loot(&bodies, 10, &mut GameRng::seeded(5));
+
+        let count = |loot: &Loot| loot.items.iter().map(|i| i.quantity).sum::<u32>();
+        assert!(lucky.caps > unlucky.caps);
+        assert!(count(&lucky) > count(&unlucky));
+    }
+
+    #[test]
+    fn test_lucky_weight_favours_rare_items() {
+        assert_eq!(
+            lucky_weight(Rarity::Legendary, 5),
+            Rarity::Legendary.weight()
+        );
+        assert_eq!(lucky_weight(Rarity::Common, 10), Rarity::Common.weight());
+        assert!(lucky_weight(Rarity::Rare, 10) > lucky_weight(Rarity::Rare, 5));
+    }
+}
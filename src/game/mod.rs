@@ -5,20 +5,35 @@
 //! ## Key Components
 //!
 //! - [`GameState`]: Main game state container that holds all game data
+//! - [`barter`]: Merchant stock and Barter-based buy/sell prices
+//! - [`bestiary`]: Enemy definitions and encounter tables loaded from data files
 //! - [`character`]: Character creation, attributes (SPECIAL), and skill management
+//! - [`clock`]: In-game time of day, day/night and the game calendar
 //! - [`combat`]: Turn-based combat system with action points
+//! - [`crafting`]: Recipes turning junk into chems, ammo and weapon mods
 //! - [`worldbook`]: Persistent world knowledge tracking locations, NPCs, and events
+//! - [`worldbook_index`]: BM25 retrieval of worldbook entries relevant to the player's action
 //! - [`story_manager`]: Narrative context management for AI conversations
+//! - [`story_memory`]: Chapter summaries of the campaign for the AI DM's long-term memory
 //! - [`items`]: Item system and inventory management
+//! - [`loot`]: Caps and items dropped by defeated enemies, improved by Luck
 //! - [`rolls`]: Dice rolling mechanics for skill checks
+//! - [`dice`]: Dice expression parser (`4d6kh3`, `1d20adv+AGI`) with roll breakdowns
+//! - [`perks`]: Perks picked on level up and their mechanical effects
+//! - [`quests`]: Quest journal with stages, objectives and rewards
+//! - [`status`]: Radiation, hunger and thirst, chem buffs and addiction
+//! - [`dm_actions`]: Structured actions the AI DM uses to change game state
+//! - [`rng`]: Seeded random number generator saved with the game
 //!
 //! ## Game State Management
 //!
 //! The [`GameState`] struct is the central hub for all game data. It includes:
 //! - Player character with SPECIAL stats and skills
 //! - Combat state for managing encounters
-//! - Story manager for AI conversation history
-//! - Location and quest tracking
+//! - Story manager for AI conversation history, and chapter summaries of
+//!   older turns
+//! - Location tracking and the quest journal
+//! - The in-game clock (day counter and time of day)
 //! - Worldbook for persistent world knowledge
 //!
 //! ## Save/Load System
@@ -27,26 +42,60 @@
 //! All save operations are handled by the [`persistence`] module with comprehensive
 //! security checks to prevent path traversal attacks.
 
+pub mod barter;
+pub mod bestiary;
 pub mod char_handlers;
 pub mod character;
+pub mod clock;
 pub mod combat;
 pub mod conversation;
+pub mod crafting;
+pub mod dice;
+pub mod dm_actions;
 pub mod handlers;
 pub mod items;
+pub mod loot;
+pub mod perks;
 pub mod persistence;
+pub mod quests;
+pub mod rng;
 pub mod rolls;
 pub mod stat_allocator;
+pub mod status;
 pub mod story_manager;
+pub mod story_memory;
 pub mod tui_game_loop;
 pub mod worldbook;
+pub mod worldbook_index;
 
+use crate::error::GameError;
+use barter::{Merchant, Prices};
+use bestiary::{bestiary, Encounter};
 use character::Character;
+use clock::{TimeOfDay, MAX_REST_HOURS};
 use combat::CombatState;
 use conversation::ConversationManager;
+use crafting::recipes;
+use items::{condition, find_item, Inventory, Item, ItemType};
+use quests::{QuestEvent, QuestLog, QuestProgress};
+use rng::GameRng;
+use rolls::perform_roll_at;
 use serde::{Deserialize, Serialize};
+use smartstring::alias::String as SmartString;
 use story_manager::StoryManager;
+use story_memory::{StoryMemory, SummaryJob};
 use worldbook::Worldbook;
 
+/// Load and check the game data files (items, enemies and recipes). Called
+/// once at startup, before the TUI takes over the terminal, so a bad data
+/// file is an error message rather than a crash mid-game.
+pub fn load_game_data() -> Result<(), GameError> {
+    items::load_registry()?;
+    bestiary::load_bestiary()?;
+    crafting::load_recipes()?;
+    Ok(())
+}
+
 /// Default value for day counter (used for backward compatibility with old saves)
 fn default_day() -> u32 {
     1
@@ -99,18 +148,41 @@ pub struct GameState {
     /// New code should use `conversation` instead
     pub story: StoryManager,
 
+    /// Chapter summaries of conversation turns too old to send to the DM
+    #[serde(default)]
+    pub story_memory: StoryMemory,
+
     /// Current location in the wasteland
     pub location: String,
 
-    /// Active quests and objectives
+    /// Legacy quest names from older saves; moved into `quests` on load
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub quest_log: Vec<String>,
 
+    /// Quest journal (stages, objectives, rewards)
+    #[serde(default)]
+    pub quests: QuestLog,
+
     /// Persistent world knowledge (locations, NPCs, events)
     pub worldbook: Worldbook,
 
     /// Current day in the wasteland (starts at 1)
     #[serde(default = "default_day")]
     pub day: u32,
+
+    /// Time of day; advanced together with `day` by [`GameState::pass_time`]
+    #[serde(default)]
+    pub time: TimeOfDay,
+
+    /// Items on the bodies from the last fight won, until the player takes
+    /// them or wins another fight
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loot: Vec<Item>,
+
+    /// Random number generator for every dice roll, check and encounter.
+    /// Saved with the game so a loaded save continues the same stream.
+    #[serde(default)]
+    pub rng: GameRng,
 }
 
 impl GameState {
@@ -146,10 +218,569 @@ impl GameState {
             combat: CombatState::new(),
             conversation: ConversationManager::new(),
             story: StoryManager::new(),
+            story_memory: StoryMemory::default(),
             location: "Vault 13 Entrance".to_string(),
-            quest_log: vec!["Find the Water Chip".to_string()],
+            quest_log: Vec::new(),
+            quests: QuestLog::with_defaults(),
             worldbook,
             day: 1,
+            time: TimeOfDay::default(),
+            loot: Vec::new(),
+            rng: GameRng::from_entropy(),
+        }
+    }
+
+    /// Creates a new game state whose random outcomes all derive from `seed`
+    pub fn with_seed(character: Character, seed: u64) -> Self {
+        GameState {
+            rng: GameRng::seeded(seed),
+            ..Self::new(character)
+        }
+    }
+
+    /// Current moment on the game calendar, as an RFC 3339 timestamp
+    pub fn timestamp(&self) -> String {
+        clock::timestamp(self.day, self.time)
+    }
+
+    /// Let time pass: moves the clock and runs status effects (chems, hunger
+    /// and thirst) for the same span. Returns anything the player should be
+    /// told.
+    pub fn pass_time(&mut self, minutes: u32) -> Vec<String> {
+        let character = &mut self.character;
+        let mut messages =
+            character
+                .status
+                .tick_minutes(minutes, &mut character.special, &mut character.skills);
+        messages.extend(self.advance_clock(minutes));
+        messages
+    }
+
+    /// Move the clock without touching status effects (combat ticks those
+    /// itself, round by round)
+    pub fn advance_clock(&mut self, minutes: u32) -> Vec<String> {
+        let period = self.time.period();
+        let days = self.time.advance(minutes);
+        self.day += days;
+        self.worldbook.set_current_time(self.timestamp());
+
+        let mut messages = Vec::new();
+        if days > 0 {
+            messages.push(format!("Day {} begins.", self.day));
+        }
+        if self.time.period() != period {
+            messages.push(self.time.period().arrival_message().to_string());
+        }
+        messages
+    }
+
+    /// Rest for a number of hours, healing [`Character::healing_rate`] HP per
+    /// hour. Not possible during combat.
+    pub fn rest(&mut self, hours: u32) -> Result<Vec<String>, String> {
+        if self.combat.active {
+            return Err("You can't rest while enemies are nearby!".to_string());
+        }
+        if hours == 0 || hours > MAX_REST_HOURS {
+            return Err(format!(
+                "You can rest between 1 and {} hours",
+                MAX_REST_HOURS
+            ));
+        }
+
+        let before = self.character.current_hp;
+        self.character
+            .heal(self.character.healing_rate() * hours as i32);
+        let healed = self.character.current_hp - before;
+
+        let hours_text = if hours == 1 { "hour" } else { "hours" };
+        let mut messages = vec![format!(
+            "You rest for {} {} and recover {} HP.",
+            hours, hours_text, healed
+        )];
+        messages.extend(self.pass_time(hours * 60));
+        Ok(messages)
+    }
+
+    /// Report something that happened to the quest journal, completing
+    /// objectives and paying out rewards. Returns messages for the player.
+    pub fn quest_event(&mut self, event: QuestEvent) -> Vec<String> {
+        let updates = self.quests.progress(Some(&event), &self.character);
+        self.report_quest_progress(updates)
+    }
+
+    /// Recheck active quests against the current inventory (after a stage
+    /// was added, for example)
+    pub fn refresh_quests(&mut self) -> Vec<String> {
+        let updates = self.quests.progress(None, &self.character);
+        self.report_quest_progress(updates)
+    }
+
+    fn report_quest_progress(&mut self, updates: Vec<QuestProgress>) -> Vec<String> {
+        let mut messages = Vec::new();
+        for update in updates {
+            messages.push(update.to_string());
+            if let QuestProgress::Completed { id, .. } = update {
+                messages.extend(self.grant_quest_rewards(&id));
+            }
+        }
+        messages
+    }
+
+    /// Hand over a completed quest's rewards, describing what was received
+    pub fn grant_quest_rewards(&mut self, quest: &str) -> Option<String> {
+        let rewards = self.quests.find(quest)?.rewards.clone();
+        if rewards.is_empty() {
+            return None;
+        }
+
+        let mut parts = Vec::new();
+        if rewards.xp > 0 {
+            self.character.add_experience(rewards.xp);
+            parts.push(format!("+{} XP", rewards.xp));
+        }
+        if rewards.caps > 0 {
+            self.character.caps += rewards.caps;
+            parts.push(format!("+{} caps", rewards.caps));
+        }
+        for reward in &rewards.items {
+            if let Some(mut item) = find_item(&reward.id) {
+                item.quantity = reward.qty;
+                parts.push(format!("{} x{}", item.name, reward.qty));
+                self.character.add_item(item);
+            }
+        }
+        Some(format!("Rewards: {}", parts.join(", ")))
+    }
+
+    /// Open trade with a merchant NPC, stocking or restocking them as
+    /// needed. Returns the merchant's worldbook ID.
+    pub fn start_trade(&mut self, npc: &str) -> Result<SmartString, String> {
+        let worldbook = &mut self.worldbook;
+        let id = worldbook
+            .find_npc_id(npc)
+            .ok_or_else(|| format!("You don't know anyone called '{}'", npc.trim()))?;
+        let merchant = &worldbook.npcs[&id];
+
+        if !merchant.alive {
+            return Err(format!("{} is dead", merchant.name));
+        }
+        if !merchant.is_merchant() {
+            return Err(format!("{} has nothing to sell", merchant.name));
+        }
+        if merchant.disposition < barter::MIN_TRADE_DISPOSITION {
+            return Err(format!("{} refuses to trade with you", merchant.name));
+        }
+        if let (Some(at), Some(here)) = (&merchant.current_location, &worldbook.current_location) {
+            if at != here {
+                return Err(format!("{} isn't here", merchant.name));
+            }
+        }
+
+        let day = self.day;
+        let level = self.character.level;
+        let rng = &mut self.rng;
+        let stock = worldbook
+            .merchants
+            .entry(id.clone())
+            .or_insert_with(|| Merchant::stocked(day, level, rng));
+        if stock.needs_restock(day) {
+            stock.restock(day, level, rng);
+        }
+        Ok(id)
+    }
+
+    /// Prices between the player and a merchant
+    pub fn trade_prices(&self, merchant: &str) -> Prices {
+        let disposition = self
+            .worldbook
+            .get_npc(merchant)
+            .map_or(0, |npc| npc.disposition);
+        Prices::for_character(&self.character, disposition)
+    }
+
+    /// Buy from a merchant opened with [`GameState::start_trade`]
+    pub fn buy(&mut self, merchant: &str, index: usize, qty: u32) -> Result<Vec<String>, String> {
+        let prices = self.trade_prices(merchant);
+        let stock = self
+            .worldbook
+            .merchants
+            .get_mut(merchant)
+            .ok_or_else(|| "You aren't trading with anyone".to_string())?;
+        let item_id = stock.stock.get(index).map(|item| item.id.clone());
+
+        let mut messages = vec![barter::buy(&mut self.character, stock, prices, index, qty)?];
+        if let Some(id) = item_id {
+            messages.extend(self.quest_event(QuestEvent::ItemObtained(id)));
+        }
+        Ok(messages)
+    }
+
+    /// Sell to a merchant opened with [`GameState::start_trade`]
+    pub fn sell(&mut self, merchant: &str, index: usize, qty: u32) -> Result<String, String> {
+        let prices = self.trade_prices(merchant);
+        let stock = self
+            .worldbook
+            .merchants
+            .get_mut(merchant)
+            .ok_or_else(|| "You aren't trading with anyone".to_string())?;
+        barter::sell(&mut self.character, stock, prices, index, qty)
+    }
+
+    /// Search the bodies from the last fight won. Caps go straight into the
+    /// player's pocket; items wait in [`GameState::loot`] to be taken.
+    /// Anything left from an earlier fight is abandoned.
+    pub fn loot_fallen(&mut self) -> Vec<String> {
+        let fallen = self.combat.take_fallen();
+        if fallen.is_empty() {
+            return Vec::new();
+        }
+
+        let found = loot::roll_loot(&fallen, self.character.special.luck, &mut self.rng);
+        self.character.caps += found.caps;
+        self.loot = found.items;
+
+        let mut messages = Vec::new();
+        if found.caps > 0 {
+            messages.push(format!("You find {} caps on the bodies.", found.caps));
+        }
+        if self.loot.is_empty() {
+            messages.push("There's nothing else worth taking.".to_string());
+        } else {
+            let items: Vec<String> = self
+                .loot
+                .iter()
+                .map(|item| format!("{} x{}", item.name, item.quantity))
+                .collect();
+            messages.push(format!("The bodies carry: {}", items.join(", ")));
+        }
+        messages
+    }
+
+    /// Take the `index`-th item from the bodies into the inventory, unless
+    /// it would take the player past their maximum carry weight
+    pub fn take_loot(&mut self, index: usize) -> Result<Vec<String>, String> {
+        let Some(item) = self.loot.get(index) else {
+            return Err("There's nothing there to take".to_string());
+        };
+        let weight = item.weight * item.quantity as f32;
+        if !self.character.can_carry(weight) {
+            return Err(format!(
+                "{} is too heavy to carry ({:.1} lbs; carrying {:.0}/{:.0} lbs)",
+                item.name,
+                weight,
+                self.character.carry_weight(),
+                self.character.max_carry_weight()
+            ));
+        }
+        let item = self.loot.remove(index);
+        let mut messages = vec![format!("Took {} x{}", item.name, item.quantity)];
+        let id = item.id.clone();
+        self.character.add_item(item);
+        messages.extend(self.quest_event(QuestEvent::ItemObtained(id)));
+        Ok(messages)
+    }
+
+    /// Take everything left on the bodies that the player can carry
+    pub fn take_all_loot(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut index = 0;
+        while index < self.loot.len() {
+            match self.take_loot(index) {
+                Ok(taken) => messages.extend(taken),
+                Err(reason) => {
+                    messages.push(reason);
+                    index += 1;
+                }
+            }
+        }
+        messages
+    }
+
+    /// Drop `qty` (or all) of the `index`-th inventory stack, leaving it at
+    /// the current location to be picked up later. Dropping the last of an
+    /// equipped item unequips it.
+    pub fn drop_item(&mut self, index: usize, qty: Option<u32>) -> Result<String, String> {
+        let location_id = self
+            .worldbook
+            .current_location
+            .clone()
+            .filter(|id| self.worldbook.locations.contains_key(id))
+            .ok_or_else(|| "There's nowhere to leave it here".to_string())?;
+        let owned = self
+            .character
+            .inventory
+            .get(index)
+            .ok_or_else(|| "You don't have that".to_string())?;
+        let qty = qty.unwrap_or(owned.quantity);
+        if qty == 0 || qty > owned.quantity {
+            return Err(format!("You only have {} {}", owned.quantity, owned.name));
+        }
+        let key = owned.key();
+        let item = self
+            .character
+            .inventory
+            .split(index, qty)
+            .ok_or_else(|| "You don't have that".to_string())?;
+
+        let character = &mut self.character;
+        if character.inventory.position_by_key(&key).is_none() {
+            if character.equipped_weapon.as_ref() == Some(&key) {
+                character.equipped_weapon = None;
+            }
+            if character.equipped_armor.as_ref() == Some(&key) {
+                character.equipped_armor = None;
+            }
+        }
+
+        let message = format!("Dropped {} x{}", item.name, qty);
+        if let Some(location) = self.worldbook.locations.get_mut(&location_id) {
+            location.leave_item(item);
+        }
+        Ok(message)
+    }
+
+    /// Pick `qty` (or all) of an item dropped at the current location back up
+    pub fn pick_up(&mut self, query: &str, qty: Option<u32>) -> Result<Vec<String>, String> {
+        let location = self
+            .worldbook
+            .current_location
+            .as_ref()
+            .and_then(|id| self.worldbook.locations.get_mut(id))
+            .ok_or_else(|| "There's nothing here to pick up".to_string())?;
+        let item = location
+            .take_item(query, qty)
+            .ok_or_else(|| format!("There's no '{}' here", query.trim()))?;
+
+        let weight = item.weight * item.quantity as f32;
+        if !self.character.can_carry(weight) {
+            let message = format!(
+                "{} is too heavy to carry ({:.1} lbs; carrying {:.0}/{:.0} lbs)",
+                item.name,
+                weight,
+                self.character.carry_weight(),
+                self.character.max_carry_weight()
+            );
+            location.leave_item(item);
+            return Err(message);
+        }
+
+        let mut messages = vec![format!("Picked up {} x{}", item.name, item.quantity)];
+        let id = item.id.clone();
+        self.character.add_item(item);
+        messages.extend(self.quest_event(QuestEvent::ItemObtained(id)));
+        Ok(messages)
+    }
+
+    /// Item stacks left at the current location
+    pub fn items_here(&self) -> Vec<Item> {
+        self.worldbook
+            .current_location
+            .as_deref()
+            .and_then(|id| self.worldbook.get_location(id))
+            .map(|location| location.dropped_items())
+            .unwrap_or_default()
+    }
+
+    /// The next batch of old conversation turns to fold into the story
+    /// summary, if one is ready
+    pub fn summary_job(&self) -> Option<SummaryJob> {
+        self.story_memory.next_job(&self.conversation)
+    }
+
+    /// Store a summary written for `job`, forgetting the turns it covers.
+    /// Returns `false` if the summary couldn't be used.
+    pub fn apply_summary(&mut self, job: &SummaryJob, response: &str) -> bool {
+        if !self.story_memory.apply(job, response) {
+            return false;
+        }
+        self.conversation.forget_evicted_through(job.last_turn());
+        true
+    }
+
+    /// Whether the current location has a workbench
+    pub fn at_workbench(&self) -> bool {
+        self.worldbook
+            .current_location
+            .as_deref()
+            .and_then(|id| self.worldbook.get_location(id))
+            .is_some_and(|location| location.has_workbench())
+    }
+
+    /// Craft a recipe (by ID or the name of what it makes), using up its
+    /// components. Takes the recipe's time.
+    pub fn craft(&mut self, query: &str) -> Result<Vec<String>, String> {
+        if self.combat.active {
+            return Err("You can't craft anything in the middle of a fight!".to_string());
+        }
+        let recipe = recipes()
+            .find(query)
+            .ok_or_else(|| format!("You don't know how to make '{}'", query.trim()))?;
+        let missing = recipe.missing(&self.character, self.at_workbench());
+        if !missing.is_empty() {
+            return Err(format!(
+                "You can't make {} yet. Needs: {}",
+                recipe.name(),
+                missing.join(", ")
+            ));
+        }
+        let item = recipe
+            .output_item()
+            .ok_or_else(|| format!("Unknown item '{}'", recipe.output))?;
+
+        for component in &recipe.components {
+            self.character
+                .remove_item_quantity(&component.id, component.qty);
+        }
+        let mut messages = vec![format!(
+            "Crafted {} x{} from {}",
+            item.name,
+            item.quantity,
+            recipe.components_text()
+        )];
+        let id = item.id.clone();
+        self.character.add_item(item);
+        messages.extend(self.quest_event(QuestEvent::ItemObtained(id)));
+        messages.extend(self.pass_time(recipe.minutes));
+        Ok(messages)
+    }
+
+    /// Repair a weapon or armor in the inventory. A spare of the same item
+    /// (another one, not equipped) is broken down for parts when there is
+    /// one; otherwise it takes a Repair check. Either way the work takes
+    /// [`condition::REPAIR_MINUTES`].
+    pub fn repair(&mut self, query: &str) -> Result<Vec<String>, String> {
+        if self.combat.active {
+            return Err("You can't repair anything in the middle of a fight!".to_string());
+        }
+        let index = self
+            .character
+            .inventory
+            .find(query)
+            .ok_or_else(|| format!("You don't have any '{}'", query.trim()))?;
+        let item = &self.character.inventory[index];
+        let current = item
+            .condition()
+            .ok_or_else(|| format!("{} doesn't need repairs", item.name))?;
+        if current >= condition::MAX_CONDITION {
+            return Err(format!("{} is already in perfect condition", item.name));
+        }
+
+        // Repair one item, not a whole stack saved before wear was kept apart
+        let index = self.character.inventory.single_out(index);
+        let item = &self.character.inventory[index];
+        let name = item.name.clone();
+        let key = item.key();
+        let spare = self
+            .character
+            .inventory
+            .iter()
+            .enumerate()
+            .position(|(i, spare)| {
+                i != index && spare.id == item.id && !self.character.is_equipped(spare)
+            });
+        let repair_skill = self.character.skills.repair;
+        let mut messages = Vec::new();
+        let amount = if let Some(spare) = spare {
+            self.character.inventory.split(spare, 1);
+            messages.push(format!("You strip a spare {} for parts.", name));
+            condition::parts_repair(repair_skill)
+        } else {
+            let roll = perform_roll_at(
+                &self.character,
+                "Repair",
+                condition::REPAIR_DC,
+                self.time.period(),
+                &mut self.rng,
+            );
+            messages.push(roll.format());
+            if roll.success {
+                condition::skill_repair(repair_skill, roll.critical)
+            } else {
+                0
+            }
+        };
+
+        if amount == 0 {
+            messages.push(format!("You can't get {} working any better.", name));
+        } else {
+            // Taking the spare may have moved the item
+            let index = self
+                .character
+                .inventory
+                .position_by_key(&key)
+                .expect("repaired item still carried");
+            let item = &mut self.character.inventory[index];
+            item.repair(amount);
+            let condition = item.condition().unwrap_or_default();
+            messages.push(format!(
+                "Repaired {} to {}% ({})",
+                name,
+                condition,
+                condition::label(condition)
+            ));
+        }
+        messages.extend(self.pass_time(condition::REPAIR_MINUTES));
+        Ok(messages)
+    }
+
+    /// Type of the current location ("vault", "ruin", ...), or the default
+    /// encounter table's type when the location is unknown
+    pub fn location_type(&self) -> &str {
+        self.worldbook
+            .current_location
+            .as_deref()
+            .and_then(|id| self.worldbook.get_location(id))
+            .map(|location| location.location_type.as_str())
+            .filter(|kind| !kind.is_empty())
+            .unwrap_or(bestiary::DEFAULT_LOCATION_TYPE)
+    }
+
+    /// Roll a random encounter for where and when the player is
+    pub fn random_encounter(&mut self) -> Option<Encounter> {
+        let location_type = self.location_type().to_string();
+        let night = self.time.period().is_night();
+        bestiary().roll_encounter(&location_type, self.character.level, night, &mut self.rng)
+    }
+
+    /// Move quest names from saves made before the journal existed into
+    /// untracked quests
+    pub fn migrate_quest_log(&mut self) {
+        for name in std::mem::take(&mut self.quest_log) {
+            if !name.trim().is_empty() && self.quests.find(&name).is_none() {
+                self.quests.add(quests::Quest::new(&name));
+            }
+        }
+    }
+
+    /// Give weapons from saves made before magazines existed the magazine
+    /// size of their catalog entry, so they use ammo (and jam) again. They
+    /// come back unloaded.
+    pub fn migrate_magazines(&mut self) {
+        fn migrate(item: &mut Item) {
+            let ItemType::Weapon(ref mut stats) = item.item_type else {
+                return;
+            };
+            if stats.magazine_size > 0 || stats.ammo_type.is_none() {
+                return;
+            }
+            if let Some(entry) = find_item(&item.id) {
+                if let Some(catalog) = entry.as_weapon() {
+                    stats.magazine_size = catalog.magazine_size;
+                }
+            }
+        }
+
+        self.character.inventory.iter_mut().for_each(migrate);
+        self.loot.iter_mut().for_each(migrate);
+        for merchant in self.worldbook.merchants.values_mut() {
+            merchant.stock.iter_mut().for_each(migrate);
+        }
+        for location in self.worldbook.locations.values_mut() {
+            let mut pile = Inventory::from(location.dropped_items());
+            if !pile.is_empty() {
+                pile.iter_mut().for_each(migrate);
+                location.store_dropped_items(&pile);
+            }
         }
     }
 
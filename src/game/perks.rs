@@ -0,0 +1,230 @@
+//! # Perks Module
+//!
+//! Perks are special abilities picked on level up. A new perk pick is earned
+//! every [`PERK_INTERVAL`] levels and stored by ID in `Character::perks`.
+//!
+//! Every perk has a mechanical effect that the combat and roll code queries
+//! through [`Character`] helpers such as `damage_bonus`, `critical_threshold`
+//! and `roll_bonus`.
+
+use super::character::{Character, Special};
+
+/// A perk pick is earned every this many levels
+pub const PERK_INTERVAL: u32 = 3;
+
+/// All perks a character can pick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Perk {
+    /// Incoming damage is reduced by a flat amount
+    Toughness,
+    /// Extra HP every level (applied retroactively when picked)
+    Lifegiver,
+    /// Extra action points each turn
+    ActionBoy,
+    /// Extra damage with guns and energy weapons
+    BonusRangedDamage,
+    /// Extra damage with melee weapons and fists
+    BonusHthDamage,
+    /// Critical hits on a natural 19 or 20
+    MoreCriticals,
+    /// Critical hits deal triple damage instead of double
+    BetterCriticals,
+    /// Extra skill points every level
+    Educated,
+    /// Bonus to Speech and Barter checks
+    Negotiator,
+    /// Bonus to Sneak, Lockpick and Steal checks
+    Thief,
+}
+
+impl Perk {
+    /// Every perk, in the order shown in the level-up screen
+    pub const ALL: [Perk; 10] = [
+        Perk::Toughness,
+        Perk::Lifegiver,
+        Perk::ActionBoy,
+        Perk::BonusRangedDamage,
+        Perk::BonusHthDamage,
+        Perk::MoreCriticals,
+        Perk::BetterCriticals,
+        Perk::Educated,
+        Perk::Negotiator,
+        Perk::Thief,
+    ];
+
+    /// Flat damage reduction from Toughness
+    pub const TOUGHNESS_REDUCTION: i32 = 2;
+    /// Extra max HP per level from Lifegiver
+    pub const LIFEGIVER_HP: i32 = 4;
+    /// Extra max AP from Action Boy
+    pub const ACTION_BOY_AP: i32 = 2;
+    /// Extra damage from the bonus damage perks
+    pub const BONUS_DAMAGE: i32 = 3;
+    /// Extra skill points per level from Educated
+    pub const EDUCATED_POINTS: u32 = 2;
+    /// Bonus to skill checks from Negotiator and Thief
+    pub const SKILL_CHECK_BONUS: i32 = 10;
+
+    /// Stable ID stored in saves
+    pub fn id(&self) -> &'static str {
+        match self {
+            Perk::Toughness => "toughness",
+            Perk::Lifegiver => "lifegiver",
+            Perk::ActionBoy => "action_boy",
+            Perk::BonusRangedDamage => "bonus_ranged_damage",
+            Perk::BonusHthDamage => "bonus_hth_damage",
+            Perk::MoreCriticals => "more_criticals",
+            Perk::BetterCriticals => "better_criticals",
+            Perk::Educated => "educated",
+            Perk::Negotiator => "negotiator",
+            Perk::Thief => "thief",
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perk::Toughness => "Toughness",
+            Perk::Lifegiver => "Lifegiver",
+            Perk::ActionBoy => "Action Boy",
+            Perk::BonusRangedDamage => "Bonus Ranged Damage",
+            Perk::BonusHthDamage => "Bonus HtH Damage",
+            Perk::MoreCriticals => "More Criticals",
+            Perk::BetterCriticals => "Better Criticals",
+            Perk::Educated => "Educated",
+            Perk::Negotiator => "Negotiator",
+            Perk::Thief => "Thief",
+        }
+    }
+
+    /// Short description of the mechanical effect
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::Toughness => "Reduce all incoming damage by 2.",
+            Perk::Lifegiver => "+4 max HP for every level, including past ones.",
+            Perk::ActionBoy => "+2 max AP in combat.",
+            Perk::BonusRangedDamage => "+3 damage with guns and energy weapons.",
+            Perk::BonusHthDamage => "+3 damage with melee weapons and unarmed attacks.",
+            Perk::MoreCriticals => "Score critical hits on a roll of 19 or 20.",
+            Perk::BetterCriticals => "Critical hits deal triple damage.",
+            Perk::Educated => "+2 skill points every level.",
+            Perk::Negotiator => "+10 to Speech and Barter checks.",
+            Perk::Thief => "+10 to Sneak, Lockpick and Steal checks.",
+        }
+    }
+
+    /// Minimum character level to pick this perk
+    pub fn min_level(&self) -> u32 {
+        match self {
+            Perk::BetterCriticals => 9,
+            Perk::Lifegiver | Perk::ActionBoy | Perk::MoreCriticals | Perk::Educated => 6,
+            _ => PERK_INTERVAL,
+        }
+    }
+
+    /// SPECIAL requirement as (stat name, minimum value)
+    pub fn special_requirement(&self) -> Option<(&'static str, u8)> {
+        match self {
+            Perk::Toughness => Some(("Endurance", 4)),
+            Perk::ActionBoy => Some(("Agility", 5)),
+            Perk::BonusRangedDamage => Some(("Perception", 6)),
+            Perk::BonusHthDamage => Some(("Strength", 6)),
+            Perk::MoreCriticals | Perk::BetterCriticals => Some(("Luck", 6)),
+            Perk::Educated => Some(("Intelligence", 6)),
+            Perk::Negotiator => Some(("Charisma", 6)),
+            Perk::Thief => Some(("Agility", 6)),
+            Perk::Lifegiver => None,
+        }
+    }
+
+    /// Human-readable requirements, e.g. "Level 6, Luck 6"
+    pub fn requirements_text(&self) -> String {
+        match self.special_requirement() {
+            Some((stat, value)) => format!("Level {}, {} {}", self.min_level(), stat, value),
+            None => format!("Level {}", self.min_level()),
+        }
+    }
+
+    /// Whether the character meets the level and SPECIAL requirements.
+    /// Chem buffs don't count towards the SPECIAL requirement.
+    pub fn requirements_met(&self, character: &Character) -> bool {
+        if character.level < self.min_level() {
+            return false;
+        }
+        match self.special_requirement() {
+            Some((stat, value)) => special_value(&character.base_special(), stat) >= value,
+            None => true,
+        }
+    }
+
+    /// Look up a perk by ID or display name (case-insensitive).
+    /// Display names normalize to IDs, e.g. "Action Boy" -> "action_boy".
+    pub fn from_name(name: &str) -> Option<Perk> {
+        let normalized = name.trim().to_lowercase().replace([' ', '-'], "_");
+        Perk::ALL
+            .iter()
+            .copied()
+            .find(|perk| perk.id() == normalized)
+    }
+}
+
+/// Read a SPECIAL stat by its display name
+fn special_value(special: &Special, stat: &str) -> u8 {
+    match stat {
+        "Strength" => special.strength,
+        "Perception" => special.perception,
+        "Endurance" => special.endurance,
+        "Charisma" => special.charisma,
+        "Intelligence" => special.intelligence,
+        "Agility" => special.agility,
+        "Luck" => special.luck,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perk_ids_round_trip() {
+        for perk in Perk::ALL {
+            assert_eq!(Perk::from_name(perk.id()), Some(perk));
+            assert_eq!(Perk::from_name(perk.name()), Some(perk));
+        }
+        assert_eq!(
+            Perk::from_name("Bonus HtH Damage"),
+            Some(Perk::BonusHthDamage)
+        );
+        assert_eq!(Perk::from_name("sniper"), None);
+    }
+
+    #[test]
+    fn test_requirements() {
+        let mut special = Special::new();
+        special.luck = 6;
+        let mut character = Character::new("Test", special);
+
+        assert!(!Perk::MoreCriticals.requirements_met(&character));
+        character.level = 6;
+        assert!(Perk::MoreCriticals.requirements_met(&character));
+        assert!(!Perk::BetterCriticals.requirements_met(&character));
+        // Strength 1 is too low for Bonus HtH Damage at any level
+        character.level = 20;
+        assert!(!Perk::BonusHthDamage.requirements_met(&character));
+        assert_eq!(Perk::MoreCriticals.requirements_text(), "Level 6, Luck 6");
+    }
+
+    #[test]
+    fn test_chem_buffs_dont_unlock_perks() {
+        let mut special = Special::new();
+        special.intelligence = 5;
+        let mut character = Character::new("Test", special);
+        character.level = 6;
+        character.add_item(crate::game::items::find_item("mentats").unwrap());
+        character.use_consumable("mentats").unwrap();
+
+        assert_eq!(character.special.intelligence, 7);
+        assert!(!Perk::Educated.requirements_met(&character));
+    }
+}
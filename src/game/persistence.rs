@@ -68,9 +68,14 @@ pub fn load_from_file(filename: &str) -> Result<GameState> {
 
     let json = fs::read_to_string(canonical_save)?;
     let mut game_state: GameState = serde_json::from_str(&json)?;
+    game_state.worldbook.populate_caches();
 
     // Migrate legacy story context to new conversation system if needed
     game_state.migrate_story_to_conversation();
+    // Move quests from the old flat list into the journal
+    game_state.migrate_quest_log();
+    // Weapons saved before magazines existed have none
+    game_state.migrate_magazines();
 
     Ok(game_state)
 }
@@ -0,0 +1,562 @@
+//! # Quests Module
+//!
+//! Structured quests tracked in the player's journal.
+//!
+//! ## Structure
+//!
+//! A [`Quest`] is a series of [`QuestStage`]s, each with one or more
+//! [`Objective`]s. Quests remember who gave them, which worldbook locations
+//! they involve, and the [`QuestRewards`] paid out on completion.
+//!
+//! ## Progress
+//!
+//! Objectives with a [`Goal`] complete on their own when the engine reports a
+//! matching [`QuestEvent`]:
+//! - Item obtained: the item shows up in the inventory (DM rewards, loot)
+//! - NPC killed: an enemy with that name dies in combat
+//! - Location visited: the player travels there
+//!
+//! When every objective in the current stage is done the quest moves on to the
+//! next stage, and finishing the last stage completes the quest. Objectives
+//! without a goal, and quests without stages ("untracked" quests), are moved
+//! along by the DM with the `update_quest` action.
+//!
+//! ## Old Saves
+//!
+//! Older saves stored quests as a plain list of names. Those are migrated into
+//! untracked quests when the save is loaded.
+
+use super::character::Character;
+use super::worldbook::Worldbook;
+use serde::{Deserialize, Serialize};
+use smartstring::alias::String as SmartString;
+use std::fmt;
+
+/// Quest status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestStatus {
+    #[default]
+    Active,
+    Completed,
+    Failed,
+}
+
+impl QuestStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuestStatus::Active => "Active",
+            QuestStatus::Completed => "Completed",
+            QuestStatus::Failed => "Failed",
+        }
+    }
+}
+
+/// Condition that completes an objective without the DM's help
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Goal {
+    /// Carry at least `quantity` of an item
+    ObtainItem { item: SmartString, quantity: u32 },
+    /// Kill an NPC (worldbook ID)
+    KillNpc { npc: SmartString },
+    /// Visit a location (worldbook ID)
+    VisitLocation { location: SmartString },
+}
+
+impl Goal {
+    /// Whether the goal is met after `event` (or right now, for `None`)
+    fn is_met(&self, event: Option<&QuestEvent>, character: &Character) -> bool {
+        match (self, event) {
+            (Goal::ObtainItem { item, quantity }, _) => character.item_count(item) >= *quantity,
+            (Goal::KillNpc { npc }, Some(QuestEvent::NpcKilled(name))) => {
+                Worldbook::generate_id(name) == *npc
+            }
+            (Goal::VisitLocation { location }, Some(QuestEvent::LocationVisited(id))) => {
+                Worldbook::generate_id(id) == *location
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Something that happened in the game that may move quests along
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuestEvent {
+    /// An item (by ID) was added to the inventory
+    ItemObtained(SmartString),
+    /// An enemy or NPC (by name) was killed
+    NpcKilled(SmartString),
+    /// The player arrived at a location (by name or ID)
+    LocationVisited(SmartString),
+}
+
+/// A single thing the player has to do
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Objective {
+    pub description: String,
+    /// Completes the objective automatically; `None` means the DM decides
+    #[serde(default)]
+    pub goal: Option<Goal>,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl Objective {
+    pub fn new(description: impl Into<String>, goal: Option<Goal>) -> Self {
+        Objective {
+            description: description.into(),
+            goal,
+            done: false,
+        }
+    }
+}
+
+/// A step of a quest; all of its objectives must be done to move on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestStage {
+    pub description: String,
+    pub objectives: Vec<Objective>,
+}
+
+impl QuestStage {
+    pub fn is_done(&self) -> bool {
+        self.objectives.iter().all(|objective| objective.done)
+    }
+}
+
+/// An item handed over when a quest is completed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardItem {
+    pub id: SmartString,
+    #[serde(default = "default_quantity")]
+    pub qty: u32,
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+/// What the player gets for completing a quest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestRewards {
+    #[serde(default)]
+    pub xp: u32,
+    #[serde(default)]
+    pub caps: u32,
+    #[serde(default)]
+    pub items: Vec<RewardItem>,
+}
+
+impl QuestRewards {
+    pub fn is_empty(&self) -> bool {
+        self.xp == 0 && self.caps == 0 && self.items.is_empty()
+    }
+
+    /// Add another set of rewards to this one
+    pub fn merge(&mut self, other: &QuestRewards) {
+        self.xp += other.xp;
+        self.caps += other.caps;
+        self.items.extend(other.items.iter().cloned());
+    }
+}
+
+/// A quest in the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quest {
+    pub id: SmartString,
+    pub name: SmartString,
+    #[serde(default)]
+    pub description: String,
+    /// Name or worldbook ID of the NPC who gave the quest
+    #[serde(default)]
+    pub giver: Option<SmartString>,
+    #[serde(default)]
+    pub status: QuestStatus,
+    #[serde(default)]
+    pub stages: Vec<QuestStage>,
+    /// Index of the current stage
+    #[serde(default)]
+    pub stage: usize,
+    #[serde(default)]
+    pub rewards: QuestRewards,
+    /// Worldbook IDs of locations involved in the quest
+    #[serde(default)]
+    pub locations: Vec<SmartString>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+impl Quest {
+    /// A new quest without stages; the DM tracks its progress
+    pub fn new(name: &str) -> Self {
+        let name = name.trim();
+        Quest {
+            id: Worldbook::generate_id(name),
+            name: name.into(),
+            description: String::new(),
+            giver: None,
+            status: QuestStatus::Active,
+            stages: Vec::new(),
+            stage: 0,
+            rewards: QuestRewards::default(),
+            locations: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Whether the engine tracks objectives for this quest
+    pub fn is_tracked(&self) -> bool {
+        !self.stages.is_empty()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == QuestStatus::Active
+    }
+
+    /// The stage the player is working on, if the quest is active
+    pub fn current_stage(&self) -> Option<&QuestStage> {
+        if self.is_active() {
+            self.stages.get(self.stage)
+        } else {
+            None
+        }
+    }
+
+    /// Append a stage, linking any locations it asks the player to visit
+    pub fn add_stage(&mut self, stage: QuestStage) {
+        for objective in &stage.objectives {
+            if let Some(Goal::VisitLocation { location }) = &objective.goal {
+                self.link_location(location);
+            }
+        }
+        self.stages.push(stage);
+    }
+
+    /// Link a worldbook location to the quest
+    pub fn link_location(&mut self, location: &str) {
+        let id = Worldbook::generate_id(location);
+        if !self.locations.contains(&id) {
+            self.locations.push(id);
+        }
+    }
+
+    /// Check the current stage's goals and advance through every stage that
+    /// is now finished
+    fn progress(
+        &mut self,
+        event: Option<&QuestEvent>,
+        character: &Character,
+    ) -> Vec<QuestProgress> {
+        let mut updates = Vec::new();
+        // The event only counts for the stage that was current when it happened
+        let mut event = event;
+
+        while self.is_active() {
+            let Some(stage) = self.stages.get_mut(self.stage) else {
+                break;
+            };
+            for objective in stage.objectives.iter_mut().filter(|o| !o.done) {
+                if objective
+                    .goal
+                    .as_ref()
+                    .is_some_and(|goal| goal.is_met(event, character))
+                {
+                    objective.done = true;
+                    updates.push(QuestProgress::ObjectiveDone {
+                        quest: self.name.clone(),
+                        objective: objective.description.clone(),
+                    });
+                }
+            }
+            if !stage.is_done() {
+                break;
+            }
+
+            self.stage += 1;
+            event = None;
+            match self.stages.get(self.stage) {
+                Some(next) => updates.push(QuestProgress::StageStarted {
+                    quest: self.name.clone(),
+                    stage: next.description.clone(),
+                }),
+                None => {
+                    self.status = QuestStatus::Completed;
+                    updates.push(QuestProgress::Completed {
+                        id: self.id.clone(),
+                        quest: self.name.clone(),
+                    });
+                }
+            }
+        }
+
+        updates
+    }
+
+    /// One-line summary for the DM prompt
+    pub fn prompt_line(&self) -> String {
+        let Some(stage) = self.current_stage() else {
+            return self.name.to_string();
+        };
+        let open: Vec<&str> = stage
+            .objectives
+            .iter()
+            .filter(|o| !o.done)
+            .map(|o| o.description.as_str())
+            .collect();
+        format!("{}: {} ({})", self.name, stage.description, open.join("; "))
+    }
+}
+
+/// A change to a quest worth telling the player about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuestProgress {
+    ObjectiveDone {
+        quest: SmartString,
+        objective: String,
+    },
+    StageStarted {
+        quest: SmartString,
+        stage: String,
+    },
+    Completed {
+        id: SmartString,
+        quest: SmartString,
+    },
+}
+
+impl fmt::Display for QuestProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestProgress::ObjectiveDone { quest, objective } => {
+                write!(f, "Objective complete: {} ({})", objective, quest)
+            }
+            QuestProgress::StageStarted { quest, stage } => write!(f, "{}: {}", quest, stage),
+            QuestProgress::Completed { quest, .. } => write!(f, "Quest completed: {}", quest),
+        }
+    }
+}
+
+/// Every quest the player has been given, in the order they were received
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestLog {
+    #[serde(default)]
+    pub quests: Vec<Quest>,
+}
+
+impl QuestLog {
+    #[allow(dead_code)] // Public API for integration tests
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Journal for a new game
+    pub fn with_defaults() -> Self {
+        QuestLog {
+            quests: vec![water_chip_quest()],
+        }
+    }
+
+    pub fn add(&mut self, quest: Quest) {
+        self.quests.push(quest);
+    }
+
+    /// Find a quest by ID or case-insensitive name
+    pub fn find(&self, quest: &str) -> Option<&Quest> {
+        let id = Worldbook::generate_id(quest.trim());
+        self.quests.iter().find(|q| q.id == id)
+    }
+
+    pub fn find_mut(&mut self, quest: &str) -> Option<&mut Quest> {
+        let id = Worldbook::generate_id(quest.trim());
+        self.quests.iter_mut().find(|q| q.id == id)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Quest> {
+        self.quests.iter().filter(|q| q.is_active())
+    }
+
+    /// Quests in journal order: active first, then completed, then failed
+    pub fn journal(&self) -> Vec<&Quest> {
+        let mut quests: Vec<&Quest> = self.quests.iter().collect();
+        quests.sort_by_key(|q| match q.status {
+            QuestStatus::Active => 0,
+            QuestStatus::Completed => 1,
+            QuestStatus::Failed => 2,
+        });
+        quests
+    }
+
+    /// Let every active quest react to an event (`None` rechecks the
+    /// inventory, e.g. after a new stage was added)
+    pub fn progress(
+        &mut self,
+        event: Option<&QuestEvent>,
+        character: &Character,
+    ) -> Vec<QuestProgress> {
+        self.quests
+            .iter_mut()
+            .flat_map(|quest| quest.progress(event, character))
+            .collect()
+    }
+}
+
+/// The quest every new game starts with
+fn water_chip_quest() -> Quest {
+    let mut quest = Quest::new("Find the Water Chip");
+    quest.description = "Vault 13's water purification chip has failed. Without a replacement \
+                         the vault will run dry within months."
+        .to_string();
+    quest.giver = Some("The Overseer".into());
+    quest.add_stage(QuestStage {
+        description: "Search the wasteland for a replacement water chip".to_string(),
+        objectives: vec![Objective::new(
+            "Find a water chip",
+            Some(Goal::ObtainItem {
+                item: "water_chip".into(),
+                quantity: 1,
+            }),
+        )],
+    });
+    quest.add_stage(QuestStage {
+        description: "Bring the water chip home".to_string(),
+        objectives: vec![Objective::new(
+            "Return to Vault 13",
+            Some(Goal::VisitLocation {
+                location: "vault_13".into(),
+            }),
+        )],
+    });
+    quest.rewards = QuestRewards {
+        xp: 1000,
+        caps: 0,
+        items: Vec::new(),
+    };
+    quest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::character::Special;
+    use crate::game::items::find_item;
+
+    fn test_character() -> Character {
+        Character::new("Tester", Special::new())
+    }
+
+    fn bounty_quest() -> Quest {
+        let mut quest = Quest::new("Bounty on Butch");
+        quest.add_stage(QuestStage {
+            description: "Track down Butch".to_string(),
+            objectives: vec![
+                Objective::new(
+                    "Go to Megaton",
+                    Some(Goal::VisitLocation {
+                        location: "megaton".into(),
+                    }),
+                ),
+                Objective::new(
+                    "Kill Butch",
+                    Some(Goal::KillNpc {
+                        npc: "butch".into(),
+                    }),
+                ),
+            ],
+        });
+        quest
+    }
+
+    #[test]
+    fn test_events_complete_objectives() {
+        let character = test_character();
+        let mut log = QuestLog::new();
+        log.add(bounty_quest());
+
+        let updates = log.progress(Some(&QuestEvent::NpcKilled("Radroach".into())), &character);
+        assert!(updates.is_empty());
+
+        let updates = log.progress(Some(&QuestEvent::NpcKilled("Butch".into())), &character);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(
+            updates[0].to_string(),
+            "Objective complete: Kill Butch (Bounty on Butch)"
+        );
+        assert!(log.find("bounty on butch").unwrap().is_active());
+
+        let updates = log.progress(
+            Some(&QuestEvent::LocationVisited("Megaton".into())),
+            &character,
+        );
+        assert!(matches!(
+            updates.last(),
+            Some(QuestProgress::Completed { id, .. }) if id == "bounty_on_butch"
+        ));
+        assert_eq!(
+            log.find("Bounty on Butch").unwrap().status,
+            QuestStatus::Completed
+        );
+        assert_eq!(log.quests[0].locations, vec![SmartString::from("megaton")]);
+    }
+
+    #[test]
+    fn test_stages_advance_and_check_inventory() {
+        let mut character = test_character();
+        let mut log = QuestLog::with_defaults();
+        assert_eq!(
+            log.active().next().unwrap().prompt_line(),
+            "Find the Water Chip: Search the wasteland for a replacement water chip (Find a water chip)"
+        );
+
+        character.add_item(find_item("water_chip").unwrap());
+        let updates = log.progress(
+            Some(&QuestEvent::ItemObtained("water_chip".into())),
+            &character,
+        );
+        assert_eq!(
+            updates[1].to_string(),
+            "Find the Water Chip: Bring the water chip home"
+        );
+
+        // Visiting the vault only counts once the chip is in hand
+        let updates = log.progress(
+            Some(&QuestEvent::LocationVisited("vault_13".into())),
+            &character,
+        );
+        assert_eq!(updates.len(), 2);
+        assert_eq!(log.active().count(), 0);
+    }
+
+    #[test]
+    fn test_untracked_quests_never_progress() {
+        let character = test_character();
+        let mut log = QuestLog::new();
+        log.add(Quest::new("Find the Water Chip"));
+
+        let quest = log.find("find the water chip").unwrap();
+        assert!(!quest.is_tracked());
+        assert_eq!(quest.prompt_line(), "Find the Water Chip");
+
+        let updates = log.progress(
+            Some(&QuestEvent::LocationVisited("vault_13".into())),
+            &character,
+        );
+        assert!(updates.is_empty());
+        assert!(log.find("find the water chip").unwrap().is_active());
+    }
+
+    #[test]
+    fn test_journal_order() {
+        let mut log = QuestLog::new();
+        let mut done = Quest::new("Done");
+        done.status = QuestStatus::Completed;
+        let mut failed = Quest::new("Failed");
+        failed.status = QuestStatus::Failed;
+        log.add(failed);
+        log.add(done);
+        log.add(Quest::new("Open"));
+
+        let names: Vec<&str> = log.journal().iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, vec!["Open", "Done", "Failed"]);
+    }
+}
@@ -0,0 +1,170 @@
+//! # RNG Module
+//!
+//! The game-owned random number generator.
+//!
+//! Every random outcome in the game (dice, attack rolls, skill checks and
+//! encounters) draws from the [`GameRng`] stored in `GameState`. The
+//! generator's full state is serialized with the save, so a loaded game
+//! continues the exact same stream, and a fixed seed replays a session
+//! roll for roll.
+//!
+//! ## Seeds
+//!
+//! Seeds come from `game.seed` in `config.toml` or the `--seed` command line
+//! option, parsed by [`parse_seed`]:
+//! - A number is used as-is (`--seed 42`)
+//! - `daily` uses today's UTC date, so everyone playing today gets the same run
+//! - Any other text is hashed (`--seed "wasteland"`)
+//!
+//! Without a seed the game picks a random one, which is still recorded so the
+//! session can be reproduced later.
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Serializable, seedable random number generator (SplitMix64)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRng {
+    /// Seed the stream started from
+    seed: u64,
+    /// Current position in the stream
+    state: u64,
+}
+
+impl GameRng {
+    /// Start a new stream from a seed
+    pub fn seeded(seed: u64) -> Self {
+        GameRng { seed, state: seed }
+    }
+
+    /// Start a new stream from a random seed
+    pub fn from_entropy() -> Self {
+        Self::seeded(rand::rng().next_u64())
+    }
+
+    /// The seed this stream started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Turn a seed option into a numeric seed.
+///
+/// Numbers are used directly, `daily` maps to today's UTC date and any other
+/// text is hashed (FNV-1a), so the same text always gives the same seed.
+pub fn parse_seed(value: &str) -> u64 {
+    let value = value.trim();
+    if let Ok(seed) = value.parse::<u64>() {
+        return seed;
+    }
+    if value.eq_ignore_ascii_case("daily") {
+        return hash_seed(&chrono::Utc::now().format("%Y-%m-%d").to_string());
+    }
+    hash_seed(value)
+}
+
+/// Pick one option at random, each with a chance proportional to its weight.
+/// Returns `None` when there are no options or every weight is zero.
+pub fn pick_weighted<'a, T>(
+    options: &'a [T],
+    weight: impl Fn(&T) -> u32,
+    rng: &mut impl Rng,
+) -> Option<&'a T> {
+    let total: u32 = options.iter().map(&weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.random_range(0..total);
+    options.iter().find(|option| {
+        let weight = weight(option);
+        if roll < weight {
+            return true;
+        }
+        roll -= weight;
+        false
+    })
+}
+
+fn hash_seed(text: &str) -> u64 {
+    text.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_stream() {
+        let mut a = GameRng::seeded(42);
+        let mut b = GameRng::seeded(42);
+        for _ in 0..100 {
+            assert_eq!(a.random_range(1..=20), b.random_range(1..=20));
+        }
+        assert_ne!(GameRng::seeded(1).next_u64(), GameRng::seeded(2).next_u64());
+    }
+
+    #[test]
+    fn test_serialized_rng_resumes_stream() {
+        let mut rng = GameRng::seeded(7);
+        rng.next_u64();
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut restored: GameRng = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.seed(), 7);
+        assert_eq!(restored.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn test_pick_weighted() {
+        let mut rng = GameRng::seeded(9);
+        let options = [("never", 0), ("rare", 1), ("common", 30)];
+        let picks: Vec<&str> = (0..200)
+            .filter_map(|_| pick_weighted(&options, |o| o.1, &mut rng))
+            .map(|o| o.0)
+            .collect();
+        assert_eq!(picks.len(), 200);
+        assert!(!picks.contains(&"never"));
+        assert!(picks.iter().filter(|&&p| p == "common").count() > 150);
+
+        let empty: [(&str, u32); 0] = [];
+        assert!(pick_weighted(&empty, |o| o.1, &mut rng).is_none());
+        assert!(pick_weighted(&[("none", 0)], |o| o.1, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed("42"), 42);
+        assert_eq!(parse_seed(" wasteland "), parse_seed("wasteland"));
+        assert_ne!(parse_seed("wasteland"), parse_seed("vault"));
+        assert_eq!(parse_seed("daily"), parse_seed("DAILY"));
+    }
+}
@@ -1,4 +1,5 @@
 use super::character::Character;
+use super::clock::DayPeriod;
 use rand::Rng;
 
 /// Represents a skill check result
@@ -285,13 +286,34 @@ pub fn truncate_response_at_skill_check(text: &str) -> Option<String> {
     None
 }
 
-/// Perform a skill or stat check
-pub fn perform_roll(character: &Character, skill_or_stat: &str, dc: i32) -> RollResult {
-    let mut rng = rand::rng();
+/// Perform a skill or stat check in daylight
+#[allow(dead_code)] // Public API for integration tests
+pub fn perform_roll(
+    character: &Character,
+    skill_or_stat: &str,
+    dc: i32,
+    rng: &mut impl Rng,
+) -> RollResult {
+    perform_roll_at(character, skill_or_stat, dc, DayPeriod::Day, rng)
+}
+
+/// Perform a skill or stat check at a time of day; Perception checks are
+/// harder in the dark
+pub fn perform_roll_at(
+    character: &Character,
+    skill_or_stat: &str,
+    dc: i32,
+    period: DayPeriod,
+    rng: &mut impl Rng,
+) -> RollResult {
     let roll = rng.random_range(1..=20);
 
     // Determine modifier based on skill or stat name
     let (skill_name, modifier) = get_modifier(character, skill_or_stat);
+    let mut modifier = modifier + character.roll_bonus(&skill_name);
+    if skill_name == "Perception" {
+        modifier += period.perception_modifier();
+    }
 
     let total = roll + modifier;
     let success = total >= dc || roll == 20;
@@ -389,6 +411,7 @@ fn get_modifier(character: &Character, name: &str) -> (String, i32) {
 mod tests {
     use super::*;
     use crate::game::character::Special;
+    use crate::game::rng::GameRng;
 
     #[test]
     fn test_parse_roll_request() {
@@ -402,6 +425,34 @@ mod tests {
         assert_eq!(result, Some(("Speech".to_string(), 12)));
     }
 
+    #[test]
+    fn test_perform_roll_includes_perk_bonus() {
+        let mut rng = GameRng::seeded(2077);
+        let mut character = Character::new("Test".to_string(), Special::new());
+        let base = perform_roll(&character, "lockpick", 10, &mut rng).modifier;
+
+        character.perks.push("thief".into());
+        let result = perform_roll(&character, "lockpick", 10, &mut rng);
+        assert_eq!(result.modifier, base + 10);
+        assert_eq!(result.total, result.roll + result.modifier);
+
+        // Thief doesn't help with unrelated checks
+        let science = perform_roll(&character, "science", 10, &mut rng);
+        assert_eq!(science.modifier, character.skills.science as i32);
+    }
+
+    #[test]
+    fn test_perception_is_harder_at_night() {
+        let mut rng = GameRng::seeded(2077);
+        let character = Character::new("Test".to_string(), Special::new());
+        let day = perform_roll(&character, "perception", 10, &mut rng).modifier;
+        let night = perform_roll_at(&character, "perception", 10, DayPeriod::Night, &mut rng);
+        assert_eq!(night.modifier, day - 3);
+
+        let lockpick = perform_roll_at(&character, "lockpick", 10, DayPeriod::Night, &mut rng);
+        assert_eq!(lockpick.modifier, character.skills.lockpick as i32);
+    }
+
     #[test]
     fn test_get_modifier() {
         let character = Character::new(
@@ -428,6 +479,7 @@ mod tests {
 
     #[test]
     fn test_perform_roll() {
+        let mut rng = GameRng::seeded(2077);
         let character = Character::new(
             "Test".to_string(),
             Special {
@@ -441,7 +493,7 @@ mod tests {
             },
         );
 
-        let result = perform_roll(&character, "lockpick", 15);
+        let result = perform_roll(&character, "lockpick", 15, &mut rng);
         assert!(result.roll >= 1 && result.roll <= 20);
         assert_eq!(result.dc, 15);
     }
@@ -492,6 +544,7 @@ mod tests {
 
     #[test]
     fn test_critical_success_nat_20() {
+        let mut rng = GameRng::seeded(2077);
         // Critical success should always be true when rolling nat 20
         let character = Character::new(
             "Test".to_string(),
@@ -510,7 +563,7 @@ mod tests {
         // but critical flag should be true
         for _ in 0..5 {
             // Run multiple times to catch a nat 20
-            let result = perform_roll(&character, "luck", 100);
+            let result = perform_roll(&character, "luck", 100, &mut rng);
             if result.roll == 20 {
                 assert!(result.critical, "Nat 20 should always be critical");
                 assert!(result.success, "Nat 20 should always succeed");
@@ -520,6 +573,7 @@ mod tests {
 
     #[test]
     fn test_critical_failure_nat_1() {
+        let mut rng = GameRng::seeded(2077);
         // Critical failure should always be true when rolling nat 1
         let character = Character::new(
             "Test".to_string(),
@@ -536,7 +590,7 @@ mod tests {
 
         // Even with nat 1 and high modifier, fumble flag should be true
         for _ in 0..5 {
-            let result = perform_roll(&character, "strength", 5);
+            let result = perform_roll(&character, "strength", 5, &mut rng);
             if result.roll == 1 {
                 assert!(result.fumble, "Nat 1 should always be fumble");
             }
@@ -545,6 +599,7 @@ mod tests {
 
     #[test]
     fn test_extreme_positive_modifier() {
+        let mut rng = GameRng::seeded(2077);
         // Test with very high skill modifier
         let mut character = Character::new(
             "TestHighSkill".to_string(),
@@ -561,7 +616,7 @@ mod tests {
         // Max out a skill to 100+
         character.skills.small_guns = 100;
 
-        let result = perform_roll(&character, "small guns", 50);
+        let result = perform_roll(&character, "small guns", 50, &mut rng);
         // With 100 skill modifier, even a nat 1 (1 + 100 = 101) should succeed against DC 50
         assert!(result.total >= 101);
         if result.roll != 1 {
@@ -571,6 +626,7 @@ mod tests {
 
     #[test]
     fn test_extreme_negative_modifier() {
+        let mut rng = GameRng::seeded(2077);
         // Test with very low stats (modifier = 0 for untrained)
         let character = Character::new(
             "TestLowStats".to_string(),
@@ -585,7 +641,7 @@ mod tests {
             },
         );
 
-        let result = perform_roll(&character, "luck", 1);
+        let result = perform_roll(&character, "luck", 1, &mut rng);
         // With luck 1 modifier and DC 1, only nat 1 fails (1 + 1 = 2 >= 1)
         assert!(result.total >= 2, "Minimum roll should be 1 + 1 = 2");
     }
@@ -620,6 +676,7 @@ mod tests {
 
     #[test]
     fn test_skill_check_very_high_dc() {
+        let mut rng = GameRng::seeded(2077);
         // Test with extremely high DC (like 100)
         let character = Character::new(
             "TestHighDC".to_string(),
@@ -634,7 +691,7 @@ mod tests {
             },
         );
 
-        let result = perform_roll(&character, "luck", 100);
+        let result = perform_roll(&character, "luck", 100, &mut rng);
         // Only nat 20 should succeed against DC 100 with low modifier
         if result.roll != 20 {
             assert!(!result.success, "Low roll should fail against DC 100");
@@ -643,6 +700,7 @@ mod tests {
 
     #[test]
     fn test_skill_check_zero_dc() {
+        let mut rng = GameRng::seeded(2077);
         // Test with DC 0 (auto-succeed)
         let character = Character::new(
             "TestZeroDC".to_string(),
@@ -657,13 +715,14 @@ mod tests {
             },
         );
 
-        let result = perform_roll(&character, "luck", 0);
+        let result = perform_roll(&character, "luck", 0, &mut rng);
         // Any roll with any positive modifier should succeed against DC 0
         assert!(result.total >= 0);
     }
 
     #[test]
     fn test_skill_check_negative_dc() {
+        let mut rng = GameRng::seeded(2077);
         // Test with negative DC (always succeed)
         let character = Character::new(
             "TestNegDC".to_string(),
@@ -678,7 +737,7 @@ mod tests {
             },
         );
 
-        let result = perform_roll(&character, "luck", -50);
+        let result = perform_roll(&character, "luck", -50, &mut rng);
         // Even nat 1 should succeed against negative DC
         assert!(
             result.success,
@@ -999,6 +1058,7 @@ mod tests {
 
     #[test]
     fn test_roll_bounds_validation() {
+        let mut rng = GameRng::seeded(2077);
         // Ensure rolls are always within 1-20 range
         let character = Character::new(
             "TestBounds".to_string(),
@@ -1014,7 +1074,7 @@ mod tests {
         );
 
         for _ in 0..20 {
-            let result = perform_roll(&character, "luck", 10);
+            let result = perform_roll(&character, "luck", 10, &mut rng);
             assert!(
                 result.roll >= 1 && result.roll <= 20,
                 "Roll {} out of bounds [1, 20]",
@@ -0,0 +1,799 @@
+//! # Status Effects Module
+//!
+//! Radiation, hunger and thirst, timed chem buffs and addiction.
+//!
+//! Every [`Character`](super::character::Character) carries a
+//! [`StatusEffects`] component. Effects change the character's `Special` and
+//! `Skills` directly, and each change is recorded as a [`Modifier`] holding
+//! the amount actually applied (after clamping to the stat's range), so it can
+//! be undone exactly when the effect ends.
+//!
+//! ## Time
+//!
+//! Durations are counted in combat rounds. Time passed outside combat is
+//! converted at [`ROUNDS_PER_MINUTE`] rounds per minute.
+//!
+//! ## Radiation
+//!
+//! Rads accumulate up to [`MAX_RADS`]. Every [`RADS_PER_LEVEL`] rads is a step
+//! of [`RadSickness`], which lowers Endurance, then Strength and Agility.
+//! Rads come from irradiated food and from exposure the DM hands out, which
+//! worn armor partly blocks (see
+//! [`Character::expose_to_radiation`](super::character::Character::expose_to_radiation)).
+//! RadAway removes rads.
+//!
+//! ## Hunger and Thirst
+//!
+//! Both meters fill over time from 0 (sated) to [`MAX_NEED`]. At
+//! [`NEED_WARNING`] the character is hungry or thirsty, and at
+//! [`NEED_CRITICAL`] starving or dehydrated, each with SPECIAL penalties.
+//! Food and drink empty the meters again.
+//!
+//! ## Chems and Addiction
+//!
+//! Chems grant a [`TimedEffect`]. Each dose adds the chem's addictiveness to
+//! the character's dependence on it, which fades by one point every
+//! [`DEPENDENCE_DECAY_INTERVAL`] rounds. Reaching [`ADDICTION_THRESHOLD`]
+//! makes the character an addict. An addict who goes [`WITHDRAWAL_DELAY`]
+//! rounds without a dose suffers withdrawal (the chem's bonus turned into a
+//! penalty) until they take another dose or stay clean for
+//! [`RECOVERY_ROUNDS`].
+
+use super::character::{Skills, Special, Stat, MAX_SKILL};
+use serde::{Deserialize, Serialize};
+use smartstring::alias::String as SmartString;
+use std::collections::HashMap;
+
+/// Combat rounds in one minute of game time
+pub const ROUNDS_PER_MINUTE: u32 = 10;
+
+/// Most rads a character can accumulate
+pub const MAX_RADS: u32 = 1000;
+/// Rads per step of radiation sickness
+pub const RADS_PER_LEVEL: u32 = 200;
+
+/// Hunger and thirst meters run from 0 (sated) to this
+pub const MAX_NEED: u32 = 100;
+/// Meter value at which hunger or thirst starts to hurt
+pub const NEED_WARNING: u32 = 50;
+/// Meter value at which the character is starving or dehydrated
+pub const NEED_CRITICAL: u32 = 80;
+/// Rounds for hunger to rise by one (10 minutes)
+pub const HUNGER_INTERVAL: u32 = 100;
+/// Rounds for thirst to rise by one (6 minutes)
+pub const THIRST_INTERVAL: u32 = 60;
+
+/// Dependence on a chem at which the character becomes addicted
+pub const ADDICTION_THRESHOLD: u32 = 100;
+/// Rounds for dependence on a chem to fall by one
+pub const DEPENDENCE_DECAY_INTERVAL: u32 = 10;
+/// Rounds without a dose before an addict goes into withdrawal (2 hours)
+pub const WITHDRAWAL_DELAY: u32 = 1_200;
+/// Rounds of withdrawal needed to beat an addiction (1 day)
+pub const RECOVERY_ROUNDS: u32 = 14_400;
+
+/// A stat that an effect can change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatTarget {
+    Special(Stat),
+    /// A skill by ID, e.g. "lockpick"
+    Skill(SmartString),
+}
+
+impl StatTarget {
+    /// Look up a SPECIAL stat (name or token) or a skill (ID or display name)
+    pub fn parse(name: &str) -> Option<StatTarget> {
+        if let Some(stat) = Stat::from_name(name) {
+            return Some(StatTarget::Special(stat));
+        }
+        let id = name.trim().to_lowercase().replace(' ', "_");
+        Skills::ALL
+            .iter()
+            .find(|(skill, _)| *skill == id)
+            .map(|(skill, _)| StatTarget::Skill(SmartString::from(*skill)))
+    }
+
+    /// Display name
+    pub fn name(&self) -> &str {
+        match self {
+            StatTarget::Special(stat) => stat.name(),
+            StatTarget::Skill(id) => Skills::ALL
+                .iter()
+                .find(|(skill, _)| *skill == id.as_str())
+                .map_or(id.as_str(), |(_, name)| *name),
+        }
+    }
+}
+
+/// A change made to a stat, holding the amount actually applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modifier {
+    pub target: StatTarget,
+    pub amount: i32,
+}
+
+/// A temporary stat change from a chem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedEffect {
+    /// Display name, e.g. "Buffout"
+    pub name: SmartString,
+    /// Item ID of the chem that caused it
+    pub source: SmartString,
+    pub modifiers: Vec<Modifier>,
+    /// Rounds left before it wears off
+    pub remaining: u32,
+}
+
+/// An addiction to a chem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Addiction {
+    /// Item ID of the chem
+    pub substance: SmartString,
+    /// Display name, e.g. "Jet"
+    pub name: SmartString,
+    /// Stat the chem boosts; withdrawal lowers it by the same amount
+    pub target: StatTarget,
+    pub amount: i32,
+    /// Rounds since the last dose
+    pub clean_rounds: u32,
+    /// Penalties applied while in withdrawal
+    pub withdrawal: Vec<Modifier>,
+}
+
+impl Addiction {
+    pub fn in_withdrawal(&self) -> bool {
+        self.clean_rounds >= WITHDRAWAL_DELAY
+    }
+}
+
+/// Stages of radiation sickness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RadSickness {
+    None,
+    Minor,
+    Advanced,
+    Critical,
+    Deadly,
+}
+
+impl RadSickness {
+    pub fn from_rads(rads: u32) -> RadSickness {
+        match rads / RADS_PER_LEVEL {
+            0 => RadSickness::None,
+            1 => RadSickness::Minor,
+            2 => RadSickness::Advanced,
+            3 => RadSickness::Critical,
+            _ => RadSickness::Deadly,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            RadSickness::None => "No Radiation Sickness",
+            RadSickness::Minor => "Minor Radiation Sickness",
+            RadSickness::Advanced => "Advanced Radiation Sickness",
+            RadSickness::Critical => "Critical Radiation Sickness",
+            RadSickness::Deadly => "Deadly Radiation Sickness",
+        }
+    }
+
+    /// SPECIAL penalties at this stage: Endurance first, then Strength and
+    /// Agility from Advanced on
+    pub fn penalties(&self) -> Vec<(Stat, i32)> {
+        let level = *self as i32;
+        let mut penalties = Vec::new();
+        if level >= 1 {
+            penalties.push((Stat::Endurance, -level));
+        }
+        if level >= 2 {
+            penalties.push((Stat::Strength, 1 - level));
+            penalties.push((Stat::Agility, 1 - level));
+        }
+        penalties
+    }
+}
+
+/// Radiation, hunger and thirst, chem buffs and addictions on a character
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusEffects {
+    pub rads: u32,
+    pub hunger: u32,
+    pub thirst: u32,
+    pub effects: Vec<TimedEffect>,
+    pub addictions: Vec<Addiction>,
+    /// Dependence built up on each chem, by item ID
+    pub dependence: HashMap<SmartString, u32>,
+    /// Penalties currently applied for radiation sickness, hunger and thirst
+    conditions: Vec<Modifier>,
+    /// Total rounds passed, for the interval-based meters
+    rounds: u64,
+}
+
+impl StatusEffects {
+    pub fn rad_sickness(&self) -> RadSickness {
+        RadSickness::from_rads(self.rads)
+    }
+
+    /// `special` with every active modifier undone: the character's own
+    /// stats, for anything that should outlast a chem or a sickness
+    pub fn base_special(&self, special: &Special) -> Special {
+        let mut base = special.clone();
+        let withdrawal = self.addictions.iter().flat_map(|a| &a.withdrawal);
+        let modifiers = self
+            .effects
+            .iter()
+            .flat_map(|effect| &effect.modifiers)
+            .chain(withdrawal)
+            .chain(&self.conditions);
+        for modifier in modifiers {
+            if let StatTarget::Special(stat) = modifier.target {
+                let value = base.get_mut(stat);
+                *value = (*value as i32 - modifier.amount).clamp(1, 10) as u8;
+            }
+        }
+        base
+    }
+
+    /// "Hungry" or "Starving", if the hunger meter is high enough to matter
+    pub fn hunger_label(&self) -> Option<&'static str> {
+        need_label(self.hunger, "Hungry", "Starving")
+    }
+
+    /// "Thirsty" or "Dehydrated", if the thirst meter is high enough to matter
+    pub fn thirst_label(&self) -> Option<&'static str> {
+        need_label(self.thirst, "Thirsty", "Dehydrated")
+    }
+
+    /// Absorb radiation
+    pub fn add_rads(
+        &mut self,
+        amount: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) -> Vec<String> {
+        let before = self.snapshot();
+        self.rads = (self.rads + amount).min(MAX_RADS);
+        self.refresh_conditions(special, skills);
+        self.changes_since(before)
+    }
+
+    /// Flush radiation, e.g. with RadAway
+    pub fn remove_rads(
+        &mut self,
+        amount: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) -> Vec<String> {
+        let before = self.snapshot();
+        self.rads = self.rads.saturating_sub(amount);
+        self.refresh_conditions(special, skills);
+        self.changes_since(before)
+    }
+
+    /// Eat or drink, lowering the hunger and thirst meters
+    pub fn nourish(
+        &mut self,
+        food: u32,
+        water: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) -> Vec<String> {
+        let before = self.snapshot();
+        self.hunger = self.hunger.saturating_sub(food);
+        self.thirst = self.thirst.saturating_sub(water);
+        self.refresh_conditions(special, skills);
+        self.changes_since(before)
+    }
+
+    /// Start a timed effect. Another dose of the same chem restarts the timer
+    /// instead of stacking.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_effect(
+        &mut self,
+        name: &str,
+        source: &str,
+        target: StatTarget,
+        amount: i32,
+        rounds: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) {
+        if let Some(index) = self.effects.iter().position(|e| e.source == source) {
+            let old = self.effects.remove(index);
+            revert(special, skills, &old.modifiers);
+        }
+        let modifier = apply(special, skills, &target, amount);
+        self.effects.push(TimedEffect {
+            name: SmartString::from(name),
+            source: SmartString::from(source),
+            modifiers: vec![modifier],
+            remaining: rounds,
+        });
+    }
+
+    /// Record a dose of a chem: ends withdrawal for addicts, otherwise builds
+    /// dependence and may start an addiction
+    #[allow(clippy::too_many_arguments)]
+    pub fn take_chem(
+        &mut self,
+        name: &str,
+        source: &str,
+        target: StatTarget,
+        amount: i32,
+        addictiveness: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) -> Vec<String> {
+        if let Some(addiction) = self.addictions.iter_mut().find(|a| a.substance == source) {
+            let mut messages = Vec::new();
+            if addiction.in_withdrawal() {
+                messages.push(format!("Your {} withdrawal eases.", addiction.name));
+            }
+            revert(special, skills, &addiction.withdrawal);
+            addiction.withdrawal.clear();
+            addiction.clean_rounds = 0;
+            return messages;
+        }
+        if addictiveness == 0 {
+            return Vec::new();
+        }
+
+        let dependence = self
+            .dependence
+            .entry(SmartString::from(source))
+            .or_insert(0);
+        *dependence += addictiveness;
+        if *dependence < ADDICTION_THRESHOLD {
+            return Vec::new();
+        }
+
+        self.dependence.remove(source);
+        self.addictions.push(Addiction {
+            substance: SmartString::from(source),
+            name: SmartString::from(name),
+            target,
+            amount,
+            clean_rounds: 0,
+            withdrawal: Vec::new(),
+        });
+        vec![format!("You are now addicted to {}.", name)]
+    }
+
+    /// Pass time, expiring effects and advancing meters and addictions.
+    /// Returns anything the player should be told.
+    pub fn tick(&mut self, rounds: u32, special: &mut Special, skills: &mut Skills) -> Vec<String> {
+        let before = self.snapshot();
+        let start = self.rounds;
+        self.rounds += rounds as u64;
+        let intervals = |interval: u32| {
+            let interval = interval as u64;
+            (self.rounds / interval - start / interval) as u32
+        };
+        let mut messages = Vec::new();
+
+        for effect in &mut self.effects {
+            effect.remaining = effect.remaining.saturating_sub(rounds);
+        }
+        for effect in self.effects.iter().filter(|e| e.remaining == 0) {
+            revert(special, skills, &effect.modifiers);
+            messages.push(format!("{} wore off.", effect.name));
+        }
+        self.effects.retain(|e| e.remaining > 0);
+
+        self.hunger = (self.hunger + intervals(HUNGER_INTERVAL)).min(MAX_NEED);
+        self.thirst = (self.thirst + intervals(THIRST_INTERVAL)).min(MAX_NEED);
+
+        let decay = intervals(DEPENDENCE_DECAY_INTERVAL);
+        self.dependence.retain(|_, dependence| {
+            *dependence = dependence.saturating_sub(decay);
+            *dependence > 0
+        });
+
+        for addiction in &mut self.addictions {
+            let was_in_withdrawal = addiction.in_withdrawal();
+            addiction.clean_rounds = addiction.clean_rounds.saturating_add(rounds);
+            if addiction.in_withdrawal() && !was_in_withdrawal {
+                let penalty = apply(special, skills, &addiction.target, -addiction.amount);
+                addiction.withdrawal.push(penalty);
+                messages.push(format!("{} withdrawal sets in.", addiction.name));
+            }
+            if addiction.clean_rounds >= WITHDRAWAL_DELAY + RECOVERY_ROUNDS {
+                revert(special, skills, &addiction.withdrawal);
+                addiction.withdrawal.clear();
+                messages.push(format!(
+                    "You have beaten your {} addiction.",
+                    addiction.name
+                ));
+            }
+        }
+        self.addictions
+            .retain(|a| a.clean_rounds < WITHDRAWAL_DELAY + RECOVERY_ROUNDS);
+
+        self.refresh_conditions(special, skills);
+        messages.extend(self.changes_since(before));
+        messages
+    }
+
+    /// Pass time in minutes of game time
+    pub fn tick_minutes(
+        &mut self,
+        minutes: u32,
+        special: &mut Special,
+        skills: &mut Skills,
+    ) -> Vec<String> {
+        self.tick(minutes.saturating_mul(ROUNDS_PER_MINUTE), special, skills)
+    }
+
+    /// One line per active condition, for the status panel and DM prompt
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.rads > 0 {
+            let sickness = self.rad_sickness();
+            if sickness == RadSickness::None {
+                lines.push(format!("{} rads", self.rads));
+            } else {
+                lines.push(format!("{} rads ({})", self.rads, sickness.name()));
+            }
+        }
+        lines.extend(self.hunger_label().map(str::to_string));
+        lines.extend(self.thirst_label().map(str::to_string));
+        for effect in &self.effects {
+            let changes: Vec<String> = effect
+                .modifiers
+                .iter()
+                .map(|m| format!("{:+} {}", m.amount, m.target.name()))
+                .collect();
+            lines.push(format!(
+                "{}: {} ({} rounds left)",
+                effect.name,
+                changes.join(", "),
+                effect.remaining
+            ));
+        }
+        for addiction in &self.addictions {
+            if addiction.in_withdrawal() {
+                lines.push(format!(
+                    "{} addiction (withdrawal: {:+} {})",
+                    addiction.name,
+                    -addiction.amount,
+                    addiction.target.name()
+                ));
+            } else {
+                lines.push(format!("{} addiction", addiction.name));
+            }
+        }
+        lines
+    }
+
+    /// Re-apply the penalties for radiation sickness, hunger and thirst
+    fn refresh_conditions(&mut self, special: &mut Special, skills: &mut Skills) {
+        revert(special, skills, &self.conditions);
+        let mut penalties = self.rad_sickness().penalties();
+        penalties.extend(need_penalties(self.hunger, Stat::Endurance, Stat::Strength));
+        penalties.extend(need_penalties(self.thirst, Stat::Perception, Stat::Agility));
+        self.conditions = penalties
+            .into_iter()
+            .map(|(stat, amount)| apply(special, skills, &StatTarget::Special(stat), amount))
+            .collect();
+    }
+
+    fn snapshot(&self) -> (RadSickness, Option<&'static str>, Option<&'static str>) {
+        (
+            self.rad_sickness(),
+            self.hunger_label(),
+            self.thirst_label(),
+        )
+    }
+
+    /// Messages for conditions that changed since `before`
+    fn changes_since(
+        &self,
+        before: (RadSickness, Option<&'static str>, Option<&'static str>),
+    ) -> Vec<String> {
+        let (sickness, hunger, thirst) = self.snapshot();
+        let mut messages = Vec::new();
+        if sickness != before.0 {
+            messages.push(if sickness > before.0 {
+                format!("You are suffering from {}.", sickness.name())
+            } else if sickness == RadSickness::None {
+                "Your radiation sickness has passed.".to_string()
+            } else {
+                format!("Your sickness eases to {}.", sickness.name())
+            });
+        }
+        for (now, was, relief) in [
+            (hunger, before.1, "You are no longer hungry."),
+            (thirst, before.2, "You are no longer thirsty."),
+        ] {
+            match now {
+                Some(label) if now != was => messages.push(format!("You are {}.", label)),
+                None if was.is_some() => messages.push(relief.to_string()),
+                _ => {}
+            }
+        }
+        messages
+    }
+}
+
+fn need_label(value: u32, warning: &'static str, critical: &'static str) -> Option<&'static str> {
+    if value >= NEED_CRITICAL {
+        Some(critical)
+    } else if value >= NEED_WARNING {
+        Some(warning)
+    } else {
+        None
+    }
+}
+
+/// -1 to the main stat when the need is pressing, -2 plus -1 to the second
+/// stat when it is critical
+fn need_penalties(value: u32, main: Stat, second: Stat) -> Vec<(Stat, i32)> {
+    if value >= NEED_CRITICAL {
+        vec![(main, -2), (second, -1)]
+    } else if value >= NEED_WARNING {
+        vec![(main, -1)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Change a stat, keeping SPECIAL within 1-10 and skills within
+/// 0-[`MAX_SKILL`], and return what was actually applied
+fn apply(special: &mut Special, skills: &mut Skills, target: &StatTarget, amount: i32) -> Modifier {
+    let (value, min, max) = match target {
+        StatTarget::Special(stat) => (special.get_mut(*stat), 1, 10),
+        StatTarget::Skill(id) => match skills.get_skill_mut(id) {
+            Some(value) => (value, 0, MAX_SKILL as i32),
+            None => {
+                return Modifier {
+                    target: target.clone(),
+                    amount: 0,
+                }
+            }
+        },
+    };
+    let old = *value as i32;
+    let new = (old + amount).clamp(min.min(old), max.max(old));
+    *value = new as u8;
+    Modifier {
+        target: target.clone(),
+        amount: new - old,
+    }
+}
+
+/// Undo modifiers in reverse order
+fn revert(special: &mut Special, skills: &mut Skills, modifiers: &[Modifier]) {
+    for modifier in modifiers.iter().rev() {
+        apply(special, skills, &modifier.target, -modifier.amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> (Special, Skills) {
+        let mut special = Special::new();
+        for stat in Stat::ALL {
+            *special.get_mut(stat) = 5;
+        }
+        let skills = Skills::from_special(&special);
+        (special, skills)
+    }
+
+    #[test]
+    fn test_rad_sickness_penalties_follow_rads() {
+        let (mut special, mut skills) = stats();
+        let mut status = StatusEffects::default();
+
+        assert!(status.add_rads(150, &mut special, &mut skills).is_empty());
+        let messages = status.add_rads(300, &mut special, &mut skills);
+        assert_eq!(status.rad_sickness(), RadSickness::Advanced);
+        assert_eq!(
+            messages,
+            vec!["You are suffering from Advanced Radiation Sickness."]
+        );
+        assert_eq!(
+            (special.endurance, special.strength, special.agility),
+            (3, 4, 4)
+        );
+
+        status.remove_rads(300, &mut special, &mut skills);
+        assert_eq!(status.rads, 150);
+        assert_eq!(
+            (special.endurance, special.strength, special.agility),
+            (5, 5, 5)
+        );
+
+        status.add_rads(5000, &mut special, &mut skills);
+        assert_eq!(status.rads, MAX_RADS);
+        assert_eq!(status.rad_sickness(), RadSickness::Deadly);
+        assert_eq!(special.endurance, 1);
+    }
+
+    #[test]
+    fn test_timed_effect_expires_and_reverts_exactly() {
+        let (mut special, mut skills) = stats();
+        special.strength = 9;
+        let mut status = StatusEffects::default();
+
+        status.add_effect(
+            "Buffout",
+            "buffout",
+            StatTarget::Special(Stat::Strength),
+            2,
+            10,
+            &mut special,
+            &mut skills,
+        );
+        // Capped at 10, so only +1 was applied
+        assert_eq!(special.strength, 10);
+        assert_eq!(
+            status.summary(),
+            vec!["Buffout: +1 Strength (10 rounds left)"]
+        );
+
+        assert!(status.tick(9, &mut special, &mut skills).is_empty());
+        assert_eq!(
+            status.tick(1, &mut special, &mut skills),
+            vec!["Buffout wore off."]
+        );
+        assert_eq!(special.strength, 9);
+        assert!(status.effects.is_empty());
+    }
+
+    #[test]
+    fn test_skill_effects_and_redosing_does_not_stack() {
+        let (mut special, mut skills) = stats();
+        let lockpick = skills.lockpick;
+        let mut status = StatusEffects::default();
+        let target = StatTarget::parse("Lockpick").unwrap();
+
+        for _ in 0..3 {
+            status.add_effect(
+                "Focus",
+                "focus",
+                target.clone(),
+                10,
+                5,
+                &mut special,
+                &mut skills,
+            );
+        }
+        assert_eq!(skills.lockpick, lockpick + 10);
+        status.tick(5, &mut special, &mut skills);
+        assert_eq!(skills.lockpick, lockpick);
+    }
+
+    #[test]
+    fn test_addiction_withdrawal_and_recovery() {
+        let (mut special, mut skills) = stats();
+        let mut status = StatusEffects::default();
+        let agility = StatTarget::Special(Stat::Agility);
+
+        assert!(status
+            .take_chem(
+                "Jet",
+                "jet",
+                agility.clone(),
+                2,
+                60,
+                &mut special,
+                &mut skills
+            )
+            .is_empty());
+        let messages = status.take_chem(
+            "Jet",
+            "jet",
+            agility.clone(),
+            2,
+            60,
+            &mut special,
+            &mut skills,
+        );
+        assert_eq!(messages, vec!["You are now addicted to Jet."]);
+        assert!(status.dependence.is_empty());
+
+        let messages = status.tick(WITHDRAWAL_DELAY, &mut special, &mut skills);
+        assert!(messages.contains(&"Jet withdrawal sets in.".to_string()));
+        assert_eq!(special.agility, 3);
+
+        // Another dose ends the withdrawal
+        status.take_chem("Jet", "jet", agility, 2, 60, &mut special, &mut skills);
+        assert_eq!(special.agility, 5);
+
+        let messages = status.tick(
+            WITHDRAWAL_DELAY + RECOVERY_ROUNDS,
+            &mut special,
+            &mut skills,
+        );
+        assert!(messages.contains(&"You have beaten your Jet addiction.".to_string()));
+        assert!(status.addictions.is_empty());
+        // A day without food or water leaves its own penalties behind
+        status.nourish(MAX_NEED, MAX_NEED, &mut special, &mut skills);
+        assert_eq!(special.agility, 5);
+    }
+
+    #[test]
+    fn test_dependence_fades_between_doses() {
+        let (mut special, mut skills) = stats();
+        let mut status = StatusEffects::default();
+        let target = StatTarget::Special(Stat::Intelligence);
+
+        status.take_chem(
+            "Mentats",
+            "mentats",
+            target.clone(),
+            2,
+            60,
+            &mut special,
+            &mut skills,
+        );
+        status.tick(DEPENDENCE_DECAY_INTERVAL * 60, &mut special, &mut skills);
+        assert!(status.dependence.is_empty());
+
+        status.take_chem(
+            "Mentats",
+            "mentats",
+            target,
+            2,
+            60,
+            &mut special,
+            &mut skills,
+        );
+        assert!(status.addictions.is_empty());
+    }
+
+    #[test]
+    fn test_hunger_and_thirst_build_over_time() {
+        let (mut special, mut skills) = stats();
+        let mut status = StatusEffects::default();
+
+        let messages = status.tick_minutes(300, &mut special, &mut skills);
+        assert_eq!(status.thirst, 50);
+        assert_eq!(status.hunger, 30);
+        assert_eq!(messages, vec!["You are Thirsty."]);
+        assert_eq!(special.perception, 4);
+
+        let messages = status.nourish(0, 40, &mut special, &mut skills);
+        assert_eq!(messages, vec!["You are no longer thirsty."]);
+        assert_eq!(special.perception, 5);
+
+        status.tick_minutes(1000, &mut special, &mut skills);
+        assert_eq!(status.hunger_label(), Some("Starving"));
+        assert_eq!(status.thirst_label(), Some("Dehydrated"));
+        assert_eq!(
+            (
+                special.endurance,
+                special.strength,
+                special.perception,
+                special.agility
+            ),
+            (3, 4, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_stat_target_parse() {
+        assert_eq!(
+            StatTarget::parse("strength"),
+            Some(StatTarget::Special(Stat::Strength))
+        );
+        assert_eq!(
+            StatTarget::parse("AGI"),
+            Some(StatTarget::Special(Stat::Agility))
+        );
+        assert_eq!(
+            StatTarget::parse("small guns"),
+            Some(StatTarget::Skill("small_guns".into()))
+        );
+        assert_eq!(StatTarget::parse("charm"), None);
+        assert_eq!(
+            StatTarget::parse("small_guns").unwrap().name(),
+            "Small Guns"
+        );
+    }
+}
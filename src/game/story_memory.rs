@@ -0,0 +1,290 @@
+//! # Story Memory
+//!
+//! Long-term memory of the campaign for the AI DM.
+//!
+//! ## Summaries
+//!
+//! The DM only sees the last [`PROMPT_TURNS`] turns of the conversation word
+//! for word. Older turns are folded, [`SUMMARY_BATCH_TURNS`] at a time, into a
+//! running "story so far" written in the background by the extraction model.
+//! Turns that fall out of the conversation window before they're summarized
+//! wait in [`ConversationManager::evicted`], so nothing is lost while a
+//! summary is being written.
+//!
+//! ## Chapters
+//!
+//! Summaries are split into [`Chapter`]s of up to [`CHAPTER_TURNS`] turns.
+//! The open chapter's summary is rewritten with each batch; once it's full a
+//! new chapter starts and the old one is kept as it is. The prompt gets the
+//! most recent chapters in full and older ones by title only, within a token
+//! budget (see [`StoryMemory::story_so_far`]).
+
+use super::conversation::{ConversationManager, ConversationTurn, PROMPT_TURNS};
+use serde::{Deserialize, Serialize};
+
+/// Turns folded into a summary at a time
+pub const SUMMARY_BATCH_TURNS: usize = 8;
+/// Turns a chapter covers before a new one starts
+pub const CHAPTER_TURNS: u32 = 40;
+
+/// Chapter summaries of everything older than the prompt's conversation window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoryMemory {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// Number of the last turn folded into a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summarized_through: Option<u32>,
+}
+
+/// One chapter of the story so far
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub number: u32,
+    /// Short title from the summarizer (may be empty)
+    #[serde(default)]
+    pub title: String,
+    pub summary: String,
+    /// First and last conversation turns the summary covers
+    pub first_turn: u32,
+    pub last_turn: u32,
+}
+
+impl Chapter {
+    /// "Chapter 2: The Road to Junktown"
+    pub fn heading(&self) -> String {
+        if self.title.is_empty() {
+            format!("Chapter {}", self.number)
+        } else {
+            format!("Chapter {}: {}", self.number, self.title)
+        }
+    }
+
+    fn turn_count(&self) -> u32 {
+        self.last_turn - self.first_turn + 1
+    }
+}
+
+/// A batch of turns to fold into a chapter summary
+#[derive(Debug, Clone)]
+pub struct SummaryJob {
+    pub chapter: u32,
+    /// The chapter's summary so far (empty for a new chapter)
+    pub previous: String,
+    pub turns: Vec<ConversationTurn>,
+}
+
+impl SummaryJob {
+    /// Number of the newest turn in the batch
+    pub fn last_turn(&self) -> u32 {
+        self.turns.last().map_or(0, |turn| turn.turn_number)
+    }
+}
+
+impl StoryMemory {
+    /// The next batch of turns to summarize, once a full batch has fallen
+    /// out of the prompt's window
+    pub fn next_job(&self, conversation: &ConversationManager) -> Option<SummaryJob> {
+        let window = conversation.get_all_turns();
+        let unsent = window.len().saturating_sub(PROMPT_TURNS);
+        let turns: Vec<ConversationTurn> = conversation
+            .evicted()
+            .iter()
+            .chain(window.iter().take(unsent))
+            .filter(|turn| {
+                self.summarized_through
+                    .is_none_or(|done| turn.turn_number > done)
+            })
+            .take(SUMMARY_BATCH_TURNS)
+            .cloned()
+            .collect();
+        if turns.len() < SUMMARY_BATCH_TURNS {
+            return None;
+        }
+
+        let open = self
+            .chapters
+            .last()
+            .filter(|chapter| chapter.turn_count() < CHAPTER_TURNS);
+        Some(SummaryJob {
+            chapter: open.map_or(self.chapters.len() as u32 + 1, |chapter| chapter.number),
+            previous: open
+                .map(|chapter| chapter.summary.clone())
+                .unwrap_or_default(),
+            turns,
+        })
+    }
+
+    /// Store the summarizer's response for a job. Returns `false` (and
+    /// changes nothing) if the response has no summary or the turns were
+    /// already summarized.
+    pub fn apply(&mut self, job: &SummaryJob, response: &str) -> bool {
+        let Some(first_turn) = job.turns.first().map(|turn| turn.turn_number) else {
+            return false;
+        };
+        let last_turn = job.last_turn();
+        if self
+            .summarized_through
+            .is_some_and(|done| done >= last_turn)
+        {
+            return false;
+        }
+        let (title, summary) = parse_summary(response);
+        if summary.is_empty() {
+            return false;
+        }
+
+        match self
+            .chapters
+            .iter_mut()
+            .find(|chapter| chapter.number == job.chapter)
+        {
+            Some(chapter) => {
+                chapter.summary = summary;
+                chapter.last_turn = last_turn;
+                if let Some(title) = title {
+                    chapter.title = title;
+                }
+            }
+            None => self.chapters.push(Chapter {
+                number: job.chapter,
+                title: title.unwrap_or_default(),
+                summary,
+                first_turn,
+                last_turn,
+            }),
+        }
+        self.summarized_through = Some(last_turn);
+        true
+    }
+
+    /// Chapters for the prompt, oldest first, within `budget` tokens: the
+    /// newest chapters in full, then older ones by heading only while they fit
+    pub fn story_so_far(&self, budget: usize, count_tokens: impl Fn(&str) -> usize) -> Vec<String> {
+        let mut sections = Vec::new();
+        let mut used = 0;
+        for chapter in self.chapters.iter().rev() {
+            let full = format!("{}\n{}", chapter.heading(), chapter.summary);
+            let cost = count_tokens(&full);
+            if used + cost <= budget {
+                used += cost;
+                sections.push(full);
+                continue;
+            }
+            let heading = chapter.heading();
+            let cost = count_tokens(&heading);
+            if used + cost > budget {
+                break;
+            }
+            used += cost;
+            sections.push(heading);
+        }
+        sections.reverse();
+        sections
+    }
+}
+
+/// Split a "TITLE: ...\nSUMMARY: ..." response into its title and summary.
+/// Text without the labels is all taken as summary.
+fn parse_summary(response: &str) -> (Option<String>, String) {
+    let mut title = None;
+    let mut summary = Vec::new();
+    for line in response.lines().map(str::trim) {
+        if let Some(text) = line.strip_prefix("TITLE:") {
+            title = Some(text.trim().to_string()).filter(|title| !title.is_empty());
+        } else if let Some(text) = line.strip_prefix("SUMMARY:") {
+            summary.push(text.trim());
+        } else if !line.is_empty() {
+            summary.push(line);
+        }
+    }
+    (title, summary.join(" ").trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(turns: usize) -> ConversationManager {
+        let mut conversation = ConversationManager::new();
+        for i in 0..turns {
+            conversation.add_player_turn(format!("Turn {}", i));
+        }
+        conversation
+    }
+
+    #[test]
+    fn test_no_job_until_a_batch_leaves_the_prompt() {
+        let memory = StoryMemory::default();
+        assert!(memory
+            .next_job(&conversation(PROMPT_TURNS + SUMMARY_BATCH_TURNS - 1))
+            .is_none());
+
+        let job = memory
+            .next_job(&conversation(PROMPT_TURNS + SUMMARY_BATCH_TURNS))
+            .unwrap();
+        assert_eq!(job.chapter, 1);
+        assert_eq!(job.turns.len(), SUMMARY_BATCH_TURNS);
+        assert_eq!(job.turns[0].message, "Turn 0");
+    }
+
+    #[test]
+    fn test_summaries_fill_chapters() {
+        let mut memory = StoryMemory::default();
+        let mut conversation = conversation(60);
+        // Evicted turns come first
+        assert_eq!(conversation.evicted().len(), 40);
+
+        let job = memory.next_job(&conversation).unwrap();
+        assert!(memory.apply(&job, "TITLE: Leaving the Vault\nSUMMARY: You left."));
+        assert!(!memory.apply(&job, "TITLE: Again\nSUMMARY: Twice."));
+        conversation.forget_evicted_through(job.last_turn());
+        assert_eq!(memory.chapters[0].heading(), "Chapter 1: Leaving the Vault");
+
+        // The open chapter is rewritten until it covers CHAPTER_TURNS turns
+        for _ in 1..CHAPTER_TURNS as usize / SUMMARY_BATCH_TURNS {
+            let job = memory.next_job(&conversation).unwrap();
+            assert_eq!(job.chapter, 1);
+            assert_eq!(job.previous, memory.chapters[0].summary);
+            assert!(memory.apply(&job, "More happened."));
+            conversation.forget_evicted_through(job.last_turn());
+        }
+        assert_eq!(memory.chapters.len(), 1);
+        assert_eq!(memory.chapters[0].summary, "More happened.");
+        assert_eq!(memory.chapters[0].title, "Leaving the Vault");
+
+        let job = memory.next_job(&conversation).unwrap();
+        assert_eq!(job.chapter, 2);
+        assert!(job.previous.is_empty());
+        assert!(!memory.apply(&job, "TITLE: Empty\nSUMMARY:"));
+    }
+
+    #[test]
+    fn test_story_so_far_fits_the_budget() {
+        let chapter = |number: u32, summary: &str| Chapter {
+            number,
+            title: format!("Part {}", number),
+            summary: summary.to_string(),
+            first_turn: 0,
+            last_turn: 0,
+        };
+        let memory = StoryMemory {
+            chapters: vec![chapter(1, "A long first chapter"), chapter(2, "Short")],
+            summarized_through: Some(0),
+        };
+        let words = |text: &str| text.split_whitespace().count();
+
+        assert_eq!(
+            memory.story_so_far(100, words),
+            vec![
+                "Chapter 1: Part 1\nA long first chapter",
+                "Chapter 2: Part 2\nShort"
+            ]
+        );
+        assert_eq!(
+            memory.story_so_far(9, words),
+            vec!["Chapter 1: Part 1", "Chapter 2: Part 2\nShort"]
+        );
+        assert!(memory.story_so_far(0, words).is_empty());
+    }
+}
@@ -2,7 +2,7 @@ use crate::ai::extractor::ExtractionAI;
 use crate::ai::AIDungeonMaster;
 use crate::config::Config;
 use crate::game::rolls::{
-    parse_natural_roll_request, perform_roll, truncate_response_at_skill_check,
+    parse_natural_roll_request, perform_roll_at, truncate_response_at_skill_check,
 };
 use crate::game::GameState;
 use crate::tui::{self, App, Event, EventHandler};
@@ -95,72 +95,124 @@ async fn run_app<B: ratatui::backend::Backend>(
 
                 // Process any pending worldbook updates from background extraction
                 app.process_worldbook_updates();
+                app.process_summary_updates();
 
                 // Process streaming tokens if available
-                if app.is_streaming {
-                    while let Some(result) = app.try_recv_token() {
-                        match result {
-                            Ok(token) => {
-                                app.append_streaming_token(token);
-                            }
-                            Err(e) => {
-                                app.cancel_streaming();
-                                app.add_error_message(format!("Stream error: {}", e));
-                                app.waiting_for_ai = false;
-                                break;
-                            }
-                        }
-                    }
+                process_stream(app, ai_dm, extractor).await;
+            }
+        }
+    }
 
-                    // Check if stream has finished
-                    if let Some(dm_response) = app.check_stream_finished() {
-                        // Mark AI as no longer waiting FIRST so user can interact immediately
-                        app.waiting_for_ai = false;
+    Ok(())
+}
 
-                        // Check if DM requested a skill check
-                        if let Err(e) = handle_skill_check_if_needed(app, &dm_response, ai_dm).await
-                        {
-                            app.add_error_message(format!("Skill check error: {}", e));
-                        }
+/// Drain any streamed DM tokens into the app and finish the turn when the stream closes
+///
+/// Called on every tick. When the stream completes this runs the skill-check
+/// follow-up and kicks off background worldbook extraction.
+pub async fn process_stream(app: &mut App, ai_dm: &AIDungeonMaster, extractor: &ExtractionAI) {
+    if !app.is_streaming {
+        return;
+    }
 
-                        // Extract worldbook entities in background (non-blocking)
-                        // Clone necessary data for the background task
-                        let extractor_clone = extractor.clone();
-                        let response_clone = dm_response.clone();
-                        let worldbook_update_tx = app.worldbook_update_sender.clone();
-
-                        tokio::spawn(async move {
-                            // Run extraction with short timeout - don't block gameplay
-                            match tokio::time::timeout(
-                                std::time::Duration::from_secs(10),
-                                extract_entities_background(&extractor_clone, &response_clone),
-                            )
-                            .await
-                            {
-                                Ok(Ok(Some((entities, summary)))) => {
-                                    // Send update to main thread via channel
-                                    let _ = worldbook_update_tx.send((entities, summary)).await;
-                                }
-                                Ok(Ok(None)) => {
-                                    // No entities found - normal
-                                    tracing::debug!("No entities extracted from narrative");
-                                }
-                                Ok(Err(e)) => {
-                                    // Extraction failed - log but don't disrupt gameplay
-                                    tracing::debug!("Worldbook extraction skipped: {}", e);
-                                }
-                                Err(_) => {
-                                    tracing::debug!("Worldbook extraction timed out");
-                                }
-                            }
-                        });
-                    }
-                }
+    while let Some(result) = app.try_recv_token() {
+        match result {
+            Ok(token) => {
+                app.append_streaming_token(token);
+            }
+            Err(e) => {
+                app.cancel_streaming();
+                app.add_error_message(format!("Stream error: {}", e));
+                app.waiting_for_ai = false;
+                break;
             }
         }
     }
 
-    Ok(())
+    // Check if stream has finished
+    let finished_response = app.check_stream_finished();
+    if !app.is_streaming {
+        // Mark AI as no longer waiting FIRST so user can interact immediately
+        app.waiting_for_ai = false;
+
+        // Apply any structured actions the DM attached to its response
+        apply_dm_actions(app);
+    }
+
+    if let Some(dm_response) = finished_response {
+        // Check if DM requested a skill check
+        if let Err(e) = handle_skill_check_if_needed(app, &dm_response, ai_dm).await {
+            app.add_error_message(format!("Skill check error: {}", e));
+        }
+
+        // Extract worldbook entities in background (non-blocking)
+        // Clone necessary data for the background task
+        let extractor_clone = extractor.clone();
+        let response_clone = dm_response.clone();
+        let worldbook_update_tx = app.worldbook_update_sender.clone();
+
+        tokio::spawn(async move {
+            // Run extraction with short timeout - don't block gameplay
+            match tokio::time::timeout(
+                std::time::Duration::from_secs(10),
+                extract_entities_background(&extractor_clone, &response_clone),
+            )
+            .await
+            {
+                Ok(Ok(Some((entities, summary)))) => {
+                    // Send update to main thread via channel
+                    let _ = worldbook_update_tx.send((entities, summary)).await;
+                }
+                Ok(Ok(None)) => {
+                    // No entities found - normal
+                    tracing::debug!("No entities extracted from narrative");
+                }
+                Ok(Err(e)) => {
+                    // Extraction failed - log but don't disrupt gameplay
+                    tracing::debug!("Worldbook extraction skipped: {}", e);
+                }
+                Err(_) => {
+                    tracing::debug!("Worldbook extraction timed out");
+                }
+            }
+        });
+
+        start_story_summary(app, extractor);
+    }
+}
+
+/// Fold old conversation turns into the story summary in the background,
+/// if a batch is ready and no summary is already being written
+fn start_story_summary(app: &mut App, extractor: &ExtractionAI) {
+    if app.summary_in_flight {
+        return;
+    }
+    let Some(job) = app.game_state.summary_job() else {
+        return;
+    };
+    app.summary_in_flight = true;
+
+    let extractor_clone = extractor.clone();
+    let summary_update_tx = app.summary_update_sender.clone();
+    tokio::spawn(async move {
+        let summary = match tokio::time::timeout(
+            std::time::Duration::from_secs(90),
+            extractor_clone.summarize(&job),
+        )
+        .await
+        {
+            Ok(Ok(summary)) => Some(summary),
+            Ok(Err(e)) => {
+                tracing::debug!("Story summary skipped: {}", e);
+                None
+            }
+            Err(_) => {
+                tracing::debug!("Story summary timed out");
+                None
+            }
+        };
+        let _ = summary_update_tx.send((job, summary)).await;
+    });
 }
 
 async fn handle_key_event(
@@ -183,6 +235,36 @@ async fn handle_key_event(
         return handle_equipment_keys(app, key);
     }
 
+    // Special handling for Level Up view mode
+    if app.view_mode == crate::tui::app::ViewMode::LevelUp {
+        return handle_level_up_keys(app, key);
+    }
+
+    // Special handling for the quest journal
+    if app.view_mode == crate::tui::app::ViewMode::Journal {
+        return handle_journal_keys(app, key);
+    }
+
+    // Special handling for the trade screen
+    if app.view_mode == crate::tui::app::ViewMode::Trade {
+        return handle_trade_keys(app, key);
+    }
+
+    // Special handling for the loot screen
+    if app.view_mode == crate::tui::app::ViewMode::Loot {
+        return handle_loot_keys(app, key);
+    }
+
+    // Special handling for the inventory screen
+    if app.view_mode == crate::tui::app::ViewMode::Inventory {
+        return handle_inventory_keys(app, key);
+    }
+
+    // Special handling for the crafting screen
+    if app.view_mode == crate::tui::app::ViewMode::Crafting {
+        return handle_crafting_keys(app, key);
+    }
+
     match key.code {
         // Quit
         KeyCode::Char('c')
@@ -194,10 +276,8 @@ async fn handle_key_event(
         }
 
         // Navigation in view modes
-        KeyCode::Esc => {
-            if app.view_mode != crate::tui::app::ViewMode::Normal {
-                app.set_view_mode(crate::tui::app::ViewMode::Normal);
-            }
+        KeyCode::Esc if app.view_mode != crate::tui::app::ViewMode::Normal => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
         }
 
         // Scroll message log
@@ -209,11 +289,9 @@ async fn handle_key_event(
         }
 
         // Enter key - submit input
-        KeyCode::Enter => {
-            if !app.input.is_empty() {
-                let input = app.take_input();
-                handle_player_input(app, &input, ai_dm).await?;
-            }
+        KeyCode::Enter if !app.input.is_empty() => {
+            let input = app.take_input();
+            handle_player_input(app, &input, ai_dm).await?;
         }
 
         // Backspace
@@ -277,35 +355,29 @@ fn handle_worldbook_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
         }
 
         // Arrow key navigation - behavior depends on focus
-        KeyCode::Left => {
-            if app.worldbook_browser.is_tab_bar_focused() {
-                // Navigate to previous tab when focused on tab bar
-                app.worldbook_browser.prev_tab();
-            }
+        KeyCode::Left if app.worldbook_browser.is_tab_bar_focused() => {
+            // Navigate to previous tab when focused on tab bar
+            app.worldbook_browser.prev_tab();
         }
-        KeyCode::Right => {
-            if app.worldbook_browser.is_tab_bar_focused() {
-                // Navigate to next tab when focused on tab bar
-                app.worldbook_browser.next_tab();
-            }
+        KeyCode::Right if app.worldbook_browser.is_tab_bar_focused() => {
+            // Navigate to next tab when focused on tab bar
+            app.worldbook_browser.next_tab();
         }
 
-        KeyCode::Up => {
-            if app.worldbook_browser.is_list_focused() {
-                // Navigate up in list
-                let max_items = match app.worldbook_browser.active_tab {
-                    WorldbookTab::Locations => app.game_state.worldbook.locations.len(),
-                    WorldbookTab::NPCs => app.game_state.worldbook.npcs.len(),
-                    WorldbookTab::Events => app.game_state.worldbook.events.len(),
-                    WorldbookTab::Search => 0,
-                };
-
-                // If at the top of the list, move focus to tab bar
-                if app.worldbook_browser.selected_index == 0 {
-                    app.worldbook_browser.focus_tab_bar();
-                } else {
-                    app.worldbook_browser.select_prev(max_items);
-                }
+        KeyCode::Up if app.worldbook_browser.is_list_focused() => {
+            // Navigate up in list
+            let max_items = match app.worldbook_browser.active_tab {
+                WorldbookTab::Locations => app.game_state.worldbook.locations.len(),
+                WorldbookTab::NPCs => app.game_state.worldbook.npcs.len(),
+                WorldbookTab::Events => app.game_state.worldbook.events.len(),
+                WorldbookTab::Search => 0,
+            };
+
+            // If at the top of the list, move focus to tab bar
+            if app.worldbook_browser.selected_index == 0 {
+                app.worldbook_browser.focus_tab_bar();
+            } else {
+                app.worldbook_browser.select_prev(max_items);
             }
         }
 
@@ -326,15 +398,15 @@ fn handle_worldbook_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
         }
 
         // Expand/collapse (for locations)
-        KeyCode::Enter | KeyCode::Char(' ') => {
-            if app.worldbook_browser.active_tab == WorldbookTab::Locations {
-                let locations = app
-                    .worldbook_browser
-                    .get_sorted_locations(&app.game_state.worldbook);
-                if app.worldbook_browser.selected_index < locations.len() {
-                    let (location_id, _) = &locations[app.worldbook_browser.selected_index];
-                    app.worldbook_browser.toggle_expansion(location_id);
-                }
+        KeyCode::Enter | KeyCode::Char(' ')
+            if app.worldbook_browser.active_tab == WorldbookTab::Locations =>
+        {
+            let locations = app
+                .worldbook_browser
+                .get_sorted_locations(&app.game_state.worldbook);
+            if app.worldbook_browser.selected_index < locations.len() {
+                let (location_id, _) = &locations[app.worldbook_browser.selected_index];
+                app.worldbook_browser.toggle_expansion(location_id);
             }
         }
 
@@ -356,6 +428,10 @@ fn handle_worldbook_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
 fn handle_equipment_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
     use crate::game::items::ItemType;
 
+    if let Some(mod_index) = app.equipment_mod_index {
+        return handle_mod_picker_keys(app, key, mod_index);
+    }
+
     match key.code {
         // Quit equipment menu
         KeyCode::Esc => {
@@ -364,10 +440,8 @@ fn handle_equipment_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
         }
 
         // Navigation
-        KeyCode::Up => {
-            if app.equipment_selected_index > 0 {
-                app.equipment_selected_index -= 1;
-            }
+        KeyCode::Up if app.equipment_selected_index > 0 => {
+            app.equipment_selected_index -= 1;
         }
         KeyCode::Down => {
             // Get count of equippable items
@@ -396,13 +470,11 @@ fn handle_equipment_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
                 .collect();
 
             if let Some(selected_item) = equippable_items.get(app.equipment_selected_index) {
-                let item_id = selected_item.id.clone();
+                let item_id = selected_item.key();
                 let item_name = selected_item.name.clone();
 
                 // Check if already equipped
-                let already_equipped = app.game_state.character.equipped_weapon.as_ref()
-                    == Some(&item_id)
-                    || app.game_state.character.equipped_armor.as_ref() == Some(&item_id);
+                let already_equipped = app.game_state.character.is_equipped(selected_item);
 
                 if !already_equipped {
                     // Equip the item
@@ -435,13 +507,10 @@ fn handle_equipment_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
                 .collect();
 
             if let Some(selected_item) = equippable_items.get(app.equipment_selected_index) {
-                let item_id = selected_item.id.clone();
                 let item_name = selected_item.name.clone();
 
                 // Check if equipped
-                let is_equipped = app.game_state.character.equipped_weapon.as_ref()
-                    == Some(&item_id)
-                    || app.game_state.character.equipped_armor.as_ref() == Some(&item_id);
+                let is_equipped = app.game_state.character.is_equipped(selected_item);
 
                 if is_equipped {
                     // Unequip the item
@@ -462,13 +531,426 @@ fn handle_equipment_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
             }
         }
 
+        // Pick a mod to attach to the selected weapon
+        KeyCode::Char('m') | KeyCode::Char('M') => {
+            let Some(index) = app.selected_equipment_item() else {
+                return Ok(());
+            };
+            let character = &app.game_state.character;
+            let weapon = &character.inventory[index];
+            if weapon.as_weapon().is_none() {
+                app.add_info_message("Only weapons take mods.".to_string());
+            } else if character.compatible_mods(index).is_empty() {
+                let message = format!("You have no mods that fit the {}.", weapon.name);
+                app.add_info_message(message);
+            } else {
+                app.equipment_mod_index = Some(0);
+            }
+        }
+
+        // Take the last attached mod off the selected weapon
+        KeyCode::Char('x') | KeyCode::Char('X') => {
+            let Some(index) = app.selected_equipment_item() else {
+                return Ok(());
+            };
+            let slot_index = app.game_state.character.inventory[index]
+                .as_weapon()
+                .map_or(0, |stats| stats.mods.len().saturating_sub(1));
+            match app.game_state.character.detach_mod(index, slot_index) {
+                Ok(message) => app.add_system_message(format!("✓ {}", message)),
+                Err(e) => app.add_error_message(e),
+            }
+        }
+
         _ => {}
     }
 
     Ok(())
 }
 
-async fn handle_player_input(
+/// Handle keyboard events while picking a mod in the equipment menu
+fn handle_mod_picker_keys(app: &mut App, key: KeyEvent, selected: usize) -> anyhow::Result<()> {
+    use crate::game::items::ItemType;
+
+    let Some(weapon_index) = app.selected_equipment_item() else {
+        app.equipment_mod_index = None;
+        return Ok(());
+    };
+    let mods = app.game_state.character.compatible_mods(weapon_index);
+
+    match key.code {
+        KeyCode::Esc => app.equipment_mod_index = None,
+        KeyCode::Up if selected > 0 => app.equipment_mod_index = Some(selected - 1),
+        KeyCode::Down if selected + 1 < mods.len() => {
+            app.equipment_mod_index = Some(selected + 1);
+        }
+        KeyCode::Enter => {
+            let Some(&mod_index) = mods.get(selected) else {
+                return Ok(());
+            };
+            // A weapon split off its stack goes to the end of the list
+            let split = app.game_state.character.inventory[weapon_index].quantity > 1;
+            match app.game_state.character.attach_mod(weapon_index, mod_index) {
+                Ok(message) => {
+                    app.add_system_message(format!("✓ {}", message));
+                    if split {
+                        app.equipment_selected_index = app
+                            .game_state
+                            .character
+                            .inventory
+                            .iter()
+                            .filter(|item| {
+                                matches!(item.item_type, ItemType::Weapon(_) | ItemType::Armor(_))
+                            })
+                            .count()
+                            - 1;
+                    }
+                }
+                Err(e) => app.add_error_message(e),
+            }
+            app.equipment_mod_index = None;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events when in the level-up screen
+fn handle_level_up_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    use crate::game::character::Skills;
+    use crate::tui::app::LevelUpFocus;
+
+    let perk_count = app.game_state.character.available_perks().len();
+    let item_count = match app.level_up_focus {
+        LevelUpFocus::Skills => Skills::ALL.len(),
+        LevelUpFocus::Perks => perk_count,
+    };
+
+    match key.code {
+        // Leave the level-up screen; unspent points are kept
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.level_up_selected_index = 0;
+        }
+
+        // Switch between the skill list and the perk list
+        KeyCode::Tab | KeyCode::BackTab => {
+            app.level_up_focus = match app.level_up_focus {
+                LevelUpFocus::Skills => LevelUpFocus::Perks,
+                LevelUpFocus::Perks => LevelUpFocus::Skills,
+            };
+            app.level_up_selected_index = 0;
+        }
+
+        // Navigation
+        KeyCode::Up if app.level_up_selected_index > 0 => {
+            app.level_up_selected_index -= 1;
+        }
+        KeyCode::Down if app.level_up_selected_index + 1 < item_count => {
+            app.level_up_selected_index += 1;
+        }
+
+        // Spend a skill point or pick a perk
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('+') => match app.level_up_focus {
+            LevelUpFocus::Skills => {
+                let (id, name) = Skills::ALL[app.level_up_selected_index.min(item_count - 1)];
+                match app.game_state.character.spend_skill_point(id) {
+                    Ok(value) => app.add_info_message(format!("{} raised to {}", name, value)),
+                    Err(e) => app.add_error_message(e),
+                }
+            }
+            LevelUpFocus::Perks => {
+                let perks = app.game_state.character.available_perks();
+                if let Some(perk) = perks.get(app.level_up_selected_index) {
+                    match app.game_state.character.choose_perk(*perk) {
+                        Ok(message) => {
+                            app.add_system_message(format!("✓ {}", message));
+                            app.level_up_selected_index = 0;
+                        }
+                        Err(e) => app.add_error_message(e),
+                    }
+                }
+            }
+        },
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events in the quest journal
+fn handle_journal_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    let quest_count = app.game_state.quests.quests.len();
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.journal_selected_index = 0;
+        }
+        KeyCode::Up if app.journal_selected_index > 0 => {
+            app.journal_selected_index -= 1;
+        }
+        KeyCode::Down if app.journal_selected_index + 1 < quest_count => {
+            app.journal_selected_index += 1;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events in the trade screen
+fn handle_trade_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    use crate::tui::app::TradeFocus;
+
+    let Some(merchant) = app.trade_npc.clone() else {
+        app.set_view_mode(crate::tui::app::ViewMode::Normal);
+        return Ok(());
+    };
+    let item_count = |app: &App| match app.trade_focus {
+        TradeFocus::Buy => app
+            .game_state
+            .worldbook
+            .merchants
+            .get(&merchant)
+            .map_or(0, |m| m.stock.len()),
+        TradeFocus::Sell => app.game_state.character.inventory.len(),
+    };
+
+    match key.code {
+        // Leave the trade screen
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.trade_npc = None;
+            app.trade_selected_index = 0;
+        }
+
+        // Switch between the merchant's stock and the player's inventory
+        KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+            app.trade_focus = match app.trade_focus {
+                TradeFocus::Buy => TradeFocus::Sell,
+                TradeFocus::Sell => TradeFocus::Buy,
+            };
+            app.trade_selected_index = 0;
+        }
+
+        // Navigation
+        KeyCode::Up if app.trade_selected_index > 0 => {
+            app.trade_selected_index -= 1;
+        }
+        KeyCode::Down if app.trade_selected_index + 1 < item_count(app) => {
+            app.trade_selected_index += 1;
+        }
+
+        // Buy or sell one of the selected item
+        KeyCode::Enter => {
+            let index = app.trade_selected_index;
+            match app.trade_focus {
+                TradeFocus::Buy => match app.game_state.buy(&merchant, index, 1) {
+                    Ok(messages) => {
+                        for message in messages {
+                            app.add_system_message(format!("✓ {}", message));
+                        }
+                    }
+                    Err(e) => app.add_error_message(e),
+                },
+                TradeFocus::Sell => match app.game_state.sell(&merchant, index, 1) {
+                    Ok(message) => app.add_system_message(format!("✓ {}", message)),
+                    Err(e) => app.add_error_message(e),
+                },
+            }
+
+            // Keep the selection on the list when the last of an item is traded
+            let count = item_count(app);
+            if app.trade_selected_index >= count {
+                app.trade_selected_index = count.saturating_sub(1);
+            }
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events in the loot screen
+fn handle_loot_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    match key.code {
+        // Leave whatever is left on the bodies
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.loot_selected_index = 0;
+        }
+
+        // Navigation
+        KeyCode::Up if app.loot_selected_index > 0 => {
+            app.loot_selected_index -= 1;
+        }
+        KeyCode::Down if app.loot_selected_index + 1 < app.game_state.loot.len() => {
+            app.loot_selected_index += 1;
+        }
+
+        // Take the selected item
+        KeyCode::Enter => match app.game_state.take_loot(app.loot_selected_index) {
+            Ok(messages) => {
+                for message in messages {
+                    app.add_system_message(format!("✓ {}", message));
+                }
+                let count = app.game_state.loot.len();
+                if app.loot_selected_index >= count {
+                    app.loot_selected_index = count.saturating_sub(1);
+                }
+            }
+            Err(e) => app.add_error_message(e),
+        },
+
+        // Take everything
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            for message in app.game_state.take_all_loot() {
+                app.add_system_message(message);
+            }
+            app.loot_selected_index = 0;
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events in the crafting screen
+fn handle_crafting_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.crafting_selected_index = 0;
+        }
+
+        // Navigation
+        KeyCode::Up if app.crafting_selected_index > 0 => {
+            app.crafting_selected_index -= 1;
+        }
+        KeyCode::Down if app.crafting_selected_index + 1 < app.crafting_rows().len() => {
+            app.crafting_selected_index += 1;
+        }
+
+        // Craft the selected recipe
+        KeyCode::Enter => {
+            let Some(id) = app
+                .crafting_rows()
+                .get(app.crafting_selected_index)
+                .map(|(recipe, _)| recipe.id.clone())
+            else {
+                return Ok(());
+            };
+            match app.game_state.craft(&id) {
+                Ok(messages) => {
+                    for message in messages {
+                        app.add_system_message(format!("✓ {}", message));
+                    }
+                    // Keep the same recipe selected as the list reorders
+                    if let Some(row) = app
+                        .crafting_rows()
+                        .iter()
+                        .position(|(recipe, _)| recipe.id == id)
+                    {
+                        app.crafting_selected_index = row;
+                    }
+                }
+                Err(e) => app.add_error_message(e),
+            }
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle keyboard events in the inventory screen
+fn handle_inventory_keys(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.set_view_mode(crate::tui::app::ViewMode::Normal);
+            app.inventory_selected_index = 0;
+        }
+
+        // Navigation
+        KeyCode::Up if app.inventory_selected_index > 0 => {
+            app.inventory_selected_index -= 1;
+        }
+        KeyCode::Down if app.inventory_selected_index + 1 < app.inventory_rows().len() => {
+            app.inventory_selected_index += 1;
+        }
+
+        // Filter by category
+        KeyCode::Tab | KeyCode::Right => app.cycle_inventory_filter(true),
+        KeyCode::BackTab | KeyCode::Left => app.cycle_inventory_filter(false),
+
+        // Sort by the next order
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            app.inventory_sort = app.inventory_sort.next();
+            let order = app.inventory_sort;
+            app.game_state.character.inventory.sort(order);
+            app.inventory_selected_index = 0;
+        }
+
+        // Use the selected consumable
+        KeyCode::Enter => {
+            let Some(index) = app.selected_inventory_item() else {
+                return Ok(());
+            };
+            let item = &app.game_state.character.inventory[index];
+            let (id, name) = (item.id.clone(), item.name.clone());
+            match app.game_state.character.use_consumable(&id) {
+                Ok(message) if message.is_empty() => {
+                    app.add_system_message(format!("✓ Used {}", name))
+                }
+                Ok(message) => app.add_system_message(format!("✓ Used {}. {}", name, message)),
+                Err(e) => app.add_error_message(e),
+            }
+        }
+
+        // Drop one, or the whole stack
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            let Some(index) = app.selected_inventory_item() else {
+                return Ok(());
+            };
+            let qty = (key.code == KeyCode::Char('d')).then_some(1);
+            match app.game_state.drop_item(index, qty) {
+                Ok(message) => app.add_system_message(format!("✓ {}", message)),
+                Err(e) => app.add_error_message(e),
+            }
+        }
+
+        _ => {}
+    }
+
+    // Keep the selection on the list as stacks are used up or dropped
+    let rows = app.inventory_rows().len();
+    if app.inventory_selected_index >= rows {
+        app.inventory_selected_index = rows.saturating_sub(1);
+    }
+
+    Ok(())
+}
+
+/// Split "stimpak 3" into the item and an optional trailing quantity
+fn item_and_quantity(args: &str) -> (&str, Option<u32>) {
+    let args = args.trim();
+    match args.rsplit_once(' ') {
+        Some((item, qty)) => match qty.parse() {
+            Ok(qty) => (item.trim(), Some(qty)),
+            Err(_) => (args, None),
+        },
+        None => (args, None),
+    }
+}
+
+/// Handle a line of player input: built-in commands, combat actions, or a DM turn
+pub async fn handle_player_input(
     app: &mut App,
     input: &str,
     ai_dm: &AIDungeonMaster,
@@ -492,9 +974,90 @@ async fn handle_player_input(
             return Ok(());
         }
         "inventory" | "inv" | "i" => {
+            app.inventory_selected_index = 0;
             app.set_view_mode(crate::tui::app::ViewMode::Inventory);
             return Ok(());
         }
+        lower if lower == "drop" || lower.starts_with("drop ") => {
+            let (item, qty) = item_and_quantity(&input[4..]);
+            if item.is_empty() {
+                app.add_error_message(
+                    "Usage: drop <item> [qty] (e.g., 'drop tin can 2')".to_string(),
+                );
+                return Ok(());
+            }
+            let result = app
+                .game_state
+                .character
+                .inventory
+                .find(item)
+                .ok_or_else(|| format!("You don't have any '{}'", item))
+                .and_then(|index| app.game_state.drop_item(index, qty));
+            match result {
+                Ok(message) => app.add_system_message(format!("✓ {}", message)),
+                Err(e) => app.add_error_message(e),
+            }
+            return Ok(());
+        }
+        lower if lower == "craft" || lower.starts_with("craft ") => {
+            let recipe = input[5..].trim();
+            if recipe.is_empty() {
+                app.crafting_selected_index = 0;
+                app.set_view_mode(crate::tui::app::ViewMode::Crafting);
+                return Ok(());
+            }
+            match app.game_state.craft(recipe) {
+                Ok(messages) => {
+                    for message in messages {
+                        app.add_system_message(format!("✓ {}", message));
+                    }
+                }
+                Err(e) => app.add_error_message(e),
+            }
+            return Ok(());
+        }
+        lower if lower == "repair" || lower.starts_with("repair ") => {
+            let item = input[6..].trim();
+            if item.is_empty() {
+                app.add_error_message(
+                    "Usage: repair <item> (e.g., 'repair 10mm pistol')".to_string(),
+                );
+                return Ok(());
+            }
+            match app.game_state.repair(item) {
+                Ok(messages) => {
+                    for message in messages {
+                        app.add_system_message(message);
+                    }
+                }
+                Err(e) => app.add_error_message(e),
+            }
+            return Ok(());
+        }
+        lower if lower == "pickup" || lower.starts_with("pickup ") => {
+            let (item, qty) = item_and_quantity(&input[6..]);
+            if item.is_empty() {
+                let here = app.game_state.items_here();
+                if here.is_empty() {
+                    app.add_info_message("You haven't left anything here.".to_string());
+                } else {
+                    app.add_info_message("Left here:".to_string());
+                    for item in here {
+                        app.add_info_message(format!("  {} x{}", item.name, item.quantity));
+                    }
+                }
+                return Ok(());
+            }
+            match app.game_state.pick_up(item, qty) {
+                Ok(messages) => {
+                    for message in messages {
+                        app.add_system_message(format!("✓ {}", message));
+                    }
+                }
+                Err(e) => app.add_error_message(e),
+            }
+            return Ok(());
+        }
         "stats" | "sheet" => {
             app.set_view_mode(crate::tui::app::ViewMode::Stats);
             return Ok(());
@@ -507,6 +1070,55 @@ async fn handle_player_input(
             app.set_view_mode(crate::tui::app::ViewMode::Equipment);
             return Ok(());
         }
+        "quests" | "journal" | "j" => {
+            app.journal_selected_index = 0;
+            app.set_view_mode(crate::tui::app::ViewMode::Journal);
+            return Ok(());
+        }
+        "levelup" | "level" | "perks" => {
+            app.level_up_selected_index = 0;
+            app.level_up_focus = crate::tui::app::LevelUpFocus::Skills;
+            app.set_view_mode(crate::tui::app::ViewMode::LevelUp);
+            return Ok(());
+        }
+        "loot" | "search" => {
+            if app.game_state.loot.is_empty() {
+                app.add_error_message("There's nothing here to loot.".to_string());
+            } else {
+                app.loot_selected_index = 0;
+                app.set_view_mode(crate::tui::app::ViewMode::Loot);
+            }
+            return Ok(());
+        }
+        lower if lower == "trade" || lower.starts_with("trade ") => {
+            let npc = input[5..].trim();
+            if npc.is_empty() {
+                app.add_error_message("Usage: trade <npc> (e.g., 'trade Moira Brown')".to_string());
+                return Ok(());
+            }
+            match app.game_state.start_trade(npc) {
+                Ok(id) => {
+                    app.trade_npc = Some(id);
+                    app.trade_focus = crate::tui::app::TradeFocus::Buy;
+                    app.trade_selected_index = 0;
+                    app.set_view_mode(crate::tui::app::ViewMode::Trade);
+                }
+                Err(e) => app.add_error_message(e),
+            }
+            return Ok(());
+        }
+        _ if dice_command(input).is_some() => {
+            if let Some(expr) = dice_command(input) {
+                match expr.roll(
+                    Some(&app.game_state.character.special),
+                    &mut app.game_state.rng,
+                ) {
+                    Ok(roll) => app.add_system_message(format!("🎲 {}", roll)),
+                    Err(e) => app.add_error_message(e.to_string()),
+                }
+            }
+            return Ok(());
+        }
         _ if input.to_lowercase().starts_with("use ") => {
             let item_id = input[4..].trim();
             if item_id.is_empty() {
@@ -532,6 +1144,23 @@ async fn handle_player_input(
             show_help(app);
             return Ok(());
         }
+        "reload" => {
+            reload_weapon(app);
+            return Ok(());
+        }
+        _ if rest_command(input).is_some() => {
+            if let Some(hours) = rest_command(input) {
+                match app.game_state.rest(hours) {
+                    Ok(messages) => {
+                        for message in messages {
+                            app.add_system_message(message);
+                        }
+                    }
+                    Err(e) => app.add_error_message(e),
+                }
+            }
+            return Ok(());
+        }
         "fight" | "combat" => {
             if app.game_state.combat.active {
                 app.add_error_message("Already in combat!".to_string());
@@ -541,7 +1170,11 @@ async fn handle_player_input(
             return Ok(());
         }
         "debug" | "context" => {
-            show_debug_context(app);
+            show_debug_context(app, ai_dm);
+            return Ok(());
+        }
+        "recap" | "story" => {
+            show_story_recap(app);
             return Ok(());
         }
         _ => {
@@ -594,255 +1227,440 @@ async fn handle_player_input(
 }
 
 fn handle_combat_command(app: &mut App, input: &str) -> Option<anyhow::Result<()>> {
-    use crate::game::combat::{attack_roll, calculate_damage, resolve_stat_modifiers};
+    use crate::game::combat::CombatAction;
 
     let lower = input.to_lowercase();
 
     // Attack command
     if lower.starts_with("attack") || lower.starts_with("a ") {
         // Parse target number
-        let parts: Vec<&str> = input.split_whitespace().collect();
-        if parts.len() >= 2 {
-            if let Ok(target) = parts[1].parse::<usize>() {
-                let target_idx = target.saturating_sub(1); // Convert to 0-based index
-                if target_idx < app.game_state.combat.enemies.len() {
-                    let enemy = &app.game_state.combat.enemies[target_idx];
-
-                    if !enemy.is_alive() {
-                        app.add_error_message("That enemy is already defeated!".to_string());
-                        return Some(Ok(()));
-                    }
-
-                    let enemy_ac = enemy.armor_class;
-                    let enemy_name = enemy.name.clone();
-                    let weapon_damage = app.game_state.character.get_equipped_damage();
-                    let resolved_damage = resolve_stat_modifiers(
-                        &weapon_damage,
-                        app.game_state.character.special.strength,
-                    );
-                    let skill = app.game_state.character.get_weapon_skill();
-
-                    if !app.game_state.character.use_ap(4) {
-                        app.add_error_message("Not enough AP!".to_string());
-                        return Some(Ok(()));
-                    }
-
-                    let (hit, critical) = attack_roll(skill, enemy_ac);
-
-                    // Trigger dice roll animation (d20 roll)
-                    let modifier = (skill as i32 - 10) / 2;
-                    app.animation_manager.start_dice_roll(
-                        if critical {
-                            20
-                        } else if !hit {
-                            1
-                        } else {
-                            10
-                        }, // Approximate result
-                        modifier,
-                    );
-
-                    if hit {
-                        let damage = calculate_damage(&resolved_damage, 0, critical);
-                        app.game_state.combat.enemies[target_idx].take_damage(damage);
-
-                        // Check if enemy died and trigger fadeout animation
-                        if !app.game_state.combat.enemies[target_idx].is_alive() {
-                            app.animation_manager.start_enemy_fadeout(target_idx);
-
-                            // Award XP
-                            let old_xp = app.game_state.character.experience;
-                            let xp_reward = app.game_state.combat.enemies[target_idx].xp_reward;
-                            app.game_state.character.add_experience(xp_reward);
-
-                            // Trigger XP fill animation
-                            app.animation_manager
-                                .start_xp_fill(old_xp, app.game_state.character.experience);
-
-                            if critical {
-                                app.add_combat_message(format!(
-                                    "⚡ CRITICAL HIT! {} damage to {}! Enemy defeated! +{} XP",
-                                    damage, enemy_name, xp_reward
-                                ));
-                            } else {
-                                app.add_combat_message(format!(
-                                    "→ Hit! {} damage to {}! Enemy defeated! +{} XP",
-                                    damage, enemy_name, xp_reward
-                                ));
-                            }
-                        } else if critical {
-                            app.add_combat_message(format!(
-                                "⚡ CRITICAL HIT! {} damage to {}!",
-                                damage, enemy_name
-                            ));
-                        } else {
-                            app.add_combat_message(format!(
-                                "→ Hit! {} damage to {}!",
-                                damage, enemy_name
-                            ));
-                        }
-                    } else {
-                        app.add_combat_message("✗ Missed!".to_string());
-                    }
-
-                    // Enemy turn
-                    handle_enemy_turn(app);
-
-                    // Check if combat ended
-                    if app.game_state.combat.all_enemies_dead() {
-                        let total_xp = app.game_state.combat.total_xp_reward();
-                        app.add_combat_message(format!(
-                            "🎉 Victory! All enemies defeated! Total XP: +{}",
-                            total_xp
-                        ));
-                        app.game_state.combat.end_combat();
-                        app.set_view_mode(crate::tui::app::ViewMode::Normal);
-                    }
-
-                    return Some(Ok(()));
-                }
-            }
+        let target = input
+            .split_whitespace()
+            .nth(1)
+            .and_then(|target| target.parse::<usize>().ok())
+            .filter(|target| (1..=app.game_state.combat.enemies.len()).contains(target));
+        match target {
+            Some(target) => run_combat_action(
+                app,
+                CombatAction::Attack {
+                    target: target - 1, // Convert to 0-based index
+                },
+            ),
+            None => app.add_error_message("Invalid target. Use: attack <number>".to_string()),
         }
-        app.add_error_message("Invalid target. Use: attack <number>".to_string());
+        return Some(Ok(()));
+    }
+
+    // End the player's turn early
+    if lower == "end" || lower == "end turn" || lower == "pass" {
+        app.add_combat_message("You end your turn.".to_string());
+        run_combat_action(app, CombatAction::EndTurn);
         return Some(Ok(()));
     }
 
     // Run command
     if lower == "run" || lower == "flee" {
-        use rand::Rng;
-        let mut rng = rand::rng();
-        if rng.random_bool(0.6) {
-            // 60% chance to escape
-            app.add_combat_message("You successfully fled from combat!".to_string());
-            app.game_state.combat.active = false;
-            app.set_view_mode(crate::tui::app::ViewMode::Normal);
-        } else {
-            app.add_combat_message("Failed to escape!".to_string());
-            handle_enemy_turn(app);
-        }
+        run_combat_action(app, CombatAction::Flee);
         return Some(Ok(()));
     }
 
     None
 }
 
-/// Handle enemy turn attacks
-fn handle_enemy_turn(app: &mut App) {
-    use crate::game::combat::{attack_roll, calculate_damage};
-
-    // Collect enemy data first to avoid borrow checker issues
-    let player_ac = 10 + app.game_state.character.special.agility as i32;
-    let enemy_attacks: Vec<_> = app
-        .game_state
+/// Resolve a combat action in the engine and render the resulting events
+fn run_combat_action(app: &mut App, action: crate::game::combat::CombatAction) {
+    let game_state = &mut app.game_state;
+    let rounds = game_state.combat.round;
+    let events = game_state
         .combat
-        .enemies
+        .apply(action, &mut game_state.character, &mut game_state.rng);
+    let killed: Vec<String> = events
         .iter()
-        .filter(|e| e.is_alive())
-        .map(|enemy| {
-            let (hit, critical) = attack_roll(enemy.skill, player_ac);
-            (
-                enemy.name.clone(),
-                enemy.damage.clone(),
-                enemy.strength,
-                hit,
-                critical,
-            )
+        .filter_map(|event| match event {
+            crate::game::combat::CombatEvent::EnemyDied { name, .. } => Some(name.to_string()),
+            _ => None,
         })
         .collect();
+    render_combat_events(app, events);
 
-    // Process attacks
-    for (enemy_name, enemy_damage, enemy_strength, hit, critical) in enemy_attacks {
-        if hit {
-            // Store old HP for animation
-            let old_hp = app.game_state.character.current_hp;
+    // Kills may finish quest objectives (and pay out XP)
+    for name in killed {
+        let event = crate::game::quests::QuestEvent::NpcKilled(name.as_str().into());
+        for message in app.game_state.quest_event(event) {
+            app.add_system_message(format!("📜 {}", message));
+        }
+    }
 
-            let str_bonus = (enemy_strength / 2) as i32;
-            let damage = calculate_damage(&enemy_damage, str_bonus, critical);
-            app.game_state.character.take_damage(damage);
+    // The fight is over (won or fled): move the clock on by however long it
+    // lasted, then apply any level ups from the XP earned during it
+    if !app.game_state.combat.active {
+        let minutes = rounds.div_ceil(crate::game::status::ROUNDS_PER_MINUTE);
+        for message in app.game_state.advance_clock(minutes) {
+            app.add_system_message(message);
+        }
+        let character = &app.game_state.character;
+        if character.is_alive() && character.can_level_up() {
+            check_level_up(app);
+        }
+    }
 
-            // Trigger HP drain animation
-            app.animation_manager
-                .start_health_drain(old_hp, app.game_state.character.current_hp);
+    // Won: search the bodies, opening the loot screen unless a level-up
+    // screen is already waiting
+    if !app.game_state.combat.fallen.is_empty() {
+        for message in app.game_state.loot_fallen() {
+            app.add_system_message(format!("💰 {}", message));
+        }
+        if !app.game_state.loot.is_empty() {
+            if app.view_mode == crate::tui::app::ViewMode::LevelUp {
+                app.add_info_message("Type 'loot' to search the bodies.".to_string());
+            } else {
+                app.loot_selected_index = 0;
+                app.set_view_mode(crate::tui::app::ViewMode::Loot);
+            }
+        }
+    }
+}
+
+/// Note on how much of the player's hit an enemy's resistance soaked up
+fn resisted_note(absorbed: i32) -> String {
+    if absorbed > 0 {
+        format!(" ({} resisted)", absorbed)
+    } else {
+        String::new()
+    }
+}
 
-            if critical {
+/// Turn combat events into log messages, animations and view changes
+fn render_combat_events(app: &mut App, events: Vec<crate::game::combat::CombatEvent>) {
+    use crate::game::combat::{CombatEvent, Combatant, Defense, RELOAD_AP_COST};
+    use crate::tui::app::{DeathInfo, ViewMode};
+
+    for event in events {
+        let critical = matches!(event, CombatEvent::Crit { .. });
+        match event {
+            CombatEvent::Hit {
+                attacker: Combatant::Player,
+                target,
+                damage,
+                absorbed,
+                ..
+            } => {
+                start_player_dice_roll(app, 10); // Approximate result
                 app.add_combat_message(format!(
-                    "⚠ {} lands a CRITICAL hit! {} damage!",
-                    enemy_name, damage
+                    "→ Hit! {} damage to {}!{}",
+                    damage,
+                    combatant_name(&target),
+                    resisted_note(absorbed)
+                ));
+            }
+            CombatEvent::Crit {
+                attacker: Combatant::Player,
+                target,
+                damage,
+                absorbed,
+                ..
+            } => {
+                start_player_dice_roll(app, 20);
+                app.add_combat_message(format!(
+                    "⚡ CRITICAL HIT! {} damage to {}!{}",
+                    damage,
+                    combatant_name(&target),
+                    resisted_note(absorbed)
                 ));
-            } else {
-                app.add_combat_message(format!("← {} hits for {} damage!", enemy_name, damage));
             }
+            CombatEvent::Miss {
+                attacker: Combatant::Player,
+                ..
+            } => {
+                start_player_dice_roll(app, 1);
+                app.add_combat_message("✗ Missed!".to_string());
+            }
+            CombatEvent::Hit {
+                attacker,
+                damage,
+                absorbed,
+                remaining_hp,
+                ..
+            }
+            | CombatEvent::Crit {
+                attacker,
+                damage,
+                absorbed,
+                remaining_hp,
+                ..
+            } => {
+                app.animation_manager
+                    .start_health_drain(remaining_hp + damage, remaining_hp);
+
+                let absorbed = if absorbed > 0 {
+                    let defense = Defense::for_character(&app.game_state.character);
+                    format!(
+                        " ({} absorbed {})",
+                        defense.armor_name.as_deref().unwrap_or("Toughness"),
+                        absorbed
+                    )
+                } else {
+                    String::new()
+                };
 
-            // Check if player died
-            if !app.game_state.character.is_alive() {
+                if critical {
+                    app.add_combat_message(format!(
+                        "⚠ {} lands a CRITICAL hit! {} damage!{}",
+                        combatant_name(&attacker),
+                        damage,
+                        absorbed
+                    ));
+                } else {
+                    app.add_combat_message(format!(
+                        "← {} hits for {} damage!{}",
+                        combatant_name(&attacker),
+                        damage,
+                        absorbed
+                    ));
+                }
+            }
+            CombatEvent::Miss { attacker, .. } => {
+                app.add_combat_message(format!("○ {} misses!", combatant_name(&attacker)));
+            }
+            CombatEvent::Click => {
+                app.add_combat_message(format!(
+                    "*Click* Your weapon is empty! Type 'reload' ({} AP) or switch weapons.",
+                    RELOAD_AP_COST
+                ));
+            }
+            CombatEvent::Jammed => {
+                app.add_combat_message(
+                    "*Clunk* Your worn weapon jams! Type 'repair <weapon>' after the fight."
+                        .to_string(),
+                );
+            }
+            CombatEvent::Reloaded {
+                rounds,
+                loaded,
+                magazine,
+                ammo_type,
+                spare,
+            } => {
+                app.add_combat_message(format!(
+                    "⟳ Reloaded {} rounds ({}/{}). {} {} left.",
+                    rounds, loaded, magazine, spare, ammo_type
+                ));
+            }
+            CombatEvent::EnemyDied { index, name } => {
+                app.animation_manager.start_enemy_fadeout(index);
+                app.add_combat_message(format!("✝ {} is defeated!", name));
+            }
+            CombatEvent::XpAwarded { amount } => {
+                let new_xp = app.game_state.character.experience;
+                app.animation_manager
+                    .start_xp_fill(new_xp.saturating_sub(amount), new_xp);
+                app.add_combat_message(format!("+{} XP", amount));
+            }
+            CombatEvent::Victory { total_xp } => {
+                app.add_combat_message(format!(
+                    "🎉 Victory! All enemies defeated! Total XP: +{}",
+                    total_xp
+                ));
+                app.set_view_mode(ViewMode::Normal);
+            }
+            CombatEvent::PlayerDied { killer } => {
                 app.add_combat_message("☠ You have been defeated!".to_string());
-                app.death_info = Some(crate::tui::app::DeathInfo {
+                app.death_info = Some(DeathInfo {
                     location: app.game_state.location.clone(),
                     day: app.game_state.day,
                     level: app.game_state.character.level,
-                    cause: format!("Killed by {}", enemy_name),
+                    cause: format!("Killed by {}", killer),
                 });
-                app.game_state.combat.end_combat();
-                app.set_view_mode(crate::tui::app::ViewMode::GameOver);
-                return;
+                app.set_view_mode(ViewMode::GameOver);
+            }
+            CombatEvent::Fled => {
+                app.add_combat_message("You successfully fled from combat!".to_string());
+                app.set_view_mode(ViewMode::Normal);
             }
-        } else {
-            app.add_combat_message(format!("○ {} misses!", enemy_name));
+            CombatEvent::FleeFailed => {
+                app.add_combat_message("Failed to escape!".to_string());
+            }
+            CombatEvent::Status { message } => app.add_combat_message(format!("☢ {}", message)),
+            CombatEvent::Rejected { reason } => app.add_error_message(reason),
         }
     }
+}
 
-    app.game_state.character.restore_ap();
-    app.game_state.combat.next_round();
+/// Animate the player's d20 roll with their weapon skill modifier
+fn start_player_dice_roll(app: &mut App, result: u8) {
+    let skill = app.game_state.character.get_weapon_skill();
+    app.animation_manager
+        .start_dice_roll(result, (skill as i32 - 10) / 2);
 }
 
-/// Spawn a random combat encounter based on player level
-fn spawn_random_encounter(app: &mut App) {
-    use crate::game::combat::Enemy;
-    use rand::Rng;
-
-    let mut rng = rand::rng();
-    let level = app.game_state.character.level;
-    let encounter_type = rng.random_range(0..3);
-
-    let enemies = match encounter_type {
-        0 => {
-            app.add_combat_message("🐛 Mutated insects emerge from the shadows!".to_string());
-            vec![
-                Enemy::radroach(level),
-                Enemy::radroach(level),
-                Enemy::radroach(level),
-            ]
-        }
-        1 => {
-            app.add_combat_message("⚔ Raiders spot you and attack!".to_string());
-            vec![Enemy::raider(level), Enemy::raider(level)]
+fn combatant_name(combatant: &crate::game::combat::Combatant) -> &str {
+    match combatant {
+        crate::game::combat::Combatant::Player => "You",
+        crate::game::combat::Combatant::Enemy { name, .. } => name,
+    }
+}
+
+/// Reload the equipped weapon; costs AP in combat
+fn reload_weapon(app: &mut App) {
+    if app.is_in_combat() {
+        run_combat_action(app, crate::game::combat::CombatAction::Reload);
+        return;
+    }
+
+    match app.game_state.character.reload_weapon() {
+        Ok(loaded) => {
+            let character = &app.game_state.character;
+            if let Some(stats) = character.equipped_weapon_stats() {
+                let ammo_type = stats.ammo_type.clone().unwrap_or_default();
+                let message = format!(
+                    "⟳ Reloaded {} rounds ({}/{}). {} {} left.",
+                    loaded,
+                    stats.loaded_rounds,
+                    stats.capacity(),
+                    character.ammo_count(&ammo_type),
+                    ammo_type
+                );
+                app.add_combat_message(message);
+            }
         }
-        _ => {
-            app.add_combat_message("👹 A Super Mutant emerges from the ruins!".to_string());
-            vec![Enemy::super_mutant(level)]
+        Err(e) => app.add_error_message(e),
+    }
+}
+
+/// Apply pending level ups after an XP award and open the level-up screen
+/// if there are points to spend
+fn check_level_up(app: &mut App) {
+    let Some(summary) = app.game_state.character.level_up() else {
+        return;
+    };
+
+    app.add_system_message(format!(
+        "★ LEVEL UP! You are now level {}. +{} max HP, +{} skill points.",
+        summary.new_level, summary.hp_gained, summary.skill_points_gained
+    ));
+    if summary.perk_points_gained > 0 {
+        app.add_system_message("★ You can choose a new perk!".to_string());
+    }
+
+    let character = &app.game_state.character;
+    if character.skill_points > 0 || character.perk_points > 0 {
+        app.level_up_selected_index = 0;
+        app.level_up_focus = crate::tui::app::LevelUpFocus::Skills;
+        app.set_view_mode(crate::tui::app::ViewMode::LevelUp);
+    }
+}
+
+/// Validate and apply the structured actions from the DM's last response
+fn apply_dm_actions(app: &mut App) {
+    use crate::game::dm_actions::DmAction;
+
+    for error in std::mem::take(&mut app.pending_action_errors) {
+        tracing::warn!("Ignoring malformed DM action: {}", error);
+    }
+
+    for action in std::mem::take(&mut app.pending_dm_actions) {
+        match action.apply(&mut app.game_state) {
+            Ok(message) => {
+                if matches!(action, DmAction::StartCombat { .. }) {
+                    app.add_combat_message(format!("⚔ {}", message));
+                    app.set_view_mode(crate::tui::app::ViewMode::Combat);
+                    app.add_system_message(
+                        "Combat started! Use 'attack <number>' to fight.".to_string(),
+                    );
+                } else {
+                    app.add_system_message(format!("✓ {}", message));
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Rejected DM action {:?}: {}", action, e);
+                app.add_info_message(format!("[DM action ignored: {}]", e));
+            }
         }
+    }
+
+    // Quest rewards may have pushed the player over a level
+    if !app.is_in_combat() && app.game_state.character.can_level_up() {
+        check_level_up(app);
+    }
+}
+
+/// Spawn a random combat encounter from the encounter tables for the
+/// current location type, player level and time of day
+fn spawn_random_encounter(app: &mut App) {
+    let Some(encounter) = app.game_state.random_encounter() else {
+        app.add_info_message("Nothing stirs. The wasteland is quiet for now.".to_string());
+        return;
     };
 
-    app.game_state.combat.start_combat(enemies);
+    app.add_combat_message(encounter.message);
+    app.game_state.combat.start_combat(encounter.enemies);
     app.set_view_mode(crate::tui::app::ViewMode::Combat);
     app.add_system_message("Combat started! Use 'attack <number>' to fight.".to_string());
 }
 
+/// Parse `rest [hours]` (default 8) or `wait [hours]` (default 1); anything
+/// else (like "wait for the guard to leave") is left for the DM
+fn rest_command(input: &str) -> Option<u32> {
+    let mut words = input.split_whitespace();
+    let default_hours = match words.next()?.to_lowercase().as_str() {
+        "rest" | "sleep" => 8,
+        "wait" => 1,
+        _ => return None,
+    };
+    let hours = match words.next() {
+        None => default_hours,
+        Some(hours) => hours.trim_end_matches('h').parse().ok()?,
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    Some(hours)
+}
+
+/// Parse `roll <dice>`; anything that isn't valid dice (like "roll under the
+/// fence") is left for the DM
+fn dice_command(input: &str) -> Option<crate::game::dice::DiceExpr> {
+    let (command, expression) = input.split_once(' ')?;
+    if !command.eq_ignore_ascii_case("roll") {
+        return None;
+    }
+    expression.parse().ok()
+}
+
 fn show_help(app: &mut App) {
     app.add_system_message("═══ COMMANDS ═══".to_string());
     app.add_info_message("inventory, inv, i  - View your inventory".to_string());
+    app.add_info_message("drop <item> [qty]  - Leave items at your location".to_string());
+    app.add_info_message("pickup [item] [qty] - List or pick up items left here".to_string());
+    app.add_info_message("repair <item>      - Fix worn weapons or armor".to_string());
+    app.add_info_message("craft [recipe]     - Browse recipes or craft one from junk".to_string());
     app.add_info_message("stats, sheet       - View character stats".to_string());
     app.add_info_message("worldbook, wb      - View worldbook".to_string());
     app.add_info_message("equip, equipment   - Equip/unequip items".to_string());
+    app.add_info_message("levelup, perks     - Spend skill points and pick perks".to_string());
+    app.add_info_message("quests, journal, j - View the quest journal".to_string());
+    app.add_info_message("trade <npc>        - Buy and sell with a merchant".to_string());
+    app.add_info_message(
+        "loot, search       - Take items from the last enemies killed".to_string(),
+    );
     app.add_info_message("use <item>         - Use consumable (stimpak, radaway)".to_string());
+    app.add_info_message("rest/wait [hours]  - Rest to heal and pass time".to_string());
+    app.add_info_message(
+        "roll <dice>        - Roll dice, e.g. 'roll 4d6kh3' or 'roll 1d20adv+AGI'".to_string(),
+    );
     app.add_info_message("fight, combat      - Start random combat encounter".to_string());
     app.add_info_message("save [name]        - Save game (default: 'quicksave')".to_string());
     app.add_info_message("help               - Show this help".to_string());
+    app.add_info_message(
+        "recap, story       - Recap the story so far, chapter by chapter".to_string(),
+    );
     app.add_info_message("debug, context     - Show AI conversation context".to_string());
     app.add_info_message("quit, exit         - Exit game".to_string());
     app.add_system_message("".to_string());
     app.add_system_message("In combat:".to_string());
-    app.add_info_message("attack <number>    - Attack enemy".to_string());
+    app.add_info_message("attack <number>    - Attack enemy (costs weapon AP)".to_string());
+    app.add_info_message("reload             - Reload your weapon (2 AP)".to_string());
+    app.add_info_message("end                - End your turn".to_string());
     app.add_info_message("use <item>         - Use consumable item".to_string());
     app.add_info_message("run, flee          - Attempt to flee".to_string());
     app.add_system_message("".to_string());
@@ -850,7 +1668,7 @@ fn show_help(app: &mut App) {
     app.add_system_message("Use PageUp/PageDown to scroll messages".to_string());
 }
 
-fn show_debug_context(app: &mut App) {
+fn show_debug_context(app: &mut App, ai_dm: &AIDungeonMaster) {
     app.add_system_message("═══ CONVERSATION CONTEXT DEBUG ═══".to_string());
     app.add_info_message(format!(
         "Total turns: {}",
@@ -860,6 +1678,7 @@ fn show_debug_context(app: &mut App) {
         "Max turns: {}",
         app.game_state.conversation.max_turns()
     ));
+    app.add_info_message(format!("RNG seed: {}", app.game_state.rng.seed()));
     app.add_system_message("".to_string());
     app.add_system_message("Recent conversation history (last 10 turns):".to_string());
 
@@ -883,6 +1702,75 @@ fn show_debug_context(app: &mut App) {
     app.add_system_message("".to_string());
     app.add_system_message("Legacy story context:".to_string());
     app.add_info_message(format!("Total events: {}", app.game_state.story.len()));
+
+    app.add_system_message("".to_string());
+    app.add_system_message("Story memory:".to_string());
+    app.add_info_message(format!(
+        "Chapters: {}",
+        app.game_state.story_memory.chapters.len()
+    ));
+    app.add_info_message(format!(
+        "Summarized through turn: {}",
+        app.game_state
+            .story_memory
+            .summarized_through
+            .map_or("none".to_string(), |turn| turn.to_string())
+    ));
+    app.add_info_message(format!(
+        "Evicted turns awaiting summary: {}",
+        app.game_state.conversation.evicted().len()
+    ));
+
+    app.add_system_message("".to_string());
+    app.add_system_message("Last prompt:".to_string());
+    match ai_dm.last_prompt_report() {
+        Some(report) => {
+            app.add_info_message(format!(
+                "{} of {} tokens (context window less the response's max tokens)",
+                report.tokens, report.budget
+            ));
+            app.add_info_message(if ai_dm.tokenizer().is_model_accurate() {
+                "Counted with the model's tokenizer".to_string()
+            } else {
+                "Counted with the cl100k estimate (server tokenizer unavailable)".to_string()
+            });
+            for section in &report.sections {
+                app.add_info_message(format!("  {}", section.summary()));
+            }
+            if report.trimmed().next().is_none() {
+                app.add_info_message("Nothing was trimmed".to_string());
+            }
+            if !report.fits() {
+                app.add_error_message(
+                    "The untrimmable sections alone are over budget - lower max_tokens or raise context_window"
+                        .to_string(),
+                );
+            }
+        }
+        None => app.add_info_message("(No prompt sent yet)".to_string()),
+    }
+}
+
+fn show_story_recap(app: &mut App) {
+    if app.game_state.story_memory.chapters.is_empty() {
+        app.add_info_message(
+            "Nothing to recap yet - the story so far is summarized as it grows.".to_string(),
+        );
+        return;
+    }
+
+    app.add_system_message("═══ THE STORY SO FAR ═══".to_string());
+    let chapters: Vec<(String, String)> = app
+        .game_state
+        .story_memory
+        .chapters
+        .iter()
+        .map(|chapter| (chapter.heading(), chapter.summary.clone()))
+        .collect();
+    for (heading, summary) in chapters {
+        app.add_system_message(heading);
+        app.add_info_message(summary);
+    }
 }
 
 /// Handle mouse events for scrolling and interaction
@@ -924,7 +1812,13 @@ async fn handle_skill_check_if_needed(
         }
 
         // Perform the roll automatically
-        let result = perform_roll(&app.game_state.character, &skill_or_stat, dc);
+        let result = perform_roll_at(
+            &app.game_state.character,
+            &skill_or_stat,
+            dc,
+            app.game_state.time.period(),
+            &mut app.game_state.rng,
+        );
 
         // Format roll result using the RollResult methods
         let roll_msg = format!("{} {}", result.emoji(), result.format());
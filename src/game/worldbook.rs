@@ -16,7 +16,7 @@
 //! - Name, description, and type (settlement, ruin, vault, wasteland)
 //! - NPCs present at the location
 //! - Visit history (first visit, last visit, visit count)
-//! - Custom state (quest flags, environmental changes)
+//! - Custom state (quest flags, environmental changes, items the player dropped there)
 //! - Player notes
 //!
 //! ## NPC System
@@ -102,6 +102,10 @@
 //! println!("Known locations: {}", worldbook.locations.len());
 //! ```
 
+use super::barter::Merchant;
+use super::clock;
+use super::crafting;
+use super::items::{Inventory, Item};
 use serde::{Deserialize, Serialize};
 use smartstring::alias::String as SmartString;
 use std::collections::HashMap;
@@ -118,6 +122,16 @@ pub struct Worldbook {
     pub npcs: HashMap<SmartString, NPC>,
     pub events: Vec<WorldEvent>,
     pub current_location: Option<SmartString>,
+    /// Current game time, used to timestamp visits and events
+    #[serde(default = "default_current_time")]
+    pub current_time: SmartString,
+    /// Stock and caps of merchant NPCs the player has traded with, by NPC ID
+    #[serde(default)]
+    pub merchants: HashMap<SmartString, Merchant>,
+}
+
+fn default_current_time() -> SmartString {
+    SmartString::from(clock::start_timestamp())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +151,68 @@ pub struct Location {
     pub state: HashMap<SmartString, SmartString>, // Custom key-value state
 }
 
+/// Prefix of `Location::state` keys holding an item stack left at the location,
+/// followed by its [`Item::key`] (the value is the item as JSON)
+pub const DROPPED_ITEM_PREFIX: &str = "dropped:";
+
+impl Location {
+    /// Whether recipes that need a workbench can be crafted here
+    pub fn has_workbench(&self) -> bool {
+        self.state
+            .get(crafting::WORKBENCH_STATE)
+            .is_some_and(|value| value == "true")
+    }
+
+    /// Mark this location as having a workbench
+    pub fn add_workbench(&mut self) {
+        self.state.insert(
+            SmartString::from(crafting::WORKBENCH_STATE),
+            SmartString::from("true"),
+        );
+    }
+
+    /// Item stacks the player left here, sorted by name (and key)
+    pub fn dropped_items(&self) -> Vec<Item> {
+        let mut items: Vec<Item> = self
+            .state
+            .iter()
+            .filter(|(key, _)| key.starts_with(DROPPED_ITEM_PREFIX))
+            .filter_map(|(_, value)| serde_json::from_str(value).ok())
+            .collect();
+        items.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.key().cmp(&b.key())));
+        items
+    }
+
+    /// Replace the items left here with `pile`
+    pub(crate) fn store_dropped_items(&mut self, pile: &Inventory) {
+        self.state
+            .retain(|key, _| !key.starts_with(DROPPED_ITEM_PREFIX));
+        for item in pile {
+            if let Ok(value) = serde_json::to_string(item) {
+                let key = format!("{}{}", DROPPED_ITEM_PREFIX, item.key());
+                self.state.insert(key.into(), value.into());
+            }
+        }
+    }
+
+    /// Leave an item here, stacking it as in an [`Inventory`] so worn or
+    /// modded gear is picked up again just as it was
+    pub fn leave_item(&mut self, item: Item) {
+        let mut pile = Inventory::from(self.dropped_items());
+        pile.add(item);
+        self.store_dropped_items(&pile);
+    }
+
+    /// Pick `qty` (or all) of a dropped item back up, by ID or name
+    pub fn take_item(&mut self, query: &str, qty: Option<u32>) -> Option<Item> {
+        let mut pile = Inventory::from(self.dropped_items());
+        let index = pile.find(query)?;
+        let item = pile.split(index, qty.unwrap_or(u32::MAX).min(pile[index].quantity))?;
+        self.store_dropped_items(&pile);
+        Some(item)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct NPC {
@@ -153,6 +229,16 @@ pub struct NPC {
     pub alive: bool,
 }
 
+impl NPC {
+    /// Whether the NPC buys and sells goods
+    pub fn is_merchant(&self) -> bool {
+        matches!(
+            self.role.to_lowercase().as_str(),
+            "merchant" | "trader" | "vendor" | "shopkeeper"
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldEvent {
     pub timestamp: SmartString,
@@ -169,17 +255,20 @@ impl Worldbook {
             npcs: HashMap::new(),
             events: Vec::new(),
             current_location: None,
+            current_time: default_current_time(),
+            merchants: HashMap::new(),
         }
     }
 
     /// Creates a new worldbook with default Fallout universe locations.
     ///
-    /// Includes Vault 13 as the starting location with appropriate description.
+    /// Includes Vault 13 as the starting location with appropriate description,
+    /// plus the settlements of Megaton and Rivet City. All three have a workbench.
     pub fn with_defaults() -> Self {
         let mut worldbook = Self::new();
 
         // Add Vault 13 as the default starting location
-        let vault_13 = Location {
+        let mut vault_13 = Location {
             id: SmartString::from("vault_13"),
             name: SmartString::from("Vault 13"),
             name_lowercase: SmartString::from("vault 13"),
@@ -193,9 +282,42 @@ impl Worldbook {
             notes: vec![],
             state: HashMap::new(),
         };
-
+        vault_13.add_workbench();
         worldbook.add_location(vault_13);
 
+        let settlements = [
+            (
+                "megaton",
+                "Megaton",
+                "A ramshackle town of scrap metal built in a crater around an unexploded atomic bomb. Traders, drifters and settlers live under Sheriff Simms' watchful eye.",
+                "Busy and wary. Scrap walls creak in the wind and the bomb ticks over in the town's heart.",
+            ),
+            (
+                "rivet_city",
+                "Rivet City",
+                "A broken aircraft carrier in the Potomac, turned into the safest city in the Capital Wasteland. Its market and labs draw traders from all around.",
+                "Crowded and guarded. Gulls cry over the flight deck and the hull groans with the tide.",
+            ),
+        ];
+        for (id, name, description, atmosphere) in settlements {
+            let mut settlement = Location {
+                id: SmartString::from(id),
+                name: SmartString::from(name),
+                name_lowercase: SmartString::new(),
+                description: SmartString::from(description),
+                location_type: SmartString::from("settlement"),
+                npcs_present: vec![],
+                atmosphere: Some(SmartString::from(atmosphere)),
+                first_visited: None,
+                last_visited: None,
+                visit_count: 0,
+                notes: vec![],
+                state: HashMap::new(),
+            };
+            settlement.add_workbench();
+            worldbook.add_location(settlement);
+        }
+
         worldbook
     }
 
@@ -213,8 +335,7 @@ impl Worldbook {
 
     /// Populate cached lowercase name fields for all locations and NPCs
     /// Call this after deserialization since cached fields are not serialized
-    #[allow(dead_code)]
-    fn populate_caches(&mut self) {
+    pub(crate) fn populate_caches(&mut self) {
         for location in self.locations.values_mut() {
             location.name_lowercase = location.name.to_lowercase().into();
         }
@@ -259,6 +380,20 @@ impl Worldbook {
         self.npcs.get(id)
     }
 
+    /// Resolve an NPC reference (ID or display name) to its worldbook ID
+    pub fn find_npc_id(&self, npc: &str) -> Option<SmartString> {
+        let id = Self::generate_id(npc.trim());
+        if self.npcs.contains_key(&id) {
+            return Some(id);
+        }
+
+        let lower = npc.trim().to_lowercase();
+        self.npcs
+            .values()
+            .find(|n| n.name.to_lowercase() == lower)
+            .map(|n| n.id.clone())
+    }
+
     // Get all NPCs at a location
     pub fn get_npcs_at_location(&self, location_id: &str) -> Vec<&NPC> {
         self.npcs
@@ -282,15 +417,20 @@ impl Worldbook {
         self.current_location = location_id;
     }
 
+    // Update the game time used for new timestamps
+    pub fn set_current_time(&mut self, timestamp: impl Into<SmartString>) {
+        self.current_time = timestamp.into();
+    }
+
     // Visit a location (update visit count and timestamp)
     pub fn visit_location(&mut self, location_id: &str) {
         if let Some(location) = self.locations.get_mut(location_id) {
-            let now = chrono::Utc::now().to_rfc3339();
+            let now = self.current_time.clone();
 
             if location.first_visited.is_none() {
-                location.first_visited = Some(SmartString::from(now.clone()));
+                location.first_visited = Some(now.clone());
             }
-            location.last_visited = Some(SmartString::from(now));
+            location.last_visited = Some(now);
             location.visit_count += 1;
         }
     }
@@ -381,9 +521,12 @@ mod tests {
     fn test_worldbook_with_defaults() {
         let wb = Worldbook::with_defaults();
 
-        // Should have Vault 13 by default
-        assert_eq!(wb.locations.len(), 1);
+        // Should have Vault 13 and the default settlements
+        assert_eq!(wb.locations.len(), 3);
         assert!(wb.locations.contains_key("vault_13"));
+        for id in ["vault_13", "megaton", "rivet_city"] {
+            assert!(wb.get_location(id).unwrap().has_workbench());
+        }
 
         // Verify Vault 13 details
         let vault_13 = wb.get_location("vault_13").unwrap();
@@ -421,6 +564,40 @@ mod tests {
         assert_eq!(added_loc.name_lowercase, "megaton");
     }
 
+    #[test]
+    fn test_dropped_items() {
+        let mut wb = Worldbook::with_defaults();
+        let vault = wb.locations.get_mut("vault_13").unwrap();
+        let mut cans = Item::new_misc("tin_can", "Tin Can", "Empty.", 0.5, 1);
+        cans.quantity = 3;
+
+        vault.leave_item(cans.clone());
+        vault.leave_item(cans);
+        assert_eq!(vault.dropped_items().len(), 1);
+        assert_eq!(vault.dropped_items()[0].quantity, 6);
+
+        let taken = vault.take_item("tin can", Some(4)).unwrap();
+        assert_eq!(taken.quantity, 4);
+        assert_eq!(vault.dropped_items()[0].quantity, 2);
+        assert_eq!(vault.take_item("tin_can", None).unwrap().quantity, 2);
+        assert!(vault.dropped_items().is_empty());
+        assert!(vault.take_item("tin_can", None).is_none());
+
+        // Worn gear keeps its condition and doesn't merge with a fresh copy
+        let fresh = crate::game::items::find_item("10mm_pistol").unwrap();
+        let mut worn = fresh.clone();
+        if let crate::game::items::ItemType::Weapon(ref mut stats) = worn.item_type {
+            stats.condition = 20;
+        }
+        vault.leave_item(worn);
+        vault.leave_item(fresh);
+        assert_eq!(vault.dropped_items().len(), 2);
+        let conditions: Vec<_> = (0..2)
+            .map(|_| vault.take_item("10mm_pistol", None).unwrap().condition())
+            .collect();
+        assert_eq!(conditions, vec![Some(20), Some(100)]);
+    }
+
     #[test]
     fn test_add_npc() {
         let mut wb = Worldbook::new();
@@ -0,0 +1,332 @@
+//! # Worldbook Retrieval
+//!
+//! Picks the worldbook entries worth telling the AI DM about beyond the
+//! current location: an NPC the player names from across the wasteland, the
+//! town they're asking about, the fight they're still angry about.
+//!
+//! ## Ranking
+//!
+//! Every NPC, location and event is an entry in a small [`Bm25Index`], built
+//! from its name, description, notes and the names of what it involves. The
+//! query is the player's action and the last few turns. An entry whose full
+//! name appears in the query (matched against the cached `name_lowercase`)
+//! gets [`NAME_MATCH_BOOST`] on top of its BM25 score.
+//!
+//! ## Budget
+//!
+//! Matching entries are added best first until the token budget is spent.
+//! The current location, the NPCs there and its recent events are already in
+//! [`Worldbook::build_context`], so they're left out.
+//!
+//! Everything is computed locally; no embedding server is needed.
+
+use super::worldbook::Worldbook;
+use std::collections::{HashMap, HashSet};
+
+/// Added to the score of an entry whose full name appears in the query
+pub const NAME_MATCH_BOOST: f32 = 5.0;
+/// Recent events at the current location already in the location context
+const LOCATION_EVENTS: usize = 3;
+/// BM25 term frequency saturation
+const K1: f32 = 1.2;
+/// BM25 document length normalization
+const B: f32 = 0.75;
+
+/// Words too common to say anything about relevance
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "do", "for", "from", "go", "he", "her",
+    "his", "i", "if", "in", "into", "is", "it", "its", "me", "my", "of", "on", "or", "she", "so",
+    "that", "the", "their", "them", "then", "there", "they", "this", "to", "up", "was", "we",
+    "what", "with", "you", "your",
+];
+
+/// Lowercase words of a text, without stop words
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Okapi BM25 over a fixed set of documents
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    /// Term counts of each document
+    documents: Vec<HashMap<String, u32>>,
+    lengths: Vec<usize>,
+    average_length: f32,
+    /// Number of documents each term appears in
+    document_frequency: HashMap<String, usize>,
+}
+
+impl Bm25Index {
+    pub fn new<'a>(documents: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut index = Bm25Index::default();
+        for document in documents {
+            let words = tokenize(document);
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for word in &words {
+                *counts.entry(word.clone()).or_default() += 1;
+            }
+            for word in counts.keys() {
+                *index.document_frequency.entry(word.clone()).or_default() += 1;
+            }
+            index.lengths.push(words.len());
+            index.documents.push(counts);
+        }
+        if !index.lengths.is_empty() {
+            index.average_length =
+                index.lengths.iter().sum::<usize>() as f32 / index.lengths.len() as f32;
+        }
+        index
+    }
+
+    /// Score of every document against the query, in document order
+    pub fn scores(&self, query: &str) -> Vec<f32> {
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let total = self.documents.len() as f32;
+        self.documents
+            .iter()
+            .zip(&self.lengths)
+            .map(|(counts, &length)| {
+                terms
+                    .iter()
+                    .filter_map(|term| {
+                        let frequency = *counts.get(term)? as f32;
+                        let containing = self.document_frequency[term] as f32;
+                        let idf = (1.0 + (total - containing + 0.5) / (containing + 0.5)).ln();
+                        let norm = 1.0 - B + B * length as f32 / self.average_length.max(1.0);
+                        Some(idf * frequency * (K1 + 1.0) / (frequency + K1 * norm))
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// A worldbook entry that can be retrieved for the prompt
+struct Entry {
+    /// What's indexed
+    document: String,
+    /// Lowercase name matched as a phrase, if the entry has one
+    name: Option<String>,
+    /// The line that goes in the prompt
+    line: String,
+}
+
+/// Whether `phrase` appears in `text` as whole words
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    !phrase.is_empty()
+        && text.match_indices(phrase).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + phrase.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
+
+impl Worldbook {
+    /// Entries not already covered by the current location's context
+    fn retrieval_entries(&self) -> Vec<Entry> {
+        let current = self.current_location.as_deref();
+        let location_name = |id: &str| -> String {
+            self.get_location(id)
+                .map_or_else(|| id.to_string(), |location| location.name.to_string())
+        };
+        let entity_name = |id: &str| -> String {
+            self.get_npc(id)
+                .map(|npc| npc.name.to_string())
+                .unwrap_or_else(|| location_name(id))
+        };
+        let mut entries = Vec::new();
+
+        let mut npcs: Vec<_> = self
+            .npcs
+            .values()
+            .filter(|npc| current.is_none() || npc.current_location.as_deref() != current)
+            .collect();
+        npcs.sort_by(|a, b| a.id.cmp(&b.id));
+        for npc in npcs {
+            let whereabouts = npc.current_location.as_deref().map(location_name);
+            let mut line = format!("NPC: {} ({})", npc.name, npc.role);
+            if let Some(place) = &whereabouts {
+                line.push_str(&format!(", last seen at {}", place));
+            }
+            line.push_str(&format!(", disposition: {}", npc.disposition));
+            if !npc.alive {
+                line.push_str(", dead");
+            }
+            if !npc.knowledge.is_empty() {
+                line.push_str(&format!(", knows about: {}", npc.knowledge.join(", ")));
+            }
+            if !npc.notes.is_empty() {
+                line.push_str(&format!(" - {}", npc.notes));
+            }
+            entries.push(Entry {
+                document: format!(
+                    "{} {} {} {} {} {}",
+                    npc.name,
+                    npc.role,
+                    npc.personality.join(" "),
+                    npc.knowledge.join(" "),
+                    npc.notes,
+                    whereabouts.unwrap_or_default()
+                ),
+                name: Some(npc.name_lowercase.to_string()),
+                line,
+            });
+        }
+
+        let mut locations: Vec<_> = self
+            .locations
+            .values()
+            .filter(|location| Some(location.id.as_str()) != current)
+            .collect();
+        locations.sort_by(|a, b| a.id.cmp(&b.id));
+        for location in locations {
+            let mut line = format!(
+                "Location: {} ({}) - {}",
+                location.name, location.location_type, location.description
+            );
+            if location.visit_count > 0 {
+                line.push_str(&format!(" (visited {} times)", location.visit_count));
+            }
+            if !location.notes.is_empty() {
+                line.push_str(&format!(" Notes: {}", location.notes.join("; ")));
+            }
+            entries.push(Entry {
+                document: format!(
+                    "{} {} {} {}",
+                    location.name,
+                    location.location_type,
+                    location.description,
+                    location.notes.join(" ")
+                ),
+                name: Some(location.name_lowercase.to_string()),
+                line,
+            });
+        }
+
+        let mut shown_here = current.map_or(0, |_| LOCATION_EVENTS);
+        for event in self.events.iter().rev() {
+            if current.is_some() && event.location.as_deref() == current && shown_here > 0 {
+                shown_here -= 1;
+                continue;
+            }
+            let place = event.location.as_deref().map(location_name);
+            let involved: Vec<String> = event.entities.iter().map(|id| entity_name(id)).collect();
+            let mut line = format!("Event ({}): {}", event.event_type, event.description);
+            if let Some(place) = &place {
+                line.push_str(&format!(" [at {}]", place));
+            }
+            entries.push(Entry {
+                document: format!(
+                    "{} {} {} {}",
+                    event.event_type,
+                    event.description,
+                    involved.join(" "),
+                    place.unwrap_or_default()
+                ),
+                name: None,
+                line,
+            });
+        }
+
+        entries
+    }
+
+    /// Worldbook entries relevant to `query` that aren't already in
+    /// [`Worldbook::build_context`], best first, within `budget` tokens
+    pub fn relevant_entries(
+        &self,
+        query: &str,
+        budget: usize,
+        count_tokens: impl Fn(&str) -> usize,
+    ) -> Vec<String> {
+        let entries = self.retrieval_entries();
+        if entries.is_empty() || budget == 0 {
+            return Vec::new();
+        }
+        let index = Bm25Index::new(entries.iter().map(|entry| entry.document.as_str()));
+        let query_lower = query.to_lowercase();
+
+        let mut ranked: Vec<(f32, &Entry)> = index
+            .scores(query)
+            .into_iter()
+            .zip(&entries)
+            .map(|(score, entry)| {
+                let named = entry
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| contains_phrase(&query_lower, name));
+                (score + if named { NAME_MATCH_BOOST } else { 0.0 }, entry)
+            })
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut lines = Vec::new();
+        let mut used = 0;
+        for (_, entry) in ranked {
+            let cost = count_tokens(&entry.line);
+            if used + cost > budget {
+                continue;
+            }
+            used += cost;
+            lines.push(entry.line.clone());
+        }
+        lines
+    }
+
+    /// Lines from [`Worldbook::relevant_entries`] as a prompt section (empty
+    /// if there are none)
+    pub fn format_relevant_context(lines: &[String]) -> String {
+        if lines.is_empty() {
+            return String::new();
+        }
+        let mut context = String::from("\n=== ALSO RELEVANT ===\n");
+        for line in lines {
+            context.push_str(&format!("  - {}\n", line));
+        }
+        context.push_str("===\n");
+        context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bm25_prefers_rare_terms() {
+        let index = Bm25Index::new([
+            "the sheriff of megaton",
+            "a merchant in megaton",
+            "a ghoul in the underworld",
+        ]);
+        let scores = index.scores("where is the sheriff");
+        assert_eq!(scores.len(), 3);
+        assert!(scores[0] > 0.0);
+        assert_eq!(scores[1], 0.0);
+        assert_eq!(scores[2], 0.0);
+
+        // "megaton" is in two documents, "ghoul" in one
+        let scores = index.scores("megaton ghoul");
+        assert!(scores[2] > scores[0]);
+    }
+
+    #[test]
+    fn test_tokenize_drops_stop_words() {
+        assert_eq!(
+            tokenize("I ask Moira about the Vault-Tec rep."),
+            vec!["ask", "moira", "about", "vault", "tec", "rep"]
+        );
+    }
+
+    #[test]
+    fn test_contains_phrase_matches_whole_words() {
+        assert!(contains_phrase("have you seen lucas simms?", "lucas simms"));
+        assert!(!contains_phrase("lucas simmsville", "lucas simms"));
+        assert!(!contains_phrase("anything", ""));
+    }
+}
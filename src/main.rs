@@ -6,6 +6,8 @@ mod templates;
 mod tui;
 mod ui;
 mod validation;
+#[allow(dead_code)] // Only the data file validators are used by the game itself
+mod validation_garde;
 
 use ai::extractor::ExtractionAI;
 use ai::server_manager::{ServerConfig, ServerManager};
@@ -33,7 +35,7 @@ async fn main() {
     UI::print_header();
 
     // Load config
-    let config = match Config::load_with_env() {
+    let mut config = match Config::load_with_env() {
         Ok(cfg) => cfg,
         Err(_) => {
             UI::print_info("No config.toml found, using defaults.");
@@ -41,6 +43,19 @@ async fn main() {
         }
     };
 
+    // Check the game data before anything else; once the TUI is up, a bad
+    // data file couldn't be reported cleanly
+    if let Err(e) = game::load_game_data() {
+        UI::print_error(&format!("{}", e));
+        std::process::exit(1);
+    }
+
+    // Command line options override config.toml
+    if let Some(seed) = seed_from_args(std::env::args().skip(1)) {
+        UI::print_info(&format!("Using RNG seed '{}'", seed));
+        config.game.seed = Some(seed);
+    }
+
     // Initialize server manager and auto-start servers if configured
     let server_manager = if config.llama.auto_start {
         UI::print_info("Auto-start enabled. Checking AI servers...");
@@ -119,6 +134,7 @@ async fn main() {
 
     // Initialize AI DM
     let ai_dm = AIDungeonMaster::new(config.llama.clone());
+    tracing::info!("Using {} backend for narrative AI", ai_dm.backend().name());
 
     // Test llama.cpp connection
     UI::print_info("Verifying connection to narrative AI server...");
@@ -143,7 +159,7 @@ async fn main() {
 
     // Test extraction AI connection
     UI::print_info("Verifying connection to extraction AI server...");
-    let extractor = ExtractionAI::new(config.llama.extraction_url.clone());
+    let extractor = ExtractionAI::from_config(&config.llama);
     match extractor.test_connection().await {
         Ok(_) => UI::print_success(&format!(
             "Connected to extraction AI at {}",
@@ -219,6 +235,19 @@ async fn main() {
     tracing::info!("Game exited normally");
 }
 
+/// Read `--seed <value>` or `--seed=<value>` from the command line
+fn seed_from_args(mut args: impl Iterator<Item = String>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next();
+        }
+        if let Some(seed) = arg.strip_prefix("--seed=") {
+            return Some(seed.to_string());
+        }
+    }
+    None
+}
+
 /// Initialize tracing subscriber for logging
 fn init_logging() {
     use tracing_subscriber::{
@@ -3,9 +3,13 @@
 //! This module provides a centralized template rendering system for all AI prompts,
 //! separating prompt logic from code and making prompts easier to maintain.
 
+use crate::config::ChatTemplate;
 use crate::error::GameError;
+use crate::game::bestiary::bestiary;
+use crate::game::items::registry;
 use once_cell::sync::Lazy;
 use serde::Serialize;
+use std::sync::Arc;
 use tera::Tera;
 
 /// Global template engine instance
@@ -29,6 +33,8 @@ pub struct CharacterContext {
     pub caps: u32,
     pub special: SpecialStats,
     pub skills: SkillsContext,
+    /// Active status effects (radiation, hunger, chems, addictions)
+    pub status: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -66,10 +72,33 @@ pub struct CombatContext {
     pub enemies: Vec<EnemyContext>,
 }
 
+/// Speaker of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    /// The player
+    User,
+    /// The DM
+    Assistant,
+}
+
+/// One turn for chat templates
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
 /// Render the system prompt template
 pub fn render_system_prompt() -> Result<String, GameError> {
+    let mut context = tera::Context::new();
+    let item_ids: Vec<&str> = registry().ids().collect();
+    context.insert("item_ids", &item_ids);
+    let enemy_ids: Vec<&str> = bestiary().ids().collect();
+    context.insert("enemy_ids", &enemy_ids);
+
     TEMPLATES
-        .render("system_prompt.tera", &tera::Context::new())
+        .render("system_prompt.tera", &context)
         .map_err(|e| GameError::InvalidInput(format!("Failed to render system prompt: {}", e)))
 }
 
@@ -83,11 +112,29 @@ pub fn render_extractor_prompt(narrative: &str) -> Result<String, GameError> {
         .map_err(|e| GameError::InvalidInput(format!("Failed to render extractor prompt: {}", e)))
 }
 
-/// Render the game context template with character, inventory, combat, and conversation data
+/// Render the story summary prompt, folding `turns` into a chapter's summary
+pub fn render_summary_prompt(
+    chapter: u32,
+    previous: &str,
+    turns: &[String],
+) -> Result<String, GameError> {
+    let mut context = tera::Context::new();
+    context.insert("chapter", &chapter);
+    context.insert("previous", previous);
+    context.insert("turns", turns);
+
+    TEMPLATES
+        .render("summary.tera", &context)
+        .map_err(|e| GameError::InvalidInput(format!("Failed to render summary prompt: {}", e)))
+}
+
+/// Render the game context template with character, inventory, combat, story
+/// summary and conversation data
 pub fn render_context(
     character: Option<&CharacterContext>,
     inventory: Option<&[String]>,
     combat: Option<&CombatContext>,
+    story_so_far: Option<&[String]>,
     conversation_history: Option<&[String]>,
 ) -> Result<String, GameError> {
     let mut context = tera::Context::new();
@@ -104,6 +151,10 @@ pub fn render_context(
         context.insert("combat", combat_data);
     }
 
+    if let Some(chapters) = story_so_far {
+        context.insert("story_so_far", chapters);
+    }
+
     if let Some(history) = conversation_history {
         context.insert("conversation_history", history);
     }
@@ -113,6 +164,101 @@ pub fn render_context(
         .map_err(|e| GameError::InvalidInput(format!("Failed to render context: {}", e)))
 }
 
+/// Render the story so far section on its own (chapter summaries, oldest first)
+pub fn render_story_so_far(chapters: &[String]) -> Result<String, GameError> {
+    let mut context = tera::Context::new();
+    context.insert("story_so_far", chapters);
+
+    TEMPLATES
+        .render("story_so_far.tera", &context)
+        .map_err(|e| GameError::InvalidInput(format!("Failed to render story so far: {}", e)))
+}
+
+/// Render the conversation history section on its own
+pub fn render_conversation_history(messages: &[String]) -> Result<String, GameError> {
+    let mut context = tera::Context::new();
+    context.insert("conversation_history", messages);
+
+    TEMPLATES
+        .render("conversation_history.tera", &context)
+        .map_err(|e| {
+            GameError::InvalidInput(format!("Failed to render conversation history: {}", e))
+        })
+}
+
+/// Name of a custom chat template inside its own Tera instance
+const CUSTOM_CHAT_TEMPLATE: &str = "custom";
+
+/// A chat template ready to render prompts, with a custom template's file
+/// read and parsed once up front
+#[derive(Clone, Default)]
+pub struct ChatFormat {
+    template: ChatTemplate,
+    custom: Option<Arc<Tera>>,
+}
+
+impl ChatFormat {
+    /// Load a chat template, reading and checking `custom_file` for
+    /// [`ChatTemplate::Custom`]
+    pub fn load(template: ChatTemplate, custom_file: Option<&str>) -> Result<Self, GameError> {
+        let custom = match (template, custom_file) {
+            (ChatTemplate::Custom, Some(path)) => {
+                let source = std::fs::read_to_string(path).map_err(|e| {
+                    GameError::InvalidInput(format!("Failed to read chat template {}: {}", path, e))
+                })?;
+                let mut tera = Tera::default();
+                tera.add_raw_template(CUSTOM_CHAT_TEMPLATE, &source)
+                    .map_err(|e| {
+                        GameError::InvalidInput(format!(
+                            "Failed to parse chat template {}: {}",
+                            path, e
+                        ))
+                    })?;
+                Some(Arc::new(tera))
+            }
+            (ChatTemplate::Custom, None) => {
+                return Err(GameError::InvalidInput(
+                    "No chat template file for a custom chat template".to_string(),
+                ))
+            }
+            _ => None,
+        };
+
+        let format = ChatFormat { template, custom };
+        if template != ChatTemplate::Plain {
+            // Catch templates that parse but can't render (e.g. unknown variables)
+            format.render("", &[])?;
+        }
+        Ok(format)
+    }
+
+    /// The template prompts are laid out in
+    pub fn template(&self) -> ChatTemplate {
+        self.template
+    }
+
+    /// Render a prompt in the template's format: the system text, then the
+    /// messages, ending where the DM's reply starts
+    pub fn render(&self, system: &str, messages: &[ChatMessage]) -> Result<String, GameError> {
+        let mut context = tera::Context::new();
+        context.insert("system", system);
+        context.insert("messages", messages);
+
+        let rendered = match (self.template.template_name(), &self.custom) {
+            (Some(name), _) => TEMPLATES.render(name, &context),
+            (None, Some(tera)) => tera.render(CUSTOM_CHAT_TEMPLATE, &context),
+            (None, None) => {
+                return Err(GameError::InvalidInput(format!(
+                    "No chat template to render for {:?}",
+                    self.template
+                )))
+            }
+        };
+        rendered
+            .map_err(|e| GameError::InvalidInput(format!("Failed to render chat template: {}", e)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,9 +282,115 @@ mod tests {
         assert!(prompt.contains("entity extractor"));
     }
 
+    #[test]
+    fn test_render_summary_prompt() {
+        let turns = vec!["Player: I leave the vault".to_string()];
+        let prompt = render_summary_prompt(2, "You woke up.", &turns).unwrap();
+        assert!(prompt.contains("chapter 2"));
+        assert!(prompt.contains("You woke up."));
+        assert!(prompt.contains("Player: I leave the vault"));
+        assert!(prompt.contains("TITLE:"));
+    }
+
+    #[test]
+    fn test_render_context_with_story_so_far() {
+        let chapters = vec!["Chapter 1: Exodus\nYou left Vault 13.".to_string()];
+        let context = render_context(None, None, None, Some(&chapters), None).unwrap();
+        assert!(context.contains("=== STORY SO FAR ==="));
+        assert!(context.contains("You left Vault 13."));
+    }
+
+    #[test]
+    fn test_render_sections_match_context() {
+        let chapters = vec!["Chapter 1: Exodus\nYou left Vault 13.".to_string()];
+        let history = vec!["Player: Hello".to_string()];
+        let context = render_context(None, None, None, Some(&chapters), Some(&history)).unwrap();
+        let story = render_story_so_far(&chapters).unwrap();
+        let conversation = render_conversation_history(&history).unwrap();
+        assert!(context.contains(&story));
+        assert!(context.contains(&conversation));
+        assert!(render_conversation_history(&[]).unwrap().trim().is_empty());
+    }
+
+    fn chat() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage {
+                role: ChatRole::User,
+                content: "I enter Megaton".to_string(),
+            },
+            ChatMessage {
+                role: ChatRole::Assistant,
+                content: "The gate creaks open.".to_string(),
+            },
+            ChatMessage {
+                role: ChatRole::User,
+                content: "Find the sheriff".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_chat_prompt_builtins() {
+        let render = |template| {
+            ChatFormat {
+                template,
+                custom: None,
+            }
+            .render("You are the DM.\n", &chat())
+        };
+
+        assert_eq!(
+            render(ChatTemplate::ChatMl).unwrap(),
+            "<|im_start|>system\nYou are the DM.<|im_end|>\n\
+             <|im_start|>user\nI enter Megaton<|im_end|>\n\
+             <|im_start|>assistant\nThe gate creaks open.<|im_end|>\n\
+             <|im_start|>user\nFind the sheriff<|im_end|>\n\
+             <|im_start|>assistant\n"
+        );
+        assert_eq!(
+            render(ChatTemplate::Mistral).unwrap(),
+            "[INST] I enter Megaton [/INST] The gate creaks open.</s>\
+             [INST] You are the DM.\n\nFind the sheriff [/INST]"
+        );
+
+        let llama3 = render(ChatTemplate::Llama3).unwrap();
+        assert!(llama3.starts_with("<|start_header_id|>system<|end_header_id|>\n\nYou are the DM."));
+        assert!(llama3.ends_with("<|start_header_id|>assistant<|end_header_id|>\n\n"));
+
+        let alpaca = render(ChatTemplate::Alpaca).unwrap();
+        assert!(alpaca.contains("### Instruction:\nFind the sheriff\n\n"));
+        assert!(alpaca.ends_with("### Response:\n"));
+
+        assert!(render(ChatTemplate::Plain).is_err());
+    }
+
+    #[test]
+    fn test_render_chat_prompt_custom() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.tera");
+        std::fs::write(
+            &path,
+            "{{ system }}{% for m in messages %}<{{ m.role }}>{{ m.content }}{% endfor %}<assistant>",
+        )
+        .unwrap();
+
+        let format = ChatFormat::load(ChatTemplate::Custom, path.to_str()).unwrap();
+        // Rendering no longer needs the file
+        std::fs::remove_file(&path).unwrap();
+        let prompt = format.render("DM. ", &chat()[..1]).unwrap();
+        assert_eq!(prompt, "DM. <user>I enter Megaton<assistant>");
+
+        assert!(ChatFormat::load(ChatTemplate::Custom, Some("missing.tera")).is_err());
+        assert!(ChatFormat::load(ChatTemplate::Custom, None).is_err());
+        std::fs::write(&path, "{{ system }}{% for m in messages %}").unwrap();
+        assert!(ChatFormat::load(ChatTemplate::Custom, path.to_str()).is_err());
+        std::fs::write(&path, "{{ system }}{{ tools }}").unwrap();
+        assert!(ChatFormat::load(ChatTemplate::Custom, path.to_str()).is_err());
+    }
+
     #[test]
     fn test_render_context_minimal() {
-        let result = render_context(None, None, None, None);
+        let result = render_context(None, None, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -168,19 +420,21 @@ mod tests {
                 science: 55,
                 sneak: 40,
             },
+            status: vec!["250 rads (Minor Radiation Sickness)".to_string()],
         };
 
-        let result = render_context(Some(&character), None, None, None);
+        let result = render_context(Some(&character), None, None, None, None);
         assert!(result.is_ok());
         let context = result.unwrap();
         assert!(context.contains("Vault Dweller"));
         assert!(context.contains("Level 5"));
+        assert!(context.contains("Status: 250 rads (Minor Radiation Sickness)"));
     }
 
     #[test]
     fn test_render_context_with_inventory() {
         let inventory = vec!["10mm Pistol".to_string(), "Stimpak".to_string()];
-        let result = render_context(None, Some(&inventory), None, None);
+        let result = render_context(None, Some(&inventory), None, None, None);
         assert!(result.is_ok());
         let context = result.unwrap();
         assert!(context.contains("10mm Pistol"));
@@ -205,7 +459,7 @@ mod tests {
             ],
         };
 
-        let result = render_context(None, None, Some(&combat), None);
+        let result = render_context(None, None, Some(&combat), None, None);
         assert!(result.is_ok());
         let context = result.unwrap();
         assert!(context.contains("Round 3"));
@@ -1,14 +1,24 @@
 use crate::ai::extractor::ExtractedEntities;
+use crate::game::crafting::{recipes, RecipeDefinition};
+use crate::game::dm_actions::{self, DmAction, ACTIONS_CLOSE_TAG, ACTIONS_OPEN_TAG};
+use crate::game::items::catalog::ItemKind;
+use crate::game::items::inventory::InventorySort;
+use crate::game::items::ItemType;
+use crate::game::story_memory::SummaryJob;
 use crate::game::GameState;
 use crate::tui::animations::AnimationManager;
 use crate::tui::theme::LoadingSpinner;
 use crate::tui::worldbook_browser::WorldbookBrowser;
+use smartstring::alias::String as SmartString;
 use std::collections::VecDeque;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Worldbook update message from background extraction
 pub type WorldbookUpdate = (ExtractedEntities, String);
 
+/// Story summary from the background summarizer (`None` if it failed)
+pub type SummaryUpdate = (SummaryJob, Option<String>);
+
 /// Information about player death for game over screen
 #[derive(Debug, Clone)]
 pub struct DeathInfo {
@@ -68,6 +78,18 @@ pub struct App {
     /// Whether we're currently in a thinking block (line started with thinking indicator)
     in_thinking_mode: bool,
 
+    /// Holds back streamed text that may be the start of a DM action block
+    action_scan_buffer: String,
+
+    /// Whether the stream is currently inside an `<actions>` block (hidden from display)
+    in_action_block: bool,
+
+    /// Structured actions parsed from the last finished DM response, waiting to be applied
+    pub pending_dm_actions: Vec<DmAction>,
+
+    /// Parse errors for malformed actions in the last finished DM response
+    pub pending_action_errors: Vec<String>,
+
     /// Whether we're currently receiving a streaming response
     pub is_streaming: bool,
 
@@ -85,6 +107,41 @@ pub struct App {
 
     /// Equipment menu state - selected item index
     pub equipment_selected_index: usize,
+    /// Equipment menu state - selected mod while picking one to attach
+    pub equipment_mod_index: Option<usize>,
+
+    /// Level-up screen state - selected row in the focused list
+    pub level_up_selected_index: usize,
+
+    /// Level-up screen state - which list has focus
+    pub level_up_focus: LevelUpFocus,
+
+    /// Quest journal state - selected quest (index into journal order)
+    pub journal_selected_index: usize,
+
+    /// Trade screen state - worldbook ID of the merchant being traded with
+    pub trade_npc: Option<SmartString>,
+
+    /// Trade screen state - which side has focus
+    pub trade_focus: TradeFocus,
+
+    /// Trade screen state - selected item on the focused side
+    pub trade_selected_index: usize,
+
+    /// Loot screen state - selected item on the bodies
+    pub loot_selected_index: usize,
+
+    /// Inventory screen state - selected row in the filtered list
+    pub inventory_selected_index: usize,
+
+    /// Inventory screen state - item category shown (everything when `None`)
+    pub inventory_filter: Option<ItemKind>,
+
+    /// Inventory screen state - last sort order applied
+    pub inventory_sort: InventorySort,
+
+    /// Crafting screen state - selected recipe (index into `crafting_rows`)
+    pub crafting_selected_index: usize,
 
     /// Command history
     pub command_history: Vec<String>,
@@ -98,6 +155,15 @@ pub struct App {
 
     /// Channel receiver for worldbook updates from background extraction
     pub worldbook_update_receiver: tokio::sync::mpsc::Receiver<WorldbookUpdate>,
+
+    /// Channel sender for story summaries from the background summarizer
+    pub summary_update_sender: tokio::sync::mpsc::Sender<SummaryUpdate>,
+
+    /// Channel receiver for story summaries from the background summarizer
+    pub summary_update_receiver: tokio::sync::mpsc::Receiver<SummaryUpdate>,
+
+    /// Whether a story summary is being written in the background
+    pub summary_in_flight: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -126,14 +192,36 @@ pub enum ViewMode {
     Worldbook, // Viewing worldbook
     Combat,    // In combat
     Equipment, // Equipment menu for equipping/unequipping items
+    LevelUp,   // Spend skill points and pick perks
+    Journal,   // Quest journal
+    Trade,     // Buying and selling with a merchant
+    Loot,      // Searching the bodies after a fight
+    Crafting,  // Crafting recipes at the current location
     #[allow(dead_code)]
     GameOver, // Player died - game over screen
 }
 
+/// Which list has focus in the level-up screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelUpFocus {
+    Skills,
+    Perks,
+}
+
+/// Which side has focus in the trade screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeFocus {
+    /// The merchant's stock
+    Buy,
+    /// The player's inventory
+    Sell,
+}
+
 impl App {
     pub fn new(game_state: GameState) -> Self {
         // Create channel for worldbook updates from background extraction
         let (worldbook_tx, worldbook_rx) = tokio::sync::mpsc::channel::<WorldbookUpdate>(16);
+        let (summary_tx, summary_rx) = tokio::sync::mpsc::channel::<SummaryUpdate>(4);
 
         let mut app = Self {
             should_quit: false,
@@ -152,6 +240,10 @@ impl App {
             filtered_streaming_message: None,
             thinking_line_buffer: String::new(),
             in_thinking_mode: false,
+            action_scan_buffer: String::new(),
+            in_action_block: false,
+            pending_dm_actions: Vec::new(),
+            pending_action_errors: Vec::new(),
             is_streaming: false,
             stream_receiver: None,
             should_flicker: false,
@@ -161,10 +253,25 @@ impl App {
                 .unwrap_or_default()
                 .as_secs(),
             equipment_selected_index: 0,
+            equipment_mod_index: None,
+            level_up_selected_index: 0,
+            level_up_focus: LevelUpFocus::Skills,
+            journal_selected_index: 0,
+            trade_npc: None,
+            trade_focus: TradeFocus::Buy,
+            trade_selected_index: 0,
+            loot_selected_index: 0,
+            inventory_selected_index: 0,
+            inventory_filter: None,
+            inventory_sort: InventorySort::default(),
+            crafting_selected_index: 0,
             command_history: Vec::new(),
             history_index: 0,
             worldbook_update_sender: worldbook_tx,
             worldbook_update_receiver: worldbook_rx,
+            summary_update_sender: summary_tx,
+            summary_update_receiver: summary_rx,
+            summary_in_flight: false,
         };
 
         // Add welcome message
@@ -320,6 +427,68 @@ impl App {
         self.view_mode = mode;
     }
 
+    /// Inventory indexes of the items shown with the current filter
+    pub fn inventory_rows(&self) -> Vec<usize> {
+        self.game_state
+            .character
+            .inventory
+            .filtered(self.inventory_filter)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Inventory index of the selected item in the inventory screen
+    pub fn selected_inventory_item(&self) -> Option<usize> {
+        self.inventory_rows()
+            .get(self.inventory_selected_index)
+            .copied()
+    }
+
+    /// Inventory index of the weapon or armor selected in the equipment menu
+    pub fn selected_equipment_item(&self) -> Option<usize> {
+        self.game_state
+            .character
+            .inventory
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches!(item.item_type, ItemType::Weapon(_) | ItemType::Armor(_)))
+            .map(|(index, _)| index)
+            .nth(self.equipment_selected_index)
+    }
+
+    /// Show the next (or previous) item category in the inventory screen,
+    /// with "everything" between the last and first categories
+    pub fn cycle_inventory_filter(&mut self, forward: bool) {
+        let mut filters: Vec<Option<ItemKind>> = vec![None];
+        filters.extend(ItemKind::ALL.into_iter().map(Some));
+        let current = filters
+            .iter()
+            .position(|&filter| filter == self.inventory_filter)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % filters.len()
+        } else {
+            (current + filters.len() - 1) % filters.len()
+        };
+        self.inventory_filter = filters[next];
+        self.inventory_selected_index = 0;
+    }
+
+    /// Every recipe with whatever the player is missing to make it here,
+    /// craftable recipes first
+    pub fn crafting_rows(&self) -> Vec<(&'static RecipeDefinition, Vec<String>)> {
+        let at_workbench = self.game_state.at_workbench();
+        let mut rows: Vec<_> = recipes()
+            .iter()
+            .map(|recipe| {
+                let missing = recipe.missing(&self.game_state.character, at_workbench);
+                (recipe, missing)
+            })
+            .collect();
+        rows.sort_by_key(|(_, missing)| !missing.is_empty());
+        rows
+    }
+
     /// Check if in combat
     pub fn is_in_combat(&self) -> bool {
         self.game_state.combat.active
@@ -344,6 +513,10 @@ impl App {
         self.filtered_streaming_message = Some(String::new());
         self.thinking_line_buffer.clear();
         self.in_thinking_mode = false;
+        self.action_scan_buffer.clear();
+        self.in_action_block = false;
+        self.pending_dm_actions.clear();
+        self.pending_action_errors.clear();
         self.stream_receiver = Some(receiver);
         self.scroll_offset = 0; // Auto-scroll to bottom when streaming
     }
@@ -384,6 +557,58 @@ impl App {
             msg.push_str(&token);
         }
 
+        // Hide DM action blocks from the display - they're parsed from the full message
+        let display = self.take_displayable_text(&token);
+        if !display.is_empty() {
+            self.filter_display_token(display);
+        }
+    }
+
+    /// Run streamed text through the action block scanner, returning the part safe to display
+    ///
+    /// Text that could be the start of an `<actions>` tag is held back until the
+    /// next token decides it.
+    fn take_displayable_text(&mut self, token: &str) -> String {
+        self.action_scan_buffer.push_str(token);
+        let mut display = String::new();
+
+        loop {
+            if self.in_action_block {
+                if let Some(end) = self.action_scan_buffer.find(ACTIONS_CLOSE_TAG) {
+                    self.action_scan_buffer
+                        .drain(..end + ACTIONS_CLOSE_TAG.len());
+                    self.in_action_block = false;
+                } else {
+                    let keep = Self::partial_tag_len(&self.action_scan_buffer, ACTIONS_CLOSE_TAG);
+                    let cut = self.action_scan_buffer.len() - keep;
+                    self.action_scan_buffer.drain(..cut);
+                    return display;
+                }
+            } else if let Some(start) = self.action_scan_buffer.find(ACTIONS_OPEN_TAG) {
+                display.push_str(&self.action_scan_buffer[..start]);
+                self.action_scan_buffer
+                    .drain(..start + ACTIONS_OPEN_TAG.len());
+                self.in_action_block = true;
+            } else {
+                let keep = Self::partial_tag_len(&self.action_scan_buffer, ACTIONS_OPEN_TAG);
+                let cut = self.action_scan_buffer.len() - keep;
+                display.push_str(&self.action_scan_buffer[..cut]);
+                self.action_scan_buffer.drain(..cut);
+                return display;
+            }
+        }
+    }
+
+    /// Length of the longest suffix of `text` that is a proper prefix of `tag`
+    fn partial_tag_len(text: &str, tag: &str) -> usize {
+        (1..tag.len())
+            .rev()
+            .find(|&len| text.ends_with(&tag[..len]))
+            .unwrap_or(0)
+    }
+
+    /// Filter a token for display (thinking tokens, meta-commentary, degenerate output)
+    fn filter_display_token(&mut self, token: String) {
         // Skip degenerate tokens early (before buffering) to avoid display garbage
         // These are tokens that are mostly punctuation/underscores/whitespace
         if Self::is_degenerate_token(&token) {
@@ -755,6 +980,13 @@ impl App {
         self.is_streaming = false;
         self.stream_receiver = None;
 
+        // Release text held back by the action scanner (it wasn't an action block after all)
+        let held_back = std::mem::take(&mut self.action_scan_buffer);
+        if !self.in_action_block && !held_back.is_empty() {
+            self.filter_display_token(held_back);
+        }
+        self.in_action_block = false;
+
         // Process any remaining content in the line buffer
         if !self.thinking_line_buffer.is_empty() {
             let remaining = std::mem::take(&mut self.thinking_line_buffer);
@@ -778,7 +1010,15 @@ impl App {
 
         // Use filtered message for display (without thinking tokens)
         // Keep full message in streaming_message for extraction/debugging
-        let _full_content = self.streaming_message.take(); // Keep for potential debugging
+        let full_content = self.streaming_message.take();
+
+        // Structured DM actions come from the full message, since display filtering
+        // drops punctuation-only tokens that JSON is made of
+        if let Some(ref full) = full_content {
+            let parsed = dm_actions::parse_dm_actions(Self::final_answer_text(full));
+            self.pending_dm_actions = parsed.actions;
+            self.pending_action_errors = parsed.errors;
+        }
 
         if let Some(content) = self.filtered_streaming_message.take() {
             if !content.is_empty() {
@@ -800,6 +1040,23 @@ impl App {
         None
     }
 
+    /// Text after the reasoning section (the model's actual answer), or the whole message
+    fn final_answer_text(content: &str) -> &str {
+        [
+            "</think>",
+            "<|channel|>final<|message|>",
+            "<|final|><|message|>",
+            "<|channel|>final>",
+        ]
+        .iter()
+        .find_map(|marker| {
+            content
+                .rfind(marker)
+                .map(|pos| &content[pos + marker.len()..])
+        })
+        .unwrap_or(content)
+    }
+
     /// Strip thinking content from response (final cleanup pass)
     /// Extracts only the final response from GPT-OSS harmony format or <think> tags
     fn strip_thinking_content(content: &str) -> String {
@@ -1299,13 +1556,19 @@ impl App {
             self.add_info_message(format!("[Worldbook: {}]", summary));
 
             // Convert extracted entities to worldbook entries
-            let (locations, npcs, events) = entities.to_worldbook_entries();
+            let (locations, npcs, events) =
+                entities.to_worldbook_entries(&self.game_state.worldbook.current_time);
             let mut saved_count = 0;
 
             // Integrate locations
             for location in locations {
                 let loc_id = location.id.clone();
-                if self.game_state.worldbook.get_location(&loc_id).is_none() {
+                if let Some(known) = self.game_state.worldbook.locations.get_mut(&loc_id) {
+                    // A workbench can turn up on a later visit
+                    if location.has_workbench() {
+                        known.add_workbench();
+                    }
+                } else {
                     self.game_state.worldbook.add_location(location);
                     saved_count += 1;
 
@@ -1336,6 +1599,25 @@ impl App {
         }
     }
 
+    /// Store story summaries finished by the background summarizer
+    pub fn process_summary_updates(&mut self) {
+        while let Ok((job, summary)) = self.summary_update_receiver.try_recv() {
+            self.summary_in_flight = false;
+            let Some(summary) = summary else {
+                continue;
+            };
+            if self.game_state.apply_summary(&job, &summary) {
+                tracing::info!(
+                    "Story summary updated: chapter {} through turn {}",
+                    job.chapter,
+                    job.last_turn()
+                );
+            } else {
+                tracing::debug!("Story summary for chapter {} not used", job.chapter);
+            }
+        }
+    }
+
     /// Cancel the current streaming message
     pub fn cancel_streaming(&mut self) {
         self.is_streaming = false;
@@ -1343,6 +1625,8 @@ impl App {
         self.filtered_streaming_message = None;
         self.thinking_line_buffer.clear();
         self.in_thinking_mode = false;
+        self.action_scan_buffer.clear();
+        self.in_action_block = false;
         self.stream_receiver = None;
     }
 
@@ -1389,6 +1673,7 @@ impl App {
         self.waiting_for_ai = false;
         self.cancel_streaming();
         self.equipment_selected_index = 0;
+        self.equipment_mod_index = None;
 
         // Add welcome message
         self.add_message(
@@ -1586,6 +1871,47 @@ mod tests {
         assert_eq!(last_message.message_type, MessageType::System);
     }
 
+    fn stream_tokens(app: &mut App, tokens: &[&str]) -> Option<String> {
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        app.start_streaming(rx);
+        for token in tokens {
+            app.append_streaming_token(token.to_string());
+        }
+        app.finish_streaming()
+    }
+
+    #[test]
+    fn test_streamed_action_block_is_hidden_and_parsed() {
+        let mut app = create_test_app();
+        let response = stream_tokens(
+            &mut app,
+            &[
+                "The trader hands you a stimpak.",
+                "\n<act",
+                "ions>[{\"action\": ",
+                "\"give_item\", \"id\": \"stimpak\"}]</act",
+                "ions>",
+            ],
+        );
+
+        assert_eq!(response.as_deref(), Some("The trader hands you a stimpak."));
+        assert_eq!(app.pending_dm_actions.len(), 1);
+        assert!(app.pending_action_errors.is_empty());
+        assert!(!app.message_log.back().unwrap().content.contains("actions"));
+    }
+
+    #[test]
+    fn test_partial_tag_is_released_when_not_an_action_block() {
+        let mut app = create_test_app();
+        let response = stream_tokens(&mut app, &["Heart rate <", "act ", "fast, Vault Dweller."]);
+
+        assert_eq!(
+            response.as_deref(),
+            Some("Heart rate <act fast, Vault Dweller.")
+        );
+        assert!(app.pending_dm_actions.is_empty());
+    }
+
     #[test]
     fn test_message_log_max_size() {
         let mut app = create_test_app();
@@ -8,7 +8,8 @@ use ratatui::{
 
 use crate::game::{
     character::Character,
-    combat::{CombatState, Enemy},
+    combat::{CombatState, Defense, Enemy, RELOAD_AP_COST},
+    items::DamageType,
 };
 use crate::tui::theme::PipBoyTheme;
 
@@ -162,6 +163,58 @@ fn render_player_panel(
         Style::default().fg(Color::Gray),
     )]));
 
+    // Magazine and spare ammo for weapons that need it
+    if let Some(stats) = character
+        .equipped_weapon_stats()
+        .filter(|stats| stats.uses_ammo())
+    {
+        let spare = stats
+            .ammo_type
+            .as_ref()
+            .map_or(0, |ammo| character.ammo_count(ammo));
+        let color = if stats.is_empty() {
+            Color::Red
+        } else {
+            Color::Gray
+        };
+        lines.push(Line::from(vec![Span::styled(
+            if stats.is_empty() {
+                format!("    EMPTY (+{})", spare)
+            } else {
+                format!(
+                    "    Ammo {}/{} (+{})",
+                    stats.loaded_rounds,
+                    stats.capacity(),
+                    spare
+                )
+            },
+            Style::default().fg(color),
+        )]));
+    }
+
+    // Equipped armor and the defense it provides
+    let defense = Defense::for_character(character);
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(" 🛡 "),
+        Span::styled(
+            defense
+                .armor_name
+                .as_deref()
+                .unwrap_or("No armor")
+                .to_string(),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]));
+    lines.push(Line::from(vec![Span::styled(
+        format!(
+            "    AC {}  DR {}",
+            defense.armor_class,
+            defense.resistance_against(&DamageType::Normal)
+        ),
+        Style::default().fg(Color::Gray),
+    )]));
+
     let content = Paragraph::new(lines).alignment(Alignment::Left);
     frame.render_widget(content, player_inner);
 }
@@ -346,16 +399,28 @@ fn render_initiative_order(frame: &mut Frame, area: Rect, enemies: &[Enemy]) {
 }
 
 /// Render action bar with available commands
-fn render_action_bar(frame: &mut Frame, area: Rect, _character: &Character) {
+fn render_action_bar(frame: &mut Frame, area: Rect, character: &Character) {
     let actions = vec![
         Span::raw("Actions: "),
         Span::styled("[A]", Style::default().fg(Color::Yellow)),
         Span::raw("ttack "),
         Span::styled("[U]", Style::default().fg(Color::Yellow)),
         Span::raw("se Item "),
+        Span::styled("Reload", Style::default().fg(Color::Yellow)),
+        Span::raw(" "),
+        Span::styled("End", Style::default().fg(Color::Yellow)),
+        Span::raw(" "),
         Span::styled("[R]", Style::default().fg(Color::Yellow)),
         Span::raw("un  |  AP Cost: "),
-        Span::styled("Attack=4", Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("Attack={}", character.attack_ap_cost()),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            format!("Reload={}", RELOAD_AP_COST),
+            Style::default().fg(Color::Cyan),
+        ),
         Span::raw(" "),
         Span::styled("Use=2", Style::default().fg(Color::Cyan)),
     ];
@@ -416,10 +481,8 @@ fn create_ap_bar(current: i32, max: i32, width: usize) -> Vec<Span<'static>> {
 fn get_weapon_display_name(character: &Character) -> String {
     if let Some(weapon_id) = &character.equipped_weapon {
         // Try to find weapon in inventory for display name
-        for item in &character.inventory {
-            if &item.id == weapon_id {
-                return item.name.to_string();
-            }
+        if let Some(index) = character.inventory.position_by_key(weapon_id) {
+            return character.inventory[index].name.to_string();
         }
         // Fallback to formatted ID
         weapon_id
@@ -588,7 +651,7 @@ mod tests {
             },
         );
         character.equipped_weapon = Some("10mm_pistol".into());
-        character.inventory.push(Item {
+        character.inventory.add(Item {
             id: "10mm_pistol".into(),
             name: "10mm Pistol".into(),
             ..Default::default()
@@ -2,13 +2,15 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Gauge, List, ListItem, Paragraph},
+    widgets::{Block, BorderType, Borders, Gauge, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
-use super::app::{App, MessageType, ViewMode};
+use super::app::{App, LevelUpFocus, MessageType, TradeFocus, ViewMode};
 use super::narrative;
-use crate::game::character::Character;
+use crate::game::character::{Character, Perk, Skills, MAX_SKILL};
+use crate::game::items::catalog::ItemKind;
+use crate::game::quests::QuestStatus;
 
 /// Main render function
 pub fn render(f: &mut Frame, app: &App) {
@@ -76,6 +78,21 @@ pub fn render(f: &mut Frame, app: &App) {
         ViewMode::Equipment => {
             render_equipment_menu(f, app, content_chunks[0]);
         }
+        ViewMode::LevelUp => {
+            render_level_up(f, app, content_chunks[0]);
+        }
+        ViewMode::Journal => {
+            render_journal(f, app, content_chunks[0]);
+        }
+        ViewMode::Trade => {
+            render_trade(f, app, content_chunks[0]);
+        }
+        ViewMode::Loot => {
+            render_loot(f, app, content_chunks[0]);
+        }
+        ViewMode::Crafting => {
+            render_crafting(f, app, content_chunks[0]);
+        }
         ViewMode::GameOver => {
             render_game_over(f, app, content_chunks[0]);
         }
@@ -371,8 +388,8 @@ fn render_quick_status(f: &mut Frame, app: &App, area: Rect) {
         if let Some(weapon_id) = &character.equipped_weapon {
             if let Some(weapon) = character
                 .inventory
-                .iter()
-                .find(|item| &item.id == weapon_id)
+                .position_by_key(weapon_id)
+                .map(|index| &character.inventory[index])
             {
                 lines.push(Line::from(vec![
                     Span::styled("🔫 ", Style::default().fg(Color::Yellow)),
@@ -398,6 +415,14 @@ fn render_quick_status(f: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Radiation, hunger and thirst, chems and addictions
+    for status in character.status.summary() {
+        lines.push(Line::from(vec![
+            Span::styled("☢ ", Style::default().fg(Color::Yellow)),
+            Span::styled(status, Style::default().fg(Color::LightYellow)),
+        ]));
+    }
+
     let block = Block::default()
         .borders(Borders::TOP)
         .border_style(Style::default().fg(Color::Green))
@@ -540,45 +565,252 @@ fn render_inventory(f: &mut Frame, app: &App, area: Rect) {
     let inner_area = block.inner(area);
     f.render_widget(block, area);
 
-    if character.inventory.is_empty() {
-        let text = Paragraph::new("Your inventory is empty.")
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Carry weight
+            Constraint::Length(1), // Category filter and sort order
+            Constraint::Min(0),    // Items and details
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+    f.render_widget(Paragraph::new(carry_weight_line(character)), rows[0]);
+
+    // Category tabs: the active filter is highlighted
+    let mut tabs = Vec::new();
+    let filters = std::iter::once(None).chain(ItemKind::ALL.into_iter().map(Some));
+    for filter in filters {
+        let name = filter.map_or("All", |kind| kind.name());
+        let style = if filter == app.inventory_filter {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        tabs.push(Span::raw(" "));
+        tabs.push(Span::styled(format!(" {} ", name), style));
+    }
+    tabs.push(Span::styled(
+        format!("   sorted by {}", app.inventory_sort.name()),
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(Paragraph::new(Line::from(tabs)), rows[1]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[2]);
+
+    let shown = app.inventory_rows();
+    if shown.is_empty() {
+        let text = if character.inventory.is_empty() {
+            "Your inventory is empty."
+        } else {
+            "Nothing of this kind."
+        };
+        let text = Paragraph::new(text)
             .style(Style::default().fg(Color::DarkGray))
             .alignment(Alignment::Center);
-        f.render_widget(text, inner_area);
-        return;
+        f.render_widget(text, columns[0]);
+    } else {
+        let items: Vec<ListItem> = shown
+            .iter()
+            .enumerate()
+            .map(|(row, &index)| {
+                let item = &character.inventory[index];
+                let is_selected = row == app.inventory_selected_index;
+                let is_equipped = character.is_equipped(item);
+                let marker = if is_selected { "► " } else { "  " };
+                let equipped = if is_equipped { " [E]" } else { "" };
+                let style = match (is_selected, is_equipped) {
+                    (true, _) => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                    (false, true) => Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                    (false, false) => Style::default().fg(Color::White),
+                };
+                let quantity = if item.quantity > 1 {
+                    format!(" x{}", item.quantity)
+                } else {
+                    String::new()
+                };
+
+                ListItem::new(format!(
+                    "{}{}{}{}  ({:.1} lbs)",
+                    marker,
+                    item.name,
+                    quantity,
+                    equipped,
+                    item.weight * item.quantity as f32
+                ))
+                .style(style)
+            })
+            .collect();
+        f.render_widget(List::new(items), columns[0]);
     }
 
-    let items: Vec<ListItem> = character
-        .inventory
-        .iter()
-        .map(|item| {
-            let is_equipped = character.equipped_weapon.as_ref() == Some(&item.id)
-                || character.equipped_armor.as_ref() == Some(&item.id);
-            let equipped = if is_equipped { " [E]" } else { "" };
-            let style = if is_equipped {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
+    let details_block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green))
+        .border_type(BorderType::Plain);
+    let details_inner = details_block.inner(columns[1]);
+    f.render_widget(details_block, columns[1]);
+    if let Some(index) = app.selected_inventory_item() {
+        let details = Paragraph::new(item_details(character, &character.inventory[index]))
+            .wrap(Wrap { trim: true });
+        f.render_widget(details, details_inner);
+    }
+
+    let hints = Paragraph::new(Span::styled(
+        " ↑/↓ select  Tab category  S sort  Enter use  d drop one  D drop all  Esc close",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(hints, rows[3]);
+}
 
-            // Show item details based on type
-            let details = match &item.item_type {
-                crate::game::items::ItemType::Weapon(stats) => format!(" - DMG: {}", stats.damage),
-                crate::game::items::ItemType::Armor(stats) => {
-                    format!(" - AC: +{}", stats.armor_class)
+/// Detail pane lines for an inventory item
+fn item_details(character: &Character, item: &crate::game::items::Item) -> Vec<Line<'static>> {
+    use crate::game::items::ItemType;
+
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{}: ", label), Style::default().fg(Color::Yellow)),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            item.name.to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            item.description.to_string(),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+        field("Type", ItemKind::of(&item.item_type).name().to_string()),
+        field("Quantity", item.quantity.to_string()),
+        field(
+            "Weight",
+            format!(
+                "{:.1} lbs ({:.1} total)",
+                item.weight,
+                item.weight * item.quantity as f32
+            ),
+        ),
+        field("Value", format!("{} caps", item.value)),
+    ];
+
+    match &item.item_type {
+        ItemType::Weapon(stats) => {
+            lines.push(field(
+                "Damage",
+                format!("{} {:?}", stats.damage, stats.effective_damage_type()),
+            ));
+            lines.push(field("AP Cost", stats.ap_cost.to_string()));
+            lines.push(field("Range", format!("{}m", stats.range)));
+            if let Some(ammo) = &stats.ammo_type {
+                lines.push(field(
+                    "Ammo",
+                    format!("{} ({}/{})", ammo, stats.loaded_rounds, stats.capacity()),
+                ));
+            }
+            for attached in &stats.mods {
+                if let ItemType::Mod(effect) = &attached.item_type {
+                    lines.push(field(
+                        effect.slot.name(),
+                        format!("{} ({})", attached.name, effect.summary()),
+                    ));
                 }
-                crate::game::items::ItemType::Consumable(_) => format!(" x{}", item.quantity),
-                crate::game::items::ItemType::Misc => String::new(),
-            };
+            }
+        }
+        ItemType::Armor(stats) => {
+            lines.push(field(
+                "Damage Resistance",
+                stats.damage_resistance.to_string(),
+            ));
+            lines.push(field("Armor Class", format!("+{}", stats.armor_class)));
+            lines.push(field(
+                "Rad Resistance",
+                format!("+{}", stats.radiation_resistance),
+            ));
+        }
+        ItemType::Consumable(effect) => {
+            lines.push(field("Effect", effect.description()));
+        }
+        ItemType::Mod(effect) => {
+            lines.push(field("Slot", effect.slot.name().to_string()));
+            lines.push(field("Effect", effect.summary()));
+        }
+        ItemType::Ammo | ItemType::Misc => {}
+    }
+    if let Some(condition) = item.condition() {
+        lines.push(condition_line("Condition: ", condition));
+    }
 
-            ListItem::new(format!("• {}{}{}", item.name, equipped, details)).style(style)
-        })
-        .collect();
+    if character.is_equipped(item) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Equipped",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines
+}
+
+/// "Condition: 85% (Good)", coloured from green (pristine) to red (jamming)
+fn condition_line(label: &'static str, condition: u8) -> Line<'static> {
+    use crate::game::items::condition::{self, JAM_CONDITION};
+
+    let color = match condition {
+        70.. => Color::Green,
+        JAM_CONDITION.. => Color::Yellow,
+        _ => Color::Red,
+    };
+    Line::from(vec![
+        Span::styled(label, Style::default().fg(Color::Yellow)),
+        Span::styled(
+            format!("{}% ({})", condition, condition::label(condition)),
+            Style::default().fg(color),
+        ),
+    ])
+}
 
-    let list = List::new(items);
-    f.render_widget(list, inner_area);
+/// "Weight: 123.5 / 200 lbs", in red with a warning when over-encumbered
+fn carry_weight_line(character: &Character) -> Line<'static> {
+    let encumbered = character.is_encumbered();
+    let color = if encumbered { Color::Red } else { Color::Green };
+    let mut spans = vec![
+        Span::raw(" Weight: "),
+        Span::styled(
+            format!(
+                "{:.1} / {:.0} lbs",
+                character.carry_weight(),
+                character.carry_capacity()
+            ),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if encumbered {
+        spans.push(Span::styled(
+            format!(
+                "  OVER-ENCUMBERED: -{} AP, can't travel, harder Agility checks",
+                crate::game::character::ENCUMBERED_AP_PENALTY
+            ),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    Line::from(spans)
 }
 
 /// Render equipment menu for equipping/unequipping items
@@ -640,8 +872,7 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
             .iter()
             .enumerate()
             .map(|(idx, item)| {
-                let is_equipped = character.equipped_weapon.as_ref() == Some(&item.id)
-                    || character.equipped_armor.as_ref() == Some(&item.id);
+                let is_equipped = character.is_equipped(item);
                 let is_selected = idx == selected_index;
 
                 // Determine marker and style
@@ -674,13 +905,24 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
 
                 // Show item details based on type
                 let details = match &item.item_type {
-                    crate::game::items::ItemType::Weapon(stats) => {
-                        format!(" - DMG: {}", stats.damage)
-                    }
+                    crate::game::items::ItemType::Weapon(stats) => match stats.mods.len() {
+                        0 => format!(" - DMG: {}, {}%", stats.modded_damage(), stats.condition),
+                        1 => format!(
+                            " - DMG: {}, {}%, 1 mod",
+                            stats.modded_damage(),
+                            stats.condition
+                        ),
+                        n => format!(
+                            " - DMG: {}, {}%, {} mods",
+                            stats.modded_damage(),
+                            stats.condition,
+                            n
+                        ),
+                    },
                     crate::game::items::ItemType::Armor(stats) => {
                         format!(
-                            " - DR: {}, AC: {}",
-                            stats.damage_resistance, stats.armor_class
+                            " - DR: {}, AC: {}, {}%",
+                            stats.damage_resistance, stats.armor_class, stats.condition
                         )
                     }
                     _ => String::new(),
@@ -739,7 +981,11 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
                 lines.push(Line::from(vec![
                     Span::styled("Damage: ", Style::default().fg(Color::Yellow)),
                     Span::styled(
-                        stats.damage.as_str(),
+                        format!(
+                            "{} {:?}",
+                            stats.modded_damage(),
+                            stats.effective_damage_type()
+                        ),
                         Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                     ),
                 ]));
@@ -757,12 +1003,16 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
                         Style::default().fg(Color::White),
                     ),
                 ]));
+                lines.push(condition_line("Condition: ", stats.condition));
+                lines.extend(weapon_mod_lines(character, stats, app.equipment_mod_index));
 
                 // Show comparison if different weapon equipped
-                if let Some(equipped_id) = &character.equipped_weapon {
-                    if equipped_id != &selected_item.id {
-                        if let Some(equipped_weapon) =
-                            character.inventory.iter().find(|i| &i.id == equipped_id)
+                if let Some(equipped_key) = &character.equipped_weapon {
+                    if !character.is_equipped(selected_item) {
+                        if let Some(equipped_weapon) = character
+                            .inventory
+                            .position_by_key(equipped_key)
+                            .map(|index| &character.inventory[index])
                         {
                             if let crate::game::items::ItemType::Weapon(equipped_stats) =
                                 &equipped_weapon.item_type
@@ -789,7 +1039,7 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
                                 lines.push(Line::from(vec![
                                     Span::styled("  Damage: ", Style::default().fg(Color::Yellow)),
                                     Span::styled(
-                                        equipped_stats.damage.as_str(),
+                                        equipped_stats.modded_damage().to_string(),
                                         Style::default().fg(Color::White),
                                     ),
                                 ]));
@@ -835,12 +1085,15 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
                         Style::default().fg(Color::White),
                     ),
                 ]));
+                lines.push(condition_line("Condition: ", stats.condition));
 
                 // Show comparison if different armor equipped
-                if let Some(equipped_id) = &character.equipped_armor {
-                    if equipped_id != &selected_item.id {
-                        if let Some(equipped_armor) =
-                            character.inventory.iter().find(|i| &i.id == equipped_id)
+                if let Some(equipped_key) = &character.equipped_armor {
+                    if !character.is_equipped(selected_item) {
+                        if let Some(equipped_armor) = character
+                            .inventory
+                            .position_by_key(equipped_key)
+                            .map(|index| &character.inventory[index])
                         {
                             if let crate::game::items::ItemType::Armor(equipped_stats) =
                                 &equipped_armor.item_type
@@ -894,8 +1147,7 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
         lines.push(Line::from(""));
 
         // Show controls
-        let is_equipped = character.equipped_weapon.as_ref() == Some(&selected_item.id)
-            || character.equipped_armor.as_ref() == Some(&selected_item.id);
+        let is_equipped = character.is_equipped(selected_item);
 
         if is_equipped {
             lines.push(Line::from(vec![
@@ -941,13 +1193,868 @@ fn render_equipment_menu(f: &mut Frame, app: &App, area: Rect) {
             ),
             Span::styled(" - Navigate", Style::default().fg(Color::White)),
         ]));
+        if selected_item.as_weapon().is_some() {
+            let key_style = Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD);
+            lines.push(Line::from(if app.equipment_mod_index.is_some() {
+                vec![
+                    Span::styled("Enter", key_style),
+                    Span::styled(" - Attach mod   ", Style::default().fg(Color::White)),
+                    Span::styled("ESC", key_style),
+                    Span::styled(" - Cancel", Style::default().fg(Color::White)),
+                ]
+            } else {
+                vec![
+                    Span::styled("M", key_style),
+                    Span::styled(" - Attach mod   ", Style::default().fg(Color::White)),
+                    Span::styled("X", key_style),
+                    Span::styled(" - Remove mod", Style::default().fg(Color::White)),
+                ]
+            }));
+        }
 
         let paragraph = Paragraph::new(lines);
         f.render_widget(paragraph, details_inner);
     }
 }
 
+/// Mods attached to a weapon in the equipment menu, then the mods that fit
+/// it while one is being picked
+fn weapon_mod_lines(
+    character: &Character,
+    stats: &crate::game::items::weapons::WeaponStats,
+    picking: Option<usize>,
+) -> Vec<Line<'static>> {
+    use crate::game::items::ItemType;
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled("Mods:", Style::default().fg(Color::Yellow))),
+    ];
+    if stats.mods.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  None attached",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for attached in &stats.mods {
+        if let ItemType::Mod(effect) = &attached.item_type {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}: ", effect.slot.name()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(attached.name.to_string(), Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(" ({})", effect.summary()),
+                    Style::default().fg(Color::Green),
+                ),
+            ]));
+        }
+    }
+
+    let Some(selected) = picking else {
+        return lines;
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Attach which mod?",
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )));
+    let fitting = character
+        .inventory
+        .iter()
+        .filter_map(|item| match &item.item_type {
+            ItemType::Mod(effect) if effect.fits(stats) => Some((item, effect)),
+            _ => None,
+        });
+    for (row, (item, effect)) in fitting.enumerate() {
+        let (marker, style) = if row == selected {
+            (
+                "► ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            ("  ", Style::default().fg(Color::White))
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}{} x{} ({}: {})",
+                marker,
+                item.name,
+                item.quantity,
+                effect.slot.name(),
+                effect.summary()
+            ),
+            style,
+        )));
+    }
+    lines
+}
+
 /// Render detailed stats view with beautiful visual hierarchy
+/// Render the level-up screen: skill point spending and perk picks
+fn render_level_up(f: &mut Frame, app: &App, area: Rect) {
+    let character = &app.game_state.character;
+
+    let block = Block::default()
+        .title("★ Level Up")
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .border_type(BorderType::Double);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    // Split into header, [skills | perks], and key hints
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Level and unspent points
+            Constraint::Min(0),    // Lists
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" Level {}", character.level),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("   Skill points: "),
+        Span::styled(
+            character.skill_points.to_string(),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("   Perk picks: "),
+        Span::styled(
+            character.perk_points.to_string(),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    f.render_widget(header, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[1]);
+
+    let focus_style = |focused: bool| {
+        if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::Green)
+        }
+    };
+    let selected_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+
+    // Left panel: skills
+    let skills_focused = app.level_up_focus == LevelUpFocus::Skills;
+    let skills_block = Block::default()
+        .title(" Skills ")
+        .borders(Borders::ALL)
+        .border_style(focus_style(skills_focused))
+        .border_type(BorderType::Plain);
+    let skill_items: Vec<ListItem> = Skills::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, (id, name))| {
+            let value = character.skills.get_skill(id);
+            let is_selected = skills_focused && idx == app.level_up_selected_index;
+            let marker = if is_selected { "► " } else { "  " };
+            let style = if is_selected {
+                selected_style
+            } else if value >= MAX_SKILL {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(format!("{}{:<16}{:>3}", marker, name, value)).style(style)
+        })
+        .collect();
+    f.render_widget(List::new(skill_items).block(skills_block), columns[0]);
+
+    // Right panel: perks
+    let perks_focused = app.level_up_focus == LevelUpFocus::Perks;
+    let perks_block = Block::default()
+        .title(" Perks ")
+        .borders(Borders::ALL)
+        .border_style(focus_style(perks_focused))
+        .border_type(BorderType::Plain);
+    let perks_inner = perks_block.inner(columns[1]);
+    f.render_widget(perks_block, columns[1]);
+
+    let available = character.available_perks();
+    let mut lines = vec![Line::from(Span::styled(
+        "Available:",
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if available.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  None yet - perks unlock every few levels",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (idx, perk) in available.iter().enumerate() {
+        let is_selected = perks_focused && idx == app.level_up_selected_index;
+        let marker = if is_selected { "► " } else { "  " };
+        let style = if is_selected {
+            selected_style
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{}{}", marker, perk.name()),
+            style,
+        )));
+        if is_selected {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", perk.description()),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    let owned: Vec<&str> = character
+        .perks
+        .iter()
+        .map(|id| Perk::from_name(id).map_or(id.as_str(), |perk| perk.name()))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Owned:",
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )));
+    if owned.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  None",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        for name in owned {
+            lines.push(Line::from(format!("  {}", name)));
+        }
+    }
+    f.render_widget(Paragraph::new(lines), perks_inner);
+
+    let hints = Paragraph::new(Span::styled(
+        " ↑/↓ select  Enter spend/pick  Tab switch list  Esc close",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(hints, rows[2]);
+}
+
+/// Render the trade screen: merchant stock on the left, the player's
+/// inventory on the right, with prices for the focused side
+fn render_trade(f: &mut Frame, app: &App, area: Rect) {
+    let Some(merchant_id) = app.trade_npc.as_deref() else {
+        return;
+    };
+    let game_state = &app.game_state;
+    let character = &game_state.character;
+    let merchant_name = game_state
+        .worldbook
+        .get_npc(merchant_id)
+        .map_or(merchant_id, |npc| npc.name.as_str());
+    let Some(merchant) = game_state.worldbook.merchants.get(merchant_id) else {
+        return;
+    };
+    let prices = game_state.trade_prices(merchant_id);
+
+    let block = Block::default()
+        .title(format!("💰 Trading with {}", merchant_name))
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .border_type(BorderType::Double);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Caps and price rates
+            Constraint::Min(0),    // Lists
+            Constraint::Length(1), // Last trade message
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::raw(" Your caps: "),
+        Span::styled(
+            character.caps.to_string(),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("   {}'s caps: ", merchant_name)),
+        Span::styled(
+            merchant.caps.to_string(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "   Buy at {:.0}% / sell at {:.0}% of value",
+                prices.buy * 100.0,
+                prices.sell * 100.0
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    f.render_widget(header, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    let selected_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let item_list =
+        |title: &str, items: &[crate::game::items::Item], focused: bool, buying: bool| {
+            let list_items: Vec<ListItem> = items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| {
+                    let is_selected = focused && idx == app.trade_selected_index;
+                    let marker = if is_selected { "► " } else { "  " };
+                    let price = if buying {
+                        prices.buy_price(item)
+                    } else {
+                        prices.sell_price(item)
+                    };
+                    let style = if is_selected {
+                        selected_style
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    ListItem::new(format!(
+                        "{}{} x{}  {} caps",
+                        marker, item.name, item.quantity, price
+                    ))
+                    .style(style)
+                })
+                .collect();
+            let border = if focused { Color::Cyan } else { Color::Green };
+            List::new(list_items).block(
+                Block::default()
+                    .title(format!(" {} ", title))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .border_type(BorderType::Plain),
+            )
+        };
+
+    f.render_widget(
+        item_list(
+            "For Sale",
+            &merchant.stock,
+            app.trade_focus == TradeFocus::Buy,
+            true,
+        ),
+        columns[0],
+    );
+    f.render_widget(
+        item_list(
+            "Your Items",
+            &character.inventory,
+            app.trade_focus == TradeFocus::Sell,
+            false,
+        ),
+        columns[1],
+    );
+
+    if let Some(last) = app.message_log.back() {
+        let color = match last.message_type {
+            MessageType::Error => Color::Red,
+            _ => Color::Green,
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!(" {}", last.content),
+                Style::default().fg(color),
+            )),
+            rows[2],
+        );
+    }
+
+    let hints = Paragraph::new(Span::styled(
+        " ↑/↓ select  Enter buy/sell one  Tab switch side  Esc close",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(hints, rows[3]);
+}
+
+/// Render the loot screen: items left on the bodies after a fight
+fn render_loot(f: &mut Frame, app: &App, area: Rect) {
+    let character = &app.game_state.character;
+
+    let block = Block::default()
+        .title("💀 Searching the Bodies")
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .border_type(BorderType::Double);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Caps
+            Constraint::Min(0),    // Items
+            Constraint::Length(1), // Last message
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+
+    let header = Paragraph::new(vec![
+        Line::from(vec![
+            Span::raw(" Your caps: "),
+            Span::styled(
+                character.caps.to_string(),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        carry_weight_line(character),
+    ]);
+    f.render_widget(header, rows[0]);
+
+    let list_items: Vec<ListItem> = if app.game_state.loot.is_empty() {
+        vec![ListItem::new("  Nothing left worth taking.")
+            .style(Style::default().fg(Color::DarkGray))]
+    } else {
+        app.game_state
+            .loot
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let is_selected = idx == app.loot_selected_index;
+                let marker = if is_selected { "► " } else { "  " };
+                let style = if is_selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                ListItem::new(format!(
+                    "{}{} x{}  ({:.1} lbs, {} caps)",
+                    marker,
+                    item.name,
+                    item.quantity,
+                    item.weight * item.quantity as f32,
+                    item.value
+                ))
+                .style(style)
+            })
+            .collect()
+    };
+    f.render_widget(
+        List::new(list_items).block(
+            Block::default()
+                .title(" On the Bodies ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .border_type(BorderType::Plain),
+        ),
+        rows[1],
+    );
+
+    if let Some(last) = app.message_log.back() {
+        let color = match last.message_type {
+            MessageType::Error => Color::Red,
+            _ => Color::Green,
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!(" {}", last.content),
+                Style::default().fg(color),
+            )),
+            rows[2],
+        );
+    }
+
+    let hints = Paragraph::new(Span::styled(
+        " ↑/↓ select  Enter take  A take all  Esc leave the rest",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(hints, rows[3]);
+}
+
+/// Render the crafting screen: recipes on the left (craftable first),
+/// requirements of the selected one on the right
+fn render_crafting(f: &mut Frame, app: &App, area: Rect) {
+    let character = &app.game_state.character;
+    let at_workbench = app.game_state.at_workbench();
+
+    let block = Block::default()
+        .title("🔧 Crafting")
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .border_type(BorderType::Double);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Skills and workbench
+            Constraint::Min(0),    // Recipes and details
+            Constraint::Length(1), // Last message
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+
+    let (bench_text, bench_color) = if at_workbench {
+        ("workbench here", Color::Green)
+    } else {
+        ("no workbench here", Color::DarkGray)
+    };
+    let header = Paragraph::new(Line::from(vec![
+        Span::raw(format!(
+            " Science {}  Repair {}  ",
+            character.skills.science, character.skills.repair
+        )),
+        Span::styled(bench_text, Style::default().fg(bench_color)),
+    ]));
+    f.render_widget(header, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[1]);
+
+    let recipes = app.crafting_rows();
+    let list_items: Vec<ListItem> = recipes
+        .iter()
+        .enumerate()
+        .map(|(idx, (recipe, missing))| {
+            let is_selected = idx == app.crafting_selected_index;
+            let marker = if is_selected { "► " } else { "  " };
+            let style = match (is_selected, missing.is_empty()) {
+                (true, _) => Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+                (false, true) => Style::default().fg(Color::White),
+                (false, false) => Style::default().fg(Color::DarkGray),
+            };
+            ListItem::new(format!("{}{} x{}", marker, recipe.name(), recipe.qty)).style(style)
+        })
+        .collect();
+    f.render_widget(
+        List::new(list_items).block(
+            Block::default()
+                .title(" Recipes ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .border_type(BorderType::Plain),
+        ),
+        columns[0],
+    );
+
+    let details_block = Block::default()
+        .title(" Requirements ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green))
+        .border_type(BorderType::Plain);
+    let details_inner = details_block.inner(columns[1]);
+    f.render_widget(details_block, columns[1]);
+
+    if let Some((recipe, missing)) = recipes.get(app.crafting_selected_index) {
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{}: ", label), Style::default().fg(Color::Yellow)),
+                Span::styled(value, Style::default().fg(Color::White)),
+            ])
+        };
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("{} x{}", recipe.name(), recipe.qty),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            field(
+                "Skill",
+                format!("{} {}", recipe.skill.name(), recipe.min_skill),
+            ),
+            field(
+                "Workbench",
+                if recipe.workbench {
+                    "required"
+                } else {
+                    "not needed"
+                }
+                .to_string(),
+            ),
+            field("Time", format!("{} minutes", recipe.minutes)),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Components:",
+                Style::default().fg(Color::Yellow),
+            )),
+        ];
+        for component in &recipe.components {
+            let have = character.item_count(&component.id);
+            let name = crate::game::items::registry()
+                .item(&component.id)
+                .map_or_else(|| component.id.clone(), |item| item.name.to_string());
+            let color = if have >= component.qty {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {} {}/{}", name, have, component.qty),
+                Style::default().fg(color),
+            )));
+        }
+        lines.push(Line::from(""));
+        if missing.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Ready to craft",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Still needs:",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            for need in missing {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", need),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+        }
+        f.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: true }),
+            details_inner,
+        );
+    }
+
+    if let Some(last) = app.message_log.back() {
+        let color = match last.message_type {
+            MessageType::Error => Color::Red,
+            _ => Color::Green,
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!(" {}", last.content),
+                Style::default().fg(color),
+            )),
+            rows[2],
+        );
+    }
+
+    let hints = Paragraph::new(Span::styled(
+        " ↑/↓ select  Enter craft  Esc close",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(hints, rows[3]);
+}
+
+/// Render the quest journal: quest list on the left, details on the right
+fn render_journal(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title("📜 Quest Journal")
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .border_type(BorderType::Double);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Lists
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner_area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[0]);
+
+    let quests = app.game_state.quests.journal();
+    if quests.is_empty() {
+        let text = Paragraph::new("No quests yet. Talk to people in the wasteland.")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(text, rows[0]);
+        return;
+    }
+    let selected = app.journal_selected_index.min(quests.len() - 1);
+
+    // Left panel: quests, active first
+    let items: Vec<ListItem> = quests
+        .iter()
+        .enumerate()
+        .map(|(idx, quest)| {
+            let (icon, color) = match quest.status {
+                QuestStatus::Active => ("●", Color::White),
+                QuestStatus::Completed => ("✓", Color::Green),
+                QuestStatus::Failed => ("✗", Color::Red),
+            };
+            let marker = if idx == selected { "► " } else { "  " };
+            let style = if idx == selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            ListItem::new(format!("{}{} {}", marker, icon, quest.name)).style(style)
+        })
+        .collect();
+    let list_block = Block::default()
+        .title(" Quests ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green))
+        .border_type(BorderType::Plain);
+    f.render_widget(List::new(items).block(list_block), columns[0]);
+
+    // Right panel: details of the selected quest
+    let quest = quests[selected];
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![
+        Line::from(Span::styled(quest.name.to_string(), heading)),
+        Line::from(format!("Status: {}", quest.status.name())),
+    ];
+    if let Some(giver) = &quest.giver {
+        let giver = app
+            .game_state
+            .worldbook
+            .get_npc(giver)
+            .map_or(giver.as_str(), |npc| npc.name.as_str());
+        lines.push(Line::from(format!("Given by: {}", giver)));
+    }
+    if !quest.description.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(quest.description.clone()));
+    }
+
+    lines.push(Line::from(""));
+    if quest.is_tracked() {
+        for (idx, stage) in quest.stages.iter().enumerate() {
+            // Only stages the player has reached are shown
+            if idx > quest.stage {
+                break;
+            }
+            let current = quest.current_stage().is_some() && idx == quest.stage;
+            let style = if current {
+                Style::default().fg(Color::Cyan)
+            } else {
+                dim
+            };
+            lines.push(Line::from(Span::styled(stage.description.clone(), style)));
+            for objective in &stage.objectives {
+                let check = if objective.done { "[x]" } else { "[ ]" };
+                lines.push(Line::from(Span::styled(
+                    format!("  {} {}", check, objective.description),
+                    if objective.done { dim } else { style },
+                )));
+            }
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            "Untracked - the DM will update this quest as the story unfolds.",
+            dim,
+        )));
+    }
+
+    if !quest.locations.is_empty() {
+        let locations: Vec<&str> = quest
+            .locations
+            .iter()
+            .map(|id| {
+                app.game_state
+                    .worldbook
+                    .get_location(id)
+                    .map_or(id.as_str(), |location| location.name.as_str())
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Locations: {}", locations.join(", "))));
+    }
+
+    if !quest.rewards.is_empty() {
+        let mut rewards = Vec::new();
+        if quest.rewards.xp > 0 {
+            rewards.push(format!("{} XP", quest.rewards.xp));
+        }
+        if quest.rewards.caps > 0 {
+            rewards.push(format!("{} caps", quest.rewards.caps));
+        }
+        for item in &quest.rewards.items {
+            rewards.push(format!("{} x{}", item.id, item.qty));
+        }
+        lines.push(Line::from(format!("Rewards: {}", rewards.join(", "))));
+    }
+
+    if !quest.notes.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Notes:", heading)));
+        for note in &quest.notes {
+            lines.push(Line::from(format!("  - {}", note)));
+        }
+    }
+
+    let details_block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green))
+        .border_type(BorderType::Plain);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(details_block)
+            .wrap(Wrap { trim: false }),
+        columns[1],
+    );
+
+    let hints = Paragraph::new(Span::styled(" ↑/↓ select  Esc close", dim));
+    f.render_widget(hints, rows[1]);
+}
+
 fn render_detailed_stats(f: &mut Frame, app: &App, area: Rect) {
     let character = &app.game_state.character;
 
@@ -1157,7 +2264,7 @@ fn render_sheet_skills(f: &mut Frame, character: &Character, area: Rect) {
         ("Sneak", skills.sneak),
         ("Doctor", skills.doctor),
     ];
-    skill_list.sort_by(|a, b| b.1.cmp(&a.1));
+    skill_list.sort_by_key(|b| std::cmp::Reverse(b.1));
     let top_skills: Vec<_> = skill_list.into_iter().take(4).collect();
 
     let mut lines = vec![
@@ -1431,6 +2538,12 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
     let character = &app.game_state.character;
     let location = &app.game_state.location;
     let day = app.game_state.day;
+    let time = app.game_state.time;
+    let time_icon = if time.period().is_night() {
+        "☾"
+    } else {
+        "☀"
+    };
 
     // Calculate HP color based on health percentage
     let hp_ratio = character.current_hp as f64 / character.max_hp as f64;
@@ -1466,7 +2579,10 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(Color::White),
         ),
         Span::raw("  │  "),
-        Span::styled(format!("Day {}", day), Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("Day {} {} {}", day, time_icon, time),
+            Style::default().fg(Color::Cyan),
+        ),
         Span::raw("  │  "),
         Span::styled("💰", Style::default().fg(Color::Yellow)),
         Span::raw(" "),
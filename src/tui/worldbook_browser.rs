@@ -3,6 +3,7 @@
 //! Provides a rich UI for exploring the worldbook with tabs, tree navigation,
 //! and detailed views of locations, NPCs, and events.
 
+use crate::game::clock;
 use crate::game::worldbook::{Location, WorldEvent, Worldbook, NPC};
 use std::collections::HashMap;
 
@@ -248,44 +249,47 @@ impl Default for WorldbookBrowser {
     }
 }
 
-/// Helper to format relative time
-pub fn format_relative_time(timestamp: &str) -> String {
-    // For now, just return "Recently" or the raw timestamp
-    // You could use chrono to calculate actual relative time
+/// Format a game-time timestamp relative to `now` (the current game time)
+pub fn format_relative_time(timestamp: &str, now: &str) -> String {
     if timestamp.is_empty() {
-        "Unknown".to_string()
-    } else {
-        // Parse the RFC3339 timestamp and format nicely
-        match chrono::DateTime::parse_from_rfc3339(timestamp) {
-            Ok(dt) => {
-                let now = chrono::Utc::now();
-                let duration = now.signed_duration_since(dt);
-
-                if duration.num_seconds() < 60 {
-                    "Just now".to_string()
-                } else if duration.num_minutes() < 60 {
-                    format!("{}m ago", duration.num_minutes())
-                } else if duration.num_hours() < 24 {
-                    format!("{}h ago", duration.num_hours())
-                } else if duration.num_days() < 7 {
-                    format!("{}d ago", duration.num_days())
-                } else {
-                    dt.format("%Y-%m-%d").to_string()
-                }
-            }
+        return "Unknown".to_string();
+    }
+
+    // Timestamps from older saves used the wall clock; show them as dates
+    let (Some((day, time)), Some((now_day, now_time))) = (
+        clock::parse_timestamp(timestamp),
+        clock::parse_timestamp(now),
+    ) else {
+        return match chrono::DateTime::parse_from_rfc3339(timestamp) {
+            Ok(dt) => dt.format("%Y-%m-%d").to_string(),
             Err(_) => timestamp.to_string(),
-        }
+        };
+    };
+
+    let minutes = (now_day as i64 - day as i64) * clock::MINUTES_PER_DAY as i64
+        + (now_time.hour() * 60 + now_time.minute()) as i64
+        - (time.hour() * 60 + time.minute()) as i64;
+    if minutes < 1 {
+        "Just now".to_string()
+    } else if minutes < 60 {
+        format!("{}m ago", minutes)
+    } else if minutes < clock::MINUTES_PER_DAY as i64 {
+        format!("{}h ago", minutes / 60)
+    } else if now_day - day < 7 {
+        format!("{}d ago", now_day - day)
+    } else {
+        format!("Day {}, {}", day, time)
     }
 }
 
 /// Get visit status string
-pub fn get_visit_status(location: &Location) -> String {
+pub fn get_visit_status(location: &Location, now: &str) -> String {
     if location.visit_count == 0 {
         "Unexplored".to_string()
     } else if location.visit_count == 1 {
         "Discovered".to_string()
     } else if let Some(last_visited) = &location.last_visited {
-        format!("Last: {}", format_relative_time(last_visited))
+        format!("Last: {}", format_relative_time(last_visited, now))
     } else {
         format!("Visited: {}x", location.visit_count)
     }
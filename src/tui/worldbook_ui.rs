@@ -147,7 +147,7 @@ fn render_locations_list(f: &mut Frame, app: &App, area: Rect) {
         let is_selected = i == browser.selected_index;
         let is_current = Some(&location.id) == worldbook.current_location.as_ref();
 
-        let visit_status = get_visit_status(location);
+        let visit_status = get_visit_status(location, &worldbook.current_time);
         let prefix = if browser.is_expanded(&location.id) {
             "▾"
         } else {
@@ -261,11 +261,14 @@ fn render_location_detail(f: &mut Frame, app: &App, area: Rect) {
         if let Some(first) = &location.first_visited {
             lines.push(Line::from(format!(
                 "First: {}",
-                format_relative_time(first)
+                format_relative_time(first, &worldbook.current_time)
             )));
         }
         if let Some(last) = &location.last_visited {
-            lines.push(Line::from(format!("Last: {}", format_relative_time(last))));
+            lines.push(Line::from(format!(
+                "Last: {}",
+                format_relative_time(last, &worldbook.current_time)
+            )));
         }
         lines.push(Line::from(""));
     }
@@ -552,7 +555,7 @@ fn render_events_list(f: &mut Frame, app: &App, area: Rect) {
                 _ => "📌",
             };
 
-            let time = format_relative_time(&event.timestamp);
+            let time = format_relative_time(&event.timestamp, &worldbook.current_time);
             let line = format!(
                 "{}{} {:<15} {}",
                 selector,
@@ -597,7 +600,10 @@ fn render_event_detail(f: &mut Frame, app: &App, area: Rect) {
             .add_modifier(Modifier::BOLD),
     )));
     lines.push(Line::from(Span::styled(
-        format!("Time: {}", format_relative_time(&event.timestamp)),
+        format!(
+            "Time: {}",
+            format_relative_time(&event.timestamp, &worldbook.current_time)
+        ),
         Style::default().fg(Color::Yellow),
     )));
     lines.push(Line::from(""));
@@ -4,6 +4,7 @@
 //! replacing manual validation logic with cleaner, more maintainable validation rules.
 
 use crate::error::{CharacterError, GameError};
+use crate::game::dice::DiceExpr;
 use garde::Validate;
 
 /// Character name with built-in validation
@@ -216,6 +217,32 @@ impl SpecialAllocation {
     }
 }
 
+/// Custom validator for IDs in the game data files (items, enemies,
+/// encounters): lowercase snake_case, as used by saves and DM actions
+pub fn validate_data_id(value: &str, _context: &()) -> garde::Result {
+    if value.is_empty() || value.len() > 40 {
+        return Err(garde::Error::new("ID must be 1-40 characters"));
+    }
+    if value
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(garde::Error::new(
+            "ID can only contain lowercase letters, digits, and underscores",
+        ))
+    }
+}
+
+/// Custom validator for dice expressions such as weapon damage
+pub fn validate_dice_expression(value: &str, _context: &()) -> garde::Result {
+    value
+        .parse::<DiceExpr>()
+        .map(|_| ())
+        .map_err(|e| garde::Error::new(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,4 +315,20 @@ mod tests {
         // Stat out of range (11 is invalid)
         assert!(SpecialAllocation::new(11, 4, 4, 4, 4, 4, 4, 28).is_err());
     }
+
+    #[test]
+    fn test_data_id_validator() {
+        assert!(validate_data_id("10mm_pistol", &()).is_ok());
+        assert!(validate_data_id("", &()).is_err());
+        assert!(validate_data_id("Plasma Rifle", &()).is_err());
+        assert!(validate_data_id("../stimpak", &()).is_err());
+    }
+
+    #[test]
+    fn test_dice_expression_validator() {
+        assert!(validate_dice_expression("2d8+3", &()).is_ok());
+        assert!(validate_dice_expression("1d6+STR", &()).is_ok());
+        assert!(validate_dice_expression("2d", &()).is_err());
+        assert!(validate_dice_expression("lots", &()).is_err());
+    }
 }
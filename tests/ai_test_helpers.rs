@@ -36,6 +36,7 @@ fn snapshot_extracted_entities_summary() {
             name: "Megaton".into(),
             description: "Settlement around a bomb".into(),
             location_type: "settlement".into(),
+            workbench: false,
         }],
         npcs: vec![ExtractedNPC {
             name: "Sheriff Simms".into(),
@@ -129,6 +130,7 @@ pub fn mock_extracted_location(name: &str, desc: &str) -> ExtractedLocation {
         name: name.into(),
         description: desc.into(),
         location_type: "settlement".into(),
+        workbench: false,
     }
 }
 
@@ -1,7 +1,7 @@
 /// Comprehensive tests for character leveling and experience system
 mod helpers;
 
-use fallout_dnd::game::character::{Character, Special};
+use fallout_dnd::game::character::{Character, Perk, Special};
 use helpers::*;
 
 #[test]
@@ -343,3 +343,63 @@ fn test_character_traits_and_perks() {
     assert_eq!(character.traits.len(), 1);
     assert_eq!(character.perks.len(), 1);
 }
+
+#[test]
+fn test_perks_stored_by_name_are_recognized() {
+    let mut character = create_test_character("Test");
+    assert_eq!(character.damage_reduction(), 0);
+
+    // Older saves or hand-edited perks may use the display name
+    character.perks.push("Toughness".into());
+    assert!(character.has_perk(Perk::Toughness));
+    assert_eq!(character.damage_reduction(), Perk::TOUGHNESS_REDUCTION);
+}
+
+#[test]
+fn test_combat_perk_effects() {
+    let mut character = create_test_character("Test");
+    assert_eq!(character.critical_threshold(), 20);
+    assert_eq!(character.critical_multiplier(), 2);
+    assert_eq!(character.damage_bonus(), 0);
+
+    character.perks.push(Perk::MoreCriticals.id().into());
+    character.perks.push(Perk::BetterCriticals.id().into());
+    assert_eq!(character.critical_threshold(), 19);
+    assert_eq!(character.critical_multiplier(), 3);
+
+    // Starting 10mm pistol is a small gun, so only the ranged bonus applies
+    character.perks.push(Perk::BonusHthDamage.id().into());
+    assert_eq!(character.damage_bonus(), 0);
+    character.perks.push(Perk::BonusRangedDamage.id().into());
+    assert_eq!(character.damage_bonus(), Perk::BONUS_DAMAGE);
+
+    character.equipped_weapon = None;
+    assert_eq!(character.damage_bonus(), Perk::BONUS_DAMAGE);
+}
+
+#[test]
+fn test_skill_points_scale_with_intelligence() {
+    let mut special = Special::new();
+    special.intelligence = 10;
+    let mut smart = Character::new("Smart", special);
+    let mut dull = Character::new("Dull", Special::new());
+
+    smart.add_experience(1000);
+    dull.add_experience(1000);
+    smart.level_up();
+    dull.level_up();
+
+    assert_eq!(smart.skill_points, 25);
+    assert_eq!(dull.skill_points, 7);
+}
+
+#[test]
+fn test_perk_picks_every_interval() {
+    let mut character = create_test_character("Test");
+    character.add_experience(8000);
+    let summary = character.level_up().unwrap();
+
+    assert_eq!(summary.new_level, 9);
+    // Levels 3, 6 and 9
+    assert_eq!(character.perk_points, 3);
+}
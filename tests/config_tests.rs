@@ -1,5 +1,5 @@
 /// Comprehensive tests for configuration loading and validation
-use fallout_dnd::config::{Config, GameConfig, LlamaConfig};
+use fallout_dnd::config::{BackendKind, ChatTemplate, Config, GameConfig, LlamaConfig};
 
 #[test]
 fn test_default_config_creation() {
@@ -196,6 +196,11 @@ fn test_custom_llama_config() {
     let custom = LlamaConfig {
         server_url: "http://custom:8080".to_string(),
         extraction_url: "http://custom:8081".to_string(),
+        backend: BackendKind::OpenAi,
+        extraction_backend: BackendKind::LlamaCpp,
+        model: Some("mistral-nemo".to_string()),
+        extraction_model: None,
+        api_key: None,
         temperature: 0.5,
         top_p: 0.85,
         top_k: 30,
@@ -203,6 +208,11 @@ fn test_custom_llama_config() {
         context_window: 8192,
         repeat_penalty: 1.0,
         system_prompt: "Custom prompt".to_string(),
+        summary_token_budget: 600,
+        worldbook_token_budget: 400,
+        chat_template: ChatTemplate::Plain,
+        chat_template_file: None,
+        chat_template_stop: Vec::new(),
         auto_start: false,
         llama_server_path: None,
         narrative_model_path: None,
@@ -237,12 +247,14 @@ fn test_custom_game_config() {
         starting_caps: 10000,
         permadeath: true,
         autosave_interval: 1,
+        seed: Some("daily".to_string()),
     };
 
     assert_eq!(custom.starting_level, 10);
     assert_eq!(custom.starting_caps, 10000);
     assert!(custom.permadeath);
     assert_eq!(custom.autosave_interval, 1);
+    assert_eq!(custom.seed.as_deref(), Some("daily"));
 }
 
 #[test]
@@ -321,3 +333,92 @@ autosave_interval = 5
     assert!(config_true.game.permadeath);
     assert!(!config_false.game.permadeath);
 }
+
+#[test]
+fn test_backend_selection_from_toml() {
+    let toml_content = r#"
+[llama]
+server_url = "http://localhost:11434"
+extraction_url = "http://localhost:8081"
+backend = "openai"
+model = "mistral-nemo"
+temperature = 0.8
+top_p = 0.9
+top_k = 40
+max_tokens = 512
+context_window = 8192
+repeat_penalty = 1.1
+system_prompt = "Test"
+
+[game]
+starting_level = 1
+starting_caps = 500
+permadeath = false
+autosave_interval = 5
+"#;
+
+    let config: Config = toml::from_str(toml_content).unwrap();
+
+    assert_eq!(config.llama.backend, BackendKind::OpenAi);
+    assert_eq!(config.llama.model.as_deref(), Some("mistral-nemo"));
+    // Unset fields fall back to llama.cpp
+    assert_eq!(config.llama.extraction_backend, BackendKind::LlamaCpp);
+    assert!(config.llama.api_key.is_none());
+}
+
+#[test]
+fn test_chat_template_from_toml() {
+    let toml_content = r#"
+[llama]
+server_url = "http://localhost:8080"
+extraction_url = "http://localhost:8081"
+chat_template = "chatml"
+temperature = 0.8
+top_p = 0.9
+top_k = 40
+max_tokens = 512
+context_window = 8192
+repeat_penalty = 1.1
+system_prompt = "Test"
+
+[game]
+starting_level = 1
+starting_caps = 500
+permadeath = false
+autosave_interval = 5
+"#;
+
+    let mut config: Config = toml::from_str(toml_content).unwrap();
+    assert_eq!(config.llama.chat_template, ChatTemplate::ChatMl);
+    assert!(config
+        .llama
+        .chat_template
+        .stop_sequences()
+        .contains(&"<|im_end|>"));
+    assert!(config.validate().is_ok());
+
+    // A custom template needs its file
+    config.llama.chat_template = ChatTemplate::Custom;
+    assert!(config.validate().is_err());
+    config.llama.chat_template_file = Some("my_model.tera".to_string());
+    assert!(config.validate().is_err()); // No such file
+
+    // The file is read and checked up front
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("my_model.tera");
+    std::fs::write(&path, "{{ system }}{% for m in messages %}").unwrap();
+    config.llama.chat_template_file = Some(path.to_string_lossy().into_owned());
+    assert!(config.validate().is_err());
+    std::fs::write(
+        &path,
+        "{{ system }}{% for m in messages %}<{{ m.role }}>{{ m.content }}{% endfor %}",
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+
+    // OpenAI-compatible servers template the prompt themselves
+    config.llama.backend = BackendKind::OpenAi;
+    assert!(config.validate().is_err());
+    config.llama.chat_template = ChatTemplate::Plain;
+    assert!(config.validate().is_ok());
+}
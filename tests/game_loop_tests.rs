@@ -0,0 +1,401 @@
+/// Deterministic tests for the TUI game loop driven by the scripted backend
+use fallout_dnd::ai::backend::ScriptedBackend;
+use fallout_dnd::ai::extractor::ExtractionAI;
+use fallout_dnd::ai::AIDungeonMaster;
+use fallout_dnd::config::{ChatTemplate, Config};
+use fallout_dnd::game::combat::Enemy;
+use fallout_dnd::game::conversation::Speaker;
+use fallout_dnd::game::tui_game_loop::{handle_player_input, process_stream};
+use fallout_dnd::tui::app::{App, ViewMode};
+use std::sync::Arc;
+
+mod helpers;
+use helpers::create_test_game_state;
+
+fn scripted_dm(responses: &[&str]) -> (AIDungeonMaster, Arc<ScriptedBackend>) {
+    let backend = Arc::new(ScriptedBackend::new(responses.iter().copied()));
+    let dm = AIDungeonMaster::with_backend(Config::default().llama, backend.clone());
+    (dm, backend)
+}
+
+fn scripted_extractor(responses: &[&str]) -> ExtractionAI {
+    ExtractionAI::with_backend(Arc::new(ScriptedBackend::new(responses.iter().copied())))
+}
+
+#[tokio::test]
+async fn test_player_turn_streams_scripted_response() {
+    let (dm, backend) = scripted_dm(&["The wind howls across the ruined highway."]);
+    let extractor = scripted_extractor(&[r#"{"locations": [], "npcs": [], "events": []}"#]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "look around", &dm)
+        .await
+        .unwrap();
+    assert!(app.is_streaming);
+    assert!(app.waiting_for_ai);
+
+    process_stream(&mut app, &dm, &extractor).await;
+
+    assert!(!app.is_streaming);
+    assert!(!app.waiting_for_ai);
+
+    let turns = app.game_state.conversation.get_all_turns();
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].speaker, Speaker::Player);
+    assert_eq!(turns[0].message, "look around");
+    assert_eq!(turns[1].speaker, Speaker::DM);
+    assert_eq!(
+        turns[1].message,
+        "The wind howls across the ruined highway."
+    );
+
+    // The prompt sent to the backend ends with the player's action
+    let prompts = backend.prompts();
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains(">>> PLAYER: look around"));
+}
+
+#[tokio::test]
+async fn test_builtin_commands_do_not_call_backend() {
+    let (dm, backend) = scripted_dm(&[]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "help", &dm).await.unwrap();
+    handle_player_input(&mut app, "inventory", &dm)
+        .await
+        .unwrap();
+
+    assert!(backend.prompts().is_empty());
+    assert!(!app.waiting_for_ai);
+    assert!(app.game_state.conversation.is_empty());
+}
+
+#[tokio::test]
+async fn test_roll_command_shows_breakdown_without_calling_backend() {
+    let (dm, backend) = scripted_dm(&["You squeeze under the fence."]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "roll 2d6+STR", &dm)
+        .await
+        .unwrap();
+    assert!(backend.prompts().is_empty());
+    assert!(app
+        .message_log
+        .iter()
+        .any(|m| m.content.starts_with("🎲 2d6+STR: [")));
+
+    // Text that isn't a dice expression still goes to the DM
+    handle_player_input(&mut app, "roll under the fence", &dm)
+        .await
+        .unwrap();
+    assert_eq!(backend.prompts().len(), 1);
+}
+
+#[tokio::test]
+async fn test_skill_check_requests_outcome_narration() {
+    let (dm, backend) = scripted_dm(&[
+        "The terminal is locked. Make a Science check (DC 10) to hack it.",
+        "The screen flickers and the lock releases.",
+    ]);
+    let extractor = scripted_extractor(&[]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "hack the terminal", &dm)
+        .await
+        .unwrap();
+    process_stream(&mut app, &dm, &extractor).await;
+
+    // The skill check triggers a second generation for the outcome
+    assert!(app.is_streaming);
+    process_stream(&mut app, &dm, &extractor).await;
+
+    assert_eq!(backend.remaining(), 0);
+    let prompts = backend.prompts();
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts[1].contains("GAME SYSTEM UPDATE"));
+
+    let last = app.game_state.conversation.get_all_turns().back().unwrap();
+    assert_eq!(last.speaker, Speaker::DM);
+    assert_eq!(last.message, "The screen flickers and the lock releases.");
+}
+
+#[tokio::test]
+async fn test_backend_error_surfaces_in_log() {
+    let (dm, _backend) = scripted_dm(&[]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "open the door", &dm)
+        .await
+        .unwrap();
+
+    assert!(!app.waiting_for_ai);
+    assert!(app
+        .message_log
+        .iter()
+        .any(|m| m.content.contains("Scripted backend has no responses left")));
+}
+
+#[tokio::test]
+async fn test_dm_actions_are_applied_to_game_state() {
+    let (dm, _backend) = scripted_dm(&["The trader takes your caps and hands over a rifle. \
+         <actions>[{\"action\": \"take_caps\", \"n\": 100}, \
+         {\"action\": \"give_item\", \"id\": \"hunting_rifle\"}, \
+         {\"action\": \"give_item\", \"id\": \"gauss_rifle\"}]</actions>"]);
+    let extractor = scripted_extractor(&[]);
+    let mut app = App::new(create_test_game_state());
+    let caps_before = app.game_state.character.caps;
+
+    handle_player_input(&mut app, "buy the rifle", &dm)
+        .await
+        .unwrap();
+    process_stream(&mut app, &dm, &extractor).await;
+
+    assert_eq!(app.game_state.character.caps, caps_before - 100);
+    assert!(app
+        .game_state
+        .character
+        .find_item_by_id("hunting_rifle")
+        .is_some());
+
+    // The unknown item is rejected, not invented
+    assert!(app
+        .game_state
+        .character
+        .find_item_by_id("gauss_rifle")
+        .is_none());
+
+    // The action block never reaches the conversation history
+    let last = app.game_state.conversation.get_all_turns().back().unwrap();
+    assert!(!last.message.contains("<actions>"));
+    assert!(last.message.starts_with("The trader takes your caps"));
+}
+
+#[tokio::test]
+async fn test_dm_can_start_combat() {
+    let (dm, _backend) = scripted_dm(&[
+        "Raiders burst from the ruins! \
+         <actions>{\"action\": \"start_combat\", \"enemies\": [{\"name\": \"raider\", \"count\": 2}]}</actions>",
+    ]);
+    let extractor = scripted_extractor(&[]);
+    let mut app = App::new(create_test_game_state());
+
+    handle_player_input(&mut app, "walk into the ruins", &dm)
+        .await
+        .unwrap();
+    process_stream(&mut app, &dm, &extractor).await;
+
+    assert!(app.game_state.combat.active);
+    assert_eq!(app.game_state.combat.enemies.len(), 2);
+    assert_eq!(app.view_mode, ViewMode::Combat);
+}
+
+#[tokio::test]
+async fn test_combat_victory_levels_up_character() {
+    let (dm, backend) = scripted_dm(&[]);
+    let mut app = App::new(create_test_game_state());
+    app.game_state.character.experience = 990;
+
+    let mut enemy = Enemy::radroach(1);
+    enemy.current_hp = 1;
+    enemy.armor_class = 0;
+    app.game_state.combat.start_combat(vec![enemy]);
+    app.set_view_mode(ViewMode::Combat);
+
+    handle_player_input(&mut app, "attack 1", &dm)
+        .await
+        .unwrap();
+
+    assert!(backend.prompts().is_empty());
+    assert!(!app.game_state.combat.active);
+    assert_eq!(app.game_state.character.level, 2);
+    assert!(app.game_state.character.skill_points > 0);
+    assert_eq!(app.view_mode, ViewMode::LevelUp);
+}
+
+#[tokio::test]
+async fn test_quest_xp_levels_up_only_after_fleeing() {
+    use fallout_dnd::game::quests::{Goal, Objective, Quest, QuestStage};
+    use fallout_dnd::game::GameState;
+
+    let (dm, _backend) = scripted_dm(&[]);
+    let character = helpers::create_test_character("Test Hero");
+    let mut app = App::new(GameState::with_seed(character, 7));
+    let mut quest = Quest::new("Bounty on Butch");
+    quest.rewards.xp = 1000;
+    quest.add_stage(QuestStage {
+        description: "Track down Butch".to_string(),
+        objectives: vec![Objective::new(
+            "Kill Butch",
+            Some(Goal::KillNpc {
+                npc: "butch".into(),
+            }),
+        )],
+    });
+    app.game_state.quests.add(quest);
+
+    let mut butch = Enemy::raider(1);
+    butch.name = "Butch".into();
+    butch.current_hp = 1;
+    butch.armor_class = 0;
+    start_test_fight(&mut app);
+    app.game_state.combat.enemies.insert(0, butch);
+
+    handle_player_input(&mut app, "attack 1", &dm)
+        .await
+        .unwrap();
+    assert!(app.game_state.combat.active);
+    assert!(app.game_state.character.experience >= 1000);
+    // No level up mid-fight
+    assert_eq!(app.game_state.character.level, 1);
+    assert_eq!(app.view_mode, ViewMode::Combat);
+
+    while app.game_state.combat.active {
+        handle_player_input(&mut app, "flee", &dm).await.unwrap();
+    }
+    assert_eq!(app.game_state.character.level, 2);
+    assert_eq!(app.view_mode, ViewMode::LevelUp);
+}
+
+/// Start a fight against one harmless enemy that can't be killed in one shot
+fn start_test_fight(app: &mut App) {
+    let mut enemy = Enemy::radroach(1);
+    enemy.current_hp = 10_000;
+    enemy.max_hp = 10_000;
+    enemy.skill = 0;
+    enemy.damage = "0d4".into();
+    app.game_state.combat.start_combat(vec![enemy]);
+    app.set_view_mode(ViewMode::Combat);
+}
+
+#[tokio::test]
+async fn test_attacks_spend_weapon_ap_and_ammo() {
+    let (dm, _backend) = scripted_dm(&[]);
+    let mut app = App::new(create_test_game_state());
+    start_test_fight(&mut app);
+    let max_ap = app.game_state.character.max_ap;
+
+    handle_player_input(&mut app, "attack 1", &dm)
+        .await
+        .unwrap();
+
+    let character = &app.game_state.character;
+    let pistol = character.equipped_weapon_stats().unwrap();
+    assert_eq!(pistol.loaded_rounds, 11);
+    // 7 AP minus the pistol's 4 leaves enough for a reload, so the turn goes on
+    assert_eq!(character.current_ap, max_ap - 4);
+    assert_eq!(app.game_state.combat.round, 1);
+
+    handle_player_input(&mut app, "end", &dm).await.unwrap();
+    assert_eq!(app.game_state.character.current_ap, max_ap);
+    assert_eq!(app.game_state.combat.round, 2);
+}
+
+#[tokio::test]
+async fn test_empty_weapon_clicks_and_reload_costs_ap() {
+    let (dm, _backend) = scripted_dm(&[]);
+    let mut app = App::new(create_test_game_state());
+    start_test_fight(&mut app);
+    app.game_state
+        .character
+        .remove_item_quantity("10mm_ammo", 24);
+    while app.game_state.character.consume_round() {}
+
+    handle_player_input(&mut app, "attack 1", &dm)
+        .await
+        .unwrap();
+    assert!(app
+        .message_log
+        .iter()
+        .any(|m| m.content.contains("*Click*")));
+
+    // No spare ammo: reloading fails and costs nothing
+    let ap = app.game_state.character.current_ap;
+    handle_player_input(&mut app, "reload", &dm).await.unwrap();
+    assert_eq!(app.game_state.character.current_ap, ap);
+
+    app.game_state
+        .character
+        .add_item(fallout_dnd::game::items::find_item("10mm_ammo").unwrap());
+    app.game_state.character.restore_ap();
+    handle_player_input(&mut app, "reload", &dm).await.unwrap();
+    let character = &app.game_state.character;
+    assert_eq!(character.equipped_weapon_stats().unwrap().loaded_rounds, 1);
+    assert_eq!(character.current_ap, character.max_ap - 2);
+}
+
+#[tokio::test]
+async fn test_prompt_trims_old_history_to_fit_context_window() {
+    let mut game_state = create_test_game_state();
+    for i in 0..10 {
+        game_state.conversation.add_player_turn(format!(
+            "Turn {}: {}",
+            i,
+            "I scavenge the ruins. ".repeat(20)
+        ));
+    }
+
+    // With the default context window everything fits
+    let (dm, _backend) = scripted_dm(&["Ok."]);
+    dm.generate_response_stream(&game_state, "look around")
+        .await
+        .unwrap();
+    let report = dm.last_prompt_report().unwrap();
+    assert!(report.fits());
+    assert_eq!(report.trimmed().count(), 0);
+    let history = report
+        .sections
+        .iter()
+        .find(|section| section.name == "conversation history")
+        .unwrap()
+        .tokens;
+
+    // Leave room for about half the history
+    let mut config = Config::default().llama;
+    config.context_window = config.max_tokens + (report.tokens - history / 2) as i32;
+    let backend = Arc::new(ScriptedBackend::new(["Ok."]));
+    let dm = AIDungeonMaster::with_backend(config, backend.clone());
+    dm.generate_response_stream(&game_state, "look around")
+        .await
+        .unwrap();
+
+    let report = dm.last_prompt_report().unwrap();
+    assert!(report.fits());
+    let trimmed: Vec<_> = report.trimmed().collect();
+    assert_eq!(trimmed.len(), 1);
+    assert_eq!(trimmed[0].name, "conversation history");
+    assert!(trimmed[0].dropped >= 5);
+
+    // The oldest turns go, the newest stay
+    let prompt = &backend.prompts()[0];
+    assert!(!prompt.contains("Turn 0:"));
+    assert!(prompt.contains("Turn 9:"));
+    assert!(prompt.ends_with(">>> PLAYER: look around\n\n>>> DM (YOU):"));
+}
+
+#[tokio::test]
+async fn test_prompt_uses_chat_template() {
+    let mut game_state = create_test_game_state();
+    game_state
+        .conversation
+        .add_player_turn("I wake up in the vault".to_string());
+    game_state
+        .conversation
+        .add_dm_turn("The overseer is waiting for you.".to_string());
+
+    let mut config = Config::default().llama;
+    config.chat_template = ChatTemplate::ChatMl;
+    let backend = Arc::new(ScriptedBackend::new(["Ok."]));
+    let dm = AIDungeonMaster::with_backend(config, backend.clone());
+    dm.generate_response_stream(&game_state, "look around")
+        .await
+        .unwrap();
+
+    // Game context in the system message, then the conversation as turns
+    let prompt = &backend.prompts()[0];
+    assert!(prompt.starts_with("<|im_start|>system\n"));
+    assert!(prompt.contains("<|im_start|>user\nI wake up in the vault<|im_end|>"));
+    assert!(prompt.contains("<|im_start|>assistant\nThe overseer is waiting for you.<|im_end|>"));
+    assert!(prompt.ends_with("<|im_start|>user\nlook around<|im_end|>\n<|im_start|>assistant\n"));
+    assert!(!prompt.contains(">>> PLAYER:"));
+    assert!(dm.last_prompt_report().unwrap().fits());
+}
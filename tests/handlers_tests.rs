@@ -91,6 +91,7 @@ fn create_test_consumable(id: &str, name: &str, effect: ConsumableEffect) -> Ite
         weight: 0.5,
         value: 10,
         quantity: 1,
+        instance: 0,
     }
 }
 
@@ -306,7 +307,7 @@ fn test_find_location_not_found() {
         .worldbook
         .locations
         .values()
-        .find(|loc| loc.name_lowercase.contains("rivet city"));
+        .find(|loc| loc.name_lowercase.contains("paradise falls"));
 
     assert!(not_found.is_none());
 }
@@ -550,7 +551,7 @@ fn test_handle_equip_with_additional_weapon() {
         4,
         300,
     );
-    game_state.character.inventory.push(weapon);
+    game_state.character.inventory.add(weapon);
 
     let weapons: Vec<_> = game_state
         .character
@@ -575,7 +576,7 @@ fn test_handle_equip_with_additional_armor() {
         10,
         500,
     );
-    game_state.character.inventory.push(armor);
+    game_state.character.inventory.add(armor);
 
     let armor_items: Vec<_> = game_state
         .character
@@ -610,10 +611,12 @@ fn test_use_consumable_radiation_removal() {
     let mut game_state = GameState::new(create_test_character("Hero"));
 
     let radaway = create_test_consumable("radaway", "RadAway", ConsumableEffect::RadAway(20));
-    game_state.character.inventory.push(radaway);
+    game_state.character.inventory.add(radaway);
+    game_state.character.add_rads(100);
 
     let result = game_state.character.use_consumable("radaway");
     assert!(result.is_ok());
+    assert!(game_state.character.status.rads < 100);
 }
 
 #[test]
@@ -631,7 +634,7 @@ fn test_use_consumable_in_combat() {
 
     game_state.character.current_hp = 50;
     let stimpak = create_test_consumable("stimpak", "Stimpak", ConsumableEffect::Healing(25));
-    game_state.character.inventory.push(stimpak);
+    game_state.character.inventory.add(stimpak);
 
     let result = game_state.character.use_consumable("stimpak");
     assert!(result.is_ok());
@@ -650,11 +653,112 @@ fn test_game_state_new_character() {
 
     assert_eq!(game_state.character.name, "Test Hero");
     assert_eq!(game_state.location, "Vault 13 Entrance");
-    assert_eq!(game_state.quest_log.len(), 1);
+    assert!(game_state.quest_log.is_empty());
+    assert_eq!(game_state.quests.active().count(), 1);
     assert!(!game_state.combat.active);
     assert_eq!(game_state.day, 1);
 }
 
+#[test]
+fn test_rest_heals_and_advances_clock() {
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    game_state.character.current_hp = 1;
+    let rate = game_state.character.healing_rate();
+
+    let messages = game_state.rest(8).unwrap();
+
+    assert_eq!(game_state.character.current_hp, 1 + rate * 8);
+    assert_eq!(game_state.time.to_string(), "16:00");
+    assert!(messages[0].starts_with("You rest for 8 hours"));
+    assert_eq!(game_state.worldbook.current_time, game_state.timestamp());
+
+    // Resting through midnight starts a new day
+    game_state.rest(10).unwrap();
+    assert_eq!(game_state.day, 2);
+    assert_eq!(game_state.time.to_string(), "02:00");
+    assert!(game_state.time.period().is_night());
+
+    assert!(game_state.rest(0).is_err());
+    assert!(game_state.rest(25).is_err());
+    game_state.combat.active = true;
+    assert!(game_state.rest(1).is_err());
+}
+
+#[test]
+fn test_trade_with_merchant() {
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    game_state.character.caps = 500;
+    game_state.worldbook.add_npc(NPC {
+        id: "moira_brown".into(),
+        name: "Moira Brown".into(),
+        name_lowercase: "moira brown".into(),
+        role: "merchant".into(),
+        disposition: 40,
+        personality: vec![],
+        current_location: None,
+        alive: true,
+        knowledge: vec![],
+        notes: "".into(),
+    });
+
+    // Non-merchants and strangers can't trade
+    assert!(game_state.start_trade("Three Dog").is_err());
+    game_state
+        .worldbook
+        .npcs
+        .get_mut("moira_brown")
+        .unwrap()
+        .role = "settler".into();
+    assert!(game_state.start_trade("Moira Brown").is_err());
+    game_state
+        .worldbook
+        .npcs
+        .get_mut("moira_brown")
+        .unwrap()
+        .role = "merchant".into();
+
+    let id = game_state.start_trade("moira brown").unwrap();
+    let merchant = &game_state.worldbook.merchants[&id];
+    let stimpak = merchant
+        .stock
+        .iter()
+        .position(|item| item.id == "stimpak")
+        .unwrap();
+    let price = game_state
+        .trade_prices(&id)
+        .buy_price(&merchant.stock[stimpak]);
+    let merchant_caps = merchant.caps;
+
+    let messages = game_state.buy(&id, stimpak, 1).unwrap();
+    assert!(messages[0].starts_with("Bought Stimpak x1"));
+    assert_eq!(game_state.character.caps, 500 - price);
+    assert_eq!(
+        game_state.worldbook.merchants[&id].caps,
+        merchant_caps + price
+    );
+
+    // Stock is refreshed after a few days
+    game_state
+        .worldbook
+        .merchants
+        .get_mut(&id)
+        .unwrap()
+        .stock
+        .clear();
+    game_state.day += fallout_dnd::game::barter::RESTOCK_DAYS;
+    game_state.start_trade("Moira Brown").unwrap();
+    assert!(!game_state.worldbook.merchants[&id].stock.is_empty());
+
+    // A merchant who hates you won't deal
+    game_state
+        .worldbook
+        .npcs
+        .get_mut("moira_brown")
+        .unwrap()
+        .disposition = -80;
+    assert!(game_state.start_trade("Moira Brown").is_err());
+}
+
 #[test]
 fn test_game_state_conversation_system() {
     let mut game_state = GameState::new(create_test_character("Hero"));
@@ -929,3 +1033,200 @@ fn test_event_type_categorization() {
         assert!(!event_type.is_empty());
     }
 }
+
+#[test]
+fn test_loot_bodies_after_victory() {
+    let mut game_state = GameState::with_seed(create_test_character("Hero"), 7);
+    game_state.character.special.luck = 10;
+    game_state.character.skills.small_guns = 100;
+    let caps = game_state.character.caps;
+
+    let raiders: Vec<Enemy> = (0..3)
+        .filter_map(|_| fallout_dnd::game::bestiary::bestiary().spawn("raider", 1))
+        .collect();
+    game_state.combat.start_combat(raiders);
+    for enemy in game_state.combat.enemies.iter_mut() {
+        enemy.current_hp = 0;
+    }
+    game_state.combat.enemies[0].current_hp = 1;
+    game_state.combat.apply(
+        fallout_dnd::game::combat::CombatAction::Attack { target: 0 },
+        &mut game_state.character,
+        &mut game_state.rng,
+    );
+    assert!(!game_state.combat.active);
+    assert_eq!(game_state.combat.fallen.len(), 3);
+
+    let messages = game_state.loot_fallen();
+    assert!(!messages.is_empty());
+    assert!(game_state.combat.fallen.is_empty());
+    assert!(game_state.character.caps > caps);
+    assert!(!game_state.loot.is_empty());
+
+    // Taking an item moves it into the inventory
+    let first = game_state.loot[0].clone();
+    let messages = game_state.take_loot(0).unwrap();
+    assert!(messages[0].starts_with(&format!("Took {}", first.name)));
+    assert!(game_state.character.find_item_by_id(&first.id).is_some());
+    assert!(game_state.take_loot(99).is_err());
+
+    game_state.take_all_loot();
+    assert!(game_state.loot.is_empty());
+
+    // Bodies can only be searched once
+    assert!(game_state.loot_fallen().is_empty());
+}
+
+#[test]
+fn test_loot_refused_beyond_carry_limit() {
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    let limit = game_state.character.max_carry_weight();
+    game_state.loot = vec![
+        Item::new_misc("crate", "Crate of Junk", "Heavy.", limit, 1),
+        fallout_dnd::game::items::find_item("stimpak").unwrap(),
+    ];
+
+    let error = game_state.take_loot(0).unwrap_err();
+    assert!(error.contains("too heavy"));
+
+    // Take-all skips what can't be carried
+    game_state.take_all_loot();
+    assert_eq!(game_state.loot.len(), 1);
+    assert_eq!(game_state.loot[0].id, "crate");
+}
+
+#[test]
+fn test_drop_and_pick_up_items() {
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    game_state.character.inventory.clear();
+    let mut cans = Item::new_misc("tin_can", "Tin Can", "Empty.", 0.5, 1);
+    cans.quantity = 5;
+    game_state.character.add_item(cans);
+
+    let message = game_state.drop_item(0, Some(2)).unwrap();
+    assert_eq!(message, "Dropped Tin Can x2");
+    assert_eq!(game_state.character.inventory[0].quantity, 3);
+    assert!(game_state.drop_item(0, Some(9)).is_err());
+
+    // Left at the current location
+    let here = game_state.items_here();
+    assert_eq!(here.len(), 1);
+    assert_eq!(here[0].quantity, 2);
+
+    let messages = game_state.pick_up("tin can", None).unwrap();
+    assert_eq!(messages[0], "Picked up Tin Can x2");
+    assert_eq!(game_state.character.inventory.len(), 1);
+    assert_eq!(game_state.character.inventory[0].quantity, 5);
+    assert!(game_state.items_here().is_empty());
+    assert!(game_state.pick_up("tin can", None).is_err());
+}
+
+#[test]
+fn test_dropping_equipped_weapon_unequips_it() {
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    let weapon = game_state.character.equipped_weapon.clone().unwrap();
+    let index = game_state.character.inventory.position(&weapon).unwrap();
+
+    game_state.drop_item(index, None).unwrap();
+    assert!(game_state.character.equipped_weapon.is_none());
+    assert!(game_state.character.inventory.position(&weapon).is_none());
+}
+
+#[test]
+fn test_repair_with_spare_parts() {
+    let mut game_state = GameState::with_seed(create_test_character("Hero"), 7);
+    game_state.character.inventory.clear();
+    let mut armor = Item::new_armor("leather_armor", "Leather Armor", "Worn.", 5, 100);
+    if let ItemType::Armor(ref mut stats) = armor.item_type {
+        stats.condition = 40;
+    }
+    game_state.character.add_item(armor);
+
+    // Nothing to strip but the armor itself, so it takes a Repair check
+    let messages = game_state.repair("leather armor").unwrap();
+    assert!(messages[0].contains("Repair"));
+    if let ItemType::Armor(ref mut stats) = game_state.character.inventory[0].item_type {
+        stats.condition = 40;
+    }
+
+    // A spare in a slot of its own goes for parts
+    let mut spare = Item::new_armor("leather_armor", "Leather Armor", "Spare.", 5, 100);
+    spare.instance = 1;
+    game_state.character.add_item(spare);
+    let messages = game_state.repair("leather armor").unwrap();
+    assert_eq!(messages[0], "You strip a spare Leather Armor for parts.");
+    assert_eq!(game_state.character.inventory.len(), 1);
+    let repaired = &game_state.character.inventory[0];
+    assert_eq!(repaired.key(), "leather_armor");
+    assert!(repaired.condition().unwrap() > 40);
+
+    // Nothing to repair on junk, or on gear in perfect condition
+    game_state
+        .character
+        .add_item(Item::new_misc("tin_can", "Tin Can", "Empty.", 0.5, 1));
+    assert!(game_state.repair("tin can").is_err());
+    game_state.character.inventory[0].repair(100);
+    assert!(game_state.repair("leather armor").is_err());
+}
+
+#[test]
+fn test_repair_skill_check_takes_time() {
+    let mut game_state = GameState::with_seed(create_test_character("Hero"), 3);
+    game_state.character.inventory.clear();
+    let mut pistol = Item::new_weapon(
+        "10mm_pistol",
+        "10mm Pistol",
+        "Rusty.",
+        "1d10",
+        fallout_dnd::game::items::DamageType::Normal,
+        fallout_dnd::game::items::WeaponType::SmallGun,
+        4,
+        150,
+    );
+    if let ItemType::Weapon(ref mut stats) = pistol.item_type {
+        stats.condition = 10;
+    }
+    game_state.character.add_item(pistol);
+    let before = game_state.timestamp();
+
+    let messages = game_state.repair("10mm_pistol").unwrap();
+    assert!(messages[0].contains("Repair"));
+    assert!(game_state.character.inventory[0].condition().unwrap() >= 10);
+    assert_ne!(game_state.timestamp(), before);
+}
+
+#[test]
+fn test_crafting_uses_components_and_needs_a_workbench() {
+    use fallout_dnd::game::items::registry;
+
+    let mut game_state = GameState::new(create_test_character("Hero"));
+    game_state.character.inventory.clear();
+    game_state.character.skills.repair = 50;
+    let give = |game_state: &mut GameState, id: &str, qty: u32| {
+        let mut item = registry().item(id).unwrap();
+        item.quantity = qty;
+        game_state.character.add_item(item);
+    };
+    give(&mut game_state, "scrap_metal", 2);
+    give(&mut game_state, "gunpowder", 2);
+    assert!(game_state.at_workbench());
+
+    let messages = game_state.craft("10mm Ammo").unwrap();
+    assert!(messages[0].starts_with("Crafted 10mm Ammo x12"));
+    assert_eq!(game_state.character.item_count("10mm_ammo"), 12);
+    assert_eq!(game_state.character.item_count("scrap_metal"), 1);
+    assert_eq!(game_state.character.item_count("gunpowder"), 0);
+
+    let error = game_state.craft("10mm_ammo").unwrap_err();
+    assert!(error.contains("2 more Gunpowder"));
+
+    // Away from the vault there's no workbench
+    give(&mut game_state, "gunpowder", 2);
+    game_state.worldbook.current_location = None;
+    assert!(!game_state.at_workbench());
+    assert!(game_state
+        .craft("10mm_ammo")
+        .unwrap_err()
+        .contains("A workbench"));
+    assert!(game_state.craft("power armor").is_err());
+}
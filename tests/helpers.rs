@@ -59,6 +59,7 @@ pub fn create_healing_item(id: &str, heal_amount: i32) -> Item {
         weight: 0.5,
         value: 25,
         quantity: 1,
+        instance: 0,
     }
 }
 
@@ -97,6 +98,10 @@ pub fn create_test_enemy(name: &str, hp: i32, skill: u8) -> Enemy {
         ap: 5,
         xp_reward: 50,
         strength: 5,
+        damage_type: DamageType::Normal,
+        kind: None,
+        damage_resistance: 0,
+        resistances: Default::default(),
     }
 }
 
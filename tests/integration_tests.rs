@@ -119,3 +119,8 @@ fn test_new_game_has_vault_13_in_worldbook() {
         Some("vault_13".into())
     );
 }
+
+#[test]
+fn test_bundled_game_data_loads() {
+    assert!(fallout_dnd::game::load_game_data().is_ok());
+}
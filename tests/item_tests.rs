@@ -3,7 +3,7 @@ mod helpers;
 
 use fallout_dnd::game::{
     character::{Character, Special},
-    items::{ConsumableEffect, DamageType, Item, ItemType, WeaponType},
+    items::{find_item, ConsumableEffect, DamageType, Item, ItemType, WeaponType},
 };
 use helpers::*;
 
@@ -59,6 +59,7 @@ fn test_create_consumable_item() {
         weight: 0.5,
         value: 25,
         quantity: 1,
+        instance: 0,
     };
 
     if let ItemType::Consumable(ConsumableEffect::Healing(amount)) = stimpak.item_type {
@@ -155,6 +156,58 @@ fn test_unequipped_returns_unarmed() {
     assert_eq!(skill, character.skills.unarmed);
 }
 
+#[test]
+fn test_weapon_mods_attach_to_one_weapon() {
+    let mut character = create_test_character("Test");
+    let base_skill = character.get_weapon_skill();
+    let base_damage = character.get_equipped_damage();
+    character.add_item(find_item("10mm_pistol").unwrap());
+    character.add_item(find_item("reflex_sight").unwrap());
+    character.add_item(find_item("incendiary_kit").unwrap());
+
+    // The loaded spare pistol has a slot of its own; the modded one gets the
+    // next instance number and stays equipped
+    let pistol = character.inventory.position("10mm_pistol").unwrap();
+    let sight = character.inventory.position("reflex_sight").unwrap();
+    character.attach_mod(pistol, sight).unwrap();
+    assert_eq!(character.item_count("10mm_pistol"), 2);
+    assert_eq!(character.item_count("reflex_sight"), 0);
+    assert_eq!(character.equipped_weapon.as_deref(), Some("10mm_pistol#2"));
+    assert_eq!(character.get_weapon_skill(), base_skill + 10);
+
+    let modded = character
+        .inventory
+        .position_by_key("10mm_pistol#2")
+        .unwrap();
+    let kit = character.inventory.position("incendiary_kit").unwrap();
+    character.attach_mod(modded, kit).unwrap();
+    assert_eq!(
+        character.get_equipped_damage(),
+        format!("{}+2", base_damage)
+    );
+    assert_eq!(
+        character
+            .equipped_weapon_stats()
+            .unwrap()
+            .effective_damage_type(),
+        DamageType::Fire
+    );
+
+    // A sight doesn't fit a melee weapon
+    character.add_item(find_item("reflex_sight").unwrap());
+    character.add_item(find_item("combat_knife").unwrap());
+    let knife = character.inventory.position("combat_knife").unwrap();
+    assert!(character.compatible_mods(knife).is_empty());
+
+    let modded = character
+        .inventory
+        .position_by_key("10mm_pistol#2")
+        .unwrap();
+    character.detach_mod(modded, 0).unwrap();
+    assert_eq!(character.item_count("reflex_sight"), 2);
+    assert_eq!(character.get_weapon_skill(), base_skill);
+}
+
 #[test]
 fn test_weapon_types_map_to_correct_skills() {
     let special = Special::new();
@@ -195,9 +248,9 @@ fn test_weapon_types_map_to_correct_skills() {
     );
 
     // Add weapons to inventory
-    character.inventory.push(small_gun);
-    character.inventory.push(energy_weapon);
-    character.inventory.push(melee);
+    character.inventory.add(small_gun);
+    character.inventory.add(energy_weapon);
+    character.inventory.add(melee);
 
     // Test small gun
     character.equipped_weapon = Some("pistol".into());
@@ -241,6 +294,7 @@ fn test_radaway_consumable_effect() {
         weight: 0.5,
         value: 50,
         quantity: 1,
+        instance: 0,
     };
 
     if let ItemType::Consumable(ConsumableEffect::RadAway(amount)) = radaway.item_type {
@@ -260,16 +314,19 @@ fn test_stat_buff_consumable_effect() {
             stat: "strength".into(),
             amount: 2,
             duration: 300,
+            addictiveness: 40,
         }),
         weight: 0.1,
         value: 75,
         quantity: 1,
+        instance: 0,
     };
 
     if let ItemType::Consumable(ConsumableEffect::StatBuff {
         stat,
         amount,
         duration,
+        ..
     }) = buff.item_type
     {
         assert_eq!(stat, "strength");
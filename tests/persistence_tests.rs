@@ -55,6 +55,61 @@ fn test_save_with_modified_character() {
     let _ = std::fs::remove_file(format!("saves/{}.json", filename));
 }
 
+#[test]
+fn test_save_resumes_rng_stream() {
+    use rand::Rng;
+
+    let mut game_state = fallout_dnd::game::GameState::with_seed(create_test_character("Hero"), 99);
+    game_state.rng.random_range(1..=20);
+
+    let filename = "test_rng_save";
+    persistence::save_to_file(&game_state, filename).unwrap();
+    let mut loaded = persistence::load_from_file(filename).unwrap();
+
+    assert_eq!(loaded.rng.seed(), 99);
+    let expected: Vec<i32> = (0..10)
+        .map(|_| game_state.rng.random_range(1..=20))
+        .collect();
+    let resumed: Vec<i32> = (0..10).map(|_| loaded.rng.random_range(1..=20)).collect();
+    assert_eq!(resumed, expected);
+
+    // Saves from before the RNG was stored still load with a fresh stream
+    let mut json = serde_json::to_value(&game_state).unwrap();
+    json.as_object_mut().unwrap().remove("rng");
+    let old_save: fallout_dnd::game::GameState = serde_json::from_value(json).unwrap();
+    assert_eq!(old_save.character.name, "Hero");
+
+    // Cleanup
+    let _ = std::fs::remove_file(format!("saves/{}.json", filename));
+}
+
+#[test]
+fn test_old_quest_log_migrates_to_journal() {
+    let game_state = fallout_dnd::game::GameState::new(create_test_character("Hero"));
+
+    // Saves from before the journal only had a list of quest names
+    let mut json = serde_json::to_value(&game_state).unwrap();
+    let fields = json.as_object_mut().unwrap();
+    fields.remove("quests");
+    fields.insert(
+        "quest_log".to_string(),
+        serde_json::json!(["Find the Water Chip", "Help Megaton"]),
+    );
+
+    let filename = "test_quest_migration";
+    std::fs::create_dir_all("saves").unwrap();
+    std::fs::write(format!("saves/{}.json", filename), json.to_string()).unwrap();
+    let loaded = persistence::load_from_file(filename).unwrap();
+
+    assert!(loaded.quest_log.is_empty());
+    let names: Vec<&str> = loaded.quests.active().map(|q| q.name.as_str()).collect();
+    assert_eq!(names, vec!["Find the Water Chip", "Help Megaton"]);
+    assert!(loaded.quests.quests.iter().all(|q| !q.is_tracked()));
+
+    // Cleanup
+    let _ = std::fs::remove_file(format!("saves/{}.json", filename));
+}
+
 #[test]
 fn test_filename_validation() {
     let character = create_test_character("Hero");
@@ -72,3 +127,81 @@ fn test_filename_validation() {
     let result = persistence::save_to_file(&game_state, ".hidden");
     assert!(result.is_err(), "Dot files should be rejected");
 }
+
+#[test]
+fn test_old_weapons_get_their_magazine_back() {
+    use fallout_dnd::game::items::ItemType;
+
+    let mut game_state = fallout_dnd::game::GameState::new(create_test_character("Hero"));
+    // Saves from before magazines existed load with a magazine size of 0
+    let pistol = game_state
+        .character
+        .inventory
+        .iter_mut()
+        .find(|item| item.id == "10mm_pistol")
+        .unwrap();
+    let ItemType::Weapon(ref mut stats) = pistol.item_type else {
+        panic!("10mm pistol should be a weapon");
+    };
+    stats.magazine_size = 0;
+    stats.loaded_rounds = 0;
+    assert!(!stats.uses_ammo());
+
+    let filename = "test_old_weapons_save";
+    persistence::save_to_file(&game_state, filename).unwrap();
+    let loaded = persistence::load_from_file(filename).unwrap();
+    let _ = std::fs::remove_file(format!("saves/{}.json", filename));
+
+    let pistol = loaded.character.inventory.find("10mm_pistol").unwrap();
+    let stats = loaded.character.inventory[pistol].as_weapon().unwrap();
+    assert_eq!(stats.magazine_size, 12);
+    assert!(stats.uses_ammo());
+    assert!(stats.is_empty());
+}
+
+#[test]
+fn test_loaded_worldbook_still_matches_names() {
+    use fallout_dnd::game::worldbook::NPC;
+
+    let npc = |id: &str, name: &str, role: &str, notes: &str| NPC {
+        id: id.into(),
+        name: name.into(),
+        name_lowercase: Default::default(),
+        role: role.into(),
+        personality: vec![],
+        current_location: None,
+        disposition: 0,
+        knowledge: vec![],
+        notes: notes.into(),
+        alive: true,
+    };
+    let mut game_state = fallout_dnd::game::GameState::new(create_test_character("Hero"));
+    game_state.worldbook.add_npc(npc(
+        "moira_01",
+        "Moira Brown",
+        "shopkeeper",
+        "Runs Craterside Supply and is writing the Wasteland Survival Guide",
+    ));
+    game_state.worldbook.add_npc(npc(
+        "gob_01",
+        "Gob",
+        "bartender",
+        "Moira Brown owes him caps. Moira Brown, always Moira Brown",
+    ));
+    let filename = "test_worldbook_names_save";
+    persistence::save_to_file(&game_state, filename).unwrap();
+    let loaded = persistence::load_from_file(filename).unwrap();
+    let _ = std::fs::remove_file(format!("saves/{}.json", filename));
+
+    // Naming an NPC still boosts them over entries that only share words
+    assert_eq!(
+        loaded.worldbook.npcs["moira_01"].name_lowercase,
+        "moira brown"
+    );
+    let words = |text: &str| text.split_whitespace().count();
+    let relevant = loaded
+        .worldbook
+        .relevant_entries("Where is Moira Brown?", 100, words);
+    assert_eq!(relevant.len(), 2);
+    assert!(relevant[0].starts_with("NPC: Moira Brown"));
+}
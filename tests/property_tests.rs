@@ -8,7 +8,7 @@ proptest! {
     fn test_dice_roll_bounds(num_dice in 1u32..10, die_size in 1u32..100, modifier in 0i32..50) {
         let dice_str = format!("{}d{}+{}", num_dice, die_size, modifier);
 
-        let result = roll_dice(&dice_str);
+        let result = roll_dice(&dice_str, &mut rand::rng()).unwrap().total;
 
         // Minimum: num_dice (all ones) + modifier
         let min_value = num_dice as i32 + modifier;
@@ -23,7 +23,7 @@ proptest! {
     #[test]
     fn test_single_die_range(die_size in 1u32..100) {
         let dice_str = format!("1d{}", die_size);
-        let result = roll_dice(&dice_str);
+        let result = roll_dice(&dice_str, &mut rand::rng()).unwrap().total;
 
         prop_assert!(result >= 1);
         prop_assert!(result <= die_size as i32);
@@ -33,7 +33,7 @@ proptest! {
     #[test]
     fn test_multiple_dice_range(num_dice in 2u32..20) {
         let dice_str = format!("{}d6", num_dice);
-        let result = roll_dice(&dice_str);
+        let result = roll_dice(&dice_str, &mut rand::rng()).unwrap().total;
 
         // With multiple dice, minimum is num_dice (all ones)
         prop_assert!(result >= num_dice as i32);
@@ -41,6 +41,17 @@ proptest! {
         prop_assert!(result <= (num_dice * 6) as i32);
     }
 
+    /// Test that keeping the highest dice stays within the kept dice's range
+    #[test]
+    fn test_keep_highest_bounds(num_dice in 1u32..10, die_size in 1u32..20, keep in 1u32..10) {
+        let keep = keep.min(num_dice);
+        let dice_str = format!("{}d{}kh{}", num_dice, die_size, keep);
+        let result = roll_dice(&dice_str, &mut rand::rng()).unwrap().total;
+
+        prop_assert!(result >= keep as i32);
+        prop_assert!(result <= (keep * die_size) as i32);
+    }
+
     /// Test character HP is always non-negative after damage
     #[test]
     fn test_character_hp_non_negative(damage in 0i32..1000) {
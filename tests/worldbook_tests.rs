@@ -40,7 +40,8 @@ fn snapshot_extraction_simple_megaton() {
         {
           "name": "Megaton",
           "description": "Settlement built around unexploded atomic bomb",
-          "location_type": "settlement"
+          "location_type": "settlement",
+          "workbench": false
         }
       ],
       "npcs": [
@@ -156,12 +157,14 @@ fn snapshot_extraction_complex_scenario() {
         {
           "name": "Vault 101",
           "description": "Underground vault where you grew up",
-          "location_type": "vault"
+          "location_type": "vault",
+          "workbench": false
         },
         {
           "name": "Springvale",
           "description": "Abandoned town near Vault 101",
-          "location_type": "ruin"
+          "location_type": "ruin",
+          "workbench": false
         }
       ],
       "npcs": [
@@ -211,6 +214,7 @@ fn snapshot_extraction_to_worldbook_conversion() {
             name: "Rivet City".to_string(),
             description: "Aircraft carrier settlement".to_string(),
             location_type: "settlement".to_string(),
+            workbench: false,
         }],
         npcs: vec![ExtractedNPC {
             name: "Doctor Li".to_string(),
@@ -226,7 +230,8 @@ fn snapshot_extraction_to_worldbook_conversion() {
         }],
     };
 
-    let (locations, npcs, events) = extracted.to_worldbook_entries();
+    let (locations, npcs, events) =
+        extracted.to_worldbook_entries(&fallout_dnd::game::clock::start_timestamp());
 
     // Snapshot the converted worldbook entries
     insta::assert_json_snapshot!((locations, npcs, events), {
@@ -549,7 +554,11 @@ fn test_location_visit_tracking() {
 
     let first_visit = loc.first_visited.clone();
 
-    std::thread::sleep(std::time::Duration::from_millis(10));
+    // Visits are stamped with game time, so move the clock forward
+    worldbook.set_current_time(fallout_dnd::game::clock::timestamp(
+        1,
+        fallout_dnd::game::clock::TimeOfDay::new(9, 0),
+    ));
     worldbook.visit_location("test");
 
     let loc = worldbook.get_location("test").unwrap();
@@ -818,3 +827,53 @@ fn test_complex_worldbook_scenario() {
     let location = worldbook.get_location("megaton_01").unwrap();
     assert_eq!(location.visit_count, 1);
 }
+
+#[test]
+fn test_relevant_context_finds_npcs_elsewhere() {
+    let mut worldbook = Worldbook::new();
+    worldbook.add_location(create_test_location(
+        "megaton_01",
+        "Megaton",
+        "A town built around a bomb",
+        "settlement",
+    ));
+    worldbook.add_location(create_test_location(
+        "rivet_city_01",
+        "Rivet City",
+        "A settlement in a beached aircraft carrier",
+        "settlement",
+    ));
+    worldbook.set_current_location(Some("megaton_01".into()));
+
+    let mut simms = create_test_npc("lucas_simms_01", "Lucas Simms", "sheriff");
+    simms.current_location = Some("megaton_01".into());
+    worldbook.add_npc(simms);
+    let mut harkness = create_test_npc("harkness_01", "Harkness", "security chief");
+    harkness.current_location = Some("rivet_city_01".into());
+    harkness.knowledge = vec!["android".into()];
+    worldbook.add_npc(harkness);
+    worldbook.add_npc(create_test_npc("moriarty_01", "Moriarty", "saloon owner"));
+
+    // Only the current location is in the base context
+    assert!(!worldbook.build_context().contains("Harkness"));
+
+    let words = |text: &str| text.split_whitespace().count();
+    let relevant = worldbook.relevant_entries("I ask about Harkness and the android", 100, words);
+    assert_eq!(relevant.len(), 1);
+    assert!(relevant[0].contains("Harkness (security chief), last seen at Rivet City"));
+
+    // Already covered by the location context
+    assert!(worldbook
+        .relevant_entries("Where is Lucas Simms?", 100, words)
+        .is_empty());
+    // A town named by the player comes with it, within the budget
+    let relevant = worldbook.relevant_entries("Take me to Rivet City", 100, words);
+    assert!(relevant[0].starts_with("Location: Rivet City"));
+    assert!(worldbook
+        .relevant_entries("Take me to Rivet City", 3, words)
+        .is_empty());
+    assert!(worldbook
+        .relevant_entries("Nothing here matches", 100, words)
+        .is_empty());
+    assert!(Worldbook::format_relevant_context(&relevant).contains("=== ALSO RELEVANT ==="));
+}